@@ -34,25 +34,32 @@
 //! # Unsupported features
 //!
 //! Missing features include:
-//! - Non-linear easings
-//! - Position keyframe (`ti`, `to`) easing
-//! - Time remapping (`tm`)
+//! - Spatial tangents (`ti`, `to`) of position keyframes. Positions move in straight lines between keyframes
 //! - Text
 //! - Image embedding
-//! - Advanced shapes (stroke dash, zig-zag, etc.)
-//! - Advanced effects (motion blur, drop shadows, etc.)
-//! - Correct color stop handling
-//! - Split rotations
-//! - Split positions
+//! - Advanced shapes (merge paths, etc.)
+//! - Advanced effects (blurs, etc.). Motion blur is only approximated, and only when enabled with [`RenderOptions::motion_blur_samples`]
+//! - Correct perspective. 3D layers are projected by an affine transformation that matches the camera around the anchor point of each layer, and camera layers ignore parenting
+//! - Expressions. With the `expressions` feature, a subset covering arithmetic, `time`, `value`, property references and `Math` is evaluated into keyframes
 
-pub(crate) mod import;
 pub(crate) mod runtime;
-pub(crate) mod schema;
 
-mod error;
-pub use error::Error;
+// Parsing, the model and evaluation live in velato-core so that crates
+// which don't render can avoid building vello.
+pub use velato_core::{
+    animation, model, AnimatedProperty, AudioEvent, AudioPlayback, Bundle, Canvas, Composition,
+    Digest, Error, ImportOptions, JoinFallback, KeyframeEvent, KeyframeObserver, KeyframeSample,
+    KeyframeSelector, KeyframeTable, LayerProperty, Painter, PlayMode, Playhead, PlayheadEvent,
+    PropertyFilter, RenderOptions, Selector, SelectorSegment, SlotValue, Summary, Theme,
+};
+
+#[cfg(feature = "arbitrary")]
+pub use velato_core::ArbitraryLottie;
 
 // Re-export vello
 pub use vello;
 
-pub use runtime::{model, Composition, Renderer};
+pub use runtime::{DamageTracker, FrameComplexity, FrameOptions, Frames, Renderer};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use runtime::{InstanceId, Priority, RendererPool};
@@ -0,0 +1,81 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+/// Draw tag that begins a layer, from the encoding of vello.
+const BEGIN_CLIP: u32 = 0x9;
+
+/// Draw tag that ends a layer.
+const END_CLIP: u32 = 0x21;
+
+/// Value of the `Clip` mix in the blend mode of a layer.
+const MIX_CLIP: u32 = 128;
+
+/// Value of the `Normal` mix.
+const MIX_NORMAL: u32 = 0;
+
+/// Value of the `SrcOver` compose.
+const COMPOSE_SRC_OVER: u32 = 3;
+
+/// Counts of the layers and draws of a rendered frame, for deciding how to
+/// present it.
+///
+/// Every layer is composited through a buffer of its own, and nested layers
+/// through nested buffers, so compositors can use these counts to choose
+/// between rendering directly and rendering through an offscreen surface,
+/// or to lower the fidelity of animations on weak GPUs.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct FrameComplexity {
+    /// Number of layers pushed for masks, mattes, blend modes, effects and
+    /// isolated groups, excluding the clip of the composition bounds.
+    pub layers: usize,
+    /// Number of those layers that only clip their content.
+    pub clip_layers: usize,
+    /// Number of those layers that blend with their backdrop using a mode
+    /// other than normal source-over compositing.
+    pub blend_layers: usize,
+    /// Deepest nesting of layers, such as a mask within a matte.
+    pub max_depth: usize,
+    /// Number of fills and strokes.
+    pub draws: usize,
+}
+
+impl FrameComplexity {
+    /// Counts the layers and draws of a scene whose first layer is the clip
+    /// of the composition bounds.
+    pub(super) fn new(scene: &vello::Scene) -> Self {
+        let encoding = scene.encoding();
+        let mut complexity = Self::default();
+        let mut depth = 0_usize;
+        // Offset in the draw data, in words.
+        let mut offset = 0;
+        for tag in &encoding.draw_tags {
+            match tag.0 {
+                BEGIN_CLIP => {
+                    depth += 1;
+                    if depth > 1 {
+                        complexity.layers += 1;
+                        complexity.max_depth = complexity.max_depth.max(depth - 1);
+                        let start = offset * 4;
+                        let blend_mode = encoding
+                            .draw_data
+                            .get(start..start + 4)
+                            .map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()));
+                        if let Some(blend_mode) = blend_mode {
+                            let (mix, compose) = (blend_mode >> 8, blend_mode & 0xff);
+                            if mix == MIX_CLIP {
+                                complexity.clip_layers += 1;
+                            } else if mix != MIX_NORMAL || compose != COMPOSE_SRC_OVER {
+                                complexity.blend_layers += 1;
+                            }
+                        }
+                    }
+                }
+                END_CLIP => depth = depth.saturating_sub(1),
+                _ => complexity.draws += 1,
+            }
+            // The size of the draw data, in words, is encoded in the tag.
+            offset += ((tag.0 >> 2) & 0x7) as usize;
+        }
+        complexity
+    }
+}
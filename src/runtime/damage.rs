@@ -0,0 +1,68 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use vello::kurbo::Rect;
+
+/// Encoded state of a top level layer in the previously rendered frame.
+#[derive(Copy, Clone, Default)]
+pub(super) struct LayerState {
+    /// Hash of the encoding of the layer.
+    pub fingerprint: u64,
+    /// Bounds, in scene coordinates, of everything drawn by the layer.
+    pub bounds: Option<Rect>,
+}
+
+/// Tracks the regions of a scene that change between rendered frames.
+///
+/// Pass the same tracker to [`Renderer::append_with_damage`] for each frame
+/// to receive the union of the bounds of all top level layers whose encoding
+/// differs from the previous frame, in both their old and new positions.
+/// Compositors can use this to present or scissor only the damaged region.
+///
+/// [`Renderer::append_with_damage`]: super::Renderer::append_with_damage
+#[derive(Default)]
+pub struct DamageTracker {
+    pub(super) layers: Vec<LayerState>,
+    /// Scratch scene into which each layer is encoded before it is appended.
+    pub(super) scratch: vello::Scene,
+}
+
+impl DamageTracker {
+    /// Creates a new tracker for which the next frame is entirely damaged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the previously rendered frame so that the next frame is
+    /// entirely damaged.
+    pub fn reset(&mut self) {
+        self.layers.clear();
+    }
+}
+
+/// Returns a hash of everything that affects the output of a scene.
+pub(super) fn fingerprint(scene: &vello::Scene) -> u64 {
+    let encoding = scene.encoding();
+    let mut hasher = DefaultHasher::new();
+    for tag in &encoding.path_tags {
+        tag.0.hash(&mut hasher);
+    }
+    encoding.path_data.hash(&mut hasher);
+    for tag in &encoding.draw_tags {
+        tag.0.hash(&mut hasher);
+    }
+    encoding.draw_data.hash(&mut hasher);
+    for transform in &encoding.transforms {
+        for value in transform.matrix.iter().chain(&transform.translation) {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    for style in &encoding.styles {
+        style.flags_and_miter_limit.hash(&mut hasher);
+        style.line_width.to_bits().hash(&mut hasher);
+    }
+    encoding.resources.color_stops.hash(&mut hasher);
+    hasher.finish()
+}
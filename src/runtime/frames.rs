@@ -0,0 +1,158 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Renderer;
+use crate::Composition;
+use vello::kurbo::Affine;
+
+/// Options for iterating over the frames of a composition with
+/// [`Renderer::iter_frames`].
+#[derive(Copy, Clone, Debug)]
+pub struct FrameOptions {
+    /// Transform applied to every frame.
+    pub transform: Affine,
+    /// Alpha applied to every frame.
+    pub alpha: f64,
+    /// Whether the frames are played in a loop.
+    ///
+    /// Looping animations end before the end of the composition, which is
+    /// also the first frame of the next loop. Animations played once end
+    /// with an additional frame showing the final state of the composition.
+    pub looping: bool,
+}
+
+impl Default for FrameOptions {
+    fn default() -> Self {
+        Self {
+            transform: Affine::IDENTITY,
+            alpha: 1.0,
+            looping: true,
+        }
+    }
+}
+
+/// Iterator over frames of a composition sampled at a fixed rate, created
+/// by [`Renderer::iter_frames`].
+///
+/// Each item is the frame of the composition and a scene rendering it.
+pub struct Frames<'a> {
+    renderer: &'a mut Renderer,
+    composition: &'a Composition,
+    fps: f64,
+    options: FrameOptions,
+    index: usize,
+    count: usize,
+}
+
+impl<'a> Frames<'a> {
+    pub(super) fn new(
+        renderer: &'a mut Renderer,
+        composition: &'a Composition,
+        fps: f64,
+        options: FrameOptions,
+    ) -> Self {
+        let frames = &composition.frames;
+        let duration = if composition.frame_rate > 0.0 {
+            (frames.end - frames.start).max(0.0) / composition.frame_rate
+        } else {
+            0.0
+        };
+        let count = if fps > 0.0 && fps.is_finite() {
+            // The tolerance keeps rounding errors from adding a frame when
+            // the duration is a whole number of output frames.
+            let looped = ((duration * fps) - 1e-9).ceil().max(1.0) as usize;
+            if options.looping || duration == 0.0 {
+                looped
+            } else {
+                looped + 1
+            }
+        } else {
+            0
+        };
+        Self {
+            renderer,
+            composition,
+            fps,
+            options,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Returns the frame of the composition displayed by the output frame
+    /// with the given index.
+    fn frame(&self, index: usize) -> f64 {
+        let frames = &self.composition.frames;
+        let frame = frames.start + index as f64 * self.composition.frame_rate / self.fps;
+        frame.min(frames.end)
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = (f64, vello::Scene);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let frame = self.frame(self.index);
+        self.index += 1;
+        let scene = self.renderer.render(
+            self.composition,
+            frame,
+            self.options.transform,
+            self.options.alpha,
+        );
+        Some((frame, scene))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Frames<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameOptions;
+    use crate::{Composition, Renderer};
+
+    #[test]
+    fn test_iter_frames() {
+        let composition = Composition {
+            frames: 0.0..60.0,
+            frame_rate: 30.0,
+            width: 10,
+            height: 10,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        let mut frames = |fps, looping| {
+            let options = FrameOptions {
+                looping,
+                ..Default::default()
+            };
+            renderer
+                .iter_frames(&composition, fps, options)
+                .map(|(frame, _)| frame)
+                .collect::<Vec<_>>()
+        };
+        // Two seconds at 10 frames per second, without the start of the
+        // next loop.
+        let looped = frames(10.0, true);
+        assert_eq!(looped.len(), 20);
+        assert_eq!(looped[1], 3.0);
+        assert_eq!(looped.last(), Some(&57.0));
+        // Played once, the final frame shows the end of the composition.
+        let once = frames(10.0, false);
+        assert_eq!(once.len(), 21);
+        assert_eq!(once.last(), Some(&60.0));
+        // Output rates that don't divide the duration end at the end.
+        let once = frames(7.2, false);
+        assert_eq!(once.len(), 16);
+        assert_eq!(once.last(), Some(&60.0));
+        assert!(frames(0.0, true).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Renderer;
+use crate::Composition;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use vello::kurbo::Affine;
+
+/// Number of consecutive updates a visible animation may be skipped before
+/// it is rendered regardless of the budget.
+const MAX_SKIPPED_UPDATES: u32 = 8;
+
+/// Identifier for an animation in a [`RendererPool`].
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct InstanceId(u64);
+
+/// Scheduling priority of an animation in a [`RendererPool`].
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub enum Priority {
+    /// Not visible. Playback time advances but nothing is rendered.
+    Hidden,
+    /// Visible. Rendered when the budget allows, otherwise updates are
+    /// decimated.
+    #[default]
+    Visible,
+    /// Rendered on every update regardless of the budget so that playback
+    /// stays in sync with external media.
+    Focused,
+}
+
+struct Instance {
+    composition: Arc<Composition>,
+    priority: Priority,
+    /// Time, in seconds, at which playback started.
+    start_time: f64,
+    scene: vello::Scene,
+    /// Frame currently encoded in the scene.
+    frame: Option<f64>,
+    /// Duration of the most recent render.
+    cost: Duration,
+    /// Number of updates since the scene was last rendered.
+    skipped: u32,
+}
+
+impl Instance {
+    /// Returns the frame that should be displayed at the given time.
+    fn frame_at(&self, time: f64) -> f64 {
+        let frames = &self.composition.frames;
+        let duration = frames.end - frames.start;
+        if duration <= 0.0 || self.composition.frame_rate <= 0.0 {
+            return frames.start;
+        }
+        let elapsed = (time - self.start_time).max(0.0);
+        frames.start + (elapsed * self.composition.frame_rate) % duration
+    }
+
+    fn render(&mut self, renderer: &mut Renderer, frame: f64) -> Duration {
+        let start = Instant::now();
+        self.scene.reset();
+        renderer.append(
+            &self.composition,
+            frame,
+            Affine::IDENTITY,
+            1.0,
+            &mut self.scene,
+        );
+        self.frame = Some(frame);
+        self.skipped = 0;
+        self.cost = start.elapsed();
+        self.cost
+    }
+}
+
+/// Renders many looping animations while sharing a time budget per update.
+///
+/// This is intended for lists of small animations, such as reactions in a
+/// chat view, where rendering every animation on every display frame is too
+/// expensive. Each update renders focused animations first, then visible
+/// animations in order of staleness while the budget allows. Visible
+/// animations that don't fit keep their previous scene, but are never
+/// skipped for more than a few consecutive updates.
+///
+/// Scenes are rendered at the origin with the size of the composition, and
+/// can be placed with [`vello::Scene::append`].
+pub struct RendererPool {
+    renderer: Renderer,
+    budget: Duration,
+    instances: HashMap<InstanceId, Instance>,
+    next_id: u64,
+    queue: Vec<InstanceId>,
+}
+
+impl RendererPool {
+    /// Creates a new pool which spends at most `budget` rendering visible
+    /// animations on each update.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            renderer: Renderer::new(),
+            budget,
+            instances: HashMap::new(),
+            next_id: 0,
+            queue: vec![],
+        }
+    }
+
+    /// Returns the renderer used for all animations in the pool.
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    /// Sets the time spent rendering on each update.
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    /// Returns the time spent rendering on each update.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Adds an animation to the pool which starts playing at `start_time`,
+    /// in seconds, on the clock passed to [`update`](Self::update).
+    pub fn insert(&mut self, composition: Arc<Composition>, start_time: f64) -> InstanceId {
+        let id = InstanceId(self.next_id);
+        self.next_id += 1;
+        self.instances.insert(
+            id,
+            Instance {
+                composition,
+                priority: Priority::default(),
+                start_time,
+                scene: vello::Scene::new(),
+                frame: None,
+                cost: Duration::ZERO,
+                skipped: 0,
+            },
+        );
+        id
+    }
+
+    /// Removes an animation from the pool. Returns false if it was not
+    /// present.
+    pub fn remove(&mut self, id: InstanceId) -> bool {
+        self.instances.remove(&id).is_some()
+    }
+
+    /// Sets the scheduling priority of an animation.
+    pub fn set_priority(&mut self, id: InstanceId, priority: Priority) {
+        if let Some(instance) = self.instances.get_mut(&id) {
+            instance.priority = priority;
+        }
+    }
+
+    /// Returns the scheduling priority of an animation.
+    pub fn priority(&self, id: InstanceId) -> Option<Priority> {
+        self.instances.get(&id).map(|instance| instance.priority)
+    }
+
+    /// Returns the most recently rendered scene for an animation.
+    pub fn scene(&self, id: InstanceId) -> Option<&vello::Scene> {
+        self.instances.get(&id).map(|instance| &instance.scene)
+    }
+
+    /// Returns the frame encoded in the scene for an animation, or `None`
+    /// if it has not been rendered yet.
+    pub fn frame(&self, id: InstanceId) -> Option<f64> {
+        self.instances.get(&id).and_then(|instance| instance.frame)
+    }
+
+    /// Renders animations for the given time in seconds and returns the
+    /// number of scenes that were updated.
+    pub fn update(&mut self, time: f64) -> usize {
+        let Self {
+            renderer,
+            budget,
+            instances,
+            queue,
+            ..
+        } = self;
+        let mut remaining = *budget;
+        let mut rendered = 0;
+        queue.clear();
+        for (id, instance) in instances.iter_mut() {
+            match instance.priority {
+                Priority::Hidden => {}
+                Priority::Visible => queue.push(*id),
+                Priority::Focused => {
+                    let frame = instance.frame_at(time);
+                    if instance.frame != Some(frame) {
+                        remaining = remaining.saturating_sub(instance.render(renderer, frame));
+                        rendered += 1;
+                    }
+                }
+            }
+        }
+        // Stalest first, then in insertion order.
+        queue.sort_by_key(|id| (std::cmp::Reverse(instances[id].skipped), *id));
+        for id in queue.iter() {
+            let Some(instance) = instances.get_mut(id) else {
+                continue;
+            };
+            let frame = instance.frame_at(time);
+            if instance.frame == Some(frame) {
+                continue;
+            }
+            if instance.cost <= remaining || instance.skipped >= MAX_SKIPPED_UPDATES {
+                remaining = remaining.saturating_sub(instance.render(renderer, frame));
+                rendered += 1;
+            } else {
+                instance.skipped += 1;
+            }
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Priority, RendererPool};
+    use crate::Composition;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_priorities() {
+        let composition = Arc::new(Composition {
+            frames: 0.0..60.0,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            ..Default::default()
+        });
+        let mut pool = RendererPool::new(Duration::ZERO);
+        let focused = pool.insert(composition.clone(), 0.0);
+        let hidden = pool.insert(composition, 0.0);
+        pool.set_priority(focused, Priority::Focused);
+        pool.set_priority(hidden, Priority::Hidden);
+        for i in 0..4 {
+            assert_eq!(pool.update(i as f64 * 0.25), 1);
+        }
+        assert_eq!(pool.frame(focused), Some(45.0));
+        assert_eq!(pool.frame(hidden), None);
+        // Playback loops after one second.
+        pool.update(1.0);
+        assert_eq!(pool.frame(focused), Some(0.0));
+    }
+}
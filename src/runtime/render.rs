@@ -1,17 +1,30 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::model::*;
-use super::Composition;
-use std::ops::Range;
-use vello::kurbo::{Affine, PathEl, Rect};
-use vello::peniko::{Fill, Mix};
+use super::complexity::FrameComplexity;
+use super::damage::{fingerprint, DamageTracker, LayerState};
+use super::frames::{FrameOptions, Frames};
+use crate::model::Matte;
+use crate::{Canvas, Composition, Digest, JoinFallback, Painter, RenderOptions};
+use vello::glyph::Glyph;
+use vello::kurbo::{Affine, Rect, Shape, Stroke};
+use vello::peniko::{BlendMode, BrushRef, Color, Fill, Font};
 
 /// Renders a composition into a scene.
+///
+/// A renderer retains scratch buffers sized for the most complex frame it
+/// has rendered, and cached instances only for the frame currently being
+/// rendered. Memory use therefore does not grow with the number of frames
+/// rendered, so a single renderer can stream arbitrarily long animations as
+/// long as the target scene is reset between frames.
+///
+/// Frames are evaluated by a [`Painter`], the same walk that computes the
+/// digests of frames without vello.
 #[derive(Default)]
 pub struct Renderer {
-    batch: Batch,
-    mask_elements: Vec<PathEl>,
+    painter: Painter<SceneCanvas>,
+    /// Scratch scene into which frames are encoded to measure them.
+    complexity_scene: vello::Scene,
 }
 
 impl Renderer {
@@ -20,6 +33,136 @@ impl Renderer {
         Self::default()
     }
 
+    /// Sets the miter limit used for strokes that do not specify one.
+    pub fn set_default_miter_limit(&mut self, miter_limit: f64) {
+        self.painter.set_default_miter_limit(miter_limit);
+    }
+
+    /// Returns the miter limit used for strokes that do not specify one.
+    pub fn default_miter_limit(&self) -> f64 {
+        self.painter.default_miter_limit()
+    }
+
+    /// Sets the policy for falling back to bevel joins on sharp corners.
+    pub fn set_join_fallback(&mut self, join_fallback: JoinFallback) {
+        self.painter.set_join_fallback(join_fallback);
+    }
+
+    /// Returns the policy for falling back to bevel joins on sharp corners.
+    pub fn join_fallback(&self) -> JoinFallback {
+        self.painter.join_fallback()
+    }
+
+    /// Sets the options that change how compositions are rendered.
+    pub fn set_render_options(&mut self, options: RenderOptions) {
+        self.painter.set_render_options(options);
+    }
+
+    /// Returns the options that change how compositions are rendered.
+    pub fn render_options(&self) -> RenderOptions {
+        self.painter.render_options()
+    }
+
+    /// Sets whether identical precomposition instances are shared within a
+    /// frame.
+    ///
+    /// When enabled, each precomposition is evaluated once per frame for
+    /// every distinct local time and opacity at which it appears, into a
+    /// scene fragment that is appended with the transform of each instance.
+    /// This skips evaluating the animated values, geometry and effects of
+    /// repeated precompositions, such as confetti, at the cost of holding the
+    /// fragments in memory for the duration of the frame. Appending still
+    /// copies the encoded fragment into the scene for every instance, so the
+    /// scene and the work to render it on the GPU are as large as without
+    /// caching.
+    pub fn set_instance_caching(&mut self, enabled: bool) {
+        self.painter.set_instance_caching(enabled);
+    }
+
+    /// Returns true if identical precomposition instances are shared within
+    /// a frame.
+    pub fn instance_caching(&self) -> bool {
+        self.painter.instance_caching()
+    }
+
+    /// Sets whether semi-transparent shape groups are isolated.
+    ///
+    /// When enabled, a group with opacity that contains multiple fills or
+    /// strokes is rendered at full opacity into a separate layer which is
+    /// then composited with the group opacity. This matches After Effects,
+    /// where a stroke overlapping the fill of the same group does not
+    /// darken the overlap, but requires an additional layer per group.
+    pub fn set_group_isolation(&mut self, enabled: bool) {
+        self.painter.set_group_isolation(enabled);
+    }
+
+    /// Returns true if semi-transparent shape groups are isolated.
+    pub fn group_isolation(&self) -> bool {
+        self.painter.group_isolation()
+    }
+
+    /// Sets whether displacement map effects are applied.
+    ///
+    /// vello cannot sample the pixels of another layer, so displacement is
+    /// approximated by moving the points of the paths of a shape layer by
+    /// amounts sampled from the colors of the map layer at each point. The
+    /// map is sampled in the space of the displaced layer, ignoring its own
+    /// transform, and must itself be a shape layer. Each point is tested
+    /// against every draw of the map, so this is much more expensive than
+    /// other effects and disabled by default.
+    pub fn set_displacement_maps(&mut self, enabled: bool) {
+        self.painter.set_displacement_maps(enabled);
+    }
+
+    /// Returns true if displacement map effects are applied.
+    pub fn displacement_maps(&self) -> bool {
+        self.painter.displacement_maps()
+    }
+
+    /// Sets whether unsupported content is drawn as placeholders.
+    ///
+    /// This is a debugging aid for reviewing animations. When enabled,
+    /// layers with text, images, or effects that cannot be rendered are
+    /// covered by a crossed-out rectangle, so that missing content is not
+    /// mistaken for an intentional gap. Placeholders are labeled with the
+    /// name of the layer and what is missing if a font is set with
+    /// [`Renderer::set_placeholder_font`].
+    pub fn set_placeholders(&mut self, enabled: bool) {
+        self.painter.set_placeholders(enabled);
+    }
+
+    /// Returns true if unsupported content is drawn as placeholders.
+    pub fn placeholders(&self) -> bool {
+        self.painter.placeholders()
+    }
+
+    /// Sets the font used to label placeholders. vello has no built-in
+    /// font, so placeholders are unlabeled without one.
+    pub fn set_placeholder_font(&mut self, font: Option<Font>) {
+        self.painter.set_placeholder_font(font);
+    }
+
+    /// Returns the font used to label placeholders.
+    pub fn placeholder_font(&self) -> Option<&Font> {
+        self.painter.placeholder_font()
+    }
+
+    /// Sets the seed of the noise that moves properties with a `wiggle`
+    /// expression.
+    ///
+    /// Wiggles depend only on the seed and the frame, so renderers with the
+    /// same seed produce the same motion on every run. The default seed is
+    /// zero.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.painter.set_seed(seed);
+    }
+
+    /// Returns the seed of the noise that moves properties with a `wiggle`
+    /// expression.
+    pub fn seed(&self) -> u64 {
+        self.painter.seed()
+    }
+
     /// Renders the animation at a given frame to a new scene.
     pub fn render(
         &mut self,
@@ -33,7 +176,28 @@ impl Renderer {
         scene
     }
 
+    /// Returns an iterator rendering the animation at a fixed number of
+    /// frames per second, for encoders of animated images such as GIF, WebP
+    /// or APNG.
+    ///
+    /// Each item is the frame of the composition and its scene. Every frame
+    /// lasts `1 / fps` seconds, except that the final frame of animations
+    /// that don't loop is held. Nothing is rendered if `fps` is not
+    /// positive.
+    pub fn iter_frames<'a>(
+        &'a mut self,
+        animation: &'a Composition,
+        fps: f64,
+        options: FrameOptions,
+    ) -> Frames<'a> {
+        Frames::new(self, animation, fps, options)
+    }
+
     /// Renders and appends the animation at a given frame to the provided scene.
+    ///
+    /// Frames before the start of the animation display the first frame.
+    /// Frames at or after the end display the last frame, so the end of the
+    /// frame range can be used to show the final state of the animation.
     pub fn append(
         &mut self,
         animation: &Composition,
@@ -42,338 +206,937 @@ impl Renderer {
         alpha: f64,
         scene: &mut vello::Scene,
     ) {
-        self.batch.clear();
-        scene.push_layer(
-            Mix::Clip,
-            1.0,
-            transform,
-            &Rect::new(0.0, 0.0, animation.width as _, animation.height as _),
-        );
-        for layer in animation.layers.iter().rev() {
-            if layer.is_mask {
-                continue;
-            }
-            self.render_layer(
-                animation,
-                &animation.layers,
-                layer,
-                transform,
-                alpha,
-                frame,
-                scene,
-            );
-        }
-        scene.pop_layer();
+        let mut canvas = SceneCanvas(std::mem::take(scene));
+        self.painter
+            .paint(animation, frame, transform, alpha, &mut canvas);
+        *scene = canvas.0;
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn render_layer(
+    /// Renders and appends the animation at a given frame to the provided
+    /// scene, and returns the region, in scene coordinates, that changed
+    /// since the previous frame rendered with the same tracker.
+    ///
+    /// Returns `None` if the frame is identical to the previous one. Each
+    /// top level layer is encoded separately so that it can be compared
+    /// with the previous frame, and precomposition instances are not
+    /// shared, so this is somewhat slower than [`append`](Self::append).
+    pub fn append_with_damage(
         &mut self,
         animation: &Composition,
-        layer_set: &[Layer],
-        layer: &Layer,
+        frame: f64,
         transform: Affine,
         alpha: f64,
-        frame: f64,
         scene: &mut vello::Scene,
-    ) {
-        if !layer.frames.contains(&frame) {
-            return;
-        }
-        let parent_transform = transform;
-        let transform = self.compute_transform(layer_set, layer, parent_transform, frame);
-        let full_rect = Rect::new(0.0, 0.0, animation.width as f64, animation.height as f64);
-        if let Some((mode, mask_index)) = layer.mask_layer {
-            // todo: re-enable masking when it is more understood (and/or if
-            // it's currently supported in vello?) Extra layer to
-            // isolate blending for the mask
-            scene.push_layer(Mix::Normal, 1.0, parent_transform, &full_rect);
-            if let Some(mask) = layer_set.get(mask_index) {
-                self.render_layer(
-                    animation,
-                    layer_set,
-                    mask,
-                    parent_transform,
-                    alpha,
-                    frame,
-                    scene,
-                );
-            }
-            scene.push_layer(mode, 1.0, parent_transform, &full_rect);
-        }
-        let alpha = alpha * layer.opacity.evaluate(frame) / 100.0;
-        for mask in &layer.masks {
-            let alpha = mask.opacity.evaluate(frame) / 100.0;
-            mask.geometry.evaluate(frame, &mut self.mask_elements);
-            scene.push_layer(
-                Mix::Clip,
-                alpha as f32,
-                transform,
-                &self.mask_elements.as_slice(),
-            );
-            self.mask_elements.clear();
-        }
-        match &layer.content {
-            Content::None => {}
-            Content::Instance {
-                name,
-                time_remap: _,
-            } => {
-                // TODO: Use time_remap
-                // let frame = time_remap
-                //     .as_ref()
-                //     .map(|tm| tm.evaluate(frame))
-                //     .unwrap_or(frame);
-                if let Some(asset_layers) = animation.assets.get(name) {
-                    let frame = frame / layer.stretch;
-                    let frame_delta = -layer.start_frame / layer.stretch;
-                    for asset_layer in asset_layers.iter().rev() {
-                        if asset_layer.is_mask {
-                            continue;
-                        }
-                        self.render_layer(
-                            animation,
-                            asset_layers,
-                            asset_layer,
-                            transform,
-                            alpha,
-                            frame + frame_delta,
-                            scene,
-                        );
+        tracker: &mut DamageTracker,
+    ) -> Option<Rect> {
+        let transform = transform * self.painter.render_options().transform(animation);
+        let clip = Rect::new(0.0, 0.0, animation.width as _, animation.height as _);
+        let clip = (transform * animation.base_transform).transform_rect_bbox(clip);
+        // Without a previous frame with the same layers, everything is
+        // damaged.
+        let mut damage = if tracker.layers.len() != animation.layers.len() {
+            tracker.layers = vec![LayerState::default(); animation.layers.len()];
+            Some(clip)
+        } else {
+            None
+        };
+        let mut canvas = SceneCanvas(std::mem::take(scene));
+        let mut scratch = SceneCanvas(std::mem::take(&mut tracker.scratch));
+        scratch.0.reset();
+        let layers = &mut tracker.layers;
+        self.painter.paint_layers(
+            animation,
+            frame,
+            transform,
+            alpha,
+            &mut canvas,
+            &mut scratch,
+            |canvas, scratch, index, bounds| {
+                canvas.0.append(&scratch.0, None);
+                let state = LayerState {
+                    fingerprint: fingerprint(&scratch.0),
+                    bounds: bounds.map(|bounds| bounds.intersect(clip)),
+                };
+                scratch.0.reset();
+                let previous = &mut layers[index];
+                if state.fingerprint != previous.fingerprint {
+                    for bounds in [previous.bounds, state.bounds].into_iter().flatten() {
+                        damage = Some(damage.map_or(bounds, |damage| damage.union(bounds)));
                     }
                 }
-            }
-            Content::Shape(shapes) => {
-                self.render_shapes(shapes, transform, alpha, frame);
-                self.batch.render(scene);
-                self.batch.clear();
-            }
-        }
-        for _ in 0..layer.masks.len() + (layer.mask_layer.is_some() as usize * 2) {
-            scene.pop_layer();
-        }
+                *previous = state;
+            },
+        );
+        *scene = canvas.0;
+        tracker.scratch = scratch.0;
+        damage
     }
 
-    fn render_shapes(&mut self, shapes: &[Shape], transform: Affine, alpha: f64, frame: f64) {
-        // Keep track of our local top of the geometry stack. Any subsequent
-        // draws are bounded by this.
-        let geometry_start = self.batch.geometries.len();
-        // Also keep track of top of draw stack for repeater evaluation.
-        let draw_start = self.batch.draws.len();
-        // Top to bottom, collect geometries and draws.
-        for shape in shapes {
-            match shape {
-                Shape::Group(shapes, group_transform) => {
-                    let (group_transform, group_alpha) =
-                        if let Some(GroupTransform { transform, opacity }) = group_transform {
-                            (
-                                transform.evaluate(frame).into_owned(),
-                                opacity.evaluate(frame) / 100.0,
-                            )
-                        } else {
-                            (Affine::IDENTITY, 1.0)
-                        };
-                    self.render_shapes(
-                        shapes,
-                        transform * group_transform,
-                        alpha * group_alpha,
-                        frame,
-                    );
-                }
-                Shape::Geometry(geometry) => {
-                    self.batch.push_geometry(geometry, transform, frame);
-                }
-                Shape::Draw(draw) => {
-                    self.batch.push_draw(draw, alpha, geometry_start, frame);
-                }
-                Shape::Repeater(repeater) => {
-                    let repeater = repeater.evaluate(frame);
-                    self.batch
-                        .repeat(repeater.as_ref(), geometry_start, draw_start);
-                }
-            }
-        }
+    /// Renders the animation at a given frame to a new scene, using a second
+    /// animation as a matte of the given mode.
+    ///
+    /// Both animations are evaluated at the same frame and with the same
+    /// transform, and a matte whose frame range ends earlier holds its last
+    /// frame. This is useful for transitions where a wipe animation reveals
+    /// arbitrary content.
+    pub fn render_with_matte(
+        &mut self,
+        content: &Composition,
+        matte: &Composition,
+        mode: Matte,
+        frame: f64,
+        transform: Affine,
+        alpha: f64,
+    ) -> vello::Scene {
+        let mut scene = vello::Scene::new();
+        self.append_with_matte(content, matte, mode, frame, transform, alpha, &mut scene);
+        scene
     }
 
-    /// Computes the transform for a single layer. This currently chases the
-    /// full transform chain each time. If it becomes a bottleneck, we can
-    /// implement caching.
-    fn compute_transform(
-        &self,
-        layer_set: &[Layer],
-        layer: &Layer,
-        global_transform: Affine,
+    /// Renders and appends the animation at a given frame to the provided
+    /// scene, using a second animation as a matte of the given mode.
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_with_matte(
+        &mut self,
+        content: &Composition,
+        matte: &Composition,
+        mode: Matte,
         frame: f64,
-    ) -> Affine {
-        let mut transform = layer.transform.evaluate(frame).into_owned();
-        let mut parent_index = layer.parent;
-        let mut count = 0usize;
-        while let Some(index) = parent_index {
-            // We don't check for cycles at import time, so this heuristic
-            // prevents infinite loops.
-            if count >= layer_set.len() {
-                break;
-            }
-            if let Some(parent) = layer_set.get(index) {
-                parent_index = parent.parent;
-                transform = parent.transform.evaluate(frame).into_owned() * transform;
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        global_transform * transform
+        transform: Affine,
+        alpha: f64,
+        scene: &mut vello::Scene,
+    ) {
+        let mut canvas = SceneCanvas(std::mem::take(scene));
+        self.painter
+            .paint_with_matte(content, matte, mode, frame, transform, alpha, &mut canvas);
+        *scene = canvas.0;
     }
-}
 
-#[derive(Clone, Debug)]
-struct DrawData {
-    stroke: Option<fixed::Stroke>,
-    brush: fixed::Brush,
-    alpha: f64,
-    /// Range into `ShapeBatch::geometries`
-    geometry: Range<usize>,
-}
+    /// Evaluates the animation at a given frame without encoding a scene and
+    /// returns a checksum of the result.
+    ///
+    /// This paints onto a [`Digest`] with the settings of this renderer, as
+    /// [`Painter::evaluate_only`] does.
+    #[deprecated(note = "use `Painter::<Digest>::evaluate_only`, which keeps its scratch buffers")]
+    pub fn evaluate_only(&mut self, animation: &Composition, frame: f64) -> u64 {
+        self.painter
+            .for_canvas::<Digest>()
+            .evaluate_only(animation, frame)
+    }
 
-impl DrawData {
-    fn new(draw: &Draw, alpha: f64, geometry: Range<usize>, frame: f64) -> Self {
-        Self {
-            stroke: draw
-                .stroke
-                .as_ref()
-                .map(|stroke| stroke.evaluate(frame).into_owned()),
-            brush: draw.brush.evaluate(1.0, frame).into_owned(),
-            alpha: alpha * draw.opacity.evaluate(frame) / 100.0,
-            geometry,
-        }
+    /// Evaluates the animation at a given frame and returns a digest of the
+    /// result that is stable across platforms, as
+    /// [`Painter::evaluate_digest`] does.
+    #[deprecated(
+        note = "use `Painter::<Digest>::evaluate_digest`, which keeps its scratch buffers"
+    )]
+    pub fn evaluate_digest(
+        &mut self,
+        animation: &Composition,
+        frame: f64,
+        quantum: Option<f64>,
+    ) -> u64 {
+        self.painter
+            .for_canvas::<Digest>()
+            .evaluate_digest(animation, frame, quantum)
     }
-}
 
-#[derive(Clone, Debug)]
-struct GeometryData {
-    /// Range into `ShapeBatch::elements`
-    elements: Range<usize>,
-    transform: Affine,
+    /// Returns the number of layers, blend modes, nesting depth and draws
+    /// that rendering the animation at a given frame encodes.
+    ///
+    /// The frame is encoded as [`render`](Self::render) would, without
+    /// being drawn, so embedders can decide whether to render through an
+    /// offscreen surface or at a lower fidelity before rendering it.
+    pub fn frame_complexity(&mut self, animation: &Composition, frame: f64) -> FrameComplexity {
+        let mut scene = std::mem::take(&mut self.complexity_scene);
+        scene.reset();
+        self.append(animation, frame, Affine::IDENTITY, 1.0, &mut scene);
+        let complexity = FrameComplexity::new(&scene);
+        self.complexity_scene = scene;
+        complexity
+    }
 }
 
+/// Canvas that encodes what a painter draws into a vello scene.
 #[derive(Default)]
-struct Batch {
-    elements: Vec<PathEl>,
-    geometries: Vec<GeometryData>,
-    draws: Vec<DrawData>,
-    repeat_geometries: Vec<GeometryData>,
-    repeat_draws: Vec<DrawData>,
-    /// Length of geometries at time of most recent draw. This is
-    /// used to prevent merging into already used geometries.
-    drawn_geometry: usize,
+struct SceneCanvas(vello::Scene);
+
+impl Canvas for SceneCanvas {
+    fn fragment(&self) -> Self {
+        Self::default()
+    }
+
+    fn push_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        self.0.push_layer(blend, alpha, transform, clip);
+    }
+
+    fn pop_layer(&mut self) {
+        self.0.pop_layer();
+    }
+
+    fn fill<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.0.fill(style, transform, brush, brush_transform, shape);
+    }
+
+    fn stroke<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.0
+            .stroke(style, transform, brush, brush_transform, shape);
+    }
+
+    fn append(&mut self, fragment: &Self, transform: Option<Affine>) {
+        self.0.append(&fragment.0, transform);
+    }
+
+    fn draw_text(&mut self, font: &Font, size: f32, transform: Affine, color: Color, text: &str) {
+        use vello::skrifa::instance::{LocationRef, Size};
+        use vello::skrifa::raw::FileRef;
+        use vello::skrifa::MetadataProvider as _;
+
+        let font_ref = match FileRef::new(font.data.as_ref()) {
+            Ok(FileRef::Font(font_ref)) => font_ref,
+            Ok(FileRef::Collection(collection)) => match collection.get(font.index) {
+                Ok(font_ref) => font_ref,
+                Err(_) => return,
+            },
+            Err(_) => return,
+        };
+        let charmap = font_ref.charmap();
+        let metrics = font_ref.glyph_metrics(Size::new(size), LocationRef::default());
+        let mut x = 0.0;
+        self.0
+            .draw_glyphs(font)
+            .font_size(size)
+            .transform(transform)
+            .brush(color)
+            .draw(
+                Fill::NonZero,
+                text.chars().map(|ch| {
+                    let id = charmap.map(ch).unwrap_or_default();
+                    let glyph = Glyph {
+                        id: id.to_u16() as u32,
+                        x,
+                        y: 0.0,
+                    };
+                    x += metrics.advance_width(id).unwrap_or_default();
+                    glyph
+                }),
+            );
+    }
 }
 
-impl Batch {
-    fn push_geometry(&mut self, geometry: &Geometry, transform: Affine, frame: f64) {
-        // Merge with the previous geometry if possible. There are two
-        // conditions:
-        // 1. The previous geometry has not yet been referenced by a draw
-        // 2. The geometries have the same transform
-        if self.drawn_geometry < self.geometries.len()
-            && self.geometries.last().map(|last| last.transform) == Some(transform)
-        {
-            geometry.evaluate(frame, &mut self.elements);
-            self.geometries.last_mut().unwrap().elements.end = self.elements.len();
-        } else {
-            let start = self.elements.len();
-            geometry.evaluate(frame, &mut self.elements);
-            let end = self.elements.len();
-            self.geometries.push(GeometryData {
-                elements: start..end,
-                transform,
-            });
+#[cfg(test)]
+mod tests {
+    use super::Renderer;
+    use crate::model::{animated, Animated, Brush, Content, Draw, Geometry, Layer, Shape};
+    use crate::model::{fixed, LayerStyle, Time};
+    use crate::model::{Mask, MaskMode, Matte, Unsupported, Value};
+    use crate::{Composition, DamageTracker, Digest, FrameComplexity, Painter, RenderOptions};
+    use vello::kurbo::{Affine, Point, Rect, Shape as _, Size};
+    use vello::peniko::Mix;
+
+    const FRAMES: f64 = 100_000.0;
+
+    fn layer(content: Content, transform: Affine) -> Layer {
+        Layer {
+            transform: crate::model::Transform::Fixed(transform),
+            opacity: Value::Fixed(100.0),
+            frames: 0.0..FRAMES,
+            stretch: 1.0,
+            content,
+            ..Default::default()
         }
     }
 
-    fn push_draw(&mut self, draw: &Draw, alpha: f64, geometry_start: usize, frame: f64) {
-        self.draws.push(DrawData::new(
-            draw,
-            alpha,
-            geometry_start..self.geometries.len(),
+    /// Returns a red 10 by 10 rectangle centered at a position.
+    fn square(position: Value<Point>) -> Vec<Shape> {
+        vec![
+            Shape::Geometry(Geometry::Rect(animated::Rect {
+                is_ccw: false,
+                position,
+                size: Value::Fixed(Size::new(10.0, 10.0)),
+                corner_radius: Value::Fixed(0.0),
+            })),
+            Shape::Draw(Draw {
+                stroke: None,
+                brush: Brush::Fixed(fixed::Color::rgb8(255, 0, 0).into()),
+                opacity: Value::Fixed(100.0),
+            }),
+        ]
+    }
+
+    /// Returns a linear keyframe time.
+    fn time(frame: f64) -> Time {
+        Time {
             frame,
-        ));
-        self.drawn_geometry = self.geometries.len();
-    }
-
-    fn repeat(&mut self, repeater: &fixed::Repeater, geometry_start: usize, draw_start: usize) {
-        // First move the relevant ranges of geometries and draws into side
-        // buffers
-        self.repeat_geometries
-            .extend(self.geometries.drain(geometry_start..));
-        self.repeat_draws.extend(self.draws.drain(draw_start..));
-        // Next, repeat the geometries and apply the offset transform
-        for geometry in self.repeat_geometries.iter() {
-            for i in 0..repeater.copies {
-                let transform = repeater.transform(i);
-                let mut geometry = geometry.clone();
-                geometry.transform *= transform;
-                self.geometries.push(geometry);
-            }
+            in_tangent: None,
+            out_tangent: None,
+            axis_in_tangents: [None; 2],
+            axis_out_tangents: [None; 2],
+            hold: false,
         }
-        // Finally, repeat the draws, taking into account opacity and the
-        // modified newly repeated geometry ranges
-        let start_alpha = repeater.start_opacity / 100.0;
-        let end_alpha = repeater.end_opacity / 100.0;
-        let delta_alpha = if repeater.copies > 1 {
-            // See note in Skottie: AE does not cover the full opacity range
-            (end_alpha - start_alpha) / repeater.copies as f64
-        } else {
-            0.0
+    }
+
+    /// Returns the number of paths drawn by a composition with a single
+    /// rectangle layer, excluding the begin and end of the composition clip.
+    fn count_paths(frames: std::ops::Range<f64>, frame: f64) -> u32 {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let composition = Composition {
+            frames: frames.clone(),
+            frame_rate: 60.0,
+            width: 10,
+            height: 10,
+            layers: vec![Layer {
+                frames,
+                ..layer(Content::Shape(rect), Affine::IDENTITY)
+            }],
+            ..Default::default()
         };
-        for i in 0..repeater.copies {
-            let alpha = start_alpha + delta_alpha * i as f64;
-            if alpha <= 0.0 {
-                continue;
-            }
-            for mut draw in self.repeat_draws.iter().cloned() {
-                draw.alpha *= alpha;
-                let count = draw.geometry.end - draw.geometry.start;
-                draw.geometry.start =
-                    geometry_start + (draw.geometry.start - geometry_start) * repeater.copies;
-                draw.geometry.end = draw.geometry.start + count * repeater.copies;
-                self.draws.push(draw);
-            }
+        let scene = Renderer::new().render(&composition, frame, Affine::IDENTITY, 1.0);
+        scene.encoding().n_paths - 2
+    }
+
+    #[test]
+    fn test_single_frame() {
+        // The nominal end frame displays the last frame.
+        assert_eq!(count_paths(0.0..1.0, 0.0), 1);
+        assert_eq!(count_paths(0.0..1.0, 1.0), 1);
+        assert_eq!(count_paths(0.0..60.0, 60.0), 1);
+        // Zero duration compositions display their only frame.
+        assert_eq!(count_paths(0.0..0.0, 0.0), 1);
+        assert_eq!(count_paths(0.0..0.0, 1.0), 1);
+        assert_eq!(count_paths(10.0..10.0, 0.0), 1);
+    }
+
+    #[test]
+    fn test_bounded_memory() {
+        let dot = square(Value::Animated(Box::new(Animated {
+            times: vec![time(0.0), time(FRAMES)],
+            values: vec![Point::new(0.0, 0.0), Point::new(100.0, 100.0)],
+            loops: Default::default(),
+        })));
+        let instance = || Content::Instance {
+            name: "dot".into(),
+            source: "dot".into(),
+            time_remap: None,
+            overrides: Default::default(),
+        };
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            assets: [(
+                "dot".to_string(),
+                vec![layer(Content::Shape(dot), Affine::IDENTITY)],
+            )]
+            .into_iter()
+            .collect(),
+            layers: vec![
+                layer(instance(), Affine::IDENTITY),
+                layer(instance(), Affine::translate((20.0, 0.0))),
+            ],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        renderer.set_instance_caching(true);
+        let mut scene = vello::Scene::new();
+        let mut encoded_len = None;
+        for frame in 0..FRAMES as usize {
+            scene.reset();
+            renderer.append(
+                &composition,
+                frame as f64,
+                Affine::IDENTITY,
+                1.0,
+                &mut scene,
+            );
+            let len = scene.encoding().path_data.len();
+            assert_eq!(*encoded_len.get_or_insert(len), len);
         }
-        // Clear the side buffers
-        self.repeat_geometries.clear();
-        self.repeat_draws.clear();
-        // Prevent merging until new geometries are pushed
-        self.drawn_geometry = self.geometries.len();
-    }
-
-    fn render(&self, scene: &mut vello::Scene) {
-        // Process all draws in reverse
-        for draw in self.draws.iter().rev() {
-            // Some nastiness to avoid cloning the brush if unnecessary
-            let modified_brush = if draw.alpha != 1.0 {
-                Some(fixed::brush_with_alpha(&draw.brush, draw.alpha))
-            } else {
-                None
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_evaluate_only() {
+        // A semi-transparent group with two fills, which isolation changes.
+        let mut shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+        shapes.push(shapes[1].clone());
+        let group = Shape::Group(
+            shapes,
+            Some(crate::model::GroupTransform {
+                transform: crate::model::Transform::Fixed(Affine::IDENTITY),
+                opacity: Value::Fixed(50.0),
+            }),
+        );
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 10,
+            height: 10,
+            layers: vec![layer(Content::Shape(vec![group]), Affine::IDENTITY)],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        let mut painter = Painter::<Digest>::new();
+        let plain = renderer.evaluate_only(&composition, 0.0);
+        assert_eq!(plain, painter.evaluate_only(&composition, 0.0));
+        // The renderer evaluates with its own settings.
+        renderer.set_group_isolation(true);
+        painter.set_group_isolation(true);
+        let isolated = renderer.evaluate_only(&composition, 0.0);
+        assert_ne!(isolated, plain);
+        assert_eq!(isolated, painter.evaluate_only(&composition, 0.0));
+        let quantum = Some(1.0 / 1024.0);
+        assert_eq!(
+            renderer.evaluate_digest(&composition, 0.0, quantum),
+            painter.evaluate_digest(&composition, 0.0, quantum)
+        );
+    }
+
+    #[test]
+    fn test_instance_caching() {
+        let dot = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let instance = || Content::Instance {
+            name: "dot".into(),
+            source: "dot".into(),
+            time_remap: None,
+            overrides: Default::default(),
+        };
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            assets: [(
+                "dot".to_string(),
+                vec![layer(Content::Shape(dot), Affine::IDENTITY)],
+            )]
+            .into_iter()
+            .collect(),
+            layers: vec![
+                layer(instance(), Affine::IDENTITY),
+                layer(instance(), Affine::translate((20.0, 0.0))),
+                layer(instance(), Affine::translate((0.0, 40.0))),
+            ],
+            ..Default::default()
+        };
+        let render = |caching| {
+            let mut renderer = Renderer::new();
+            renderer.set_instance_caching(caching);
+            renderer.render(&composition, 0.0, Affine::IDENTITY, 1.0)
+        };
+        let (cached, uncached) = (render(true), render(false));
+        let (cached, uncached) = (cached.encoding(), uncached.encoding());
+        // Caching skips evaluation but encodes the same paths, draws and
+        // transforms. Appended fragments only restate their styles.
+        assert_eq!(cached.n_paths, uncached.n_paths);
+        assert_eq!(cached.n_path_segments, uncached.n_path_segments);
+        assert_eq!(cached.path_data, uncached.path_data);
+        assert!(cached.draw_tags == uncached.draw_tags);
+        assert_eq!(cached.draw_data, uncached.draw_data);
+        let (mut cached, mut uncached) = (cached.transforms.clone(), uncached.transforms.clone());
+        cached.dedup();
+        uncached.dedup();
+        assert_eq!(cached, uncached);
+    }
+
+    #[test]
+    fn test_damage() {
+        let square = |position| layer(Content::Shape(square(position)), Affine::IDENTITY);
+        let composition = Composition {
+            frames: 0.0..10.0,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![
+                square(Value::Fixed(Point::new(20.0, 20.0))),
+                square(Value::Animated(Box::new(Animated {
+                    times: vec![time(0.0), time(10.0)],
+                    values: vec![Point::new(50.0, 50.0), Point::new(60.0, 50.0)],
+                    loops: Default::default(),
+                }))),
+            ],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        let mut tracker = DamageTracker::new();
+        let mut damage = |frame| {
+            let mut scene = vello::Scene::new();
+            renderer.append_with_damage(
+                &composition,
+                frame,
+                Affine::IDENTITY,
+                1.0,
+                &mut scene,
+                &mut tracker,
+            )
+        };
+        // The first frame is entirely damaged.
+        assert_eq!(damage(0.0), Some(Rect::new(0.0, 0.0, 100.0, 100.0)));
+        assert_eq!(damage(0.0), None);
+        // Only the old and new positions of the moving square are damaged.
+        assert_eq!(damage(1.0), Some(Rect::new(45.0, 45.0, 56.0, 55.0)));
+    }
+
+    #[test]
+    fn test_instance_damage() {
+        let moving = square(Value::Animated(Box::new(Animated {
+            times: vec![time(0.0), time(10.0)],
+            values: vec![Point::new(20.0, 20.0), Point::new(30.0, 20.0)],
+            loops: Default::default(),
+        })));
+        let instance = || Content::Instance {
+            name: "square".into(),
+            source: "square".into(),
+            time_remap: None,
+            overrides: Default::default(),
+        };
+        let composition = Composition {
+            frames: 0.0..10.0,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            assets: [(
+                "square".to_string(),
+                vec![layer(Content::Shape(moving), Affine::IDENTITY)],
+            )]
+            .into_iter()
+            .collect(),
+            layers: vec![
+                layer(instance(), Affine::IDENTITY),
+                layer(instance(), Affine::translate((0.0, 50.0))),
+            ],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        renderer.set_instance_caching(true);
+        let mut tracker = DamageTracker::new();
+        let mut damage = |frame| {
+            let mut scene = vello::Scene::new();
+            renderer.append_with_damage(
+                &composition,
+                frame,
+                Affine::IDENTITY,
+                1.0,
+                &mut scene,
+                &mut tracker,
+            )
+        };
+        damage(0.0);
+        // Both instances of the moving asset are damaged, even though they
+        // share an asset that could be cached.
+        assert_eq!(damage(1.0), Some(Rect::new(15.0, 15.0, 26.0, 75.0)));
+    }
+
+    #[test]
+    fn test_mirror() {
+        let composition = Composition {
+            width: 100,
+            height: 50,
+            ..Default::default()
+        };
+        let options = RenderOptions {
+            mirror_x: true,
+            ..Default::default()
+        };
+        let transform = options.transform(&composition);
+        assert_eq!(transform * Point::new(10.0, 5.0), Point::new(90.0, 5.0));
+        // Mirroring happens before a base transform folded into the layers.
+        let composition = Composition {
+            base_transform: Affine::scale(2.0),
+            ..composition
+        };
+        let transform = options.transform(&composition);
+        assert_eq!(transform * Point::new(20.0, 10.0), Point::new(180.0, 10.0));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![Layer {
+                name: "Title".into(),
+                unsupported: vec![Unsupported::Text],
+                ..layer(Content::None, Affine::IDENTITY)
+            }],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        // Returns the number of paths and glyphs drawn.
+        let count = |renderer: &mut Renderer| {
+            let scene = renderer.render(&composition, 0.0, Affine::IDENTITY, 1.0);
+            let encoding = scene.encoding();
+            (encoding.n_paths - 2, encoding.resources.glyphs.len())
+        };
+        assert_eq!(count(&mut renderer), (0, 0));
+        // A filled and outlined rectangle.
+        renderer.set_placeholders(true);
+        assert_eq!(count(&mut renderer), (2, 0));
+        // The label is clipped to the rectangle.
+        let font = include_bytes!("../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = vello::peniko::Font::new(font.to_vec().into(), 0);
+        renderer.set_placeholder_font(Some(font));
+        assert_eq!(count(&mut renderer), (4, "Title: text".len()));
+    }
+
+    #[test]
+    fn test_motion_blur() {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 10,
+            height: 10,
+            layers: vec![Layer {
+                motion_blur: true,
+                ..layer(Content::Shape(rect), Affine::IDENTITY)
+            }],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        let mut count_paths = |samples| {
+            renderer.set_render_options(RenderOptions {
+                motion_blur_samples: samples,
+                ..Default::default()
+            });
+            let scene = renderer.render(&composition, 1.0, Affine::IDENTITY, 1.0);
+            scene.encoding().n_paths - 2
+        };
+        assert_eq!(count_paths(0), 1);
+        assert_eq!(count_paths(1), 1);
+        // Each sample is drawn into its own layer, within a layer that
+        // isolates them.
+        assert_eq!(count_paths(4), 2 + 4 * 3);
+    }
+
+    #[test]
+    fn test_blend_isolation() {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let count_paths = |preserve_transparency, knockout, blend_mode| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers: vec![Layer {
+                    preserve_transparency,
+                    knockout,
+                    blend_mode,
+                    ..layer(Content::Shape(rect.clone()), Affine::IDENTITY)
+                }],
+                ..Default::default()
             };
-            let brush = modified_brush.as_ref().unwrap_or(&draw.brush);
-            for geometry in self.geometries[draw.geometry.clone()].iter() {
-                let path = &self.elements[geometry.elements.clone()];
-                let transform = geometry.transform;
-                if let Some(stroke) = draw.stroke.as_ref() {
-                    scene.stroke(stroke, transform, brush, None, &path);
-                } else {
-                    scene.fill(Fill::NonZero, transform, brush, None, &path);
-                }
+            let scene = Renderer::new().render(&composition, 1.0, Affine::IDENTITY, 1.0);
+            scene.encoding().n_paths - 2
+        };
+        assert_eq!(count_paths(false, false, None), 1);
+        // The layer is drawn within a layer composited onto the backdrop.
+        assert_eq!(count_paths(true, false, None), 3);
+        // The layer is drawn once into a layer that erases the backdrop,
+        // then once more normally.
+        assert_eq!(count_paths(false, true, None), 4);
+        // Blend modes also composite through a layer, which is shared with
+        // preserved transparency.
+        let multiply = Some(Mix::Multiply.into());
+        assert_eq!(count_paths(false, false, multiply), 3);
+        assert_eq!(count_paths(true, false, multiply), 3);
+    }
+
+    #[test]
+    fn test_frame_complexity() {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let mask = Mask {
+            mode: MaskMode::Add,
+            inverted: false,
+            expansion: Value::Fixed(0.0),
+            feather: Value::Fixed(0.0),
+            geometry: Geometry::Fixed(Rect::new(0.0, 0.0, 5.0, 5.0).path_elements(0.1).collect()),
+            opacity: Value::Fixed(100.0),
+        };
+        let complexity = |blend_mode, masks| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers: vec![
+                    layer(Content::Shape(rect.clone()), Affine::IDENTITY),
+                    Layer {
+                        blend_mode,
+                        masks,
+                        ..layer(Content::Shape(rect.clone()), Affine::IDENTITY)
+                    },
+                ],
+                ..Default::default()
+            };
+            Renderer::new().frame_complexity(&composition, 1.0)
+        };
+        let simple = complexity(None, vec![]);
+        assert_eq!(
+            simple,
+            FrameComplexity {
+                draws: 2,
+                ..Default::default()
             }
-        }
+        );
+        let complex = complexity(Some(Mix::Multiply.into()), vec![mask]);
+        assert_eq!(
+            complex,
+            FrameComplexity {
+                layers: 2,
+                clip_layers: 1,
+                blend_layers: 1,
+                max_depth: 2,
+                draws: 2,
+            }
+        );
     }
 
-    fn clear(&mut self) {
-        self.elements.clear();
-        self.geometries.clear();
-        self.draws.clear();
-        self.repeat_geometries.clear();
-        self.repeat_draws.clear();
-        self.drawn_geometry = 0;
+    #[test]
+    fn test_masks() {
+        let complexity = |mask: Mask| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers: vec![Layer {
+                    masks: vec![mask],
+                    ..layer(
+                        Content::Shape(square(Value::Fixed(Point::new(5.0, 5.0)))),
+                        Affine::IDENTITY,
+                    )
+                }],
+                ..Default::default()
+            };
+            Renderer::new().frame_complexity(&composition, 1.0)
+        };
+        let mask = Mask {
+            mode: MaskMode::Add,
+            inverted: false,
+            expansion: Value::Fixed(0.0),
+            feather: Value::Fixed(0.0),
+            geometry: Geometry::Fixed(Rect::new(0.0, 0.0, 5.0, 5.0).path_elements(0.1).collect()),
+            opacity: Value::Fixed(100.0),
+        };
+        // A single mask clips the content.
+        let clip = complexity(mask.clone());
+        assert_eq!((clip.layers, clip.clip_layers, clip.draws), (1, 1, 1));
+        // An inverted mask is filled around its geometry in a layer that
+        // the content is then drawn into.
+        let inverted = complexity(Mask {
+            inverted: true,
+            ..mask
+        });
+        assert_eq!(
+            (inverted.layers, inverted.blend_layers, inverted.draws),
+            (2, 1, 2)
+        );
+    }
+
+    #[test]
+    fn test_hidden() {
+        let [geometry, draw] = <[Shape; 2]>::try_from(square(Value::Fixed(Point::new(5.0, 5.0))))
+            .ok()
+            .unwrap();
+        let complexity = |layers| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers,
+                ..Default::default()
+            };
+            Renderer::new().frame_complexity(&composition, 1.0)
+        };
+        let shapes = vec![geometry.clone(), draw.clone()];
+        let visible = complexity(vec![layer(Content::Shape(shapes), Affine::IDENTITY)]);
+        assert_eq!(visible.draws, 1);
+        let hidden = Layer {
+            hidden: true,
+            ..layer(
+                Content::Shape(vec![geometry.clone(), draw.clone()]),
+                Affine::IDENTITY,
+            )
+        };
+        assert_eq!(complexity(vec![hidden]).draws, 0);
+        let shapes = vec![geometry.clone(), Shape::Hidden(Box::new(draw.clone()))];
+        let hidden_draw = complexity(vec![layer(Content::Shape(shapes), Affine::IDENTITY)]);
+        assert_eq!(hidden_draw.draws, 0);
+        // Hidden mattes still matte, as in After Effects, where hiding a
+        // track matte only hides its own content.
+        let matte = |hidden| Layer {
+            is_mask: true,
+            hidden,
+            ..layer(
+                Content::Shape(vec![geometry.clone(), draw.clone()]),
+                Affine::IDENTITY,
+            )
+        };
+        let matted = Layer {
+            mask_layer: Some((Matte::Alpha, 0)),
+            ..layer(
+                Content::Shape(vec![geometry.clone(), draw.clone()]),
+                Affine::IDENTITY,
+            )
+        };
+        let hidden_matte = complexity(vec![matte(true), matted.clone()]);
+        assert_eq!(hidden_matte, complexity(vec![matte(false), matted]));
+        assert_eq!(hidden_matte.draws, 2);
+        assert_eq!(hidden_matte.blend_layers, 1);
+    }
+
+    #[test]
+    fn test_instance_blend_isolation() {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let count_paths = |blend_mode| {
+            let asset = Layer {
+                blend_mode,
+                ..layer(Content::Shape(rect.clone()), Affine::IDENTITY)
+            };
+            let instance = Layer {
+                content: Content::Instance {
+                    name: "asset".into(),
+                    source: "asset".into(),
+                    time_remap: None,
+                    overrides: Default::default(),
+                },
+                ..layer(Content::None, Affine::IDENTITY)
+            };
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers: vec![instance],
+                assets: [("asset".to_string(), vec![asset])].into_iter().collect(),
+                ..Default::default()
+            };
+            let scene = Renderer::new().render(&composition, 1.0, Affine::IDENTITY, 1.0);
+            scene.encoding().n_paths - 2
+        };
+        assert_eq!(count_paths(None), 1);
+        // The precomposition is isolated around the layer that blends.
+        assert_eq!(count_paths(Some(Mix::Screen.into())), 5);
+    }
+
+    #[test]
+    fn test_layer_styles() {
+        let rect = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let shadow = |radius| animated::DropShadow {
+            color: Value::Fixed(fixed::Color::BLACK),
+            opacity: Value::Fixed(75.0),
+            direction: Value::Fixed(135.0),
+            distance: Value::Fixed(2.0),
+            radius: Value::Fixed(radius),
+            shadow_only: false,
+        };
+        let count_paths = |styles| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                width: 10,
+                height: 10,
+                layers: vec![Layer {
+                    styles,
+                    ..layer(Content::Shape(rect.clone()), Affine::IDENTITY)
+                }],
+                ..Default::default()
+            };
+            let scene = Renderer::new().render(&composition, 1.0, Affine::IDENTITY, 1.0);
+            scene.encoding().n_paths - 2
+        };
+        assert_eq!(count_paths(vec![]), 1);
+        let stroke = LayerStyle::Stroke(animated::StrokeStyle {
+            color: Value::Fixed(fixed::Color::WHITE),
+            size: Value::Fixed(2.0),
+        });
+        assert_eq!(count_paths(vec![stroke]), 2);
+        assert_eq!(count_paths(vec![LayerStyle::DropShadow(shadow(0.0))]), 2);
+        // Glows average offset copies of the content in a layer of their
+        // own.
+        let glow = LayerStyle::OuterGlow(animated::OuterGlow {
+            color: Value::Fixed(fixed::Color::WHITE),
+            opacity: Value::Fixed(75.0),
+            radius: Value::Fixed(5.0),
+        });
+        assert_eq!(count_paths(vec![glow]), 1 + 2 + 9 * 3);
+        // Inner shadows fill the isolated content, then erase an offset copy
+        // of it.
+        assert_eq!(
+            count_paths(vec![LayerStyle::InnerShadow(shadow(0.0))]),
+            1 + 2 + 2 + 1 + 2 + 1
+        );
+    }
+
+    #[test]
+    fn test_solid() {
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![layer(
+                Content::Solid {
+                    color: fixed::Color::rgb8(0, 0, 255),
+                    width: 20.0,
+                    height: 10.0,
+                },
+                Affine::translate((5.0, 5.0)),
+            )],
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new();
+        let scene = renderer.render(&composition, 0.0, Affine::IDENTITY, 1.0);
+        assert_eq!(scene.encoding().n_paths - 2, 1);
+        // The solid is damaged like any other draw.
+        let mut tracker = DamageTracker::new();
+        let mut scene = vello::Scene::new();
+        let damage = renderer.append_with_damage(
+            &composition,
+            0.0,
+            Affine::IDENTITY,
+            1.0,
+            &mut scene,
+            &mut tracker,
+        );
+        assert_eq!(damage, Some(Rect::new(0.0, 0.0, 100.0, 100.0)));
+        scene.reset();
+        renderer.append_with_damage(
+            &composition,
+            0.0,
+            Affine::IDENTITY,
+            1.0,
+            &mut scene,
+            &mut tracker,
+        );
+        assert_eq!(
+            tracker.layers[0].bounds,
+            Some(Rect::new(5.0, 5.0, 25.0, 15.0))
+        );
     }
 }
@@ -1,12 +0,0 @@
-// Copyright 2024 the Velato Authors
-// SPDX-License-Identifier: Apache-2.0 OR MIT
-
-use serde_repr::{Deserialize_repr, Serialize_repr};
-
-/// Type of a gradient
-#[repr(u8)]
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
-pub enum GradientType {
-    Linear = 1,
-    Radial = 2,
-}
@@ -1,14 +0,0 @@
-// Copyright 2024 the Velato Authors
-// SPDX-License-Identifier: Apache-2.0 OR MIT
-
-pub mod color_overlay_style;
-pub mod gradient_overlay_style;
-pub mod layer_style;
-pub mod outer_glow_style;
-pub mod satin_style;
-// todo pub mod bevel_embess_style;
-// todo pub mod inner_glow_style;
-// todo pub mod stroke_style;
-// todo pub mod drop_shadow_style;
-// todo pub mod inner_shadow_style;
-// todo pub mod layer_style;
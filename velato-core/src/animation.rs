@@ -0,0 +1,36 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Interpolation utilities shared with the renderer.
+//!
+//! These allow applications to animate their own values, such as the
+//! position of a widget, with the same interpolation that velato applies
+//! between keyframes, so that motion stays consistent with Lottie content.
+
+pub use crate::runtime::model::{Easing, EasingHandle, Tween};
+
+/// Interpolates between two values at the normalized time `t` using the
+/// given easing.
+///
+/// Times outside `0..=1` are clamped. This is exactly the interpolation
+/// used between keyframes: the time is mapped through the easing curve with
+/// [`Easing::evaluate`], and the value moves by the resulting progress.
+///
+/// ```
+/// use velato_core::animation::{interpolate, Easing, EasingHandle};
+/// use velato_core::kurbo::Point;
+///
+/// let a = Point::new(0.0, 0.0);
+/// let b = Point::new(100.0, 50.0);
+/// assert_eq!(interpolate(&a, &b, 0.5, Easing::LERP), Point::new(50.0, 25.0));
+///
+/// // Easing in starts slowly.
+/// let ease_in = Easing {
+///     o: EasingHandle { x: 0.42, y: 0.0 },
+///     i: EasingHandle { x: 1.0, y: 1.0 },
+/// };
+/// assert!(interpolate(&a, &b, 0.25, ease_in).x < 25.0);
+/// ```
+pub fn interpolate<T: Tween>(a: &T, b: &T, t: f64, easing: Easing) -> T {
+    a.tween(b, t.clamp(0.0, 1.0), &easing)
+}
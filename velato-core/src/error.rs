@@ -0,0 +1,20 @@
+// Copyright 2023 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use thiserror::Error;
+
+/// Triggered when is an issue parsing a lottie file.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Error parsing lottie: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No animation found in document")]
+    AnimationNotFound,
+    #[error("Invalid selector {0:?}: {1}")]
+    Selector(String, &'static str),
+    #[error("Animation {0:?} of the manifest is missing from the bundle")]
+    MissingAnimation(String),
+    #[error("dotLottie version {0:?} is not supported, the newest supported version is {1}")]
+    UnsupportedVersion(String, u32),
+}
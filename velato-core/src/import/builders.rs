@@ -0,0 +1,260 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::converters::{
+    conv_blend_mode, conv_effects, conv_feather, conv_frames, conv_mask_mode, conv_matte_mode,
+    conv_scalar, conv_shape_geometry, conv_stretch, conv_styles, conv_transform, conv_wiggles,
+};
+use super::defaults::{FLOAT_VALUE_ONE_HUNDRED, FLOAT_VALUE_ZERO};
+use crate::runtime::model::{animated, Layer, MaskMode, Matte, Value};
+use crate::schema::helpers::int_boolean::BoolInt;
+use crate::{runtime, schema};
+use std::sync::Arc;
+
+pub fn setup_precomp_layer(
+    source: &schema::layers::precomposition::PrecompositionLayer,
+    target: &mut Layer,
+    cameras: &Arc<animated::Cameras>,
+) -> (usize, Option<Matte>) {
+    target.name = source.properties.name.clone().unwrap_or_default();
+    target.class = source.properties.css_class.clone().unwrap_or_default();
+    target.parent = source.properties.parent_index;
+    let (transform, opacity) = conv_transform(
+        &source.properties.transform,
+        (source.properties.three_dimensional == Some(BoolInt::True)).then(|| cameras.clone()),
+    );
+    target.transform = transform;
+    target.opacity = opacity;
+    target.width = source.width;
+    target.height = source.height;
+    target.hidden = source.properties.hidden.unwrap_or(false);
+    target.is_mask = source
+        .properties
+        .matte_target
+        .as_ref()
+        .map_or(false, |td| *td == BoolInt::True);
+
+    let matte_mode = source.properties.matte_mode.as_ref().map(conv_matte_mode);
+
+    target.blend_mode = conv_blend_mode(
+        source
+            .properties
+            .blend_mode
+            .as_ref()
+            .unwrap_or(&crate::schema::constants::blend_mode::BlendMode::Normal),
+    );
+    if target.blend_mode == Some(peniko::Mix::Normal.into()) {
+        target.blend_mode = None;
+    }
+    target.stretch = conv_stretch(source.properties.time_stretch);
+    target.frames = conv_frames(source.properties.in_point, source.properties.out_point);
+    target.start_frame = source.properties.start_time;
+    target.effects = conv_effects(&source.properties, &mut target.unsupported);
+    target.styles = conv_styles(&source.properties, &mut target.unsupported);
+    target.motion_blur = source.properties.motion_blur.unwrap_or(false);
+    target.preserve_transparency = source.properties.preserve_transparency == Some(BoolInt::True);
+    target.knockout = source.properties.knockout == Some(BoolInt::True);
+    target.auto_orient = source.properties.rotate_to_match_anim_pos_path == Some(BoolInt::True);
+
+    for mask_source in source
+        .properties
+        .masks_properties
+        .as_ref()
+        .unwrap_or(&Vec::default())
+    {
+        if let Some(shape) = &mask_source.shape {
+            if let Some(geometry) = conv_shape_geometry(shape) {
+                let mode = mask_source
+                    .mode
+                    .as_ref()
+                    .map_or(MaskMode::default(), conv_mask_mode);
+                let opacity = conv_scalar(
+                    mask_source
+                        .opacity
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+                );
+                target.masks.push(runtime::model::Mask {
+                    mode,
+                    inverted: mask_source.inverted.unwrap_or(false),
+                    expansion: conv_scalar(
+                        mask_source.expand.as_ref().unwrap_or(&FLOAT_VALUE_ZERO),
+                    ),
+                    feather: mask_source
+                        .feather
+                        .as_ref()
+                        .map_or(Value::Fixed(0.0), conv_feather),
+                    geometry,
+                    opacity,
+                });
+            }
+        }
+    }
+
+    target.wiggles = conv_wiggles(
+        &source.properties.transform,
+        source.properties.index.unwrap_or(0) as u64,
+    );
+    (source.properties.index.unwrap_or(0), matte_mode)
+}
+
+pub fn setup_shape_layer(
+    source: &schema::layers::shape::ShapeLayer,
+    target: &mut Layer,
+    cameras: &Arc<animated::Cameras>,
+) -> (usize, Option<Matte>) {
+    target.name = source.properties.name.clone().unwrap_or_default();
+    target.class = source.properties.css_class.clone().unwrap_or_default();
+    target.parent = source.properties.parent_index;
+    let (transform, opacity) = conv_transform(
+        &source.properties.transform,
+        (source.properties.three_dimensional == Some(BoolInt::True)).then(|| cameras.clone()),
+    );
+    target.transform = transform;
+    target.opacity = opacity;
+    target.hidden = source.properties.hidden.unwrap_or(false);
+    target.is_mask = source
+        .properties
+        .matte_target
+        .as_ref()
+        .map_or(false, |td| *td == BoolInt::True);
+
+    let matte_mode = source.properties.matte_mode.as_ref().map(conv_matte_mode);
+
+    target.blend_mode = conv_blend_mode(
+        source
+            .properties
+            .blend_mode
+            .as_ref()
+            .unwrap_or(&crate::schema::constants::blend_mode::BlendMode::Normal),
+    );
+    if target.blend_mode == Some(peniko::Mix::Normal.into()) {
+        target.blend_mode = None;
+    }
+    target.stretch = conv_stretch(source.properties.time_stretch);
+    target.frames = conv_frames(source.properties.in_point, source.properties.out_point);
+    target.start_frame = source.properties.start_time;
+    target.effects = conv_effects(&source.properties, &mut target.unsupported);
+    target.styles = conv_styles(&source.properties, &mut target.unsupported);
+    target.motion_blur = source.properties.motion_blur.unwrap_or(false);
+    target.preserve_transparency = source.properties.preserve_transparency == Some(BoolInt::True);
+    target.knockout = source.properties.knockout == Some(BoolInt::True);
+    target.auto_orient = source.properties.rotate_to_match_anim_pos_path == Some(BoolInt::True);
+
+    for mask_source in source
+        .properties
+        .masks_properties
+        .as_ref()
+        .unwrap_or(&Vec::default())
+    {
+        if let Some(shape) = &mask_source.shape {
+            if let Some(geometry) = conv_shape_geometry(shape) {
+                let mode = mask_source
+                    .mode
+                    .as_ref()
+                    .map_or(MaskMode::default(), conv_mask_mode);
+                let opacity = conv_scalar(
+                    mask_source
+                        .opacity
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+                );
+                target.masks.push(runtime::model::Mask {
+                    mode,
+                    inverted: mask_source.inverted.unwrap_or(false),
+                    expansion: conv_scalar(
+                        mask_source.expand.as_ref().unwrap_or(&FLOAT_VALUE_ZERO),
+                    ),
+                    feather: mask_source
+                        .feather
+                        .as_ref()
+                        .map_or(Value::Fixed(0.0), conv_feather),
+                    geometry,
+                    opacity,
+                });
+            }
+        }
+    }
+
+    target.wiggles = conv_wiggles(
+        &source.properties.transform,
+        source.properties.index.unwrap_or(0) as u64,
+    );
+    (source.properties.index.unwrap_or(0), matte_mode)
+}
+
+pub fn setup_layer_base(
+    source: &schema::layers::visual::VisualLayer,
+    target: &mut Layer,
+    cameras: &Arc<animated::Cameras>,
+) -> (usize, Option<Matte>) {
+    target.name = source.name.clone().unwrap_or_default();
+    target.class = source.css_class.clone().unwrap_or_default();
+    target.parent = source.parent_index;
+    let (transform, opacity) = conv_transform(
+        &source.transform,
+        (source.three_dimensional == Some(BoolInt::True)).then(|| cameras.clone()),
+    );
+    target.transform = transform;
+    target.opacity = opacity;
+    target.hidden = source.hidden.unwrap_or(false);
+    target.is_mask = source
+        .matte_target
+        .as_ref()
+        .map_or(false, |td| *td == BoolInt::True);
+
+    let matte_mode = source.matte_mode.as_ref().map(conv_matte_mode);
+
+    target.blend_mode = conv_blend_mode(
+        source
+            .blend_mode
+            .as_ref()
+            .unwrap_or(&crate::schema::constants::blend_mode::BlendMode::Normal),
+    );
+    // TODO: Why do we do this next part?
+    if target.blend_mode == Some(peniko::Mix::Normal.into()) {
+        target.blend_mode = None;
+    }
+    target.stretch = conv_stretch(source.time_stretch);
+    target.frames = conv_frames(source.in_point, source.out_point);
+    target.start_frame = source.start_time;
+    target.effects = conv_effects(source, &mut target.unsupported);
+    target.styles = conv_styles(source, &mut target.unsupported);
+    target.motion_blur = source.motion_blur.unwrap_or(false);
+    target.preserve_transparency = source.preserve_transparency == Some(BoolInt::True);
+    target.knockout = source.knockout == Some(BoolInt::True);
+    target.auto_orient = source.rotate_to_match_anim_pos_path == Some(BoolInt::True);
+
+    for mask_source in source.masks_properties.as_ref().unwrap_or(&Vec::default()) {
+        if let Some(shape) = &mask_source.shape {
+            if let Some(geometry) = conv_shape_geometry(shape) {
+                let mode = mask_source
+                    .mode
+                    .as_ref()
+                    .map_or(MaskMode::default(), conv_mask_mode);
+                let opacity = conv_scalar(
+                    mask_source
+                        .opacity
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+                );
+                target.masks.push(runtime::model::Mask {
+                    mode,
+                    inverted: mask_source.inverted.unwrap_or(false),
+                    expansion: conv_scalar(
+                        mask_source.expand.as_ref().unwrap_or(&FLOAT_VALUE_ZERO),
+                    ),
+                    feather: mask_source
+                        .feather
+                        .as_ref()
+                        .map_or(Value::Fixed(0.0), conv_feather),
+                    geometry,
+                    opacity,
+                });
+            }
+        }
+    }
+
+    target.wiggles = conv_wiggles(&source.transform, source.index.unwrap_or(0) as u64);
+    (source.index.unwrap_or(0), matte_mode)
+}
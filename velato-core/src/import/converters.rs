@@ -0,0 +1,2006 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::builders::{setup_layer_base, setup_precomp_layer, setup_shape_layer};
+use super::defaults::{
+    FLOAT_VALUE_FOUR, FLOAT_VALUE_ONE_HUNDRED, FLOAT_VALUE_ZERO, MULTIDIM_ONE, POSITION_ZERO,
+};
+use crate::runtime::model::animated::{self, Position};
+use crate::runtime::model::fixed;
+use crate::runtime::model::{
+    self, Content, DisplacementChannel, Draw, EasingHandle, Effect, GroupTransform, Layer,
+    LayerStyle, Loop, LoopMode, Loops, MaskMode, Matte, RepeaterComposite, Slot, SplineToPath,
+    StrokeCap, StrokeJoin, StrokePaintStyle, Time, Tween, Unsupported, Value, Wiggle,
+    WipeDirection,
+};
+use crate::runtime::{self, LayerProperty};
+use crate::schema::animated_properties::keyframe_base::KeyframeBase;
+use crate::schema::animated_properties::keyframe_bezier_handle::{
+    KeyframeBezierHandle, KeyframeComponent,
+};
+use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
+use crate::schema::animated_properties::split_vector::SplitVector;
+use crate::schema::constants::gradient_type::{AnyGradientType, GradientType};
+use crate::schema::constants::line_cap::{AnyLineCap, LineCap};
+use crate::schema::constants::line_join::{AnyLineJoin, LineJoin};
+use crate::schema::helpers::int_boolean::BoolInt;
+use crate::{schema, Composition};
+use kurbo::{Affine, Join, Point, Size, Vec2};
+use peniko::{BlendMode, Color, Compose, Mix};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+pub fn conv_animation(source: schema::Animation) -> Composition {
+    let mut target = Composition {
+        frames: source.in_point..source.out_point,
+        frame_rate: source.frame_rate,
+        width: source.width,
+        height: source.height,
+        assets: Default::default(),
+        layers: Default::default(),
+        base_transform: Affine::IDENTITY,
+        shutter: Default::default(),
+        markers: Default::default(),
+        audio_tracks: Default::default(),
+    };
+    if let Some(motion_blur) = &source.motion_blur {
+        let shutter = &mut target.shutter;
+        shutter.angle = motion_blur.shutter_angle.unwrap_or(shutter.angle);
+        shutter.phase = motion_blur.shutter_phase.unwrap_or(shutter.phase);
+    }
+
+    // Collect assets and layers
+    let camera = fixed::Camera::for_size(source.width as f64, source.height as f64);
+    let mut image_sizes = HashMap::new();
+    let mut files = HashMap::new();
+    if let Some(assets) = source.assets {
+        for asset in assets {
+            match asset {
+                schema::assets::AnyAsset::Precomposition(precomp) => {
+                    let layers = conv_layers(&precomp.composition.layers, camera);
+                    target.assets.insert(precomp.asset.id.clone(), layers);
+                }
+                // Images are not supported, but their sizes give the bounds
+                // of the layers that reference them.
+                schema::assets::AnyAsset::Image(image) => {
+                    // Sound assets have the same fields as images, so they
+                    // are parsed as images.
+                    let file_asset = &image.file_asset;
+                    let file = match &file_asset.dir {
+                        Some(dir) if file_asset.embedded != Some(BoolInt::True) => {
+                            format!("{dir}{}", file_asset.file_name)
+                        }
+                        _ => file_asset.file_name.clone(),
+                    };
+                    files.insert(file_asset.asset.id.clone(), file);
+                    if let (Some(width), Some(height)) = (image.width, image.height) {
+                        image_sizes.insert(image.file_asset.asset.id, (width, height));
+                    }
+                }
+            }
+        }
+    }
+    target.layers = conv_layers(&source.layers, camera);
+    target.markers = source
+        .markers
+        .iter()
+        .flatten()
+        .filter_map(conv_marker)
+        .collect();
+    target.audio_tracks = source
+        .layers
+        .iter()
+        .filter_map(|layer| match layer {
+            schema::layers::AnyLayer::Audio(layer) => conv_audio_track(layer, &files),
+            _ => None,
+        })
+        .collect();
+    // Properties are bound to their slots as they are converted, so the
+    // values of the slots are set once every layer exists.
+    for (id, components) in conv_slots(source.slots.as_ref()) {
+        runtime::slots::set(
+            &target,
+            &id,
+            runtime::slots::SlotSource::Components(&components),
+        );
+    }
+    runtime::overrides::apply(&mut target);
+    for layer in target
+        .layers
+        .iter_mut()
+        .chain(target.assets.values_mut().flatten())
+    {
+        if let Some(Unsupported::Image(name)) = layer.unsupported.first() {
+            if let Some(&(width, height)) = image_sizes.get(name) {
+                layer.width = width;
+                layer.height = height;
+            }
+        }
+    }
+
+    target
+}
+
+/// Converts a marker, skipping markers without a time.
+fn conv_marker(value: &schema::helpers::marker::Marker) -> Option<model::Marker> {
+    Some(model::Marker {
+        name: value.comment.clone().unwrap_or_default(),
+        frame: value.time.filter(|time| time.is_finite())?,
+        duration: value
+            .duration
+            .filter(|duration| duration.is_finite())
+            .unwrap_or_default()
+            .max(0.0),
+    })
+}
+
+/// Converts an audio layer, skipping hidden layers and layers without a
+/// sound.
+fn conv_audio_track(
+    value: &schema::layers::audio::AudioLayer,
+    files: &HashMap<String, String>,
+) -> Option<model::AudioTrack> {
+    if value.hidden == Some(true) {
+        return None;
+    }
+    let asset = value.sound_id.clone()?;
+    Some(model::AudioTrack {
+        name: value.name.clone().unwrap_or_default(),
+        file: files.get(&asset).cloned(),
+        asset,
+        frames: value.in_point..value.out_point,
+        start_frame: value.start_time,
+        stretch: value.time_stretch.filter(|sr| *sr > 0.0).unwrap_or(1.0),
+    })
+}
+
+/// Converts the static values of slots to their components, by id.
+///
+/// Animated slots are skipped, so that they keep the values of the
+/// properties that reference them.
+fn conv_slots(
+    slots: Option<&BTreeMap<String, schema::animation::slot::Slot>>,
+) -> BTreeMap<String, Vec<f64>> {
+    use schema::animated_properties::animated_property::AnimatedPropertyK;
+    use schema::animation::slot::AnySlotValue;
+
+    slots
+        .into_iter()
+        .flatten()
+        .filter_map(|(id, slot)| {
+            let components = match &slot.property.as_ref()?.value {
+                AnimatedPropertyK::Static(AnySlotValue::Number(value)) => vec![*value],
+                AnimatedPropertyK::Static(AnySlotValue::Vector(value)) => value.clone(),
+                _ => return None,
+            };
+            Some((id.clone(), components))
+        })
+        .collect()
+}
+
+/// Converts a list of layers, resolving parents and mattes from layer
+/// indices to positions in the resulting list. 3D layers are projected with
+/// the camera layers in the list, or the given camera when none is active.
+pub fn conv_layers(source: &[schema::layers::AnyLayer], camera: fixed::Camera) -> Vec<Layer> {
+    let cameras = Arc::new(animated::Cameras {
+        layers: source
+            .iter()
+            .filter_map(|layer| match layer {
+                schema::layers::AnyLayer::Camera(camera_layer)
+                    if camera_layer.properties.hidden != Some(true) =>
+                {
+                    Some(conv_camera(camera_layer))
+                }
+                _ => None,
+            })
+            .collect(),
+        default: camera,
+    });
+    let camera_parents = source
+        .iter()
+        .filter_map(|layer| match layer {
+            schema::layers::AnyLayer::Camera(camera_layer) => camera_layer.properties.parent_index,
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let mut idmap: HashMap<usize, usize> = HashMap::default();
+    let mut layers = vec![];
+    let mut mattes = vec![];
+    let mut mask_layer = None;
+    for source_layer in source {
+        let index = layers.len();
+        if let Some((mut layer, id, matte)) = conv_layer(source_layer, &cameras) {
+            if let schema::layers::AnyLayer::Null(null_layer) = source_layer {
+                if is_camera_null(&null_layer.properties, &camera_parents) {
+                    // The transform positions children relative to the
+                    // camera, which is projected on its own, so parent them
+                    // to the composition instead.
+                    layer.transform = Default::default();
+                }
+            }
+            if let (Some(matte), Some(mask_layer)) = (matte, mask_layer.take()) {
+                if matte != Matte::Normal {
+                    layer.mask_layer = Some((matte, mask_layer));
+                }
+            }
+            if layer.is_mask {
+                mask_layer = Some(index);
+            }
+            // Newer files reference the matte layer by index rather than
+            // relying on it being directly above.
+            if let (Some(matte), Some(matte_index)) = (matte, matte_layer_index(source_layer)) {
+                mattes.push((index, matte, matte_index));
+            }
+            idmap.insert(id, index);
+            layers.push(layer);
+        }
+    }
+    for (index, matte, matte_index) in mattes {
+        let mask_layer = idmap.get(&matte_index).copied();
+        layers[index].mask_layer = mask_layer
+            .filter(|_| matte != Matte::Normal)
+            .map(|mask_layer| (matte, mask_layer));
+    }
+    // Layers are drawn with their mattes, so mattes referencing each other
+    // would recurse forever. The reference closing a cycle is dropped.
+    for index in 0..layers.len() {
+        let mut current = index;
+        for _ in 0..layers.len() {
+            match layers[current].mask_layer {
+                Some((_, next)) if next == index => {
+                    layers[current].mask_layer = None;
+                    break;
+                }
+                Some((_, next)) if next < layers.len() => current = next,
+                _ => break,
+            }
+        }
+    }
+    for layer in &mut layers {
+        if let Some(parent) = layer.parent {
+            layer.parent = idmap.get(&parent).copied();
+        }
+        for effect in &mut layer.effects {
+            if let Effect::DisplacementMap(map) = effect {
+                map.layer = map.layer.and_then(|id| idmap.get(&id).copied());
+            }
+        }
+    }
+    layers
+}
+
+/// Returns the index of the layer explicitly referenced as a matte.
+fn matte_layer_index(source: &schema::layers::AnyLayer) -> Option<usize> {
+    use schema::layers::AnyLayer;
+
+    let properties = match source {
+        AnyLayer::Precomposition(layer) => &layer.properties,
+        AnyLayer::SolidColor(layer) => &layer.properties,
+        AnyLayer::Shape(layer) => &layer.properties,
+        AnyLayer::Null(layer) => &layer.properties,
+        AnyLayer::Image(layer) => &layer.properties,
+        AnyLayer::Text(layer) => &layer.properties,
+        AnyLayer::Camera(layer) => &layer.properties,
+        AnyLayer::Adjustment(layer) => &layer.properties,
+        AnyLayer::Audio(_) => return None,
+    };
+    properties.matte_layer_index.map(|index| index as usize)
+}
+
+pub fn conv_layer(
+    source: &schema::layers::AnyLayer,
+    cameras: &Arc<animated::Cameras>,
+) -> Option<(Layer, usize, Option<Matte>)> {
+    let mut layer = Layer::default();
+
+    let params = match source {
+        schema::layers::AnyLayer::Null(null_layer) => {
+            setup_layer_base(&null_layer.properties, &mut layer, cameras)
+        }
+        schema::layers::AnyLayer::Precomposition(precomp_layer) => {
+            let params = setup_precomp_layer(precomp_layer, &mut layer, cameras);
+            let name = precomp_layer.precomp_id.clone();
+            let time_remap = precomp_layer.time_remap.as_ref().map(conv_scalar);
+            let overrides = conv_slots(precomp_layer.slots.as_ref());
+            layer.content = Content::Instance {
+                source: name.clone(),
+                name,
+                time_remap,
+                overrides,
+            };
+
+            params
+        }
+        schema::layers::AnyLayer::Shape(shape_layer) => {
+            let params = setup_shape_layer(shape_layer, &mut layer, cameras);
+            let mut shapes = vec![];
+            for shape in &shape_layer.shapes {
+                if let Some(shape) = conv_shape(shape) {
+                    shapes.push(shape);
+                }
+            }
+            layer.content = Content::Shape(shapes);
+
+            params
+        }
+        schema::layers::AnyLayer::SolidColor(solid_color_layer) => {
+            let params = setup_layer_base(&solid_color_layer.properties, &mut layer, cameras);
+            layer.width = solid_color_layer.width;
+            layer.height = solid_color_layer.height;
+            if let Some(color) = conv_hex_color(&solid_color_layer.color) {
+                layer.content = Content::Solid {
+                    color,
+                    width: solid_color_layer.width,
+                    height: solid_color_layer.height,
+                };
+            }
+
+            params
+        }
+        schema::layers::AnyLayer::Image(image_layer) => {
+            let params = setup_layer_base(&image_layer.properties, &mut layer, cameras);
+            let name = image_layer.image_id.clone();
+            layer.unsupported.insert(0, Unsupported::Image(name));
+
+            params
+        }
+        schema::layers::AnyLayer::Text(text_layer) => {
+            let params = setup_layer_base(&text_layer.properties, &mut layer, cameras);
+            layer.unsupported.insert(0, Unsupported::Text);
+
+            params
+        }
+        schema::layers::AnyLayer::Camera(camera_layer) => {
+            // Cameras project 3D layers rather than draw anything, so only
+            // the timing and index are kept.
+            let params = setup_layer_base(&camera_layer.properties, &mut layer, cameras);
+            layer.transform = Default::default();
+
+            params
+        }
+        schema::layers::AnyLayer::Adjustment(adjustment_layer) => {
+            let params = setup_layer_base(&adjustment_layer.properties, &mut layer, cameras);
+            layer.content = Content::Adjustment;
+
+            params
+        }
+        // Audio layers are collected separately by `conv_audio_tracks`.
+        schema::layers::AnyLayer::Audio(_) => return None,
+    };
+
+    let (id, matte_mode) = params;
+    Some((layer, id, matte_mode))
+}
+
+/// Parses a color in the `#rrggbb` form used by solid color layers. The
+/// leading `#` is optional, and a shorthand `#rgb` form is also accepted.
+fn conv_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize, len: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16);
+    let (r, g, b) = match hex.len() {
+        6 => (channel(0, 2), channel(1, 2), channel(2, 2)),
+        3 => {
+            let expand = |c: Result<u8, _>| c.map(|c| c * 17);
+            (
+                expand(channel(0, 1)),
+                expand(channel(1, 1)),
+                expand(channel(2, 1)),
+            )
+        }
+        _ => return None,
+    };
+    Some(Color::rgb8(r.ok()?, g.ok()?, b.ok()?))
+}
+
+/// Returns true if the layer is a null that After Effects exports to carry a
+/// camera, which is a parentless 3D null that a camera layer is parented to.
+///
+/// These nulls keep the 3D position of the camera, which places any other
+/// layers parented to them far outside the canvas. Nulls that no camera is
+/// parented to are rigs, such as camera shake controls, and keep their
+/// transform.
+fn is_camera_null(
+    layer: &schema::layers::visual::VisualLayer,
+    camera_parents: &HashSet<usize>,
+) -> bool {
+    layer.parent_index.is_none()
+        && layer.three_dimensional == Some(BoolInt::True)
+        && layer
+            .index
+            .is_some_and(|index| camera_parents.contains(&index))
+}
+
+/// Converts a layer transform. The transforms of 3D layers are projected
+/// with the given cameras.
+pub fn conv_transform(
+    value: &schema::helpers::transform::Transform,
+    cameras: Option<Arc<animated::Cameras>>,
+) -> (runtime::model::Transform, Value<f64>) {
+    use schema::helpers::transform::{AnyTransformP, AnyTransformR};
+
+    let (rotation_in, split_rotation) = match &value.rotation {
+        Some(AnyTransformR::Rotation(float_value)) => (float_value, None),
+        Some(AnyTransformR::SplitRotation {
+            x_rotation,
+            y_rotation,
+            z_rotation,
+            orientation,
+        }) => (z_rotation, Some((x_rotation, y_rotation, orientation))),
+        None => (value.rotation_z.as_ref().unwrap_or(&FLOAT_VALUE_ZERO), None),
+    };
+
+    let (position, position_z) = match &value.position {
+        AnyTransformP::Position(position) => (
+            Position::Value(conv_pos_point(position)),
+            conv_pos(position, |x| x.get(2).copied().unwrap_or(0.0)),
+        ),
+        AnyTransformP::SplitPosition(SplitVector { x, y, z, .. }) => (
+            Position::SplitValues((conv_scalar(x), conv_scalar(y))),
+            conv_scalar(z.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+        ),
+    };
+
+    let anchor = value.anchor_point.as_ref().unwrap_or(&POSITION_ZERO);
+    let depth = cameras.map(|cameras| {
+        let (rotation_x, rotation_y, orientation) = match split_rotation {
+            Some((x, y, orientation)) => (Some(x), Some(y), Some(orientation)),
+            None => (
+                value.rotation_x.as_ref(),
+                value.rotation_y.as_ref(),
+                value.orientation.as_ref(),
+            ),
+        };
+        let orientation = |i: usize| {
+            orientation.map_or(Value::Fixed(0.0), |value| {
+                conv_multi(value, |x| x.get(i).copied().unwrap_or(0.0))
+            })
+        };
+        Box::new(animated::Depth {
+            anchor: conv_pos(anchor, |x| x.get(2).copied().unwrap_or(0.0)),
+            position: position_z,
+            scale: value.scale.as_ref().map_or(Value::Fixed(100.0), |value| {
+                conv_multi(value, |x| x.get(2).copied().unwrap_or(100.0))
+            }),
+            rotation_x: conv_scalar(rotation_x.unwrap_or(&FLOAT_VALUE_ZERO)),
+            rotation_y: conv_scalar(rotation_y.unwrap_or(&FLOAT_VALUE_ZERO)),
+            orientation: [orientation(0), orientation(1), orientation(2)],
+            cameras,
+        })
+    });
+
+    let transform = animated::Transform {
+        anchor: conv_pos_point(anchor),
+        position,
+        scale: conv_vec2(value.scale.as_ref().unwrap_or(&MULTIDIM_ONE)),
+        rotation: conv_scalar(rotation_in),
+        skew: conv_scalar(value.skew.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+        skew_angle: conv_scalar(value.skew_axis.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+        depth,
+    };
+    let opacity = conv_scalar(value.opacity.as_ref().unwrap_or(&FLOAT_VALUE_ONE_HUNDRED));
+    // Wiggles offset the components of the transform, which are lost once
+    // it is fixed.
+    let wiggled = conv_wiggles(value, 0)
+        .iter()
+        .any(|wiggle| wiggle.property != LayerProperty::Opacity);
+    let transform = if wiggled {
+        runtime::model::Transform::Animated(Box::new(transform))
+    } else {
+        transform.into_model()
+    };
+    (transform, opacity)
+}
+
+/// Converts the `wiggle` expressions of the properties of a layer
+/// transform. The key distinguishes the wiggles of different layers.
+///
+/// Positions with separate dimensions are not wiggled.
+pub fn conv_wiggles(value: &schema::helpers::transform::Transform, key: u64) -> Vec<Wiggle> {
+    use schema::helpers::transform::{AnyTransformP, AnyTransformR};
+
+    let rotation = match &value.rotation {
+        Some(AnyTransformR::Rotation(rotation)) => Some(rotation),
+        Some(AnyTransformR::SplitRotation { z_rotation, .. }) => Some(z_rotation),
+        None => value.rotation_z.as_ref(),
+    };
+    let position = match &value.position {
+        AnyTransformP::Position(position) => position.expression.as_deref(),
+        AnyTransformP::SplitPosition(_) => None,
+    };
+    let expressions = [
+        (
+            LayerProperty::Anchor,
+            value
+                .anchor_point
+                .as_ref()
+                .and_then(|anchor| anchor.expression.as_deref()),
+        ),
+        (LayerProperty::Position, position),
+        (
+            LayerProperty::Rotation,
+            rotation.and_then(|rotation| rotation.animated_property.expression.as_deref()),
+        ),
+        (
+            LayerProperty::Scale,
+            value
+                .scale
+                .as_ref()
+                .and_then(|scale| scale.animated_property.expression.as_deref()),
+        ),
+        (
+            LayerProperty::Opacity,
+            value
+                .opacity
+                .as_ref()
+                .and_then(|opacity| opacity.animated_property.expression.as_deref()),
+        ),
+    ];
+    expressions
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (property, expression))| {
+            conv_wiggle(
+                property,
+                expression?,
+                key.wrapping_mul(8).wrapping_add(i as u64),
+            )
+        })
+        .collect()
+}
+
+/// Converts an expression that only calls `wiggle`, such as
+/// `wiggle(2, 10)`, with numbers for the frequency, amplitude and optional
+/// octaves and amplitude multiplier.
+fn conv_wiggle(property: LayerProperty, expression: &str, key: u64) -> Option<Wiggle> {
+    let (name, args) = conv_call(expression)?;
+    if name != "wiggle" {
+        return None;
+    }
+    let args = args
+        .map(|arg| arg.parse::<f64>().ok().filter(|arg| arg.is_finite()))
+        .collect::<Option<Vec<_>>>()?;
+    let (frequency, amplitude, octaves, amplitude_multiplier) = match args[..] {
+        [frequency, amplitude] => (frequency, amplitude, 1.0, 0.5),
+        [frequency, amplitude, octaves] => (frequency, amplitude, octaves, 0.5),
+        [frequency, amplitude, octaves, multiplier] => (frequency, amplitude, octaves, multiplier),
+        _ => return None,
+    };
+    Some(Wiggle {
+        property,
+        frequency,
+        amplitude,
+        octaves: octaves.max(0.0) as u32,
+        amplitude_multiplier,
+        key,
+    })
+}
+
+/// Splits an expression that consists of a single call, such as
+/// `loopOut("cycle")`, into the name of the function and its arguments.
+fn conv_call(expression: &str) -> Option<(&str, impl Iterator<Item = &str>)> {
+    // bodymovin exports expressions as an assignment to `$bm_rt`.
+    let body = expression.trim().trim_start_matches("var $bm_rt;").trim();
+    let body = body
+        .strip_prefix("$bm_rt")
+        .and_then(|body| body.trim_start().strip_prefix('='))
+        .unwrap_or(body);
+    let body = body.trim().trim_end_matches(';').trim_end();
+    let (name, args) = body.strip_suffix(')')?.split_once('(')?;
+    // Arguments may be named, as in `loopOut(type = "cycle")`.
+    let args = args
+        .split(',')
+        .map(|arg| arg.split_once('=').map_or(arg, |(_, value)| value).trim());
+    Some((name.trim_end(), args))
+}
+
+/// Converts a camera layer.
+pub fn conv_camera(value: &schema::layers::camera::CameraLayer) -> animated::Camera {
+    use schema::helpers::transform::{AnyTransformP, AnyTransformR};
+
+    let transform = &value.properties.transform;
+    let (position, position_z) = match &transform.position {
+        AnyTransformP::Position(position) => (
+            Position::Value(conv_pos_point(position)),
+            conv_pos(position, |x| x.get(2).copied().unwrap_or(0.0)),
+        ),
+        AnyTransformP::SplitPosition(SplitVector { x, y, z, .. }) => (
+            Position::SplitValues((conv_scalar(x), conv_scalar(y))),
+            conv_scalar(z.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+        ),
+    };
+    let scalar = |value: Option<&schema::animated_properties::value::FloatValue>| {
+        conv_scalar(value.unwrap_or(&FLOAT_VALUE_ZERO))
+    };
+    let (rotation, orientation) = match &transform.rotation {
+        Some(AnyTransformR::SplitRotation {
+            x_rotation,
+            y_rotation,
+            z_rotation,
+            orientation,
+        }) => (
+            [x_rotation, y_rotation, z_rotation].map(|value| scalar(Some(value))),
+            Some(orientation),
+        ),
+        rotation => {
+            let z_rotation = match rotation {
+                Some(AnyTransformR::Rotation(value)) => Some(value),
+                _ => transform.rotation_z.as_ref(),
+            };
+            (
+                [
+                    scalar(transform.rotation_x.as_ref()),
+                    scalar(transform.rotation_y.as_ref()),
+                    scalar(z_rotation),
+                ],
+                transform.orientation.as_ref(),
+            )
+        }
+    };
+    animated::Camera {
+        frames: value.properties.in_point..value.properties.out_point,
+        position,
+        position_z,
+        point_of_interest: transform.anchor_point.as_ref().map(|anchor| {
+            (
+                conv_pos_point(anchor),
+                conv_pos(anchor, |x| x.get(2).copied().unwrap_or(0.0)),
+            )
+        }),
+        rotation,
+        orientation: std::array::from_fn(|i| {
+            orientation.map_or(Value::Fixed(0.0), |value| {
+                conv_multi(value, |x| x.get(i).copied().unwrap_or(0.0))
+            })
+        }),
+        zoom: conv_scalar(&value.perspective),
+    }
+}
+
+pub fn conv_shape_transform(value: &schema::shapes::transform::TransformShape) -> GroupTransform {
+    // Shape groups are flat, so only the rotation around the Z axis applies.
+    let rotation_in = match &value.transform.rotation {
+        Some(any_trans) => match any_trans {
+            schema::helpers::transform::AnyTransformR::Rotation(float_value) => float_value,
+            schema::helpers::transform::AnyTransformR::SplitRotation { z_rotation, .. } => {
+                z_rotation
+            }
+        },
+        None => value
+            .transform
+            .rotation_z
+            .as_ref()
+            .unwrap_or(&FLOAT_VALUE_ZERO),
+    };
+    let position = match &value.transform.position {
+        schema::helpers::transform::AnyTransformP::Position(position) => {
+            Position::Value(conv_pos_point(position))
+        }
+        schema::helpers::transform::AnyTransformP::SplitPosition(SplitVector { x, y, .. }) => {
+            Position::SplitValues((conv_scalar(x), conv_scalar(y)))
+        }
+    };
+
+    let transform = animated::Transform {
+        anchor: conv_pos_point(
+            value
+                .transform
+                .anchor_point
+                .as_ref()
+                .unwrap_or(&POSITION_ZERO),
+        ),
+        position,
+        scale: conv_vec2(value.transform.scale.as_ref().unwrap_or(&MULTIDIM_ONE)),
+        rotation: conv_scalar(rotation_in),
+        skew: conv_scalar(value.transform.skew.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+        skew_angle: conv_scalar(
+            value
+                .transform
+                .skew_axis
+                .as_ref()
+                .unwrap_or(&FLOAT_VALUE_ZERO),
+        ),
+        depth: None,
+    };
+
+    let opacity = conv_scalar(
+        value
+            .transform
+            .opacity
+            .as_ref()
+            .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+    );
+
+    GroupTransform {
+        transform: transform.into_model(),
+        opacity,
+    }
+}
+
+pub fn conv_keyframes<'a, T: Tween>(
+    keyframes: impl Iterator<Item = &'a schema::animated_properties::keyframe::Keyframe>,
+    f: impl Fn(&schema::animated_properties::keyframe::Keyframe) -> T,
+) -> Value<T> {
+    let mut frames = vec![];
+    let mut values = vec![];
+    for keyframe in keyframes {
+        frames.push(conv_time(&keyframe.base));
+        values.push(f(keyframe));
+    }
+    Value::Animated(Box::new(runtime::model::Animated {
+        times: frames,
+        values,
+        loops: Loops::default(),
+    }))
+}
+
+/// Converts the frame, hold and easing of a keyframe.
+///
+/// Keyframes of multi-dimensional values may ease each dimension
+/// separately, with an array of coordinates in their easing handles. The
+/// first dimension's easing is also used by values eased as a whole.
+fn conv_time(base: &KeyframeBase) -> Time {
+    let in_tangents = base
+        .in_tangent
+        .as_ref()
+        .map(conv_keyframe_handles)
+        .unwrap_or_default();
+    let out_tangents = base
+        .out_tangent
+        .as_ref()
+        .map(conv_keyframe_handles)
+        .unwrap_or_default();
+    Time {
+        frame: base.time,
+        in_tangent: in_tangents.first().copied(),
+        out_tangent: out_tangents.first().copied(),
+        axis_in_tangents: [1, 2].map(|axis| in_tangents.get(axis).copied()),
+        axis_out_tangents: [1, 2].map(|axis| out_tangents.get(axis).copied()),
+        hold: base.hold == Some(BoolInt::True),
+    }
+}
+
+/// Converts an easing handle to a handle for each dimension, where a single
+/// coordinate applies to every dimension.
+fn conv_keyframe_handles(handle: &KeyframeBezierHandle) -> Vec<EasingHandle> {
+    match (&handle.x_coordinate, &handle.y_coordinate) {
+        (KeyframeComponent::ArrayOfValues(xarr), KeyframeComponent::ArrayOfValues(yarr)) => xarr
+            .iter()
+            .zip(yarr)
+            .map(|(x, y)| EasingHandle { x: *x, y: *y })
+            .collect(),
+        (KeyframeComponent::ArrayOfValues(xarr), KeyframeComponent::SingleValue(y)) => {
+            xarr.iter().map(|x| EasingHandle { x: *x, y: *y }).collect()
+        }
+        (KeyframeComponent::SingleValue(x), KeyframeComponent::ArrayOfValues(yarr)) => {
+            yarr.iter().map(|y| EasingHandle { x: *x, y: *y }).collect()
+        }
+        (KeyframeComponent::SingleValue(x), KeyframeComponent::SingleValue(y)) => {
+            vec![EasingHandle { x: *x, y: *y }]
+        }
+    }
+}
+
+fn conv_gradient_colors(
+    value: &schema::animated_properties::gradient_colors::GradientColors,
+) -> runtime::model::ColorStops {
+    use schema::animated_properties::animated_property::AnimatedPropertyK::*;
+
+    let count = value.count;
+    match &value.colors.animated_property.value {
+        Static(value) => {
+            runtime::model::ColorStops::Fixed(animated::ColorStops::merge(value, count))
+        }
+        AnimatedValue(animated) => {
+            let mut frames = vec![];
+            let mut values: Vec<Vec<f64>> = vec![];
+            for value in animated {
+                frames.push(conv_time(&value.base));
+                values.push(value.value.clone());
+            }
+            runtime::model::ColorStops::Animated(Box::new(animated::ColorStops {
+                frames,
+                values,
+                count,
+            }))
+        }
+    }
+}
+
+fn conv_draw(value: &schema::shapes::AnyShape) -> Option<runtime::model::Draw> {
+    use schema::shapes::AnyShape;
+
+    match value {
+        AnyShape::Fill(value) => {
+            let brush = conv_any_color(&value.color).into_model();
+            let opacity = conv_scalar(value.opacity.as_ref().unwrap_or(&FLOAT_VALUE_ONE_HUNDRED));
+            Some(runtime::model::Draw {
+                stroke: None,
+                brush,
+                opacity,
+            })
+        }
+        AnyShape::Stroke(value) => {
+            let (dashes, dash_offset) = conv_dashes(value.dash_array.as_deref());
+            let stroke = animated::Stroke {
+                width: conv_scalar(&value.stroke_width),
+                join: conv_line_join(value.line_join.as_ref(), LineJoin::Bevel),
+                miter_limit: value.miter_limit,
+                cap: conv_line_cap(value.line_cap.as_ref(), LineCap::Butt),
+                dashes,
+                dash_offset,
+            };
+            let brush = conv_any_color(&value.stroke_color).into_model();
+            let opacity = conv_scalar(&value.opacity);
+            Some(runtime::model::Draw {
+                stroke: Some(stroke.into_model()),
+                brush,
+                opacity,
+            })
+        }
+        AnyShape::GradientFill(value) => {
+            let is_radial = conv_gradient_type(value.gradient.gradient_type.as_ref());
+            let start_point = conv_multi_point(&value.gradient.start_point);
+            let end_point = conv_multi_point(&value.gradient.end_point);
+            let gradient = animated::Gradient {
+                is_radial,
+                start_point,
+                end_point,
+                highlight_length: conv_scalar(
+                    value
+                        .gradient
+                        .highlight_length
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ZERO),
+                ),
+                highlight_angle: conv_scalar(
+                    value
+                        .gradient
+                        .highlight_angle
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ZERO),
+                ),
+                stops: conv_gradient_colors(&value.gradient.colors),
+            };
+            let brush = animated::Brush::Gradient(gradient).into_model();
+            Some(Draw {
+                stroke: None,
+                brush,
+                opacity: conv_scalar(&value.opacity),
+            })
+        }
+        AnyShape::GradientStroke(value) => {
+            let (dashes, dash_offset) = conv_dashes(value.base_stroke.dashes.as_deref());
+            let stroke = animated::Stroke {
+                width: conv_scalar(&value.base_stroke.width),
+                join: conv_line_join(value.base_stroke.line_join.as_ref(), LineJoin::Round),
+                miter_limit: value.base_stroke.miter_limit,
+                cap: conv_line_cap(value.base_stroke.line_cap.as_ref(), LineCap::Round),
+                dashes,
+                dash_offset,
+            };
+            let is_radial = conv_gradient_type(value.gradient.gradient_type.as_ref());
+            let start_point = conv_multi_point(&value.gradient.start_point);
+            let end_point = conv_multi_point(&value.gradient.end_point);
+            let gradient = animated::Gradient {
+                is_radial,
+                start_point,
+                end_point,
+                highlight_length: conv_scalar(
+                    value
+                        .gradient
+                        .highlight_length
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ZERO),
+                ),
+                highlight_angle: conv_scalar(
+                    value
+                        .gradient
+                        .highlight_angle
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ZERO),
+                ),
+                stops: conv_gradient_colors(&value.gradient.colors),
+            };
+            let brush = animated::Brush::Gradient(gradient).into_model();
+            let opacity = conv_scalar(&value.base_stroke.opacity);
+            Some(Draw {
+                stroke: Some(stroke.into_model()),
+                brush,
+                opacity,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Converts the type of a gradient to a value that is true for radial
+/// gradients. Keyframed types snap to the type of each keyframe.
+fn conv_gradient_type(value: Option<&AnyGradientType>) -> Value<bool> {
+    let is_radial = |x: f64| x.round() == GradientType::Radial as u8 as f64;
+    match value {
+        None => Value::Fixed(false),
+        Some(AnyGradientType::Fixed(value)) => Value::Fixed(*value == GradientType::Radial),
+        Some(AnyGradientType::Animated(value)) => conv_numeric(value, is_radial),
+    }
+}
+
+fn conv_line_join(value: Option<&AnyLineJoin>, default: LineJoin) -> Value<StrokeJoin> {
+    let conv = |value: &LineJoin| match value {
+        LineJoin::Miter => StrokeJoin::Miter,
+        LineJoin::Round => StrokeJoin::Round,
+        LineJoin::Bevel => StrokeJoin::Bevel,
+    };
+    let from_number = |x: f64| match x.round() as i64 {
+        1 => StrokeJoin::Miter,
+        2 => StrokeJoin::Round,
+        3 => StrokeJoin::Bevel,
+        _ => conv(&default),
+    };
+    match value {
+        None => Value::Fixed(conv(&default)),
+        Some(AnyLineJoin::Fixed(value)) => Value::Fixed(conv(value)),
+        Some(AnyLineJoin::Animated(value)) => conv_numeric(value, from_number),
+    }
+}
+
+fn conv_line_cap(value: Option<&AnyLineCap>, default: LineCap) -> Value<StrokeCap> {
+    let conv = |value: &LineCap| match value {
+        LineCap::Butt => StrokeCap::Butt,
+        LineCap::Round => StrokeCap::Round,
+        LineCap::Square => StrokeCap::Square,
+    };
+    let from_number = |x: f64| match x.round() as i64 {
+        1 => StrokeCap::Butt,
+        2 => StrokeCap::Round,
+        3 => StrokeCap::Square,
+        _ => conv(&default),
+    };
+    match value {
+        None => Value::Fixed(conv(&default)),
+        Some(AnyLineCap::Fixed(value)) => Value::Fixed(conv(value)),
+        Some(AnyLineCap::Animated(value)) => conv_numeric(value, from_number),
+    }
+}
+
+/// Converts keyframes holding the numeric value of an enumeration.
+fn conv_numeric<T: Tween>(
+    value: &schema::animated_properties::value::FloatValue,
+    f: impl Fn(f64) -> T,
+) -> Value<T> {
+    fn numeric<T: Tween>(value: Value<f64>, f: impl Fn(f64) -> T) -> Value<T> {
+        match value {
+            Value::Fixed(value) => Value::Fixed(f(value)),
+            Value::Animated(animated) => Value::Animated(Box::new(model::Animated {
+                times: animated.times,
+                values: animated.values.into_iter().map(f).collect(),
+                loops: animated.loops,
+            })),
+            // Slots hold numbers rather than enumerations.
+            Value::Slot(slot) => numeric(slot.into_inner(), f),
+        }
+    }
+    numeric(conv_scalar(value), f)
+}
+
+fn conv_dashes(
+    value: Option<&[schema::shapes::stroke_dash::StrokeDash]>,
+) -> (Vec<Value<f64>>, Value<f64>) {
+    use schema::constants::stroke_dash_type::StrokeDashType;
+
+    let mut dashes = vec![];
+    let mut offset = Value::Fixed(0.0);
+    for dash in value.unwrap_or_default() {
+        let length = dash
+            .length
+            .as_ref()
+            .map(conv_scalar)
+            .unwrap_or(Value::Fixed(0.0));
+        match dash.dash_type.as_ref().unwrap_or(&StrokeDashType::Dash) {
+            StrokeDashType::Dash | StrokeDashType::Gap => dashes.push(length),
+            StrokeDashType::Offset => offset = length,
+        }
+    }
+    (dashes, offset)
+}
+
+/// Converts a shape item, keeping hidden items in a
+/// [`Shape::Hidden`](model::Shape::Hidden) element.
+fn conv_shape(value: &schema::shapes::AnyShape) -> Option<crate::runtime::model::Shape> {
+    let shape = conv_shape_element(value)?;
+    if is_hidden(value) {
+        Some(crate::runtime::model::Shape::Hidden(Box::new(shape)))
+    } else {
+        Some(shape)
+    }
+}
+
+fn conv_shape_element(value: &schema::shapes::AnyShape) -> Option<crate::runtime::model::Shape> {
+    if let Some(draw) = conv_draw(value) {
+        return Some(crate::runtime::model::Shape::Draw(draw));
+    } else if let Some(geometry) = conv_geometry(value) {
+        return Some(crate::runtime::model::Shape::Geometry(geometry));
+    }
+
+    if let Some(modifier) = conv_modifier(value) {
+        return Some(crate::runtime::model::Shape::Modifier(
+            modifier.into_model(),
+        ));
+    }
+
+    match value {
+        schema::shapes::AnyShape::Group(value) => {
+            let mut shapes = vec![];
+            let mut group_transform = None;
+            for item in &value.shapes {
+                match item {
+                    schema::shapes::AnyShape::Transform(transform) => {
+                        group_transform = Some(conv_shape_transform(transform));
+                    }
+                    _ => {
+                        if let Some(shape) = conv_shape(item) {
+                            shapes.push(shape);
+                        }
+                    }
+                }
+            }
+            if !shapes.is_empty() {
+                Some(crate::runtime::model::Shape::Group(shapes, group_transform))
+            } else {
+                None
+            }
+        }
+        schema::shapes::AnyShape::Repeater(value) => {
+            let transform = &value.transform;
+            let repeater = animated::Repeater {
+                copies: conv_scalar(&value.copies),
+                offset: conv_scalar(value.offset.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+                anchor_point: conv_pos_point(
+                    transform.anchor_point.as_ref().unwrap_or(&POSITION_ZERO),
+                ),
+                position: conv_pos_point(transform.position.as_ref().unwrap_or(&POSITION_ZERO)),
+                rotation: conv_scalar(transform.rotation.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+                scale: transform
+                    .scale
+                    .as_ref()
+                    .map(conv_vec2)
+                    .unwrap_or(Value::Fixed(Vec2::new(100.0, 100.0))),
+                start_opacity: conv_scalar(
+                    transform
+                        .start_opacity
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+                ),
+                end_opacity: conv_scalar(
+                    transform
+                        .end_opacity
+                        .as_ref()
+                        .unwrap_or(&FLOAT_VALUE_ONE_HUNDRED),
+                ),
+                // Lottie has no per-copy trim offset; it is set through the
+                // model.
+                trim_offset: Value::Fixed(0.0),
+                composite: match value.composite {
+                    Some(schema::constants::composite::Composite::Below) => {
+                        RepeaterComposite::Below
+                    }
+                    _ => RepeaterComposite::Above,
+                },
+            };
+            Some(crate::runtime::model::Shape::Repeater(
+                repeater.into_model(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn conv_modifier(value: &schema::shapes::AnyShape) -> Option<animated::PathModifier> {
+    use schema::shapes::AnyShape;
+    match value {
+        AnyShape::ZigZag(value) => {
+            // Point type is animatable in the schema but only meaningful as a
+            // fixed choice.
+            let point_type = conv_scalar(&value.point_type).evaluate(0.0);
+            Some(animated::PathModifier::ZigZag(animated::ZigZag {
+                ridges: conv_scalar(&value.frequency),
+                size: conv_scalar(&value.amplitude),
+                is_smooth: point_type.round() == 2.0,
+            }))
+        }
+        AnyShape::OffsetPath(value) => {
+            use schema::constants::line_join::LineJoin;
+            Some(animated::PathModifier::OffsetPath(animated::OffsetPath {
+                amount: conv_scalar(value.amount.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+                join: match value.line_join.as_ref().unwrap_or(&LineJoin::Round) {
+                    LineJoin::Bevel => Join::Bevel,
+                    LineJoin::Round => Join::Round,
+                    LineJoin::Miter => Join::Miter,
+                },
+                miter_limit: conv_scalar(value.miter_limit.as_ref().unwrap_or(&FLOAT_VALUE_FOUR)),
+            }))
+        }
+        AnyShape::PuckerBloat(value) => {
+            Some(animated::PathModifier::PuckerBloat(animated::PuckerBloat {
+                amount: conv_scalar(value.amount.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+            }))
+        }
+        AnyShape::Twist(value) => Some(animated::PathModifier::Twist(animated::Twist {
+            angle: conv_scalar(value.angle.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+            center: value
+                .center
+                .as_ref()
+                .map(conv_vec2)
+                .unwrap_or(Value::Fixed(Vec2::ZERO)),
+        })),
+        AnyShape::Trim(value) => {
+            use schema::constants::trim_multiple_shapes::TrimMultipleShapes;
+            Some(animated::PathModifier::Trim(animated::Trim {
+                start: conv_scalar(&value.start),
+                end: conv_scalar(&value.end),
+                offset: conv_scalar(&value.offset),
+                individually: value.multiple == Some(TrimMultipleShapes::Individually),
+            }))
+        }
+        AnyShape::RoundedCorners(value) => Some(animated::PathModifier::RoundedCorners(
+            animated::RoundedCorners {
+                radius: conv_scalar(&value.radius),
+            },
+        )),
+        _ => None,
+    }
+}
+
+fn conv_geometry(value: &schema::shapes::AnyShape) -> Option<crate::runtime::model::Geometry> {
+    use schema::shapes::AnyShape;
+    match value {
+        AnyShape::Ellipse(value) => {
+            let ellipse = animated::Ellipse {
+                is_ccw: is_reversed(&value.direction),
+                position: conv_pos_point(&value.position),
+                size: conv_size(&value.size),
+            };
+            Some(crate::runtime::model::Geometry::Ellipse(ellipse))
+        }
+        AnyShape::Rectangle(value) => {
+            let rect = animated::Rect {
+                is_ccw: is_reversed(&value.direction),
+                position: conv_pos_point(&value.position),
+                size: conv_size(&value.size),
+                corner_radius: conv_scalar(&value.rounded_corner_radius),
+            };
+            Some(crate::runtime::model::Geometry::Rect(rect))
+        }
+        AnyShape::PolyStar(value) => {
+            use schema::constants::star_type::StarType;
+            let star = animated::Star {
+                is_polygon: value.star_type == StarType::Polygon,
+                direction: if is_reversed(&value.direction) {
+                    -1.0
+                } else {
+                    1.0
+                },
+                position: conv_pos_point(&value.position),
+                inner_radius: conv_scalar(value.inner_radius.as_ref().unwrap_or(&FLOAT_VALUE_ZERO)),
+                inner_roundness: conv_scalar(
+                    value.inner_roundness.as_ref().unwrap_or(&FLOAT_VALUE_ZERO),
+                ),
+                outer_radius: conv_scalar(&value.outer_radius),
+                outer_roundness: conv_scalar(&value.outer_roundness),
+                rotation: conv_scalar(&value.rotation),
+                points: conv_scalar(&value.points),
+            };
+            Some(crate::runtime::model::Geometry::Star(star))
+        }
+        AnyShape::Path(value) => {
+            let geometry = conv_shape_geometry(&value.shape_property)?;
+            if !is_reversed(&value.shape.direction) {
+                return Some(geometry);
+            }
+            Some(match geometry {
+                runtime::model::Geometry::Fixed(mut path) => {
+                    runtime::model::reverse_subpaths(&mut path, 0);
+                    runtime::model::Geometry::Fixed(path)
+                }
+                runtime::model::Geometry::Spline(spline) => {
+                    runtime::model::Geometry::Spline(animated::Spline {
+                        is_ccw: true,
+                        ..spline
+                    })
+                }
+                geometry => geometry,
+            })
+        }
+        // todo: generic shape
+        _ => None,
+    }
+}
+
+/// Returns true if a shape is drawn in the reverse of its usual direction,
+/// which is counterclockwise for rectangles, ellipses and stars.
+fn is_reversed(direction: &Option<schema::constants::shape_direction::ShapeDirection>) -> bool {
+    *direction == Some(schema::constants::shape_direction::ShapeDirection::Reversed)
+}
+
+/// Returns true if a shape item is hidden.
+fn is_hidden(value: &schema::shapes::AnyShape) -> bool {
+    use schema::shapes::AnyShape;
+    let element = match value {
+        AnyShape::Group(value) => &value.shape_element,
+        AnyShape::Rectangle(value) => &value.shape_element,
+        AnyShape::Ellipse(value) => &value.shape_element,
+        AnyShape::PolyStar(value) => &value.shape_element,
+        AnyShape::Transform(value) => &value.shape_element,
+        AnyShape::Stroke(value) => &value.shape_element,
+        AnyShape::PuckerBloat(value) => &value.shape_element,
+        AnyShape::Merge(value) => &value.shape_element,
+        AnyShape::Repeater(value) => &value.shape_element,
+        AnyShape::OffsetPath(value) => &value.shape_element,
+        AnyShape::Fill(value) => &value.shape_element,
+        AnyShape::Trim(value) => &value.shape_element,
+        AnyShape::Path(value) => &value.shape.shape_element,
+        AnyShape::GradientFill(value) => &value.shape_element,
+        AnyShape::GradientStroke(value) => &value.shape_element,
+        AnyShape::ZigZag(value) => &value.shape_element,
+        AnyShape::RoundedCorners(value) => &value.shape_element,
+        AnyShape::Twist(value) => &value.shape_element,
+    };
+    element.hidden == Some(true)
+}
+
+pub fn conv_shape_geometry(
+    value: &schema::animated_properties::shape_property::ShapeProperty,
+) -> Option<runtime::model::Geometry> {
+    use schema::animated_properties::shape_property::ShapePropertyK::*;
+    match &value.value {
+        Static(value) => {
+            let (points, is_closed) = conv_spline(value);
+            let mut path = vec![];
+            points.as_slice().to_path(is_closed, &mut path);
+            Some(runtime::model::Geometry::Fixed(path))
+        }
+        Animated(animated) => {
+            let mut frames = vec![];
+            let mut values = vec![];
+            let mut closed = vec![];
+            for value in animated {
+                frames.push(conv_time(&value.base));
+                let spline = value.start.first()?;
+                let (points, _) = conv_spline(spline);
+                values.push(points);
+                // Keyframes that leave out whether they are closed continue
+                // the previous keyframe.
+                let is_frame_closed = spline
+                    .closed
+                    .unwrap_or_else(|| closed.last().copied().unwrap_or(false));
+                closed.push(is_frame_closed);
+            }
+            Some(runtime::model::Geometry::Spline(animated::Spline {
+                is_ccw: false,
+                times: frames,
+                values,
+                closed,
+                loops: conv_loops(value.expression.as_deref()),
+            }))
+        }
+    }
+}
+
+pub fn conv_spline(value: &schema::helpers::bezier::Bezier) -> (Vec<Point>, bool) {
+    use core::iter::repeat;
+    let mut points = Vec::with_capacity(value.vertices.len() * 3);
+    let is_closed = value.closed.unwrap_or(false);
+
+    for ((v, i), o) in value
+        .vertices
+        .iter()
+        .zip(value.in_tangents.iter().chain(repeat(&[0.0, 0.0])))
+        .zip(value.out_tangents.iter().chain(repeat(&[0.0, 0.0])))
+    {
+        points.push((v[0], v[1]).into());
+        points.push((i[0], i[1]).into());
+        points.push((o[0], o[1]).into());
+    }
+    (points, is_closed)
+}
+
+pub fn conv_mask_mode(value: &schema::constants::mask_mode::MaskMode) -> MaskMode {
+    use schema::constants::mask_mode::MaskMode as SchemaMaskMode;
+
+    match value {
+        SchemaMaskMode::None => MaskMode::None,
+        SchemaMaskMode::Add => MaskMode::Add,
+        SchemaMaskMode::Subtract => MaskMode::Subtract,
+        SchemaMaskMode::Intersect => MaskMode::Intersect,
+        SchemaMaskMode::Lighten => MaskMode::Lighten,
+        SchemaMaskMode::Darken => MaskMode::Darken,
+        SchemaMaskMode::Difference => MaskMode::Difference,
+    }
+}
+
+pub fn conv_matte_mode(value: &schema::constants::matte_mode::MatteMode) -> Matte {
+    use schema::constants::matte_mode::MatteMode;
+
+    match value {
+        MatteMode::Normal => Matte::Normal,
+        MatteMode::Alpha => Matte::Alpha,
+        MatteMode::InvertedAlpha => Matte::InvertAlpha,
+        MatteMode::Luma => Matte::Luma,
+        MatteMode::InvertedLuma => Matte::InvertLuma,
+    }
+}
+
+pub fn conv_blend_mode(
+    value: &crate::schema::constants::blend_mode::BlendMode,
+) -> Option<BlendMode> {
+    use crate::schema::constants::blend_mode::BlendMode::*;
+
+    Some(match value {
+        Normal => return None,
+        Multiply => BlendMode::from(Mix::Multiply),
+        Screen => BlendMode::from(Mix::Screen),
+        Overlay => BlendMode::from(Mix::Overlay),
+        Darken => BlendMode::from(Mix::Darken),
+        Lighten => BlendMode::from(Mix::Lighten),
+        ColorDodge => BlendMode::from(Mix::ColorDodge),
+        ColorBurn => BlendMode::from(Mix::ColorBurn),
+        HardLight => BlendMode::from(Mix::HardLight),
+        SoftLight => BlendMode::from(Mix::SoftLight),
+        Difference => BlendMode::from(Mix::Difference),
+        Exclusion => BlendMode::from(Mix::Exclusion),
+        Hue => BlendMode::from(Mix::Hue),
+        Saturation => BlendMode::from(Mix::Saturation),
+        Color => BlendMode::from(Mix::Color),
+        Luminosity => BlendMode::from(Mix::Luminosity),
+        Add => BlendMode::new(Mix::Normal, Compose::Plus),
+        // There is no equivalent of hard mix, so those layers blend
+        // normally.
+        HardMix => return None,
+    })
+}
+
+/// Converts the effects of a layer, recording those that are not supported.
+pub fn conv_effects(
+    source: &schema::layers::visual::VisualLayer,
+    unsupported: &mut Vec<Unsupported>,
+) -> Vec<Effect> {
+    use schema::effects::effect::{
+        DISPLACEMENT_MAP_EFFECT, DROP_SHADOW_EFFECT, FILL_EFFECT, LEVELS_EFFECT,
+        LINEAR_WIPE_MATCH_NAME, RADIAL_WIPE_EFFECT, STROKE_EFFECT,
+    };
+
+    let mut effects = vec![];
+    for effect in source.effects.iter().flatten() {
+        if effect.enabled == Some(BoolInt::False) {
+            continue;
+        }
+        if effect.effect_type == DROP_SHADOW_EFFECT {
+            effects.push(Effect::DropShadow(conv_drop_shadow_effect(effect)));
+        } else if effect.effect_type == FILL_EFFECT {
+            effects.push(Effect::Fill(conv_fill_effect(effect)));
+        } else if effect.effect_type == LEVELS_EFFECT {
+            effects.push(Effect::Levels(Box::new(conv_levels_effect(effect))));
+        } else if effect.effect_type == STROKE_EFFECT {
+            effects.push(Effect::Stroke(conv_stroke_effect(effect)));
+        } else if effect.effect_type == RADIAL_WIPE_EFFECT {
+            effects.push(Effect::RadialWipe(conv_radial_wipe_effect(effect)));
+        } else if effect.effect_type == DISPLACEMENT_MAP_EFFECT {
+            effects.push(Effect::DisplacementMap(conv_displacement_map_effect(
+                effect,
+            )));
+        } else if effect.visual_object.match_name.as_deref() == Some(LINEAR_WIPE_MATCH_NAME) {
+            effects.push(Effect::LinearWipe(conv_linear_wipe_effect(effect)));
+        } else {
+            let object = &effect.visual_object;
+            let name = object.name.clone().or_else(|| object.match_name.clone());
+            let name = name.unwrap_or_else(|| format!("type {}", effect.effect_type));
+            unsupported.push(Unsupported::Effect(name));
+        }
+    }
+    effects
+}
+
+/// Converts the layer styles of a layer, recording those that are not
+/// supported.
+pub fn conv_styles(
+    source: &schema::layers::visual::VisualLayer,
+    unsupported: &mut Vec<Unsupported>,
+) -> Vec<LayerStyle> {
+    use schema::styles::AnyLayerStyle;
+
+    let mut styles = vec![];
+    for style in source.styles.iter().flatten() {
+        match style {
+            AnyLayerStyle::Stroke(style) => {
+                styles.push(LayerStyle::Stroke(conv_stroke_style(style)));
+            }
+            AnyLayerStyle::DropShadow(style) => {
+                styles.push(LayerStyle::DropShadow(conv_drop_shadow_style(style)));
+            }
+            AnyLayerStyle::InnerShadow(style) => {
+                styles.push(LayerStyle::InnerShadow(conv_inner_shadow_style(style)));
+            }
+            AnyLayerStyle::OuterGlow(style) => {
+                styles.push(LayerStyle::OuterGlow(conv_outer_glow_style(style)));
+            }
+            AnyLayerStyle::Other(style) => {
+                let name = style.visual_object.name.clone();
+                unsupported.push(Unsupported::Effect(
+                    name.unwrap_or_else(|| "layer style".into()),
+                ));
+            }
+        }
+    }
+    styles
+}
+
+/// Converts the value of an effect at the given position to a scalar.
+fn conv_effect_scalar(
+    effect: &schema::effects::effect::Effect,
+    index: usize,
+    default: f64,
+) -> Value<f64> {
+    use schema::effects::effect_value::EffectValueK;
+
+    match effect.values.get(index).and_then(|v| v.value.as_ref()) {
+        Some(EffectValueK::Scalar(value)) => conv_scalar(value),
+        Some(EffectValueK::Vector(value)) => {
+            conv_multi(value, |x| x.first().copied().unwrap_or(default))
+        }
+        None => Value::Fixed(default),
+    }
+}
+
+/// Converts the value of an effect at the given position to a vector.
+fn conv_effect_vector<T: Tween>(
+    effect: &schema::effects::effect::Effect,
+    index: usize,
+    default: T,
+    f: impl Fn(&Vec<f64>) -> T,
+) -> Value<T> {
+    use schema::effects::effect_value::EffectValueK;
+
+    match effect.values.get(index).and_then(|v| v.value.as_ref()) {
+        Some(EffectValueK::Vector(value)) => conv_multi(value, f),
+        _ => Value::Fixed(default),
+    }
+}
+
+/// Converts the value of an effect at the given position to a color.
+fn conv_effect_color(
+    effect: &schema::effects::effect::Effect,
+    index: usize,
+    default: Color,
+) -> Value<Color> {
+    conv_effect_vector(effect, index, default, |x| {
+        Color::rgb(
+            x.first().copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+            x.get(2).copied().unwrap_or(0.0),
+        )
+    })
+}
+
+fn conv_fill_effect(value: &schema::effects::effect::Effect) -> animated::FillEffect {
+    // Values are identified by their position: mask, all masks, color,
+    // invert, horizontal feather, vertical feather and opacity. Masks are
+    // not supported, so the fill applies to the whole layer.
+    animated::FillEffect {
+        color: conv_effect_color(value, 2, Color::RED),
+        // Opacity ranges from 0 to 1.
+        opacity: conv_effect_scalar(value, 6, 1.0).map(|x| x * 100.0),
+    }
+}
+
+fn conv_stroke_effect(value: &schema::effects::effect::Effect) -> animated::StrokeEffect {
+    // Values are identified by their position: mask, all masks, stroke
+    // sequentially, color, brush size, brush hardness, opacity, start, end,
+    // spacing and paint style. Masks are numbered from 1. Hardness and
+    // spacing are not supported, and each mask is stroked separately.
+    let scalar = |index, default| conv_effect_scalar(value, index, default);
+    let mask = if scalar(1, 0.0).evaluate(0.0) != 0.0 {
+        None
+    } else {
+        Some((scalar(0, 1.0).evaluate(0.0) as usize).saturating_sub(1))
+    };
+    let paint_style = match scalar(10, 1.0).evaluate(0.0) as u32 {
+        2 => StrokePaintStyle::Transparent,
+        3 => StrokePaintStyle::Reveal,
+        _ => StrokePaintStyle::OnOriginal,
+    };
+    animated::StrokeEffect {
+        mask,
+        color: conv_effect_color(value, 3, Color::WHITE),
+        width: scalar(4, 2.0),
+        // Opacity ranges from 0 to 1.
+        opacity: scalar(6, 1.0).map(|x| x * 100.0),
+        start: scalar(7, 0.0),
+        end: scalar(8, 100.0),
+        paint_style,
+    }
+}
+
+fn conv_levels_effect(value: &schema::effects::effect::Effect) -> animated::Levels {
+    // Values are identified by their position: a channel selector and
+    // histogram, followed by groups for the composite, red, green, blue and
+    // alpha channels. Each group holds input black, input white, gamma,
+    // output black and output white between a pair of group markers. Levels
+    // range from 0 to 1.
+    let channel = |start| animated::LevelsChannel {
+        input_black: conv_effect_scalar(value, start, 0.0),
+        input_white: conv_effect_scalar(value, start + 1, 1.0),
+        gamma: conv_effect_scalar(value, start + 2, 1.0),
+        output_black: conv_effect_scalar(value, start + 3, 0.0),
+        output_white: conv_effect_scalar(value, start + 4, 1.0),
+    };
+    animated::Levels {
+        rgb: channel(3),
+        red: channel(10),
+        green: channel(17),
+        blue: channel(24),
+        alpha: channel(31),
+    }
+}
+
+fn conv_drop_shadow_effect(value: &schema::effects::effect::Effect) -> animated::DropShadow {
+    // Values are identified by their position: color, opacity, direction,
+    // distance, softness and shadow only.
+    let scalar = |index, default| conv_effect_scalar(value, index, default);
+    animated::DropShadow {
+        color: conv_effect_color(value, 0, Color::BLACK),
+        // Opacity ranges from 0 to 255.
+        opacity: scalar(1, 127.5).map(|x| x * 100.0 / 255.0),
+        direction: scalar(2, 135.0),
+        distance: scalar(3, 5.0),
+        // Softness is twice the radius.
+        radius: scalar(4, 0.0).map(|x| x / 2.0),
+        shadow_only: scalar(5, 0.0).evaluate(0.0) != 0.0,
+    }
+}
+
+fn conv_linear_wipe_effect(value: &schema::effects::effect::Effect) -> animated::LinearWipe {
+    // Values are identified by their position: completion, angle and
+    // feather.
+    animated::LinearWipe {
+        completion: conv_effect_scalar(value, 0, 0.0),
+        angle: conv_effect_scalar(value, 1, 90.0),
+        feather: conv_effect_scalar(value, 2, 0.0),
+    }
+}
+
+fn conv_radial_wipe_effect(value: &schema::effects::effect::Effect) -> animated::RadialWipe {
+    // Values are identified by their position: completion, start angle,
+    // center, direction and feather.
+    #[allow(clippy::get_first)]
+    let center = conv_effect_vector(value, 2, Point::ZERO, |x| {
+        Point::new(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+        )
+    });
+    let direction = match conv_effect_scalar(value, 3, 1.0).evaluate(0.0) as i32 {
+        2 => WipeDirection::Counterclockwise,
+        3 => WipeDirection::Both,
+        _ => WipeDirection::Clockwise,
+    };
+    animated::RadialWipe {
+        completion: conv_effect_scalar(value, 0, 0.0),
+        start_angle: conv_effect_scalar(value, 1, 0.0),
+        center,
+        direction,
+        feather: conv_effect_scalar(value, 4, 0.0),
+    }
+}
+
+fn conv_displacement_map_effect(
+    value: &schema::effects::effect::Effect,
+) -> animated::DisplacementMap {
+    // Values are identified by their position: map layer, horizontal
+    // channel, maximum horizontal displacement, vertical channel, maximum
+    // vertical displacement, map behavior, edge behavior and expand output.
+    // The map layer is referenced by its index and resolved with the other
+    // layers. Maps are always centered on the layer, and edges and output
+    // bounds do not apply to vector content.
+    let scalar = |index, default| conv_effect_scalar(value, index, default);
+    let channel = |index, default| match scalar(index, default).evaluate(0.0) as u32 {
+        1 => DisplacementChannel::Red,
+        2 => DisplacementChannel::Green,
+        3 => DisplacementChannel::Blue,
+        4 => DisplacementChannel::Alpha,
+        5 => DisplacementChannel::Luminance,
+        6 => DisplacementChannel::Hue,
+        7 => DisplacementChannel::Lightness,
+        8 => DisplacementChannel::Saturation,
+        9 => DisplacementChannel::Full,
+        10 => DisplacementChannel::Half,
+        _ => DisplacementChannel::Off,
+    };
+    let layer = scalar(0, 0.0).evaluate(0.0);
+    animated::DisplacementMap {
+        layer: (layer > 0.0).then_some(layer as usize),
+        horizontal: channel(1, 1.0),
+        max_horizontal: scalar(2, 5.0),
+        vertical: channel(3, 2.0),
+        max_vertical: scalar(4, 5.0),
+    }
+}
+
+fn conv_drop_shadow_style(
+    value: &schema::styles::drop_shadow_style::DropShadowStyle,
+) -> animated::DropShadow {
+    let scalar = |value: &Option<schema::animated_properties::value::FloatValue>, default| {
+        value.as_ref().map_or(Value::Fixed(default), conv_scalar)
+    };
+    animated::DropShadow {
+        color: value
+            .color
+            .as_ref()
+            .map_or(Value::Fixed(Color::BLACK), conv_color),
+        opacity: scalar(&value.opacity, 75.0),
+        // The angle is that of the light, measured counterclockwise from the
+        // right, so the shadow is cast in the opposite direction.
+        direction: scalar(&value.angle, 120.0).map(|x| 270.0 - x),
+        distance: scalar(&value.distance, 5.0),
+        radius: scalar(&value.size, 5.0),
+        shadow_only: false,
+    }
+}
+
+fn conv_inner_shadow_style(
+    value: &schema::styles::inner_shadow_style::InnerShadowStyle,
+) -> animated::DropShadow {
+    let scalar = |value: &Option<schema::animated_properties::value::FloatValue>, default| {
+        value.as_ref().map_or(Value::Fixed(default), conv_scalar)
+    };
+    animated::DropShadow {
+        color: value
+            .color
+            .as_ref()
+            .map_or(Value::Fixed(Color::BLACK), conv_color),
+        opacity: scalar(&value.opacity, 75.0),
+        direction: scalar(&value.angle, 120.0).map(|x| 270.0 - x),
+        distance: scalar(&value.distance, 5.0),
+        radius: scalar(&value.size, 5.0),
+        shadow_only: false,
+    }
+}
+
+fn conv_outer_glow_style(
+    value: &schema::styles::outer_glow_style::OuterGlowStyle,
+) -> animated::OuterGlow {
+    let scalar = |value: &Option<schema::animated_properties::value::FloatValue>, default| {
+        value.as_ref().map_or(Value::Fixed(default), conv_scalar)
+    };
+    animated::OuterGlow {
+        color: value
+            .color
+            .as_ref()
+            .map_or(Value::Fixed(Color::rgb8(255, 255, 190)), conv_color),
+        opacity: scalar(&value.opacity, 75.0),
+        radius: scalar(&value.size, 5.0),
+    }
+}
+
+fn conv_stroke_style(value: &schema::styles::stroke_style::StrokeStyle) -> animated::StrokeStyle {
+    animated::StrokeStyle {
+        color: value
+            .color
+            .as_ref()
+            .map_or(Value::Fixed(Color::RED), conv_color),
+        size: value.size.as_ref().map_or(Value::Fixed(3.0), conv_scalar),
+    }
+}
+
+/// Converts the time stretch of a layer. Layers with a zero stretch would
+/// never advance, so they play at normal speed, as do layers without one.
+pub fn conv_stretch(value: Option<f64>) -> f64 {
+    value
+        .filter(|stretch| stretch.is_finite() && *stretch != 0.0)
+        .unwrap_or(1.0)
+}
+
+/// Converts the in and out points of a layer. Some tools export reversed
+/// layers with the points swapped.
+pub fn conv_frames(in_point: f64, out_point: f64) -> std::ops::Range<f64> {
+    in_point.min(out_point)..in_point.max(out_point)
+}
+
+pub fn conv_scalar(float_value: &schema::animated_properties::value::FloatValue) -> Value<f64> {
+    use crate::schema::animated_properties::animated_property::AnimatedPropertyK::*;
+    let expression = float_value.animated_property.expression.as_deref();
+    let value = match &float_value.animated_property.value {
+        Static(number) => Value::Fixed(*number),
+        AnimatedValue(keyframes) => {
+            let mut frames = vec![];
+            let mut values = vec![];
+            for keyframe in keyframes {
+                let Some(&data) = keyframe.value.first() else {
+                    continue;
+                };
+                frames.push(conv_time(&keyframe.base));
+                values.push(data);
+                // todo: end_value deprecated but should we still push it if it
+                // exists?
+            }
+            Value::Animated(Box::new(model::Animated {
+                times: frames,
+                values,
+                loops: conv_loops(expression),
+            }))
+        }
+    };
+    with_slot(value, float_value.animated_property.slot_id.as_ref())
+}
+
+pub fn conv_multi<T: Tween>(
+    multidimensional: &schema::animated_properties::multi_dimensional::MultiDimensional,
+    f: impl Fn(&Vec<f64>) -> T,
+) -> Value<T> {
+    use crate::schema::animated_properties::animated_property::AnimatedPropertyK::*;
+
+    match &multidimensional.animated_property.value {
+        Static(components) => Value::Fixed(f(components)),
+        AnimatedValue(keyframes) => with_loops(
+            conv_keyframes(keyframes.iter(), |k| f(&k.value)),
+            multidimensional.animated_property.expression.as_deref(),
+        ),
+    }
+}
+
+pub fn conv_multi_color<T: Tween>(
+    color: &schema::animated_properties::color_value::ColorValue,
+    f: impl Fn(&Vec<f64>) -> T,
+) -> Value<T> {
+    use crate::schema::animated_properties::animated_property::AnimatedPropertyK::*;
+
+    match &color.animated_property.value {
+        Static(components) => Value::Fixed(f(components)),
+        AnimatedValue(keyframes) => with_loops(
+            conv_keyframes(keyframes.iter(), |k| f(&k.value)),
+            color.animated_property.expression.as_deref(),
+        ),
+    }
+}
+
+pub fn conv_pos<T: Tween>(
+    position: &schema::animated_properties::position::Position,
+    f: impl Fn(&Vec<f64>) -> T,
+) -> Value<T> {
+    use crate::schema::animated_properties::position::PositionValueK::*;
+
+    match &position.value {
+        Static(components) => Value::Fixed(f(components)),
+        Animated(pos_keyframes) => {
+            // TODO: Are we using PositionKeyframes here how we're supposed to?
+            // there are in_tangents and out_tangents in addition to the keyframes.
+            let roved = rove(pos_keyframes);
+            let keyframes = roved.as_deref().unwrap_or(pos_keyframes);
+            with_loops(
+                conv_keyframes(keyframes.iter().map(|pk| &pk.keyframe), |k| f(&k.value)),
+                position.expression.as_deref(),
+            )
+        }
+    }
+}
+
+/// Number of line segments approximating each segment of a spatial path
+/// when measuring its length.
+const ROVE_SAMPLES: usize = 32;
+
+/// Retimes roving position keyframes so the motion through them has a
+/// constant speed, as in After Effects.
+///
+/// Each run of roving keyframes takes the time between the keyframes around
+/// it, shared in proportion to the length of the spatial path. The first and
+/// last keyframes never rove. Returns `None` if no keyframe is retimed.
+fn rove(
+    keyframes: &[schema::animated_properties::position_keyframe::PositionKeyframe],
+) -> Option<Vec<schema::animated_properties::position_keyframe::PositionKeyframe>> {
+    let is_roving = |i: usize| {
+        i != 0 && i != keyframes.len() - 1 && keyframes[i].roving.as_ref() == Some(&BoolInt::True)
+    };
+    if !(0..keyframes.len()).any(is_roving) {
+        return None;
+    }
+    let mut roved = keyframes.to_vec();
+    let mut start = 0;
+    while start < keyframes.len() - 1 {
+        let end = (start + 1..keyframes.len())
+            .find(|&i| !is_roving(i))
+            .unwrap_or(keyframes.len() - 1);
+        if end > start + 1 {
+            // Length of the path from the start of the run to each keyframe.
+            let mut lengths = vec![0.0];
+            for i in start..end {
+                let length =
+                    lengths[lengths.len() - 1] + rove_length(&keyframes[i], &keyframes[i + 1]);
+                lengths.push(length);
+            }
+            let total = lengths[lengths.len() - 1];
+            let start_time = keyframes[start].keyframe.base.time;
+            let duration = keyframes[end].keyframe.base.time - start_time;
+            for (i, length) in (start + 1..end).zip(&lengths[1..]) {
+                // Without any motion, the time is shared evenly instead.
+                let t = if total > 0.0 {
+                    length / total
+                } else {
+                    (i - start) as f64 / (end - start) as f64
+                };
+                roved[i].keyframe.base.time = start_time + duration * t;
+            }
+        }
+        start = end;
+    }
+    Some(roved)
+}
+
+/// Returns the length of the spatial path between two position keyframes,
+/// along the curve given by their tangents.
+fn rove_length(
+    from: &schema::animated_properties::position_keyframe::PositionKeyframe,
+    to: &schema::animated_properties::position_keyframe::PositionKeyframe,
+) -> f64 {
+    let p0 = &from.keyframe.value;
+    let p3 = &to.keyframe.value;
+    let component = |values: &Vec<f64>, i: usize| values.get(i).copied().unwrap_or(0.0);
+    let tangent = |tangent: &Option<Vec<f64>>, i: usize| {
+        tangent
+            .as_ref()
+            .map_or(0.0, |tangent| component(tangent, i))
+    };
+    let dimensions = p0.len().max(p3.len());
+    let point = |t: f64| {
+        let mt = 1.0 - t;
+        (0..dimensions)
+            .map(|i| {
+                let a = component(p0, i);
+                let d = component(p3, i);
+                let b = a + tangent(&from.value_out_tangent, i);
+                let c = d + tangent(&from.value_in_tangent, i);
+                mt * mt * mt * a + 3.0 * mt * mt * t * b + 3.0 * mt * t * t * c + t * t * t * d
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut length = 0.0;
+    let mut previous = point(0.0);
+    for step in 1..=ROVE_SAMPLES {
+        let next = point(step as f64 / ROVE_SAMPLES as f64);
+        length += previous
+            .iter()
+            .zip(&next)
+            .map(|(a, b)| (b - a) * (b - a))
+            .sum::<f64>()
+            .sqrt();
+        previous = next;
+    }
+    length
+}
+
+/// Binds a value to the slot referenced by its property, if any.
+fn with_slot<T: Tween>(value: Value<T>, slot_id: Option<&String>) -> Value<T> {
+    match slot_id {
+        Some(id) => Value::Slot(Box::new(Slot::new(id.clone(), value))),
+        None => value,
+    }
+}
+
+/// Sets the repetition of the keyframes of a value from its expression.
+fn with_loops<T: Tween>(mut value: Value<T>, expression: Option<&str>) -> Value<T> {
+    if let Value::Animated(animated) = &mut value {
+        animated.loops = conv_loops(expression);
+    }
+    value
+}
+
+/// Converts an expression that only calls `loopIn` or `loopOut`, such as
+/// `loopOut("pingpong")`, to a repetition of keyframes.
+///
+/// Other expressions, and the `offset` and `continue` loop types, are not
+/// evaluated and leave the keyframes unrepeated.
+pub fn conv_loops(expression: Option<&str>) -> Loops {
+    let mut loops = Loops::default();
+    let Some((name, mut args)) = expression.and_then(conv_call) else {
+        return loops;
+    };
+    let mode = match args.next().map(|arg| arg.trim_matches(['"', '\''])) {
+        None | Some("" | "cycle") => LoopMode::Cycle,
+        Some("pingpong") => LoopMode::PingPong,
+        _ => return loops,
+    };
+    let segments = match args.next().map(str::parse::<f64>) {
+        None => 0,
+        Some(Ok(segments)) if segments >= 0.0 => segments as u32,
+        _ => return loops,
+    };
+    if args.next().is_some() {
+        return loops;
+    }
+    let repeat = Some(Loop { mode, segments });
+    match name {
+        "loopIn" => loops.before = repeat,
+        "loopOut" => loops.after = repeat,
+        _ => {}
+    }
+    loops
+}
+
+#[allow(clippy::get_first)]
+pub fn conv_pos_point(value: &schema::animated_properties::position::Position) -> Value<Point> {
+    let point = conv_pos(value, |x| {
+        Point::new(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+        )
+    });
+    with_slot(point, value.slot_id.as_ref())
+}
+
+#[allow(clippy::get_first)]
+pub fn conv_multi_point(
+    value: &schema::animated_properties::multi_dimensional::MultiDimensional,
+) -> Value<Point> {
+    let point = conv_multi(value, |x| {
+        Point::new(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+        )
+    });
+    with_slot(point, value.animated_property.slot_id.as_ref())
+}
+
+#[allow(clippy::get_first)]
+pub fn conv_color(value: &schema::animated_properties::color_value::ColorValue) -> Value<Color> {
+    let color = conv_multi_color(value, |x| {
+        Color::rgb(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+            x.get(2).copied().unwrap_or(0.0),
+        )
+    });
+    with_slot(color, value.animated_property.slot_id.as_ref())
+}
+
+pub fn conv_any_color(
+    value: &schema::animated_properties::color_value::AnyColorValue,
+) -> animated::Brush {
+    use schema::animated_properties::color_value::AnyColorValue;
+
+    match value {
+        AnyColorValue::Color(color) => animated::Brush::Solid(conv_color(color)),
+        AnyColorValue::SplitColor(color) => animated::Brush::SplitSolid((
+            conv_scalar(&color.r),
+            conv_scalar(&color.g),
+            conv_scalar(&color.b),
+        )),
+    }
+}
+
+/// Converts a feather, which may differ horizontally and vertically, to a
+/// single width.
+pub fn conv_feather(value: &MultiDimensional) -> Value<f64> {
+    conv_multi(value, |x| x.iter().copied().fold(0.0, f64::max))
+}
+
+#[allow(clippy::get_first)]
+pub fn conv_vec2(value: &MultiDimensional) -> Value<Vec2> {
+    let vector = conv_multi(value, |x| {
+        Vec2::new(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+        )
+    });
+    with_slot(vector, value.animated_property.slot_id.as_ref())
+}
+
+#[allow(clippy::get_first)]
+pub fn conv_size(value: &MultiDimensional) -> Value<Size> {
+    let size = conv_multi(value, |x| {
+        Size::new(
+            x.get(0).copied().unwrap_or(0.0),
+            x.get(1).copied().unwrap_or(0.0),
+        )
+    });
+    with_slot(size, value.animated_property.slot_id.as_ref())
+}
@@ -29,6 +29,16 @@ pub static FLOAT_VALUE_ONE_HUNDRED: Lazy<FloatValue> = Lazy::new(|| FloatValue {
     },
 });
 
+pub static FLOAT_VALUE_FOUR: Lazy<FloatValue> = Lazy::new(|| FloatValue {
+    animated_property: AnimatedProperty {
+        property_index: None,
+        animated: Some(BoolInt::False),
+        expression: None,
+        slot_id: None,
+        value: AnimatedPropertyK::Static(4.0),
+    },
+});
+
 pub static MULTIDIM_ONE: Lazy<MultiDimensional> = Lazy::new(|| MultiDimensional {
     animated_property: AnimatedProperty {
         property_index: None,
@@ -44,6 +54,7 @@ pub static POSITION_ZERO: Lazy<Position> = Lazy::new(|| Position {
     property_index: None,
     animated: Some(BoolInt::False),
     expression: None,
+    slot_id: None,
     length: None,
     value: schema::animated_properties::position::PositionValueK::Static(vec![0.0, 0.0]),
 });
@@ -0,0 +1,659 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Interpreter for parsed expressions.
+
+use super::parser::{self, BinaryOp, Expr, Stmt, UnaryOp};
+use crate::import::converters::conv_keyframes;
+use crate::schema::animated_properties::keyframe::Keyframe;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+
+/// Maximum depth of expressions evaluated through property references.
+///
+/// This stops reference cycles, which After Effects reports as errors.
+const MAX_DEPTH: usize = 8;
+
+/// Composition that the evaluated layers belong to.
+pub struct Comp<'a> {
+    pub layers: &'a [Json],
+    pub width: f64,
+    pub height: f64,
+    pub frame_rate: f64,
+}
+
+#[derive(Clone, Debug)]
+enum Value<'a> {
+    Number(f64),
+    Array(Vec<f64>),
+    Bool(bool),
+    String(String),
+    Ref(Ref<'a>),
+}
+
+/// Object that is only resolved when its members are accessed.
+#[derive(Copy, Clone, Debug)]
+enum Ref<'a> {
+    Math,
+    Comp,
+    Layer(&'a Json),
+    Transform(&'a Json),
+    Property { layer: &'a Json, property: &'a Json },
+}
+
+/// Evaluates an expression of a property of a layer at the given frame and
+/// returns the components of the result.
+pub fn evaluate<'a>(
+    comp: &Comp<'a>,
+    program: &[Stmt],
+    layer: &'a Json,
+    property: &'a Json,
+    frame: f64,
+) -> Option<Vec<f64>> {
+    let value = Interpreter::new(comp, layer, property, frame, 0).run(program)?;
+    match value {
+        Value::Number(value) => Some(vec![value]),
+        Value::Array(values) => Some(values),
+        Value::Bool(value) => Some(vec![f64::from(u8::from(value))]),
+        _ => None,
+    }
+}
+
+struct Interpreter<'c, 'a> {
+    comp: &'c Comp<'a>,
+    layer: &'a Json,
+    property: &'a Json,
+    frame: f64,
+    depth: usize,
+    variables: HashMap<String, Value<'a>>,
+}
+
+impl<'c, 'a> Interpreter<'c, 'a> {
+    fn new(
+        comp: &'c Comp<'a>,
+        layer: &'a Json,
+        property: &'a Json,
+        frame: f64,
+        depth: usize,
+    ) -> Self {
+        Self {
+            comp,
+            layer,
+            property,
+            frame,
+            depth,
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Runs a program and returns `$bm_rt` if it was assigned, or the value
+    /// of the last expression statement otherwise.
+    fn run(mut self, program: &[Stmt]) -> Option<Value<'a>> {
+        let mut result = None;
+        self.block(program, &mut result)?;
+        let value = self.variables.remove("$bm_rt").or(result)?;
+        self.resolve(value)
+    }
+
+    fn block(&mut self, statements: &[Stmt], result: &mut Option<Value<'a>>) -> Option<()> {
+        for statement in statements {
+            match statement {
+                Stmt::Var(declarations) => {
+                    for (name, value) in declarations {
+                        if let Some(value) = value {
+                            let value = self.expr(value)?;
+                            self.variables.insert(name.clone(), value);
+                        }
+                    }
+                }
+                Stmt::Expr(expr) => *result = Some(self.expr(expr)?),
+                Stmt::If(condition, then, otherwise) => {
+                    let condition = self.expr(condition)?;
+                    if self.truthy(condition)? {
+                        self.block(then, result)?;
+                    } else {
+                        self.block(otherwise, result)?;
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Option<Value<'a>> {
+        match expr {
+            Expr::Number(value) => Some(Value::Number(*value)),
+            Expr::String(value) => Some(Value::String(value.clone())),
+            Expr::Ident(name) => match self.variables.get(name) {
+                Some(value) => Some(value.clone()),
+                None => self.global(name),
+            },
+            Expr::Array(items) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    let item = self.expr(item)?;
+                    values.push(self.number(item)?);
+                }
+                Some(Value::Array(values))
+            }
+            Expr::Member(object, name) => {
+                let object = self.expr(object)?;
+                self.member(object, name)
+            }
+            Expr::Index(object, index) => {
+                let object = self.expr(object)?;
+                let index = self.expr(index)?;
+                let index = self.number(index)?;
+                match self.resolve(object)? {
+                    Value::Array(values) if index >= 0.0 && index.fract() == 0.0 => {
+                        values.get(index as usize).copied().map(Value::Number)
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Call(callee, args) => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(self.expr(arg)?);
+                }
+                match callee.as_ref() {
+                    Expr::Member(object, name) => {
+                        let object = self.expr(object)?;
+                        self.method(object, name, values)
+                    }
+                    Expr::Ident(name) => self.function(name, values),
+                    _ => None,
+                }
+            }
+            Expr::Unary(op, operand) => {
+                let operand = self.expr(operand)?;
+                match op {
+                    UnaryOp::Neg => self.negate(operand),
+                    UnaryOp::Plus => self.number(operand).map(Value::Number),
+                    UnaryOp::Not => self.truthy(operand).map(|value| Value::Bool(!value)),
+                }
+            }
+            Expr::Binary(BinaryOp::And, lhs, rhs) => {
+                let lhs = self.expr(lhs)?;
+                if self.truthy(lhs.clone())? {
+                    self.expr(rhs)
+                } else {
+                    Some(lhs)
+                }
+            }
+            Expr::Binary(BinaryOp::Or, lhs, rhs) => {
+                let lhs = self.expr(lhs)?;
+                if self.truthy(lhs.clone())? {
+                    Some(lhs)
+                } else {
+                    self.expr(rhs)
+                }
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.expr(lhs)?;
+                let rhs = self.expr(rhs)?;
+                self.binary(*op, lhs, rhs)
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                let condition = self.expr(condition)?;
+                if self.truthy(condition)? {
+                    self.expr(then)
+                } else {
+                    self.expr(otherwise)
+                }
+            }
+            Expr::Assign(name, op, value) => {
+                let mut value = self.expr(value)?;
+                if let Some(op) = op {
+                    let current = self.variables.get(name)?.clone();
+                    value = self.binary(*op, current, value)?;
+                }
+                self.variables.insert(name.clone(), value.clone());
+                Some(value)
+            }
+        }
+    }
+
+    fn global(&self, name: &str) -> Option<Value<'a>> {
+        match name {
+            "time" => Some(Value::Number(self.frame / self.comp.frame_rate)),
+            "value" => keyframed_value(self.property, self.frame),
+            "thisComp" => Some(Value::Ref(Ref::Comp)),
+            "thisLayer" => Some(Value::Ref(Ref::Layer(self.layer))),
+            "Math" => Some(Value::Ref(Ref::Math)),
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            // Members of the layer can be accessed without `thisLayer`.
+            _ => self.layer_member(self.layer, name),
+        }
+    }
+
+    fn member(&self, object: Value<'a>, name: &str) -> Option<Value<'a>> {
+        match object {
+            Value::Ref(Ref::Math) => {
+                let value = match name {
+                    "PI" => std::f64::consts::PI,
+                    "E" => std::f64::consts::E,
+                    "LN2" => std::f64::consts::LN_2,
+                    "LN10" => std::f64::consts::LN_10,
+                    "LOG2E" => std::f64::consts::LOG2_E,
+                    "LOG10E" => std::f64::consts::LOG10_E,
+                    "SQRT1_2" => std::f64::consts::FRAC_1_SQRT_2,
+                    "SQRT2" => std::f64::consts::SQRT_2,
+                    _ => return None,
+                };
+                Some(Value::Number(value))
+            }
+            Value::Ref(Ref::Comp) => {
+                let value = match name {
+                    "width" => self.comp.width,
+                    "height" => self.comp.height,
+                    "frameDuration" => 1.0 / self.comp.frame_rate,
+                    "numLayers" => self.comp.layers.len() as f64,
+                    _ => return None,
+                };
+                Some(Value::Number(value))
+            }
+            Value::Ref(Ref::Layer(layer)) => self.layer_member(layer, name),
+            Value::Ref(Ref::Transform(layer)) => transform_member(layer, name),
+            Value::Ref(Ref::Property { layer, property }) if name == "value" => {
+                self.property_value(layer, property, self.frame)
+            }
+            Value::Array(values) if name == "length" => Some(Value::Number(values.len() as f64)),
+            Value::String(value) if name == "length" => {
+                Some(Value::Number(value.chars().count() as f64))
+            }
+            _ => None,
+        }
+    }
+
+    fn layer_member(&self, layer: &'a Json, name: &str) -> Option<Value<'a>> {
+        let number = |key: &str| layer.get(key)?.as_f64();
+        match name {
+            "transform" => Some(Value::Ref(Ref::Transform(layer))),
+            "index" => number("ind").map(Value::Number),
+            "name" => Some(Value::String(layer.get("nm")?.as_str()?.to_string())),
+            "width" => number("w").map(Value::Number),
+            "height" => number("h").map(Value::Number),
+            "inPoint" => Some(Value::Number(number("ip")? / self.comp.frame_rate)),
+            "outPoint" => Some(Value::Number(number("op")? / self.comp.frame_rate)),
+            "startTime" => Some(Value::Number(number("st")? / self.comp.frame_rate)),
+            _ => transform_member(layer, name),
+        }
+    }
+
+    fn method(&mut self, object: Value<'a>, name: &str, args: Vec<Value<'a>>) -> Option<Value<'a>> {
+        match object {
+            Value::Ref(Ref::Math) => {
+                let mut numbers = Vec::with_capacity(args.len());
+                for arg in args {
+                    numbers.push(self.number(arg)?);
+                }
+                math(name, &numbers).map(Value::Number)
+            }
+            Value::Ref(Ref::Comp) if name == "layer" => {
+                let [arg] = <[_; 1]>::try_from(args).ok()?;
+                let layer = match self.resolve(arg)? {
+                    Value::Number(index) => self
+                        .comp
+                        .layers
+                        .iter()
+                        .find(|layer| layer.get("ind").and_then(Json::as_f64) == Some(index)),
+                    Value::String(name) => self
+                        .comp
+                        .layers
+                        .iter()
+                        .find(|layer| layer.get("nm").and_then(Json::as_str) == Some(&name)),
+                    _ => None,
+                }?;
+                Some(Value::Ref(Ref::Layer(layer)))
+            }
+            Value::Ref(Ref::Property { layer, property }) if name == "valueAtTime" => {
+                let [time] = <[_; 1]>::try_from(args).ok()?;
+                let time = self.number(time)?;
+                self.property_value(layer, property, time * self.comp.frame_rate)
+            }
+            _ => None,
+        }
+    }
+
+    fn function(&mut self, name: &str, args: Vec<Value<'a>>) -> Option<Value<'a>> {
+        let mut args = args
+            .into_iter()
+            .map(|arg| self.resolve(arg))
+            .collect::<Option<Vec<_>>>()?;
+        match (name, args.len()) {
+            ("add" | "sum" | "$bm_sum", 2) => {
+                self.binary(BinaryOp::Add, args.remove(0), args.remove(0))
+            }
+            ("sub" | "$bm_sub", 2) => self.binary(BinaryOp::Sub, args.remove(0), args.remove(0)),
+            ("mul" | "$bm_mul", 2) => self.binary(BinaryOp::Mul, args.remove(0), args.remove(0)),
+            ("div" | "$bm_div", 2) => self.binary(BinaryOp::Div, args.remove(0), args.remove(0)),
+            ("$bm_mod", 2) => self.binary(BinaryOp::Rem, args.remove(0), args.remove(0)),
+            ("$bm_neg", 1) => self.negate(args.remove(0)),
+            ("degreesToRadians", 1) => {
+                Some(Value::Number(self.number(args.remove(0))?.to_radians()))
+            }
+            ("radiansToDegrees", 1) => {
+                Some(Value::Number(self.number(args.remove(0))?.to_degrees()))
+            }
+            ("clamp", 3) => {
+                let max = args.pop()?;
+                let min = args.pop()?;
+                let value = args.pop()?;
+                let value = zip(value, min, f64::max)?;
+                zip(value, max, f64::min)
+            }
+            ("length", 1 | 2) => {
+                let mut point = args.remove(0);
+                if let Some(other) = args.pop() {
+                    point = self.binary(BinaryOp::Sub, point, other)?;
+                }
+                let length = match point {
+                    Value::Number(value) => value.abs(),
+                    Value::Array(values) => {
+                        values.iter().map(|value| value * value).sum::<f64>().sqrt()
+                    }
+                    _ => return None,
+                };
+                Some(Value::Number(length))
+            }
+            ("linear", 3 | 5) => {
+                let end = args.pop()?;
+                let start = args.pop()?;
+                let (t, t_min, t_max) = match args.len() {
+                    1 => (self.number(args.remove(0))?, 0.0, 1.0),
+                    _ => (
+                        self.number(args.remove(0))?,
+                        self.number(args.remove(0))?,
+                        self.number(args.remove(0))?,
+                    ),
+                };
+                let weight = if t_max == t_min {
+                    if t <= t_min {
+                        0.0
+                    } else {
+                        1.0
+                    }
+                } else {
+                    ((t - t_min) / (t_max - t_min)).clamp(0.0, 1.0)
+                };
+                let delta = self.binary(BinaryOp::Sub, end, start.clone())?;
+                let delta = self.binary(BinaryOp::Mul, delta, Value::Number(weight))?;
+                self.binary(BinaryOp::Add, start, delta)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a property at the given frame, including its own
+    /// expression.
+    fn property_value(&self, layer: &'a Json, property: &'a Json, frame: f64) -> Option<Value<'a>> {
+        if let Some(source) = property.get("x").and_then(Json::as_str) {
+            if self.depth < MAX_DEPTH {
+                let value = parser::parse(source).and_then(|program| {
+                    Interpreter::new(self.comp, layer, property, frame, self.depth + 1)
+                        .run(&program)
+                });
+                if value.is_some() {
+                    return value;
+                }
+            }
+            // Expressions that cannot be evaluated are left to their
+            // keyframes, which are also what gets rendered.
+        }
+        // Split positions have a property for each dimension.
+        if property.get("s").and_then(Json::as_bool) == Some(true) {
+            let mut values = vec![];
+            for key in ["x", "y", "z"] {
+                let Some(dimension) = property.get(key) else {
+                    continue;
+                };
+                let value = self.property_value(layer, dimension, frame)?;
+                values.push(self.number(value)?);
+            }
+            return Some(Value::Array(values));
+        }
+        keyframed_value(property, frame)
+    }
+
+    /// Resolves property references to their values.
+    fn resolve(&self, value: Value<'a>) -> Option<Value<'a>> {
+        match value {
+            Value::Ref(Ref::Property { layer, property }) => {
+                self.property_value(layer, property, self.frame)
+            }
+            value => Some(value),
+        }
+    }
+
+    fn number(&self, value: Value<'a>) -> Option<f64> {
+        match self.resolve(value)? {
+            Value::Number(value) => Some(value),
+            Value::Bool(value) => Some(f64::from(u8::from(value))),
+            _ => None,
+        }
+    }
+
+    fn truthy(&self, value: Value<'a>) -> Option<bool> {
+        Some(match self.resolve(value)? {
+            Value::Number(value) => value != 0.0 && !value.is_nan(),
+            Value::Bool(value) => value,
+            Value::String(value) => !value.is_empty(),
+            Value::Array(_) | Value::Ref(_) => true,
+        })
+    }
+
+    fn negate(&self, value: Value<'a>) -> Option<Value<'a>> {
+        match self.resolve(value)? {
+            Value::Array(values) => Some(Value::Array(values.iter().map(|v| -v).collect())),
+            value => Some(Value::Number(-self.number(value)?)),
+        }
+    }
+
+    /// Applies a binary operator with the vector semantics of After Effects.
+    fn binary(&self, op: BinaryOp, lhs: Value<'a>, rhs: Value<'a>) -> Option<Value<'a>> {
+        let lhs = self.resolve(lhs)?;
+        let rhs = self.resolve(rhs)?;
+        match (op, lhs, rhs) {
+            (BinaryOp::Add, Value::String(lhs), rhs) => Some(Value::String(lhs + &display(rhs)?)),
+            (BinaryOp::Add, lhs, Value::String(rhs)) => Some(Value::String(display(lhs)? + &rhs)),
+            (BinaryOp::Add | BinaryOp::Sub, Value::Array(lhs), Value::Array(rhs)) => {
+                let len = lhs.len().max(rhs.len());
+                let component = |values: &[f64], i| values.get(i).copied().unwrap_or(0.0);
+                let values = (0..len)
+                    .map(|i| {
+                        let (lhs, rhs) = (component(&lhs, i), component(&rhs, i));
+                        if op == BinaryOp::Add {
+                            lhs + rhs
+                        } else {
+                            lhs - rhs
+                        }
+                    })
+                    .collect();
+                Some(Value::Array(values))
+            }
+            // Adding a number to a vector only offsets its first component.
+            (BinaryOp::Add | BinaryOp::Sub, Value::Array(mut values), rhs) => {
+                let rhs = self.number(rhs)?;
+                let first = values.first_mut()?;
+                *first = if op == BinaryOp::Add {
+                    *first + rhs
+                } else {
+                    *first - rhs
+                };
+                Some(Value::Array(values))
+            }
+            (BinaryOp::Add | BinaryOp::Sub, lhs, Value::Array(mut values)) => {
+                let lhs = self.number(lhs)?;
+                let first = values.first_mut()?;
+                *first = if op == BinaryOp::Add {
+                    lhs + *first
+                } else {
+                    lhs - *first
+                };
+                Some(Value::Array(values))
+            }
+            (BinaryOp::Mul | BinaryOp::Div, Value::Array(values), rhs) => {
+                let rhs = self.number(rhs)?;
+                let values = values
+                    .iter()
+                    .map(|value| {
+                        if op == BinaryOp::Mul {
+                            value * rhs
+                        } else {
+                            value / rhs
+                        }
+                    })
+                    .collect();
+                Some(Value::Array(values))
+            }
+            (BinaryOp::Mul, lhs, Value::Array(values)) => {
+                let lhs = self.number(lhs)?;
+                Some(Value::Array(
+                    values.iter().map(|value| lhs * value).collect(),
+                ))
+            }
+            (BinaryOp::Eq | BinaryOp::Ne, lhs, rhs) => {
+                let equal = match (lhs, rhs) {
+                    (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+                    (
+                        lhs @ (Value::Number(_) | Value::Bool(_)),
+                        rhs @ (Value::Number(_) | Value::Bool(_)),
+                    ) => self.number(lhs)? == self.number(rhs)?,
+                    _ => false,
+                };
+                Some(Value::Bool(equal == (op == BinaryOp::Eq)))
+            }
+            (op, lhs, rhs) => {
+                let lhs = self.number(lhs)?;
+                let rhs = self.number(rhs)?;
+                Some(match op {
+                    BinaryOp::Add => Value::Number(lhs + rhs),
+                    BinaryOp::Sub => Value::Number(lhs - rhs),
+                    BinaryOp::Mul => Value::Number(lhs * rhs),
+                    BinaryOp::Div => Value::Number(lhs / rhs),
+                    BinaryOp::Rem => Value::Number(lhs % rhs),
+                    BinaryOp::Lt => Value::Bool(lhs < rhs),
+                    BinaryOp::Le => Value::Bool(lhs <= rhs),
+                    BinaryOp::Gt => Value::Bool(lhs > rhs),
+                    BinaryOp::Ge => Value::Bool(lhs >= rhs),
+                    BinaryOp::Eq | BinaryOp::Ne | BinaryOp::And | BinaryOp::Or => return None,
+                })
+            }
+        }
+    }
+}
+
+fn transform_member<'a>(layer: &'a Json, name: &str) -> Option<Value<'a>> {
+    let transform = layer.get("ks")?;
+    let key = match name {
+        "anchorPoint" => "a",
+        "position" => "p",
+        "scale" => "s",
+        "rotation" | "zRotation" if transform.get("r").is_some() => "r",
+        "rotation" | "zRotation" => "rz",
+        "xRotation" => "rx",
+        "yRotation" => "ry",
+        "orientation" => "or",
+        "opacity" => "o",
+        "skew" => "sk",
+        "skewAxis" => "sa",
+        _ => return None,
+    };
+    let property = transform.get(key)?;
+    Some(Value::Ref(Ref::Property { layer, property }))
+}
+
+/// Returns the value of the keyframes of a property, ignoring its
+/// expression.
+fn keyframed_value<'a>(property: &Json, frame: f64) -> Option<Value<'a>> {
+    let values = match property.get("k")? {
+        Json::Number(value) => return value.as_f64().map(Value::Number),
+        Json::Array(items) if items.iter().all(Json::is_number) => {
+            items.iter().map(Json::as_f64).collect::<Option<Vec<_>>>()?
+        }
+        keyframes => {
+            let keyframes: Vec<Keyframe> = serde_json::from_value(keyframes.clone()).ok()?;
+            let len = keyframes.first()?.value.len();
+            (0..len)
+                .map(|i| {
+                    conv_keyframes(keyframes.iter(), |keyframe| {
+                        keyframe.value.get(i).copied().unwrap_or_default()
+                    })
+                    .evaluate(frame)
+                })
+                .collect()
+        }
+    };
+    Some(match values[..] {
+        [value] => Value::Number(value),
+        _ => Value::Array(values),
+    })
+}
+
+/// Applies a function to each component, broadcasting numbers over arrays.
+fn zip<'a>(lhs: Value<'a>, rhs: Value<'a>, f: fn(f64, f64) -> f64) -> Option<Value<'a>> {
+    match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => Some(Value::Number(f(lhs, rhs))),
+        (Value::Array(lhs), Value::Number(rhs)) => {
+            Some(Value::Array(lhs.iter().map(|lhs| f(*lhs, rhs)).collect()))
+        }
+        (Value::Array(lhs), Value::Array(rhs)) => Some(Value::Array(
+            lhs.iter()
+                .zip(&rhs)
+                .map(|(lhs, rhs)| f(*lhs, *rhs))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+fn display(value: Value) -> Option<String> {
+    Some(match value {
+        Value::Number(value) => value.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::String(value) => value,
+        Value::Array(values) => values
+            .iter()
+            .map(f64::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::Ref(_) => return None,
+    })
+}
+
+/// Evaluates a function of the `Math` object.
+fn math(name: &str, args: &[f64]) -> Option<f64> {
+    Some(match (name, args) {
+        ("abs", [x]) => x.abs(),
+        ("acos", [x]) => x.acos(),
+        ("asin", [x]) => x.asin(),
+        ("atan", [x]) => x.atan(),
+        ("atan2", [y, x]) => y.atan2(*x),
+        ("cbrt", [x]) => x.cbrt(),
+        ("ceil", [x]) => x.ceil(),
+        ("cos", [x]) => x.cos(),
+        ("cosh", [x]) => x.cosh(),
+        ("exp", [x]) => x.exp(),
+        ("floor", [x]) => x.floor(),
+        ("hypot", _) => args.iter().map(|x| x * x).sum::<f64>().sqrt(),
+        ("log", [x]) => x.ln(),
+        ("log10", [x]) => x.log10(),
+        ("log2", [x]) => x.log2(),
+        ("max", _) => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        ("min", _) => args.iter().copied().fold(f64::INFINITY, f64::min),
+        ("pow", [x, y]) => x.powf(*y),
+        // Halves round up, as in JavaScript.
+        ("round", [x]) => (x + 0.5).floor(),
+        ("sign", [x]) if *x == 0.0 => 0.0,
+        ("sign", [x]) => x.signum(),
+        ("sin", [x]) => x.sin(),
+        ("sinh", [x]) => x.sinh(),
+        ("sqrt", [x]) => x.sqrt(),
+        ("tan", [x]) => x.tan(),
+        ("tanh", [x]) => x.tanh(),
+        ("trunc", [x]) => x.trunc(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,216 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Evaluation of a subset of expressions.
+//!
+//! Expressions are baked into linear keyframes at every frame of their layer
+//! before the animation is deserialized, so the rest of the import and the
+//! renderer never see them. Properties with expressions outside of the
+//! subset keep their keyframes, as they do without the `expressions`
+//! feature.
+
+mod interpreter;
+mod parser;
+
+use interpreter::Comp;
+use serde_json::{json, Value as Json};
+
+/// Maximum number of frames baked for a single property.
+const MAX_FRAMES: f64 = 10_000.0;
+
+/// Replaces the expressions of an animation with the keyframes that they
+/// evaluate to.
+pub fn bake(mut animation: Json) -> Json {
+    let baked = {
+        let animation = &animation;
+        let number = |key: &str| animation.get(key).and_then(Json::as_f64);
+        let frame_rate = number("fr").filter(|fr| *fr > 0.0).unwrap_or(60.0);
+        let mut compositions = vec![("/layers".to_string(), animation.get("layers"))];
+        if let Some(assets) = animation.get("assets").and_then(Json::as_array) {
+            for (i, asset) in assets.iter().enumerate() {
+                compositions.push((format!("/assets/{i}/layers"), asset.get("layers")));
+            }
+        }
+        let mut baked = vec![];
+        for (pointer, layers) in compositions {
+            let Some(layers) = layers.and_then(Json::as_array) else {
+                continue;
+            };
+            let comp = Comp {
+                layers,
+                width: number("w").unwrap_or_default(),
+                height: number("h").unwrap_or_default(),
+                frame_rate,
+            };
+            for (i, layer) in layers.iter().enumerate() {
+                let mut properties = vec![];
+                find_expressions(layer, &mut format!("{pointer}/{i}"), &mut properties);
+                for (pointer, property) in properties {
+                    if let Some(value) = bake_property(&comp, layer, property) {
+                        baked.push((pointer, value));
+                    }
+                }
+            }
+        }
+        baked
+    };
+    for (pointer, value) in baked {
+        let Some(Json::Object(property)) = animation.pointer_mut(&pointer) else {
+            continue;
+        };
+        let animated = value
+            .as_array()
+            .is_some_and(|k| k.iter().all(Json::is_object));
+        property.remove("x");
+        property.insert("a".to_string(), json!(u8::from(animated)));
+        property.insert("k".to_string(), value);
+    }
+    animation
+}
+
+/// Collects the properties with an expression, along with their JSON
+/// pointers.
+fn find_expressions<'a>(value: &'a Json, pointer: &mut String, out: &mut Vec<(String, &'a Json)>) {
+    let len = pointer.len();
+    match value {
+        Json::Object(map) => {
+            if map.get("x").is_some_and(Json::is_string) && map.contains_key("k") {
+                out.push((pointer.clone(), value));
+                return;
+            }
+            for (key, child) in map {
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                find_expressions(child, pointer, out);
+                pointer.truncate(len);
+            }
+        }
+        Json::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                pointer.push_str(&format!("/{i}"));
+                find_expressions(child, pointer, out);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Evaluates the expression of a property at every frame of its layer and
+/// returns the new value of its `k` field.
+fn bake_property(comp: &Comp, layer: &Json, property: &Json) -> Option<Json> {
+    let program = parser::parse(property.get("x")?.as_str()?)?;
+    let in_point = layer.get("ip")?.as_f64()?;
+    let out_point = layer.get("op")?.as_f64()?;
+    // Reversed layers may have their points swapped.
+    let start = in_point.min(out_point).floor();
+    let end = in_point.max(out_point).ceil();
+    if !(0.0..=MAX_FRAMES).contains(&(end - start)) {
+        return None;
+    }
+    let mut keyframes = vec![];
+    for i in 0..=(end - start) as usize {
+        let frame = start + i as f64;
+        let value = interpreter::evaluate(comp, &program, layer, property, frame)?;
+        // Non-finite numbers cannot be represented in JSON.
+        if !value.iter().all(|value| value.is_finite()) {
+            return None;
+        }
+        keyframes.push((frame, value));
+    }
+    let first = &keyframes.first()?.1;
+    if keyframes.iter().all(|(_, value)| value == first) {
+        // Scalar properties only accept a number as their static value.
+        let scalar = match property.get("k")? {
+            Json::Number(_) => true,
+            Json::Array(k) => k
+                .first()
+                .and_then(|keyframe| keyframe.get("s"))
+                .and_then(Json::as_array)
+                .is_some_and(|value| value.len() == 1),
+            _ => false,
+        };
+        return Some(match first[..] {
+            [value] if scalar => json!(value),
+            _ => json!(first),
+        });
+    }
+    let keyframes = keyframes
+        .into_iter()
+        .map(|(frame, value)| json!({ "t": frame, "s": value }))
+        .collect();
+    Some(Json::Array(keyframes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bake;
+    use crate::Composition;
+    use serde_json::json;
+
+    #[test]
+    fn test_bake() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 30, "w": 200, "h": 100,
+            "layers": [
+                {
+                    "ty": 3, "ind": 1, "nm": "Leader", "ip": 0, "op": 30, "st": 0,
+                    "ks": {
+                        "p": { "a": 1, "k": [
+                            { "t": 0, "s": [0, 0] },
+                            { "t": 30, "s": [60, 30] }
+                        ] },
+                        "r": { "a": 0, "k": 0, "x": "var $bm_rt;\n$bm_rt = $bm_mul(time, 90);" }
+                    }
+                },
+                {
+                    "ty": 3, "ind": 2, "ip": 0, "op": 30, "st": 0,
+                    "ks": {
+                        "p": {
+                            "a": 0, "k": [0, 0],
+                            "x": "thisComp.layer('Leader').transform.position + [10, -value[1]]"
+                        },
+                        "o": { "a": 0, "k": 100, "x": "Math.min(thisComp.width / 4, 50)" },
+                        "s": { "a": 0, "k": [100, 100], "x": "wiggle(2, 10)" }
+                    }
+                }
+            ]
+        });
+        let baked = bake(source.clone());
+        let rotation = &baked["layers"][0]["ks"]["r"];
+        assert_eq!(rotation["a"], 1);
+        assert_eq!(rotation["x"], json!(null));
+        assert_eq!(rotation["k"][15], json!({ "t": 15.0, "s": [45.0] }));
+        assert_eq!(
+            baked["layers"][1]["ks"]["p"]["k"][10],
+            json!({ "t": 10.0, "s": [30.0, 10.0] })
+        );
+        // Constant results become static values.
+        assert_eq!(baked["layers"][1]["ks"]["o"], json!({ "a": 0, "k": 50.0 }));
+        // Unsupported expressions are left alone.
+        assert_eq!(
+            baked["layers"][1]["ks"]["s"],
+            source["layers"][1]["ks"]["s"]
+        );
+
+        let composition = Composition::from_json(source).unwrap();
+        let transform = composition.layers[1].transform.evaluate(20.0);
+        assert_eq!(transform.into_owned().translation().x, 50.0);
+    }
+
+    #[test]
+    fn test_bake_nesting() {
+        let depth = 100_000;
+        let expression = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 30, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ip": 0, "op": 30, "st": 0,
+                "ks": { "o": { "a": 0, "k": 50, "x": expression } }
+            }]
+        });
+        // Expressions nested too deeply to parse leave the property static.
+        let baked = bake(source.clone());
+        assert_eq!(baked, source);
+    }
+}
@@ -0,0 +1,425 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Parser for the subset of JavaScript used by expressions.
+
+/// Binary operator.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// Unary operator.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UnaryOp {
+    Neg,
+    Plus,
+    Not,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expr {
+    Number(f64),
+    String(String),
+    Ident(String),
+    Array(Vec<Expr>),
+    Member(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// Assignment to a variable, with the operator of a compound assignment.
+    Assign(String, Option<BinaryOp>, Box<Expr>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Stmt {
+    Var(Vec<(String, Option<Expr>)>),
+    Expr(Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    String(String),
+    Ident(String),
+    Punct(&'static str),
+}
+
+/// Punctuation, longest first so that the longest match wins.
+const PUNCTUATION: &[&str] = &[
+    "===", "!==", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "+", "-", "*", "/",
+    "%", "<", ">", "!", "?", ":", "(", ")", "[", "]", "{", "}", ",", ";", ".", "=",
+];
+
+fn tokenize(source: &str) -> Option<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = source;
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("/*") {
+            rest = &comment[comment.find("*/")? + 2..];
+            continue;
+        }
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let mut end = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            // Exponents, such as 1e-5.
+            if rest[end..].starts_with(['e', 'E']) {
+                let exponent = &rest[end + 1..];
+                let sign = usize::from(exponent.starts_with(['+', '-']));
+                let digits = exponent[sign..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(exponent.len() - sign);
+                if digits > 0 {
+                    end += 1 + sign + digits;
+                }
+            }
+            tokens.push(Token::Number(rest[..end].parse().ok()?));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            let mut chars = rest[1..].char_indices();
+            loop {
+                let (i, next) = chars.next()?;
+                match next {
+                    '\\' => value.push(chars.next()?.1),
+                    _ if next == c => {
+                        rest = &rest[i + 2..];
+                        break;
+                    }
+                    _ => value.push(next),
+                }
+            }
+            tokens.push(Token::String(value));
+        } else {
+            let punct = PUNCTUATION.iter().find(|punct| rest.starts_with(**punct))?;
+            tokens.push(Token::Punct(punct));
+            rest = &rest[punct.len()..];
+        }
+    }
+    Some(tokens)
+}
+
+/// Maximum nesting of expressions and blocks, beyond which sources are
+/// rejected rather than risk overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Parses an expression into a list of statements, or returns `None` if it
+/// uses syntax outside of the supported subset or nests too deeply.
+pub fn parse(source: &str) -> Option<Vec<Stmt>> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        depth: 0,
+    };
+    let mut statements = vec![];
+    while parser.pos < parser.tokens.len() {
+        if !parser.eat(";") {
+            statements.push(parser.statement()?);
+        }
+    }
+    Some(statements)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Current nesting of expressions, unary operators and blocks.
+    depth: usize,
+}
+
+impl Parser {
+    /// Parses one level deeper, failing beyond `MAX_DEPTH`.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Option<()> {
+        self.eat(punct).then_some(())
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.next()? {
+            Token::Ident(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn statement(&mut self) -> Option<Stmt> {
+        let statement =
+            if self.eat_keyword("var") || self.eat_keyword("let") || self.eat_keyword("const") {
+                let mut declarations = vec![];
+                loop {
+                    let name = self.ident()?;
+                    let value = if self.eat("=") {
+                        Some(self.expression()?)
+                    } else {
+                        None
+                    };
+                    declarations.push((name, value));
+                    if !self.eat(",") {
+                        break;
+                    }
+                }
+                Stmt::Var(declarations)
+            } else if self.eat_keyword("if") {
+                self.expect("(")?;
+                let condition = self.expression()?;
+                self.expect(")")?;
+                let then = self.nested(Self::block)?;
+                let otherwise = if self.eat_keyword("else") {
+                    self.nested(Self::block)?
+                } else {
+                    vec![]
+                };
+                return Some(Stmt::If(condition, then, otherwise));
+            } else {
+                Stmt::Expr(self.expression()?)
+            };
+        // Statements end with a semicolon, a closing brace or the end of the
+        // source.
+        if !self.eat(";") && !matches!(self.peek(), None | Some(Token::Punct("}"))) {
+            return None;
+        }
+        Some(statement)
+    }
+
+    /// Parses a braced block or a single statement.
+    fn block(&mut self) -> Option<Vec<Stmt>> {
+        if !self.eat("{") {
+            return Some(vec![self.statement()?]);
+        }
+        let mut statements = vec![];
+        while !self.eat("}") {
+            if !self.eat(";") {
+                statements.push(self.statement()?);
+            }
+        }
+        Some(statements)
+    }
+
+    fn expression(&mut self) -> Option<Expr> {
+        self.nested(Self::assignment)
+    }
+
+    fn assignment(&mut self) -> Option<Expr> {
+        let target = self.conditional()?;
+        let op = match self.peek() {
+            Some(Token::Punct("=")) => None,
+            Some(Token::Punct("+=")) => Some(BinaryOp::Add),
+            Some(Token::Punct("-=")) => Some(BinaryOp::Sub),
+            Some(Token::Punct("*=")) => Some(BinaryOp::Mul),
+            Some(Token::Punct("/=")) => Some(BinaryOp::Div),
+            _ => return Some(target),
+        };
+        let Expr::Ident(name) = target else {
+            return None;
+        };
+        self.pos += 1;
+        Some(Expr::Assign(name, op, Box::new(self.expression()?)))
+    }
+
+    fn conditional(&mut self) -> Option<Expr> {
+        let condition = self.binary(0)?;
+        if !self.eat("?") {
+            return Some(condition);
+        }
+        let then = self.expression()?;
+        self.expect(":")?;
+        let otherwise = self.expression()?;
+        Some(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    /// Parses binary operators that bind at least as tightly as
+    /// `min_precedence`.
+    fn binary(&mut self, min_precedence: u8) -> Option<Expr> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Punct(punct)) = self.peek() {
+            let (op, precedence) = match *punct {
+                "||" => (BinaryOp::Or, 0),
+                "&&" => (BinaryOp::And, 1),
+                "==" | "===" => (BinaryOp::Eq, 2),
+                "!=" | "!==" => (BinaryOp::Ne, 2),
+                "<" => (BinaryOp::Lt, 3),
+                "<=" => (BinaryOp::Le, 3),
+                ">" => (BinaryOp::Gt, 3),
+                ">=" => (BinaryOp::Ge, 3),
+                "+" => (BinaryOp::Add, 4),
+                "-" => (BinaryOp::Sub, 4),
+                "*" => (BinaryOp::Mul, 5),
+                "/" => (BinaryOp::Div, 5),
+                "%" => (BinaryOp::Rem, 5),
+                _ => break,
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(precedence + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<Expr> {
+        let op = if self.eat("-") {
+            UnaryOp::Neg
+        } else if self.eat("+") {
+            UnaryOp::Plus
+        } else if self.eat("!") {
+            UnaryOp::Not
+        } else {
+            return self.postfix();
+        };
+        Some(Expr::Unary(op, Box::new(self.nested(Self::unary)?)))
+    }
+
+    fn postfix(&mut self) -> Option<Expr> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat(".") {
+                expr = Expr::Member(Box::new(expr), self.ident()?);
+            } else if self.eat("[") {
+                let index = self.expression()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.eat("(") {
+                let args = self.list(")")?;
+                expr = Expr::Call(Box::new(expr), args);
+            } else {
+                return Some(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Option<Expr> {
+        match self.next()? {
+            Token::Number(value) => Some(Expr::Number(value)),
+            Token::String(value) => Some(Expr::String(value)),
+            Token::Ident(name) => Some(Expr::Ident(name)),
+            Token::Punct("(") => {
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Some(expr)
+            }
+            Token::Punct("[") => Some(Expr::Array(self.list("]")?)),
+            Token::Punct(_) => None,
+        }
+    }
+
+    /// Parses comma separated expressions up to the closing punctuation.
+    fn list(&mut self, close: &str) -> Option<Vec<Expr>> {
+        let mut items = vec![];
+        while !self.eat(close) {
+            items.push(self.expression()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Some(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, BinaryOp, Expr, Stmt};
+
+    #[test]
+    fn test_precedence() {
+        let statements = parse("var $bm_rt; $bm_rt = 1 + 2 * time; // spin").unwrap();
+        assert_eq!(statements.len(), 2);
+        let Stmt::Expr(Expr::Assign(name, None, value)) = &statements[1] else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(name, "$bm_rt");
+        let Expr::Binary(BinaryOp::Add, _, rhs) = value.as_ref() else {
+            panic!("expected an addition");
+        };
+        assert!(matches!(rhs.as_ref(), Expr::Binary(BinaryOp::Mul, ..)));
+        // Loops and functions are outside of the subset.
+        assert_eq!(parse("for (var i = 0; i < 2; i++) {}"), None);
+    }
+
+    #[test]
+    fn test_depth() {
+        let nested = |open: &str, close: &str, depth| {
+            format!("{}1{}", open.repeat(depth), close.repeat(depth))
+        };
+        assert!(parse(&nested("(", ")", 16)).is_some());
+        assert!(parse(&nested("!", "", 16)).is_some());
+        assert!(parse(&nested("if (1) ", "", 16)).is_some());
+        // Deeper sources are rejected instead of overflowing the stack.
+        assert_eq!(parse(&nested("(", ")", 100_000)), None);
+        assert_eq!(parse(&nested("!", "", 100_000)), None);
+        assert_eq!(parse(&nested("[", "]", 100_000)), None);
+        assert_eq!(parse(&nested("if (1) ", "", 100_000)), None);
+    }
+}
@@ -4,5 +4,9 @@
 mod builders;
 mod converters;
 mod defaults;
+#[cfg(feature = "expressions")]
+mod expressions;
 
 pub use converters::conv_animation;
+#[cfg(feature = "expressions")]
+pub use expressions::bake as bake_expressions;
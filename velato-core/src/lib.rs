@@ -0,0 +1,33 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Parse Lottie animations into a [`Composition`] and evaluate them.
+//!
+//! This is the part of [velato](https://docs.rs/velato) that does not
+//! depend on vello or a GPU stack, for servers, tools and tests that only
+//! need to inspect or evaluate animations. Applications that render should
+//! use velato, which re-exports everything in this crate.
+//!
+//! This crate also re-exports [`kurbo`] and [`peniko`], which are the
+//! versions used by the vello release that velato supports.
+
+pub mod animation;
+pub(crate) mod import;
+pub(crate) mod runtime;
+pub(crate) mod schema;
+
+mod error;
+pub use error::Error;
+
+pub use kurbo;
+pub use peniko;
+
+pub use runtime::{
+    model, AnimatedProperty, AudioEvent, AudioPlayback, Bundle, Canvas, Composition, Digest,
+    ImportOptions, JoinFallback, KeyframeEvent, KeyframeObserver, KeyframeSample, KeyframeSelector,
+    KeyframeTable, LayerProperty, Painter, PlayMode, Playhead, PlayheadEvent, PropertyFilter,
+    RenderOptions, Selector, SelectorSegment, SlotValue, Summary, Theme,
+};
+
+#[cfg(feature = "arbitrary")]
+pub use runtime::ArbitraryLottie;
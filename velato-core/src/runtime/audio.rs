@@ -0,0 +1,82 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Composition;
+
+/// Change in the sounds that should be playing, reported by
+/// [`AudioPlayback::update`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AudioEvent {
+    /// The track with the given index in [`Composition::audio_tracks`]
+    /// should start playing from the given time within the sound, in
+    /// seconds.
+    Start {
+        /// Index of the track.
+        track: usize,
+        /// Time within the sound at which to start.
+        offset: f64,
+    },
+    /// The track with the given index should stop playing.
+    Stop {
+        /// Index of the track.
+        track: usize,
+    },
+}
+
+/// Follows playback of a composition to report when its sounds should start
+/// and stop, so that applications can play them in sync with the animation.
+#[derive(Clone, Default, Debug)]
+pub struct AudioPlayback {
+    playing: Vec<bool>,
+    last_frame: Option<f64>,
+}
+
+impl AudioPlayback {
+    /// Creates a new playback with no playing tracks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops every playing track, as when playback is paused, and forgets
+    /// the previous frame.
+    pub fn reset(&mut self) -> Vec<AudioEvent> {
+        self.last_frame = None;
+        let mut events = vec![];
+        for (track, playing) in self.playing.iter_mut().enumerate() {
+            if std::mem::take(playing) {
+                events.push(AudioEvent::Stop { track });
+            }
+        }
+        events
+    }
+
+    /// Advances playback to the given frame and returns the tracks that
+    /// should start or stop.
+    ///
+    /// A decrease in frame is treated as the animation looping back or
+    /// seeking, which restarts tracks that are still playing from their new
+    /// offset.
+    pub fn update(&mut self, composition: &Composition, frame: f64) -> Vec<AudioEvent> {
+        let tracks = composition.audio_tracks();
+        let mut events = vec![];
+        // Tracks of a previous composition are stopped.
+        if self.playing.len() != tracks.len() {
+            events = self.reset();
+            self.playing.resize(tracks.len(), false);
+        }
+        let rewound = self.last_frame.is_some_and(|last_frame| frame < last_frame);
+        self.last_frame = Some(frame);
+        for (track, (source, playing)) in tracks.iter().zip(&mut self.playing).enumerate() {
+            let active = source.is_active(frame);
+            if *playing && (!active || rewound) {
+                events.push(AudioEvent::Stop { track });
+            }
+            if active && (!*playing || rewound) {
+                let offset = source.offset(frame, composition.frame_rate);
+                events.push(AudioEvent::Start { track, offset });
+            }
+            *playing = active;
+        }
+        events
+    }
+}
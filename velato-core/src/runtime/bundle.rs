@@ -0,0 +1,282 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Composition;
+use crate::Error;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Newest major version of the dotLottie format that bundles are read from.
+const FORMAT_VERSION: u32 = 2;
+
+/// Animations of a dotLottie archive, with the images they share and the
+/// animation that is active.
+///
+/// The archive is given as its entries, with their paths within the archive
+/// and their contents, so that embedders can read it with the zip library
+/// of their choice. Animations are the entries under `animations/` (`a/` in
+/// version 2 of the format), named by their file name without the `.json`
+/// extension, and images are the entries under `images/` (`i/` in version
+/// 2), named by their file name, which is the path (`p`) of the image
+/// assets that refer to them.
+///
+/// Every animation is parsed when the bundle is created, so switching the
+/// active animation does not read the archive again.
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    /// Animations in the order of the manifest, or by name if the archive
+    /// has no manifest.
+    animations: Vec<(String, Composition)>,
+    images: HashMap<String, Vec<u8>>,
+    active: usize,
+}
+
+impl Bundle {
+    /// Creates a bundle from the entries of a dotLottie archive.
+    ///
+    /// The animations are those listed in `manifest.json`, and the active
+    /// animation is the one that the manifest makes active at first
+    /// (`activeAnimationId`, or `initial.animation` in version 2 of the
+    /// format), or otherwise the first. Without a manifest, every
+    /// animation of the archive is used, in order of name.
+    ///
+    /// Archives whose manifest has a major `version` newer than the
+    /// supported one, or one that is not a number, are rejected with
+    /// [`Error::UnsupportedVersion`] rather than read as an older version.
+    /// Minor versions of a supported version are read as that version.
+    pub fn from_entries<P: AsRef<str>, B: AsRef<[u8]>>(
+        entries: impl IntoIterator<Item = (P, B)>,
+    ) -> Result<Self, Error> {
+        let mut manifest = None;
+        let mut sources = HashMap::new();
+        let mut images = HashMap::new();
+        for (path, contents) in entries {
+            let path = path.as_ref().trim_start_matches('/');
+            let contents = contents.as_ref();
+            if path == "manifest.json" {
+                manifest = Some(serde_json::from_slice::<Value>(contents)?);
+            } else if let Some(name) = strip_dir(path, "animations/", "a/") {
+                if let Some(id) = name.strip_suffix(".json") {
+                    sources.insert(id.to_string(), contents.to_vec());
+                }
+            } else if let Some(name) = strip_dir(path, "images/", "i/") {
+                if !name.is_empty() {
+                    images.insert(name.to_string(), contents.to_vec());
+                }
+            }
+        }
+        let (ids, active) = match &manifest {
+            Some(manifest) => {
+                check_version(manifest)?;
+                let ids = manifest
+                    .get("animations")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|animation| animation.get("id")?.as_str())
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                let active = manifest
+                    .get("activeAnimationId")
+                    .or_else(|| manifest.get("initial")?.get("animation"))
+                    .and_then(Value::as_str)
+                    .map(String::from);
+                (ids, active)
+            }
+            None => {
+                let mut ids = sources.keys().cloned().collect::<Vec<_>>();
+                ids.sort();
+                (ids, None)
+            }
+        };
+        let mut animations = Vec::with_capacity(ids.len());
+        for id in ids {
+            let source = sources
+                .get(&id)
+                .ok_or_else(|| Error::MissingAnimation(id.clone()))?;
+            let composition = Composition::from_slice(source)?;
+            animations.push((id, composition));
+        }
+        if animations.is_empty() {
+            return Err(Error::AnimationNotFound);
+        }
+        let active = active
+            .and_then(|active| animations.iter().position(|(id, _)| *id == active))
+            .unwrap_or(0);
+        Ok(Self {
+            animations,
+            images,
+            active,
+        })
+    }
+
+    /// Returns the names of the animations, in the order of the manifest.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.animations.iter().map(|(id, _)| id.as_str())
+    }
+
+    /// Returns the animation with the given name.
+    pub fn get(&self, id: &str) -> Option<&Composition> {
+        self.animations
+            .iter()
+            .find(|(animation, _)| animation == id)
+            .map(|(_, composition)| composition)
+    }
+
+    /// Returns the name of the active animation.
+    pub fn active_id(&self) -> &str {
+        &self.animations[self.active].0
+    }
+
+    /// Returns the active animation.
+    pub fn active(&self) -> &Composition {
+        &self.animations[self.active].1
+    }
+
+    /// Makes the animation with the given name active, and returns false
+    /// if there is no such animation.
+    pub fn set_active(&mut self, id: &str) -> bool {
+        match self
+            .animations
+            .iter()
+            .position(|(animation, _)| animation == id)
+        {
+            Some(active) => {
+                self.active = active;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the images shared by the animations, by file name.
+    pub fn images(&self) -> &HashMap<String, Vec<u8>> {
+        &self.images
+    }
+}
+
+/// Checks that the major version of a manifest is one that can be read.
+/// Manifests without a version are from the first version of the format.
+fn check_version(manifest: &Value) -> Result<(), Error> {
+    let version = match manifest.get("version") {
+        None => return Ok(()),
+        Some(Value::String(version)) => version.clone(),
+        Some(version) => version.to_string(),
+    };
+    let major = version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok());
+    match major {
+        Some(1..=FORMAT_VERSION) => Ok(()),
+        _ => Err(Error::UnsupportedVersion(version, FORMAT_VERSION)),
+    }
+}
+
+/// Returns the path within the directory of the first version of the
+/// format, or of the directory of the second version.
+fn strip_dir<'a>(path: &'a str, v1: &str, v2: &str) -> Option<&'a str> {
+    path.strip_prefix(v1).or_else(|| path.strip_prefix(v2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bundle;
+    use crate::Error;
+    use serde_json::json;
+
+    #[test]
+    fn test_bundle() {
+        let animation = |op: u32| {
+            json!({ "v": "5.5.2", "ip": 0, "op": op, "fr": 30, "w": 100, "h": 100, "layers": [] })
+                .to_string()
+        };
+        let manifest = json!({
+            "version": "1",
+            "animations": [{ "id": "idle" }, { "id": "hover" }],
+            "activeAnimationId": "hover"
+        })
+        .to_string();
+        let entries = [
+            ("manifest.json", manifest.into_bytes()),
+            ("animations/idle.json", animation(30).into_bytes()),
+            ("animations/hover.json", animation(60).into_bytes()),
+            ("images/img_0.png", vec![1, 2, 3]),
+        ];
+        let mut bundle = Bundle::from_entries(entries.clone()).unwrap();
+        assert_eq!(bundle.ids().collect::<Vec<_>>(), ["idle", "hover"]);
+        assert_eq!(bundle.active_id(), "hover");
+        assert_eq!(bundle.active().frames, 0.0..60.0);
+        assert!(bundle.set_active("idle"));
+        assert_eq!(bundle.active().frames, 0.0..30.0);
+        assert!(!bundle.set_active("missing"));
+        assert_eq!(bundle.active_id(), "idle");
+        assert_eq!(bundle.images()["img_0.png"], [1, 2, 3]);
+        // Without a manifest, every animation is used in order of name.
+        let bundle = Bundle::from_entries(entries[1..].iter().cloned()).unwrap();
+        assert_eq!(bundle.ids().collect::<Vec<_>>(), ["hover", "idle"]);
+        assert_eq!(bundle.active_id(), "hover");
+        // Version 2 archives use shorter directories, and their manifests
+        // name the initial animation instead.
+        let manifest = json!({
+            "version": "2",
+            "animations": [{ "id": "idle" }, { "id": "hover" }],
+            "initial": { "animation": "idle" }
+        })
+        .to_string();
+        let v2 = [
+            ("manifest.json", manifest.into_bytes()),
+            ("a/idle.json", animation(30).into_bytes()),
+            ("a/hover.json", animation(60).into_bytes()),
+            ("i/img_0.png", vec![1, 2, 3]),
+        ];
+        let bundle = Bundle::from_entries(v2).unwrap();
+        assert_eq!(bundle.ids().collect::<Vec<_>>(), ["idle", "hover"]);
+        assert_eq!(bundle.active_id(), "idle");
+        assert_eq!(bundle.images()["img_0.png"], [1, 2, 3]);
+        assert!(matches!(
+            Bundle::from_entries(entries[..2].iter().cloned()),
+            Err(Error::MissingAnimation(id)) if id == "hover"
+        ));
+        assert!(matches!(
+            Bundle::from_entries(entries[3..].iter().cloned()),
+            Err(Error::AnimationNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_bundle_version() {
+        let animation =
+            json!({ "v": "5.5.2", "ip": 0, "op": 30, "fr": 30, "w": 100, "h": 100, "layers": [] })
+                .to_string();
+        let bundle = |version: Option<serde_json::Value>| {
+            let mut manifest = json!({ "animations": [{ "id": "idle" }] });
+            if let Some(version) = version {
+                manifest["version"] = version;
+            }
+            Bundle::from_entries([
+                ("manifest.json", manifest.to_string()),
+                ("animations/idle.json", animation.clone()),
+            ])
+        };
+        // Manifests without a version are from the first version, and minor
+        // versions are read as their major version.
+        for version in [None, Some(json!("1.0")), Some(json!(1)), Some(json!("2.1"))] {
+            assert!(bundle(version).is_ok());
+        }
+        // Newer or malformed versions are rejected with both versions.
+        for version in ["3", "10.0", "next"] {
+            match bundle(Some(json!(version))) {
+                Err(Error::UnsupportedVersion(found, supported)) => {
+                    assert_eq!(found, version);
+                    assert_eq!(supported, 2);
+                }
+                result => panic!("expected an unsupported version, found {result:?}"),
+            }
+        }
+        assert_eq!(
+            bundle(Some(json!(3))).unwrap_err().to_string(),
+            "dotLottie version \"3\" is not supported, the newest supported version is 2"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use kurbo::{Affine, Shape, Stroke};
+use peniko::{BlendMode, BrushRef, Color, Fill, Font};
+
+/// Target of the drawing commands of a [`Painter`](super::Painter).
+///
+/// The commands are those of a vello `Scene`, which velato paints into.
+/// Other canvases, such as [`Digest`](super::Digest), observe exactly what
+/// would be drawn without depending on a renderer.
+pub trait Canvas: Sized {
+    /// Returns an empty canvas with the settings of this one, into which a
+    /// precomposition instance is painted once to be appended many times.
+    fn fragment(&self) -> Self;
+
+    /// Pushes a layer clipped to a shape, which subsequent commands draw
+    /// into until it is popped, and which is then composited with the given
+    /// blend mode and alpha.
+    fn push_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    );
+
+    /// Pops the most recently pushed layer.
+    fn pop_layer(&mut self);
+
+    /// Fills a shape with a brush.
+    fn fill<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    );
+
+    /// Strokes a shape with a brush.
+    fn stroke<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    );
+
+    /// Appends the commands of a fragment, with an optional transform.
+    fn append(&mut self, fragment: &Self, transform: Option<Affine>);
+
+    /// Draws a single line of text with its baseline starting at the origin.
+    ///
+    /// Text is only drawn to label placeholders, so canvases that cannot
+    /// shape text may ignore it.
+    fn draw_text(&mut self, font: &Font, size: f32, transform: Affine, color: Color, text: &str);
+}
@@ -0,0 +1,275 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Canvas;
+use kurbo::{Affine, PathEl, Point, Shape, Stroke};
+use peniko::{BlendMode, BrushRef, Color, Fill, Font};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Canvas that accumulates the commands painted onto it into a checksum.
+///
+/// Painting onto a digest runs the same evaluation as rendering, but needs
+/// neither vello nor a GPU, which makes it suitable for checking that
+/// evaluation is deterministic and does not panic across entire catalogs
+/// of animations.
+///
+/// The hash is FNV-1a over integers written in little endian order, so the
+/// result only depends on the values fed to it, and not on the platform or
+/// the version of the standard library. With a quantum, floating point
+/// numbers are rounded to the nearest multiple of it before they are hashed,
+/// so that differences in the last bits of results, such as those of libm
+/// implementations or fused multiply-adds, do not change the checksum.
+#[derive(Clone, Debug)]
+pub struct Digest {
+    state: u64,
+    quantum: Option<f64>,
+}
+
+impl Digest {
+    /// Creates a digest that hashes floating point numbers exactly, or
+    /// rounded to multiples of the given quantum.
+    pub fn new(quantum: Option<f64>) -> Self {
+        Self {
+            state: FNV_OFFSET,
+            quantum: quantum.filter(|quantum| quantum.is_finite() && *quantum > 0.0),
+        }
+    }
+
+    fn float(&mut self, value: f64) {
+        match self.quantum {
+            // Saturating casts keep large values stable, and NaN maps to
+            // zero on every platform.
+            Some(quantum) if value.is_finite() => {
+                self.write_u8(0);
+                self.write_i64((value / quantum).round() as i64);
+            }
+            Some(_) => {
+                self.write_u8(1);
+                self.write_i64(if value.is_nan() {
+                    0
+                } else {
+                    value.signum() as i64
+                });
+            }
+            None => self.write_u64(value.to_bits()),
+        }
+    }
+
+    fn affine(&mut self, transform: Affine) {
+        for coefficient in transform.as_coeffs() {
+            self.float(coefficient);
+        }
+    }
+
+    fn shape(&mut self, shape: &impl Shape) {
+        for element in shape.path_elements(0.1) {
+            let (tag, points) = match element {
+                PathEl::MoveTo(p) => (0u8, [p, Point::ZERO, Point::ZERO]),
+                PathEl::LineTo(p) => (1, [p, Point::ZERO, Point::ZERO]),
+                PathEl::QuadTo(p1, p2) => (2, [p1, p2, Point::ZERO]),
+                PathEl::CurveTo(p1, p2, p3) => (3, [p1, p2, p3]),
+                PathEl::ClosePath => (4, [Point::ZERO; 3]),
+            };
+            self.write_u8(tag);
+            for point in points {
+                self.float(point.x);
+                self.float(point.y);
+            }
+        }
+    }
+
+    /// Feeds a value without a `Hash` implementation through its debug
+    /// representation, which is exact for floating point numbers. With a
+    /// quantum, every number in the representation is rounded.
+    fn debug(&mut self, value: &impl Debug) {
+        let text = format!("{value:?}");
+        if self.quantum.is_none() {
+            text.hash(self);
+            return;
+        }
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            // Digits that are part of identifiers, such as `Vec2`, are text.
+            let is_word = rest[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            let end = start
+                + rest[start..]
+                    .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '-')))
+                    .unwrap_or(rest.len() - start);
+            let negative = rest[..start].ends_with('-');
+            let prefix = &rest[..if negative { start - 1 } else { start }];
+            match rest[start..end].parse::<f64>() {
+                Ok(number) if !is_word => {
+                    prefix.hash(self);
+                    self.float(if negative { -number } else { number });
+                }
+                _ => rest[..end].hash(self),
+            }
+            rest = &rest[end..];
+        }
+        rest.hash(self);
+    }
+}
+
+impl Canvas for Digest {
+    fn fragment(&self) -> Self {
+        Self::new(self.quantum)
+    }
+
+    fn push_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        self.write_u8(0);
+        self.debug(&blend.into());
+        self.float(alpha as f64);
+        self.affine(transform);
+        self.shape(clip);
+    }
+
+    fn pop_layer(&mut self) {
+        self.write_u8(1);
+    }
+
+    fn fill<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.write_u8(2);
+        self.debug(&(style, brush.into()));
+        self.affine(transform);
+        self.affine(brush_transform.unwrap_or(transform));
+        self.shape(shape);
+    }
+
+    fn stroke<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.write_u8(3);
+        self.debug(&(style, brush.into()));
+        self.affine(transform);
+        self.affine(brush_transform.unwrap_or(transform));
+        self.shape(shape);
+    }
+
+    fn append(&mut self, fragment: &Self, transform: Option<Affine>) {
+        self.write_u8(4);
+        self.write_u64(fragment.finish());
+        self.affine(transform.unwrap_or(Affine::IDENTITY));
+    }
+
+    fn draw_text(&mut self, _: &Font, size: f32, transform: Affine, color: Color, text: &str) {
+        self.write_u8(5);
+        self.float(size as f64);
+        self.affine(transform);
+        self.debug(&color);
+        text.hash(self);
+    }
+}
+
+impl Hasher for Digest {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        // The width of usize differs between platforms.
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write_u128(value as u128);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Digest;
+    use std::hash::Hasher;
+
+    fn digest(quantum: Option<f64>, feed: impl FnOnce(&mut Digest)) -> u64 {
+        let mut digest = Digest::new(quantum);
+        feed(&mut digest);
+        digest.finish()
+    }
+
+    #[test]
+    fn test_quantized_floats() {
+        // The hash is independent of the platform.
+        assert_eq!(digest(None, |_| {}), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(digest(None, |d| d.write_usize(1)), 0x89cd_3129_1d2a_efa4);
+
+        let exact = |value| digest(None, |d| d.float(value));
+        assert_ne!(exact(0.1 + 0.2), exact(0.3));
+        let quantized = |value| digest(Some(1e-6), |d| d.float(value));
+        assert_eq!(quantized(0.1 + 0.2), quantized(0.3));
+        assert_eq!(quantized(0.0), quantized(-0.0));
+        assert_ne!(quantized(0.3), quantized(0.3001));
+        assert_ne!(quantized(f64::INFINITY), quantized(f64::NEG_INFINITY));
+
+        let debug = |value: &[f64]| digest(Some(1e-6), |d| d.debug(&(value, "Vec2")));
+        assert_eq!(debug(&[0.1 + 0.2, -1.5]), debug(&[0.3, -1.5]));
+        assert_ne!(debug(&[0.3, -1.5]), debug(&[0.3, 1.5]));
+        assert_ne!(debug(&[1e-3]), debug(&[1e-4]));
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{
+    animated, Brush, Content, Draw, Effect, Geometry, GroupTransform, Layer, LayerStyle, Mask,
+    PathModifier, Repeater, Shape, Stroke, Transform, Tween, Value,
+};
+use super::Composition;
+use std::collections::HashMap;
+
+/// Seed of the noise of wiggles, matching the default seed of renderers.
+const SEED: u64 = 0;
+
+/// Builds a copy of a composition with every value fixed at its evaluation
+/// at the given frame.
+pub(crate) fn freeze(composition: &Composition, frame: f64) -> Composition {
+    let mut freezer = Freezer {
+        source: composition,
+        assets: HashMap::new(),
+    };
+    let layers = freezer.layers(&composition.layers, frame);
+    Composition {
+        frames: composition.frames.clone(),
+        frame_rate: composition.frame_rate,
+        width: composition.width,
+        height: composition.height,
+        assets: freezer.assets,
+        layers,
+        base_transform: composition.base_transform,
+        shutter: composition.shutter,
+        markers: composition.markers.clone(),
+        audio_tracks: composition.audio_tracks.clone(),
+    }
+}
+
+struct Freezer<'a> {
+    source: &'a Composition,
+    /// Frozen assets, named after the asset and the frame at which it is
+    /// frozen, since instances may show the same asset at different frames.
+    assets: HashMap<String, Vec<Layer>>,
+}
+
+impl Freezer<'_> {
+    fn layers(&mut self, layers: &[Layer], frame: f64) -> Vec<Layer> {
+        layers
+            .iter()
+            .map(|layer| self.layer(layer, frame))
+            .collect()
+    }
+
+    fn layer(&mut self, layer: &Layer, frame: f64) -> Layer {
+        let frame_rate = self.source.frame_rate;
+        let transform = Transform::Fixed(layer.evaluate_transform(frame, frame_rate, SEED));
+        // Every frozen layer is active on every frame of the composition,
+        // so inactive layers keep only what other layers refer to: their
+        // transform, for children, and their role as a matte.
+        if !layer.is_active(frame) {
+            return Layer {
+                name: layer.name.clone(),
+                class: layer.class.clone(),
+                parent: layer.parent,
+                transform,
+                opacity: Value::Fixed(0.0),
+                width: layer.width,
+                height: layer.height,
+                frames: self.source.frames.clone(),
+                is_mask: layer.is_mask,
+                hidden: layer.hidden,
+                ..Default::default()
+            };
+        }
+        let content = match &layer.content {
+            Content::Instance {
+                name, overrides, ..
+            } => {
+                let local_frame = layer
+                    .timeline(frame_rate)
+                    .map_or(frame, |timeline| timeline.local_frame(frame));
+                let frozen = format!("{name}@{local_frame}");
+                if !self.assets.contains_key(&frozen) {
+                    if let Some(asset) = self.source.assets.get(name) {
+                        let asset = self.layers(asset, local_frame);
+                        self.assets.insert(frozen.clone(), asset);
+                    }
+                }
+                Content::Instance {
+                    source: frozen.clone(),
+                    name: frozen,
+                    time_remap: None,
+                    overrides: overrides.clone(),
+                }
+            }
+            Content::Shape(shapes) => Content::Shape(freeze_shapes(shapes, frame)),
+            content => content.clone(),
+        };
+        Layer {
+            name: layer.name.clone(),
+            class: layer.class.clone(),
+            parent: layer.parent,
+            transform,
+            opacity: Value::Fixed(layer.evaluate_opacity(frame, frame_rate, SEED)),
+            width: layer.width,
+            height: layer.height,
+            blend_mode: layer.blend_mode,
+            frames: self.source.frames.clone(),
+            // Instances show their asset at the frame of the composition.
+            stretch: 1.0,
+            start_frame: 0.0,
+            masks: layer
+                .masks
+                .iter()
+                .map(|mask| freeze_mask(mask, frame))
+                .collect(),
+            is_mask: layer.is_mask,
+            hidden: layer.hidden,
+            mask_layer: layer.mask_layer,
+            effects: layer
+                .effects
+                .iter()
+                .map(|effect| freeze_effect(effect, frame))
+                .collect(),
+            styles: layer
+                .styles
+                .iter()
+                .map(|style| freeze_style(style, frame))
+                .collect(),
+            unsupported: layer.unsupported.clone(),
+            // A still layer has no motion to blur.
+            motion_blur: false,
+            preserve_transparency: layer.preserve_transparency,
+            knockout: layer.knockout,
+            wiggles: vec![],
+            auto_orient: false,
+            content,
+        }
+    }
+}
+
+fn fix<T: Tween>(value: &Value<T>, frame: f64) -> Value<T> {
+    Value::Fixed(value.evaluate(frame))
+}
+
+fn freeze_geometry(geometry: &Geometry, frame: f64) -> Geometry {
+    let mut path = vec![];
+    geometry.evaluate(frame, &mut path);
+    Geometry::Fixed(path)
+}
+
+fn freeze_transform(transform: &Transform, frame: f64) -> Transform {
+    Transform::Fixed(transform.evaluate(frame).into_owned())
+}
+
+fn freeze_shapes(shapes: &[Shape], frame: f64) -> Vec<Shape> {
+    shapes
+        .iter()
+        .map(|shape| freeze_shape(shape, frame))
+        .collect()
+}
+
+fn freeze_shape(shape: &Shape, frame: f64) -> Shape {
+    match shape {
+        Shape::Group(shapes, transform) => Shape::Group(
+            freeze_shapes(shapes, frame),
+            transform.as_ref().map(|transform| GroupTransform {
+                transform: freeze_transform(&transform.transform, frame),
+                opacity: fix(&transform.opacity, frame),
+            }),
+        ),
+        Shape::Geometry(geometry) => Shape::Geometry(freeze_geometry(geometry, frame)),
+        Shape::Draw(draw) => Shape::Draw(Draw {
+            stroke: draw
+                .stroke
+                .as_ref()
+                .map(|stroke| Stroke::Fixed(stroke.evaluate(frame).into_owned())),
+            brush: Brush::Fixed(draw.brush.evaluate(1.0, frame).into_owned()),
+            opacity: fix(&draw.opacity, frame),
+        }),
+        Shape::Repeater(repeater) => {
+            Shape::Repeater(Repeater::Fixed(repeater.evaluate(frame).into_owned()))
+        }
+        Shape::Modifier(modifier) => {
+            Shape::Modifier(PathModifier::Fixed(modifier.evaluate(frame).into_owned()))
+        }
+        Shape::Hidden(shape) => Shape::Hidden(Box::new(freeze_shape(shape, frame))),
+    }
+}
+
+fn freeze_mask(mask: &Mask, frame: f64) -> Mask {
+    Mask {
+        mode: mask.mode,
+        inverted: mask.inverted,
+        expansion: fix(&mask.expansion, frame),
+        feather: fix(&mask.feather, frame),
+        geometry: freeze_geometry(&mask.geometry, frame),
+        opacity: fix(&mask.opacity, frame),
+    }
+}
+
+fn freeze_shadow(shadow: &animated::DropShadow, frame: f64) -> animated::DropShadow {
+    animated::DropShadow {
+        color: fix(&shadow.color, frame),
+        opacity: fix(&shadow.opacity, frame),
+        direction: fix(&shadow.direction, frame),
+        distance: fix(&shadow.distance, frame),
+        radius: fix(&shadow.radius, frame),
+        shadow_only: shadow.shadow_only,
+    }
+}
+
+fn freeze_levels_channel(channel: &animated::LevelsChannel, frame: f64) -> animated::LevelsChannel {
+    animated::LevelsChannel {
+        input_black: fix(&channel.input_black, frame),
+        input_white: fix(&channel.input_white, frame),
+        gamma: fix(&channel.gamma, frame),
+        output_black: fix(&channel.output_black, frame),
+        output_white: fix(&channel.output_white, frame),
+    }
+}
+
+fn freeze_effect(effect: &Effect, frame: f64) -> Effect {
+    match effect {
+        Effect::DropShadow(shadow) => Effect::DropShadow(freeze_shadow(shadow, frame)),
+        Effect::Fill(fill) => Effect::Fill(animated::FillEffect {
+            color: fix(&fill.color, frame),
+            opacity: fix(&fill.opacity, frame),
+        }),
+        Effect::Levels(levels) => Effect::Levels(Box::new(animated::Levels {
+            rgb: freeze_levels_channel(&levels.rgb, frame),
+            red: freeze_levels_channel(&levels.red, frame),
+            green: freeze_levels_channel(&levels.green, frame),
+            blue: freeze_levels_channel(&levels.blue, frame),
+            alpha: freeze_levels_channel(&levels.alpha, frame),
+        })),
+        Effect::Stroke(stroke) => Effect::Stroke(animated::StrokeEffect {
+            mask: stroke.mask,
+            color: fix(&stroke.color, frame),
+            width: fix(&stroke.width, frame),
+            opacity: fix(&stroke.opacity, frame),
+            start: fix(&stroke.start, frame),
+            end: fix(&stroke.end, frame),
+            paint_style: stroke.paint_style,
+        }),
+        Effect::LinearWipe(wipe) => Effect::LinearWipe(animated::LinearWipe {
+            completion: fix(&wipe.completion, frame),
+            angle: fix(&wipe.angle, frame),
+            feather: fix(&wipe.feather, frame),
+        }),
+        Effect::RadialWipe(wipe) => Effect::RadialWipe(animated::RadialWipe {
+            completion: fix(&wipe.completion, frame),
+            start_angle: fix(&wipe.start_angle, frame),
+            center: fix(&wipe.center, frame),
+            direction: wipe.direction,
+            feather: fix(&wipe.feather, frame),
+        }),
+        Effect::DisplacementMap(map) => Effect::DisplacementMap(animated::DisplacementMap {
+            layer: map.layer,
+            horizontal: map.horizontal,
+            max_horizontal: fix(&map.max_horizontal, frame),
+            vertical: map.vertical,
+            max_vertical: fix(&map.max_vertical, frame),
+        }),
+    }
+}
+
+fn freeze_style(style: &LayerStyle, frame: f64) -> LayerStyle {
+    match style {
+        LayerStyle::Stroke(stroke) => LayerStyle::Stroke(animated::StrokeStyle {
+            color: fix(&stroke.color, frame),
+            size: fix(&stroke.size, frame),
+        }),
+        LayerStyle::DropShadow(shadow) => LayerStyle::DropShadow(freeze_shadow(shadow, frame)),
+        LayerStyle::InnerShadow(shadow) => LayerStyle::InnerShadow(freeze_shadow(shadow, frame)),
+        LayerStyle::OuterGlow(glow) => LayerStyle::OuterGlow(animated::OuterGlow {
+            color: fix(&glow.color, frame),
+            opacity: fix(&glow.opacity, frame),
+            radius: fix(&glow.radius, frame),
+        }),
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Composition;
+use crate::schema::Animation;
+use crate::Error;
+use arbitrary::{Arbitrary, Unstructured};
+
+/// Random Lottie file, for fuzzing and property-based tests of code that
+/// imports, evaluates or renders animations.
+///
+/// Files are generated from the schema that files are parsed with, so they
+/// cover every kind of layer, shape and property that is imported. Random
+/// values may be ambiguous between alternatives of the schema or fail
+/// validation, so not every file imports, but none should panic.
+#[derive(Clone, Debug)]
+pub struct ArbitraryLottie {
+    json: String,
+}
+
+impl ArbitraryLottie {
+    /// Returns the file as Lottie JSON.
+    pub fn json(&self) -> &str {
+        &self.json
+    }
+
+    /// Imports the file into a composition.
+    pub fn composition(&self) -> Result<Composition, Error> {
+        Composition::from_slice(&self.json)
+    }
+}
+
+impl<'a> Arbitrary<'a> for ArbitraryLottie {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let animation = Animation::arbitrary(u)?;
+        let json =
+            serde_json::to_string(&animation).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        Ok(Self { json })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArbitraryLottie;
+    use crate::{Digest, Painter};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary_lottie() {
+        // Xorshift with a fixed seed so that failures are reproducible.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut imported = 0;
+        for _ in 0..512 {
+            let bytes = (0..4096)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect::<Vec<_>>();
+            let lottie = ArbitraryLottie::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let Ok(composition) = lottie.composition() else {
+                continue;
+            };
+            imported += 1;
+            // Evaluate frames spread over the animation, and beyond it.
+            let mut painter = Painter::<Digest>::new();
+            let frames = composition.frames.clone();
+            for step in -1..=9 {
+                let frame = frames.start + (frames.end - frames.start) * step as f64 / 8.0;
+                painter.evaluate_only(&composition, frame);
+            }
+        }
+        assert!(imported > 0, "no animation was imported");
+    }
+}
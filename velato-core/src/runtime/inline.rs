@@ -0,0 +1,107 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{Content, GroupTransform, Layer, Shape};
+use super::Composition;
+use std::collections::HashMap;
+
+/// Replaces instances of assets made only of plain shape layers with the
+/// shapes of those layers, each in a group with the transform and opacity
+/// of its layer.
+///
+/// Nested instances are inlined first, so that assets used as folders of
+/// other folders are flattened entirely.
+pub(crate) fn inline_instances(composition: &mut Composition) {
+    loop {
+        let mut changed = false;
+        let names: Vec<_> = composition.assets.keys().cloned().collect();
+        for name in names {
+            // The asset is taken out while inlining into it, so that it can
+            // never be inlined into itself.
+            let Some(mut layers) = composition.assets.remove(&name) else {
+                continue;
+            };
+            changed |= inline_layers(&mut layers, &composition.assets);
+            composition.assets.insert(name, layers);
+        }
+        changed |= inline_layers(&mut composition.layers, &composition.assets);
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Inlines the instances among the layers, returning true if any was
+/// inlined.
+fn inline_layers(layers: &mut [Layer], assets: &HashMap<String, Vec<Layer>>) -> bool {
+    let mut changed = false;
+    for layer in layers {
+        let Content::Instance {
+            name,
+            time_remap: None,
+            ..
+        } = &layer.content
+        else {
+            continue;
+        };
+        // The asset must be shown at the frames of the composition, and
+        // masks of the instance apply to it as a whole.
+        if layer.stretch != 1.0 || layer.start_frame != 0.0 || !layer.masks.is_empty() {
+            continue;
+        }
+        let Some(asset) = assets.get(name) else {
+            continue;
+        };
+        if !asset
+            .iter()
+            .all(|asset_layer| is_inlinable(asset_layer, layer))
+        {
+            continue;
+        }
+        let shapes = asset
+            .iter()
+            .map(|asset_layer| {
+                let Content::Shape(shapes) = &asset_layer.content else {
+                    unreachable!();
+                };
+                let group = Shape::Group(
+                    shapes.clone(),
+                    Some(GroupTransform {
+                        transform: asset_layer.transform.clone(),
+                        opacity: asset_layer.opacity.clone(),
+                    }),
+                );
+                if asset_layer.hidden {
+                    Shape::Hidden(Box::new(group))
+                } else {
+                    group
+                }
+            })
+            .collect();
+        layer.content = Content::Shape(shapes);
+        changed = true;
+    }
+    changed
+}
+
+/// Returns true if a layer of an asset renders the same as a group of its
+/// shapes wherever the instance is active.
+fn is_inlinable(asset_layer: &Layer, instance: &Layer) -> bool {
+    matches!(asset_layer.content, Content::Shape(_))
+        && asset_layer.parent.is_none()
+        && asset_layer.blend_mode.is_none()
+        && asset_layer.masks.is_empty()
+        && !asset_layer.is_mask
+        && asset_layer.mask_layer.is_none()
+        && asset_layer.effects.is_empty()
+        && asset_layer.styles.is_empty()
+        && asset_layer.unsupported.is_empty()
+        && !asset_layer.motion_blur
+        && !asset_layer.preserve_transparency
+        && !asset_layer.knockout
+        && asset_layer.wiggles.is_empty()
+        && !asset_layer.auto_orient
+        && asset_layer.frames.start < asset_layer.frames.end
+        && asset_layer.frames.start <= instance.frames.start
+        && asset_layer.frames.end >= instance.frames.end
+}
@@ -0,0 +1,2138 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod audio;
+mod bundle;
+mod canvas;
+mod digest;
+mod freeze;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod inline;
+mod observer;
+pub(crate) mod overrides;
+mod paint;
+mod playhead;
+mod properties;
+mod selector;
+pub(crate) mod slots;
+mod summary;
+mod table;
+mod wrapper;
+
+use crate::import;
+use crate::schema::helpers::permissive;
+use crate::schema::Animation;
+use crate::Error;
+use kurbo::Affine;
+use std::collections::HashMap;
+use std::ops::Range;
+
+pub mod model;
+
+pub use audio::{AudioEvent, AudioPlayback};
+pub use bundle::Bundle;
+pub use canvas::Canvas;
+pub use digest::Digest;
+#[cfg(feature = "arbitrary")]
+pub use fuzz::ArbitraryLottie;
+pub use observer::{KeyframeEvent, KeyframeObserver, KeyframeSelector, LayerProperty};
+pub use paint::{JoinFallback, Painter, RenderOptions};
+pub use playhead::{PlayMode, Playhead, PlayheadEvent};
+pub use properties::{AnimatedProperty, PropertyFilter};
+pub use selector::{Selector, SelectorSegment};
+pub use slots::{SlotValue, Theme};
+pub use summary::Summary;
+pub use table::{KeyframeSample, KeyframeTable};
+
+/// Model of a Lottie file.
+#[derive(Clone, Default, Debug)]
+pub struct Composition {
+    /// Frames in which the animation is active.
+    pub frames: Range<f64>,
+    /// Frames per second.
+    pub frame_rate: f64,
+    /// Width of the animation.
+    pub width: usize,
+    /// Height of the animation.
+    pub height: usize,
+    /// Precomposed layers that may be instanced.
+    pub assets: HashMap<String, Vec<model::Layer>>,
+    /// Collection of layers.
+    pub layers: Vec<model::Layer>,
+    /// Transform folded into the root layers at import, which also applies
+    /// to the bounds of the composition.
+    pub base_transform: Affine,
+    /// Shutter of the virtual camera for layers with motion blur.
+    pub shutter: model::Shutter,
+    /// Named portions of the timeline, in the order of the file.
+    pub markers: Vec<model::Marker>,
+    /// Sounds played by the audio layers of the composition.
+    pub audio_tracks: Vec<model::AudioTrack>,
+}
+
+/// Options for creating a composition.
+#[derive(Copy, Clone, Debug)]
+pub struct ImportOptions {
+    /// Transform folded into the root layers, as if it were applied to every
+    /// render of the composition.
+    pub base_transform: Affine,
+    /// Alpha folded into the opacity of the root layers, as if it were
+    /// applied to every render of the composition.
+    ///
+    /// The alpha multiplies the opacity of each layer rather than the
+    /// composition as a whole, so where layers overlap, the layers below
+    /// show through more than they would through a group alpha. Track
+    /// mattes and adjustment layers keep their opacity, since they do not
+    /// draw themselves but mask or recolor the layers that do.
+    pub base_alpha: f64,
+    /// True to replace instances of assets that only contain shape layers,
+    /// such as precompositions used as folders, with groups of their shapes.
+    ///
+    /// Only instances without time remapping, stretching, an offset start
+    /// or masks are inlined, and only if every layer of the asset is a
+    /// plain shape layer, active wherever the instance is. This removes a
+    /// level of indirection when rendering, but the opacity of inlined
+    /// layers applies to them as a whole when group isolation is enabled.
+    pub inline_instances: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            base_transform: Affine::IDENTITY,
+            base_alpha: 1.0,
+            inline_instances: false,
+        }
+    }
+}
+
+impl Composition {
+    /// Creates a new runtime composition from a buffer of Lottie file contents.
+    pub fn from_slice(source: impl AsRef<[u8]>) -> Result<Composition, Error> {
+        if cfg!(feature = "expressions") {
+            return Self::from_json(serde_json::from_slice(source.as_ref())?);
+        }
+        let source = Animation::from_slice(source.as_ref())?;
+        let composition = import::conv_animation(source);
+        Ok(composition)
+    }
+
+    /// Creates a new runtime composition from a buffer of Lottie file
+    /// contents, rejecting values in loose encodings.
+    ///
+    /// [`from_slice`](Self::from_slice) accepts numbers encoded as strings,
+    /// such as `"100"`, and booleans encoded as numbers or the other way
+    /// around, which some exporters write. This only accepts the encodings
+    /// of the schema, for validating files.
+    ///
+    /// Strictness is a per-thread setting of the deserializers that is
+    /// enabled for the duration of this call only, so compositions loaded
+    /// at the same time on other threads are still loaded permissively.
+    pub fn from_slice_strict(source: impl AsRef<[u8]>) -> Result<Composition, Error> {
+        permissive::strict(|| Self::from_slice(source))
+    }
+
+    /// Creates a new runtime composition from a json object of Lottie file contents.
+    ///
+    /// With the `expressions` feature, expressions within the supported
+    /// subset are evaluated into keyframes first.
+    pub fn from_json(v: serde_json::Value) -> Result<Composition, Error> {
+        #[cfg(feature = "expressions")]
+        let v = import::bake_expressions(v);
+        let source = Animation::from_json(v)?;
+        let composition = import::conv_animation(source);
+        Ok(composition)
+    }
+
+    /// Creates a new runtime composition from a buffer of Lottie file
+    /// contents, with a base transform and alpha folded into its layers and
+    /// the other transformations selected by the options.
+    ///
+    /// This is useful for embedders that always render an animation with
+    /// the same transform, which can then render with the identity.
+    pub fn from_slice_with_options(
+        source: impl AsRef<[u8]>,
+        options: &ImportOptions,
+    ) -> Result<Composition, Error> {
+        let mut composition = Self::from_slice(source)?;
+        if options.inline_instances {
+            inline::inline_instances(&mut composition);
+        }
+        composition.fold_base(options);
+        Ok(composition)
+    }
+
+    /// Folds a base transform and alpha into the layers of the composition.
+    fn fold_base(&mut self, options: &ImportOptions) {
+        let base = options.base_transform;
+        // Animated transforms cannot absorb an arbitrary affine, so those
+        // root layers are parented to an additional layer with the base
+        // transform instead.
+        let base_parent = self.layers.len();
+        let mut needs_parent = false;
+        for layer in &mut self.layers {
+            let draws = !layer.is_mask && !matches!(layer.content, model::Content::Adjustment);
+            if options.base_alpha != 1.0 && draws {
+                let opacity = std::mem::take(&mut layer.opacity);
+                layer.opacity = opacity.map(|opacity| opacity * options.base_alpha);
+            }
+            if layer.parent.is_some() {
+                continue;
+            }
+            match &mut layer.transform {
+                model::Transform::Fixed(transform) => *transform = base * *transform,
+                model::Transform::Animated(_) => {
+                    layer.parent = Some(base_parent);
+                    needs_parent = true;
+                }
+            }
+        }
+        if needs_parent {
+            self.layers.push(model::Layer {
+                transform: model::Transform::Fixed(base),
+                ..Default::default()
+            });
+        }
+        self.base_transform = base * self.base_transform;
+    }
+
+    /// Creates a new runtime composition from a buffer containing Lottie
+    /// file contents within a wrapper, such as an API envelope or a JSONP
+    /// callback.
+    ///
+    /// The animation is located with the given JSON pointer, such as
+    /// `"/data/animation"`. If no pointer is given, the first object that
+    /// has the layers and timing of an animation is used, including objects
+    /// encoded as strings. Text before the first `{` and after the last `}`
+    /// is ignored if the buffer is not valid JSON on its own.
+    pub fn from_wrapped_slice(
+        source: impl AsRef<[u8]>,
+        pointer: Option<&str>,
+    ) -> Result<Composition, Error> {
+        let animation = wrapper::unwrap_animation(source.as_ref(), pointer)?;
+        Self::from_json(animation)
+    }
+
+    /// Returns a textual summary of the animation, suitable for accessibility
+    /// descriptions.
+    pub fn summary(&self) -> Summary {
+        Summary::new(self)
+    }
+
+    /// Returns the path and keyframes of every animated property, for
+    /// finding which properties animate over which parts of the timeline.
+    ///
+    /// Frames are in the local time of the layer containing the property.
+    pub fn animated_properties(&self) -> impl Iterator<Item = AnimatedProperty> {
+        properties::collect(self).into_iter()
+    }
+
+    /// Samples the numbers, points, vectors, sizes and colors of the
+    /// animated properties selected by `filter` at each keyframe, and at
+    /// `steps` evenly spaced frames between consecutive keyframes, for
+    /// checking how their easing is interpreted.
+    ///
+    /// Frames are in the local time of the layer containing the property.
+    pub fn keyframe_table(&self, filter: &PropertyFilter, steps: usize) -> KeyframeTable {
+        table::sample(self, filter, steps)
+    }
+
+    /// Returns the frame of the keyframe nearest to `frame` among the
+    /// animated properties selected by `filter`, for snapping a scrubber to
+    /// keyframes.
+    ///
+    /// Only properties of the top level layers are considered, since
+    /// keyframes of precomposed assets are in the local time of each
+    /// instance. Ties resolve to the earlier keyframe.
+    pub fn nearest_keyframe(&self, frame: f64, filter: PropertyFilter) -> Option<f64> {
+        properties::collect(self)
+            .iter()
+            .filter(|property| {
+                property.path.starts_with("layers/") && filter.matches(&property.path)
+            })
+            .flat_map(|property| property.keyframes.iter().copied())
+            .min_by(|a, b| {
+                let distance = |x: f64| (x - frame).abs();
+                distance(*a).total_cmp(&distance(*b)).then(a.total_cmp(b))
+            })
+    }
+
+    /// Returns a copy of the composition with every value fixed at its
+    /// evaluation at `frame`, for showing a still of the animation without
+    /// evaluating keyframes on every render.
+    ///
+    /// The copy covers the same frames, and renders the same image on each
+    /// of them as the original does at `frame`. Layers inactive at `frame`
+    /// are kept empty, so that parent and matte indices stay valid, and
+    /// precomposed assets are frozen at the local frame of each instance.
+    /// Wiggles are evaluated with the default seed.
+    pub fn freeze(&self, frame: f64) -> Composition {
+        freeze::freeze(self, frame)
+    }
+
+    /// Replaces the layers of the precomposition asset with the given name
+    /// and returns the previous layers.
+    ///
+    /// All layers instancing the asset render the new layers with their
+    /// existing transforms, timing and mattes. Instances with overrides
+    /// are given new copies of the layers with their overrides applied
+    /// again. Parent and matte indices within the new layers refer to
+    /// positions in the new list, and any instances they contain are
+    /// resolved by name in this composition.
+    ///
+    /// Instances replaced by their shapes with
+    /// [`ImportOptions::inline_instances`] no longer refer to the asset and
+    /// keep rendering the layers it had at import.
+    ///
+    /// If there was no asset with the name, it is added and `None` is
+    /// returned.
+    pub fn swap_asset(
+        &mut self,
+        name: impl Into<String>,
+        layers: Vec<model::Layer>,
+    ) -> Option<Vec<model::Layer>> {
+        let previous = self.assets.insert(name.into(), layers);
+        overrides::rebuild(self);
+        previous
+    }
+
+    /// Sets the value of every property that references the slot with the
+    /// given id through its `sid`, as when applying a theme, and returns
+    /// true if any property was set.
+    ///
+    /// Colors only apply to color properties, numbers to scalar properties
+    /// and points to positions, scales and sizes. Slots belong to this
+    /// composition: clones keep their own values, and the new value takes
+    /// effect on the next frame rendered from it.
+    pub fn set_slot(&mut self, id: &str, value: SlotValue) -> bool {
+        slots::set(self, id, slots::SlotSource::Value(value))
+    }
+
+    /// Sets the value of every color property that references the slot with
+    /// the given id to a color parsed with [`SlotValue::parse_color`], and
+    /// returns true if the color is valid and any property was set.
+    pub fn set_slot_color(&mut self, id: &str, color: &str) -> bool {
+        SlotValue::parse_color(color).is_some_and(|value| self.set_slot(id, value))
+    }
+
+    /// Sets every slot of the theme, as with [`set_slot`](Self::set_slot),
+    /// and returns true if any property was set.
+    pub fn apply_theme(&mut self, theme: &Theme) -> bool {
+        slots::apply(self, theme)
+    }
+
+    /// Sets every slot to the blend of two themes at `t` in `0..=1`, as with
+    /// [`Theme::blend`], and returns true if any property was set.
+    ///
+    /// Applying the blend with increasing `t` over several frames animates
+    /// the transition between the themes rather than snapping between them.
+    /// As with other slots, the blend stays applied until the slots are set
+    /// again. To render several themes at once, apply them to clones of the
+    /// composition.
+    pub fn apply_theme_blend(&mut self, a: &Theme, b: &Theme, t: f64) -> bool {
+        self.apply_theme(&a.blend(b, t))
+    }
+
+    /// Returns the named portions of the timeline.
+    pub fn markers(&self) -> &[model::Marker] {
+        &self.markers
+    }
+
+    /// Returns the frames covered by the first marker with the given name,
+    /// which can be played as a segment of the animation.
+    pub fn frames_for_marker(&self, name: &str) -> Option<Range<f64>> {
+        self.markers
+            .iter()
+            .find(|marker| marker.name == name)
+            .map(model::Marker::frames)
+    }
+
+    /// Returns the sounds played by the top level audio layers, which can be
+    /// synchronized with playback using [`AudioPlayback`].
+    pub fn audio_tracks(&self) -> &[model::AudioTrack] {
+        &self.audio_tracks
+    }
+}
+
+impl std::str::FromStr for Composition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if cfg!(feature = "expressions") {
+            return Self::from_slice(s);
+        }
+        let source = Animation::from_str(s)?;
+        let composition = import::conv_animation(source);
+        Ok(composition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AudioEvent, AudioPlayback, Composition, Digest, ImportOptions, Painter, PlayMode, Playhead,
+        PlayheadEvent, PropertyFilter, Selector, SlotValue, Theme,
+    };
+    use crate::model::{
+        animated, fixed, Brush, Content, Effect, Geometry, Layer, LayerStyle, MaskMode, Matte,
+        PathModifier, Repeater, RepeaterComposite, Shape, Shutter, Stroke, Transform, Unsupported,
+        Value,
+    };
+    use kurbo::{Affine, Cap, Join, PathEl, Point};
+    use peniko::{self, BlendMode, Compose, GradientKind, Mix};
+    use serde_json::json;
+
+    #[test]
+    fn test_fold_base() {
+        let layer = |position| {
+            json!({
+                "ty": 4, "ip": 0, "op": 60, "st": 0, "shapes": [],
+                "ks": {
+                    "p": position,
+                    "r": { "a": 0, "k": 0 },
+                    "s": { "a": 0, "k": [100, 100] },
+                    "o": { "a": 0, "k": 80 }
+                }
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [
+                layer(json!({ "a": 0, "k": [10, 20] })),
+                layer(json!({ "a": 1, "k": [
+                    { "t": 0, "s": [0, 0] },
+                    { "t": 60, "s": [50, 50] }
+                ] })),
+            ]
+        })
+        .to_string();
+        let options = ImportOptions {
+            base_transform: Affine::scale(2.0),
+            base_alpha: 0.5,
+            ..Default::default()
+        };
+        let composition = Composition::from_slice_with_options(source, &options).unwrap();
+        assert_eq!(composition.base_transform, Affine::scale(2.0));
+        let Transform::Fixed(transform) = composition.layers[0].transform else {
+            panic!("expected a fixed transform");
+        };
+        assert_eq!(
+            transform,
+            Affine::scale(2.0) * Affine::translate((10.0, 20.0))
+        );
+        // The animated layer is parented to an additional layer with the base
+        // transform.
+        assert_eq!(composition.layers.len(), 3);
+        assert_eq!(composition.layers[1].parent, Some(2));
+        assert!(matches!(composition.layers[1].opacity, Value::Fixed(opacity) if opacity == 40.0));
+
+        // Mattes keep their opacity, so that matted layers are not faded
+        // twice.
+        let matte = |key: &str| {
+            let mut layer = layer(json!({ "a": 0, "k": [0, 0] }));
+            layer[key] = json!(1);
+            layer
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [matte("td"), matte("tt")]
+        })
+        .to_string();
+        let composition = Composition::from_slice_with_options(source, &options).unwrap();
+        assert!(composition.layers[0].is_mask);
+        assert_eq!(
+            composition.layers[1].mask_layer.map(|(_, index)| index),
+            Some(0)
+        );
+        let opacities = composition.layers.iter().map(|layer| match layer.opacity {
+            Value::Fixed(opacity) => opacity,
+            _ => panic!("expected a fixed opacity"),
+        });
+        assert_eq!(opacities.collect::<Vec<_>>(), [80.0, 40.0]);
+    }
+
+    #[test]
+    fn test_gradient_type_keyframes() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 0, "k": [0, 0] },
+                    "r": { "a": 0, "k": 0 },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": [{
+                    "ty": "gf",
+                    "s": { "a": 0, "k": [0, 0] },
+                    "e": { "a": 0, "k": [100, 0] },
+                    "t": { "a": 1, "k": [{ "t": 0, "s": [1] }, { "t": 30, "s": [2] }] },
+                    "g": { "p": 3, "k": { "a": 1, "k": [
+                        { "t": 0, "s": [0, 1, 0, 0, 0.5, 0, 1, 0, 1, 0, 0, 1] },
+                        { "t": 30, "s": [0, 1, 0, 0, 1, 0, 0, 1] }
+                    ] } },
+                    "o": { "a": 0, "k": 100 }
+                }]
+            }]
+        })
+        .to_string();
+        let composition = Composition::from_slice(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Draw(draw) = &shapes[0] else {
+            panic!("expected a draw");
+        };
+        let Brush::Animated(brush) = &draw.brush else {
+            panic!("expected an animated brush");
+        };
+        let gradient = |frame| match brush.evaluate(1.0, frame) {
+            peniko::Brush::Gradient(gradient) => gradient,
+            _ => panic!("expected a gradient"),
+        };
+        // Both the type and the mismatched stops hold until the next
+        // keyframe.
+        let before = gradient(29.0);
+        assert!(matches!(before.kind, GradientKind::Linear { .. }));
+        assert_eq!(before.stops.len(), 3);
+        let after = gradient(30.0);
+        assert!(matches!(after.kind, GradientKind::Radial { .. }));
+        assert_eq!(after.stops.len(), 2);
+        assert_eq!(after.stops[1].offset, 1.0);
+    }
+
+    #[test]
+    fn test_stroke_style_keyframes() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 0, "k": [0, 0] },
+                    "r": { "a": 0, "k": 0 },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": [{
+                    "ty": "st",
+                    "c": { "a": 0, "k": [1, 0, 0, 1] },
+                    "o": { "a": 0, "k": 100 },
+                    "w": { "a": 0, "k": 4 },
+                    "lc": 2,
+                    "lj": { "a": 1, "k": [{ "t": 0, "s": [1] }, { "t": 30, "s": [3] }] },
+                    "ml": 4
+                }]
+            }]
+        })
+        .to_string();
+        let composition = Composition::from_slice(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Draw(draw) = &shapes[0] else {
+            panic!("expected a draw");
+        };
+        let stroke = |frame| draw.stroke.as_ref().unwrap().evaluate(frame).into_owned();
+        // The join holds until the next keyframe.
+        assert_eq!(stroke(29.0).join, Join::Miter);
+        assert_eq!(stroke(30.0).join, Join::Bevel);
+        assert_eq!(stroke(0.0).start_cap, Cap::Round);
+    }
+
+    #[test]
+    fn test_stroke_fixed() {
+        let stroke = |dashes, miter_limit| {
+            json!({
+                "ty": "st",
+                "c": { "a": 0, "k": [1, 0, 0, 1] },
+                "o": { "a": 0, "k": 100 },
+                "w": { "a": 0, "k": 4 },
+                "d": dashes,
+                "ml": miter_limit
+            })
+        };
+        let dash = |ty, length| json!({ "n": ty, "v": length });
+        let fixed = json!({ "a": 0, "k": 2 });
+        let animated = json!({ "a": 1, "k": [{ "t": 0, "s": [0] }, { "t": 30, "s": [10] }] });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [
+                    stroke(json!([dash("d", &fixed), dash("o", &fixed)]), json!(4)),
+                    stroke(json!([dash("d", &fixed), dash("o", &animated)]), json!(4)),
+                    stroke(json!([dash("d", &fixed)]), json!(null))
+                ]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shapes");
+        };
+        let stroke = |index: usize| {
+            let Shape::Draw(draw) = &shapes[index] else {
+                panic!("expected a draw");
+            };
+            draw.stroke.as_ref().unwrap()
+        };
+        assert!(stroke(0).is_fixed());
+        // An animated dash offset makes the stroke animated.
+        let Stroke::Animated(dashed) = stroke(1) else {
+            panic!("expected an animated stroke");
+        };
+        assert!(!dashed.is_fixed());
+        assert_eq!(dashed.evaluate(15.0).dash_offset, 5.0);
+        // Strokes without a miter limit are fixed, but stay animated so that
+        // the renderer can supply its default.
+        let Stroke::Animated(unlimited) = stroke(2) else {
+            panic!("expected an animated stroke");
+        };
+        assert!(unlimited.is_fixed());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        use crate::model::Unsupported;
+        let transform = json!({
+            "p": { "a": 0, "k": [0, 0] },
+            "r": { "a": 0, "k": 0 },
+            "s": { "a": 0, "k": [100, 100] }
+        });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 120, "w": 100, "h": 100,
+            "assets": [{ "id": "image_0", "w": 40, "h": 30, "u": "", "p": "image.png" }],
+            "layers": [
+                { "ty": 5, "nm": "Title", "ip": 0, "op": 120, "st": 0, "ks": transform },
+                {
+                    "ty": 2, "nm": "Photo", "refId": "image_0", "ip": 0, "op": 120, "st": 0,
+                    "ks": transform,
+                    "ef": [{ "ty": 29, "nm": "Gaussian Blur", "ef": [] }]
+                }
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let [text, image] = &composition.layers[..] else {
+            panic!("expected two layers");
+        };
+        assert_eq!(text.unsupported, [Unsupported::Text]);
+        assert_eq!(
+            image.unsupported,
+            [
+                Unsupported::Image("image_0".into()),
+                Unsupported::Effect("Gaussian Blur".into())
+            ]
+        );
+        assert_eq!((image.width, image.height), (40.0, 30.0));
+    }
+
+    #[test]
+    fn test_motion_blur() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 120, "w": 100, "h": 100,
+            "mb": { "sa": 90, "sp": 0, "spf": 16, "asl": 128 },
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 120, "st": 0, "mb": true, "shapes": [],
+                "ks": {
+                    "p": { "a": 0, "k": [0, 0] },
+                    "r": { "a": 0, "k": 0 },
+                    "s": { "a": 0, "k": [100, 100] }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        assert!(composition.layers[0].motion_blur);
+        // A quarter frame starting at the frame.
+        let frames = composition
+            .shutter
+            .sample_frames(10.0, 2)
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [10.0625, 10.1875]);
+        // Half a frame centered on the frame by default.
+        let frames = Shutter::default()
+            .sample_frames(10.0, 2)
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [9.875, 10.125]);
+    }
+
+    #[test]
+    fn test_three_d() {
+        let layer = |ddd: u8, mut ks: serde_json::Value| {
+            ks["s"] = json!({ "a": 0, "k": [100, 100, 100] });
+            json!({ "ty": 3, "ip": 0, "op": 60, "st": 0, "ddd": ddd, "ks": ks })
+        };
+        // The default camera is 500 units from the center.
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 300, "h": 400, "ddd": 1,
+            "layers": [
+                layer(1, json!({ "p": { "a": 0, "k": [150, 200, 0] } })),
+                layer(1, json!({ "p": { "a": 0, "k": [150, 200, 500] } })),
+                layer(1, json!({ "p": { "a": 0, "k": [150, 200, 0] }, "ry": { "a": 0, "k": 60 } })),
+                layer(1, json!({ "p": { "a": 0, "k": [150, 200, -600] } })),
+                layer(0, json!({ "p": { "a": 0, "k": [150, 200, 500] } })),
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let transforms = composition
+            .layers
+            .iter()
+            .map(|layer| {
+                let coeffs = layer.transform.evaluate(0.0).into_owned().as_coeffs();
+                coeffs.map(|x| (x * 1e6).round() / 1e6 + 0.0)
+            })
+            .collect::<Vec<_>>();
+        // Layers at the plane of the composition are unchanged.
+        assert_eq!(transforms[0], [1.0, 0.0, 0.0, 1.0, 150.0, 200.0]);
+        // Twice as far from the camera is half the size.
+        assert_eq!(transforms[1], [0.5, 0.0, 0.0, 0.5, 150.0, 200.0]);
+        // Turning around the Y axis foreshortens the X axis.
+        assert_eq!(transforms[2], [0.5, 0.0, 0.0, 1.0, 150.0, 200.0]);
+        // Layers behind the camera are not visible.
+        assert_eq!(transforms[3], [0.0; 6]);
+        // 2D layers ignore depth.
+        assert_eq!(transforms[4], [1.0, 0.0, 0.0, 1.0, 150.0, 200.0]);
+    }
+
+    #[test]
+    fn test_camera() {
+        let layer = |z: f64| {
+            json!({
+                "ty": 3, "ip": 0, "op": 60, "st": 0, "ddd": 1,
+                "ks": {
+                    "p": { "a": 0, "k": [150, 200, z] },
+                    "s": { "a": 0, "k": [100, 100, 100] }
+                }
+            })
+        };
+        // A camera looking at the layers from 100 units to the right, until
+        // frame 30.
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 300, "h": 400, "ddd": 1,
+            "layers": [
+                {
+                    "ty": 13, "ip": 0, "op": 30, "st": 0, "ddd": 1,
+                    "pe": { "a": 0, "k": 1000 },
+                    "ks": {
+                        "p": { "a": 0, "k": [250, 200, -1000] },
+                        "a": { "a": 0, "k": [250, 200, 0] }
+                    }
+                },
+                layer(0.0),
+                layer(1000.0),
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let transform = |index: usize, frame| {
+            let transform = composition.layers[index].transform.evaluate(frame);
+            transform.into_owned().as_coeffs()
+        };
+        assert_eq!(transform(1, 0.0), [1.0, 0.0, 0.0, 1.0, 50.0, 200.0]);
+        assert_eq!(transform(2, 0.0), [0.5, 0.0, 0.0, 0.5, 100.0, 200.0]);
+        // The default camera is used once the camera layer ends.
+        assert_eq!(transform(1, 40.0), [1.0, 0.0, 0.0, 1.0, 150.0, 200.0]);
+    }
+
+    #[test]
+    fn test_camera_null() {
+        let null_layer = |index: usize, name: &str| {
+            json!({
+                "ty": 3, "ind": index, "nm": name, "ip": 0, "op": 60, "st": 0, "ddd": 1,
+                "ks": {
+                    "p": { "a": 0, "k": [50, 60, -500] },
+                    "s": { "a": 0, "k": [100, 100, 100] }
+                }
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 300, "h": 400, "ddd": 1,
+            "layers": [
+                {
+                    "ty": 13, "ind": 3, "parent": 1, "ip": 0, "op": 60, "st": 0, "ddd": 1,
+                    "pe": { "a": 0, "k": 1000 },
+                    "ks": { "p": { "a": 0, "k": [150, 200, -1000] } }
+                },
+                null_layer(1, "Camera"),
+                null_layer(2, "Camera Shake Ctrl"),
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let transform = |index: usize| {
+            let transform = composition.layers[index].transform.evaluate(0.0);
+            transform.into_owned()
+        };
+        // Only the null that the camera is parented to loses its transform.
+        assert_eq!(transform(1), Affine::IDENTITY);
+        assert_ne!(transform(2), Affine::IDENTITY);
+    }
+
+    #[test]
+    fn test_loops() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": {
+                        "a": 1,
+                        "k": [{ "t": 10, "s": [0, 0] }, { "t": 20, "s": [100, 0] }],
+                        "x": "var $bm_rt;\n$bm_rt = loopOut('cycle', 0);"
+                    },
+                    "s": { "a": 0, "k": [100, 100] },
+                    "o": {
+                        "a": 1,
+                        "k": [{ "t": 10, "s": [0] }, { "t": 20, "s": [100] }],
+                        "x": "loopIn(type = \"pingpong\")"
+                    }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        let x = |frame| layer.transform.evaluate(frame).into_owned().translation().x;
+        assert_eq!(x(15.0), 50.0);
+        assert_eq!(x(25.0), 50.0);
+        assert_eq!(x(32.0), 20.0);
+        assert_eq!(layer.opacity.evaluate(8.0), 20.0);
+        assert_eq!(layer.opacity.evaluate(2.0), 80.0);
+        // Loops only repeat keyframes on their own side.
+        assert_eq!(layer.opacity.evaluate(30.0), 100.0);
+    }
+
+    #[test]
+    fn test_auto_orient() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ip": 0, "op": 60, "st": 0, "ao": 1,
+                "ks": {
+                    "p": { "a": 1, "k": [
+                        { "t": 10, "s": [0, 0] },
+                        { "t": 20, "s": [100, 100] },
+                        { "t": 30, "s": [100, 0] }
+                    ] },
+                    "s": { "a": 0, "k": [100, 100] }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        let angle = |frame| {
+            let transform = layer.evaluate_transform(frame, 30.0, 0);
+            (transform * Point::new(1.0, 0.0) - transform * Point::ZERO)
+                .atan2()
+                .to_degrees()
+        };
+        assert!((angle(15.0) - 45.0).abs() < 1e-6);
+        assert!((angle(25.0) + 90.0).abs() < 1e-6);
+        // Before and after the motion, the layer keeps the direction of the
+        // nearest end of the path.
+        assert!((angle(0.0) - 45.0).abs() < 1e-6);
+        assert!((angle(50.0) + 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_audio_tracks() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 90, "w": 100, "h": 100,
+            "assets": [{ "id": "audio_0", "u": "sounds/", "p": "chime.mp3", "e": 0 }],
+            "layers": [
+                {
+                    "ty": 4, "ind": 1, "ip": 0, "op": 90, "st": 0, "shapes": [],
+                    "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } }
+                },
+                {
+                    "ty": 6, "ind": 2, "nm": "Chime", "refId": "audio_0",
+                    "ip": 30, "op": 60, "st": 15, "au": { "lv": { "a": 0, "k": [0, 0] } }
+                }
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        assert_eq!(composition.layers.len(), 1);
+        let tracks = composition.audio_tracks();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].name, "Chime");
+        assert_eq!(tracks[0].file.as_deref(), Some("sounds/chime.mp3"));
+
+        let mut playback = AudioPlayback::new();
+        assert_eq!(playback.update(&composition, 0.0), vec![]);
+        // The sound starts half a second in, as the layer starts at frame
+        // 15.
+        assert_eq!(
+            playback.update(&composition, 30.0),
+            vec![AudioEvent::Start {
+                track: 0,
+                offset: 0.5
+            }]
+        );
+        assert_eq!(playback.update(&composition, 45.0), vec![]);
+        // Seeking back restarts the sound from the new frame.
+        assert_eq!(
+            playback.update(&composition, 36.0),
+            vec![
+                AudioEvent::Stop { track: 0 },
+                AudioEvent::Start {
+                    track: 0,
+                    offset: 0.7
+                }
+            ]
+        );
+        assert_eq!(
+            playback.update(&composition, 60.0),
+            vec![AudioEvent::Stop { track: 0 }]
+        );
+        playback.update(&composition, 40.0);
+        assert_eq!(playback.reset(), vec![AudioEvent::Stop { track: 0 }]);
+    }
+
+    #[test]
+    fn test_solid_layers() {
+        let layer = |color: &str| {
+            json!({
+                "ty": 1, "ip": 0, "op": 60, "st": 0, "sc": color, "sw": 20, "sh": 10,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } }
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [layer("#ff8000"), layer("#0f0"), layer("orange")]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Solid {
+            color,
+            width,
+            height,
+        } = &composition.layers[0].content
+        else {
+            panic!("expected a solid");
+        };
+        assert_eq!(*color, peniko::Color::rgb8(255, 128, 0));
+        assert_eq!((*width, *height), (20.0, 10.0));
+        assert_eq!(composition.layers[0].width, 20.0);
+        assert!(matches!(
+            &composition.layers[1].content,
+            Content::Solid { color, .. } if *color == peniko::Color::rgb8(0, 255, 0)
+        ));
+        // Invalid colors leave the layer empty.
+        assert!(matches!(composition.layers[2].content, Content::None));
+    }
+
+    #[test]
+    fn test_time_stretch() {
+        let layer = |sr: f64, ip: f64, op: f64| {
+            json!({
+                "ty": 0, "refId": "comp", "w": 10, "h": 10,
+                "ip": ip, "op": op, "st": 60, "sr": sr,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } }
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "assets": [{ "id": "comp", "layers": [] }],
+            "layers": [layer(-1.0, 60.0, 0.0), layer(0.0, 0.0, 60.0)]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        // Swapped points of reversed layers are put back in order.
+        let reversed = &composition.layers[0];
+        assert_eq!(reversed.frames, 0.0..60.0);
+        assert!(reversed.is_active(0.0));
+        let timeline = reversed.timeline(30.0).unwrap();
+        assert_eq!(timeline.local_frame(0.0), 59.0);
+        // A zero stretch plays at normal speed rather than dividing by zero.
+        assert_eq!(composition.layers[1].stretch, 1.0);
+    }
+
+    #[test]
+    fn test_nested_opacity() {
+        let transform = |opacity: f64| {
+            json!({
+                "ty": "tr", "o": { "a": 0, "k": opacity },
+                "p": { "a": 0, "k": [0, 0] }, "a": { "a": 0, "k": [0, 0] },
+                "s": { "a": 0, "k": [100, 100] }, "r": { "a": 0, "k": 0 }
+            })
+        };
+        let rect = json!({
+            "ty": "rc", "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] },
+            "r": { "a": 0, "k": 0 }
+        });
+        let fill =
+            json!({ "ty": "fl", "c": { "a": 0, "k": [1, 0, 0, 1] }, "o": { "a": 0, "k": 50 } });
+        let gradient = json!({
+            "ty": "gf", "t": 1, "o": { "a": 0, "k": 50 },
+            "s": { "a": 0, "k": [0, 0] }, "e": { "a": 0, "k": [10, 0] },
+            "g": { "p": 2, "k": { "a": 0, "k": [0, 0, 0, 0, 1, 1, 1, 1] } }
+        });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "o": { "a": 0, "k": 50 },
+                    "p": { "a": 0, "k": [0, 0] },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": [
+                    {
+                        "ty": "gr",
+                        "it": [
+                            { "ty": "gr", "it": [rect.clone(), fill, transform(50.0)] },
+                            transform(50.0)
+                        ]
+                    },
+                    rect,
+                    gradient
+                ]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        // Each of the layer, the two groups and the fill halves the opacity
+        // once, and gradient fills respect their own opacity.
+        assert_eq!(
+            composition.layers[0].draw_opacities(0.0, 60.0, 0),
+            vec![0.0625, 0.25]
+        );
+    }
+
+    #[test]
+    fn test_shape_split_position() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [{
+                    "ty": "gr",
+                    "it": [{
+                        "ty": "rc", "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [10, 10] },
+                        "r": { "a": 0, "k": 0 }
+                    }, {
+                        "ty": "tr",
+                        "p": {
+                            "s": true,
+                            "x": { "a": 1, "k": [{ "t": 0, "s": [0] }, { "t": 10, "s": [20] }] },
+                            "y": { "a": 0, "k": 5 }
+                        },
+                        "a": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] },
+                        "r": { "a": 0, "k": 0 }, "o": { "a": 0, "k": 100 }
+                    }]
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Group(_, Some(group)) = &shapes[0] else {
+            panic!("expected a group with a transform");
+        };
+        let translation = group.transform.evaluate(5.0).into_owned().translation();
+        assert_eq!(translation, kurbo::Vec2::new(10.0, 5.0));
+    }
+
+    #[test]
+    fn test_blend_modes() {
+        let layer = |mode| {
+            json!({
+                "ty": 4, "ip": 0, "op": 60, "st": 0, "shapes": [], "bm": mode,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } }
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": (0..=17).map(layer).collect::<Vec<_>>()
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let modes = composition
+            .layers
+            .iter()
+            .map(|layer| layer.blend_mode)
+            .collect::<Vec<_>>();
+        assert_eq!(modes[0], None);
+        assert_eq!(modes[1], Some(Mix::Multiply.into()));
+        assert_eq!(modes[15], Some(Mix::Luminosity.into()));
+        // Every separable and non-separable mode has its own mix.
+        let mixes = modes[1..16].iter().map(|mode| mode.unwrap().mix as u8);
+        assert_eq!(mixes.collect::<std::collections::BTreeSet<_>>().len(), 15);
+        assert_eq!(modes[16], Some(BlendMode::new(Mix::Normal, Compose::Plus)));
+        // Hard mix has no equivalent and blends normally.
+        assert_eq!(modes[17], None);
+    }
+
+    #[test]
+    fn test_repeaters_and_trims() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [
+                    {
+                        "ty": "rp", "c": { "a": 0, "k": 4 }, "m": 2,
+                        "tr": { "r": { "a": 0, "k": 90 }, "so": { "a": 0, "k": 50 } }
+                    },
+                    {
+                        "ty": "tm", "s": { "a": 0, "k": 0 }, "e": { "a": 0, "k": 25 },
+                        "o": { "a": 1, "k": [{ "t": 0, "s": [0] }, { "t": 60, "s": [360] }] },
+                        "m": 2
+                    }
+                ]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Repeater(Repeater::Fixed(repeater)) = &shapes[0] else {
+            panic!("expected a fixed repeater");
+        };
+        assert_eq!(repeater.copies, 4);
+        assert_eq!(repeater.rotation, 90.0);
+        assert_eq!(repeater.scale, kurbo::Vec2::new(100.0, 100.0));
+        assert_eq!(
+            (repeater.start_opacity, repeater.end_opacity),
+            (50.0, 100.0)
+        );
+        assert_eq!(repeater.trim_offset, 0.0);
+        assert_eq!(repeater.composite, RepeaterComposite::Below);
+        let Shape::Modifier(PathModifier::Animated(modifier)) = &shapes[1] else {
+            panic!("expected an animated modifier");
+        };
+        let fixed::PathModifier::Trim(trim) = modifier.evaluate(30.0) else {
+            panic!("expected a trim");
+        };
+        assert_eq!((trim.start, trim.end, trim.offset), (0.0, 25.0, 180.0));
+        assert!(trim.individually);
+    }
+
+    #[test]
+    fn test_shape_direction() {
+        let square = json!({
+            "c": true, "v": [[0, 0], [10, 0], [10, 10], [0, 10]],
+            "i": [[0, 0], [0, 0], [0, 0], [0, 0]], "o": [[0, 0], [0, 0], [0, 0], [0, 0]]
+        });
+        let shapes = json!([
+            { "ty": "rc", "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] }, "r": { "a": 0, "k": 0 } },
+            { "ty": "el", "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] } },
+            {
+                "ty": "sr", "sy": 2, "p": { "a": 0, "k": [5, 5] }, "or": { "a": 0, "k": 5 },
+                "os": { "a": 0, "k": 0 }, "r": { "a": 0, "k": 0 }, "pt": { "a": 0, "k": 5 }
+            },
+            { "ty": "sh", "ks": { "a": 0, "k": square } },
+            { "ty": "sh", "ks": { "a": 1, "k": [{ "t": 0, "s": [square] }, { "t": 60, "s": [square] }] } }
+        ]);
+        // Returns the signed area of each geometry with the given direction.
+        let areas = |direction| {
+            let shapes = shapes
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|shape| {
+                    let mut shape = shape.clone();
+                    shape["d"] = json!(direction);
+                    shape
+                })
+                .collect::<Vec<_>>();
+            let source = json!({
+                "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+                "layers": [{
+                    "ty": 4, "ip": 0, "op": 60, "st": 0, "shapes": shapes,
+                    "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } }
+                }]
+            });
+            let composition = Composition::from_json(source).unwrap();
+            let Content::Shape(shapes) = &composition.layers[0].content else {
+                panic!("expected shape content");
+            };
+            shapes
+                .iter()
+                .map(|shape| {
+                    let Shape::Geometry(geometry) = shape else {
+                        panic!("expected geometry");
+                    };
+                    let mut path = vec![];
+                    geometry.evaluate(30.0, &mut path);
+                    kurbo::Shape::area(&kurbo::BezPath::from_vec(path))
+                })
+                .collect::<Vec<_>>()
+        };
+        let normal = areas(1);
+        let reversed = areas(3);
+        for (normal, reversed) in normal.iter().zip(&reversed) {
+            assert!(normal.abs() > 1.0);
+            assert!((normal + reversed).abs() < 1e-6, "{normal} {reversed}");
+        }
+    }
+
+    #[test]
+    fn test_hidden() {
+        let transform = json!({ "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [
+                { "ty": 3, "ind": 1, "hd": true, "ip": 0, "op": 60, "st": 0, "ks": transform },
+                {
+                    "ty": 4, "ind": 2, "parent": 1, "ip": 0, "op": 60, "st": 0, "ks": transform,
+                    "shapes": [
+                        { "ty": "el", "hd": 1, "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] } },
+                        { "ty": "fl", "c": { "a": 0, "k": [1, 0, 0] }, "o": { "a": 0, "k": 100 } }
+                    ]
+                }
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        // Hidden layers are kept, so children still follow them.
+        assert_eq!(composition.layers.len(), 2);
+        assert!(composition.layers[0].hidden);
+        assert!(!composition.layers[1].hidden);
+        assert_eq!(composition.layers[1].parent, Some(0));
+        let Content::Shape(shapes) = &composition.layers[1].content else {
+            panic!("expected shape content");
+        };
+        assert!(
+            matches!(&shapes[0], Shape::Hidden(shape) if matches!(**shape, Shape::Geometry(_)))
+        );
+        assert!(matches!(shapes[1], Shape::Draw(_)));
+    }
+
+    #[test]
+    fn test_track_mattes() {
+        let layer = |key: &str, value| {
+            let mut layer = json!({
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [
+                    { "ty": "rc", "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] }, "r": { "a": 0, "k": 0 } },
+                    { "ty": "fl", "c": { "a": 0, "k": [1, 0, 0] }, "o": { "a": 0, "k": 100 } }
+                ]
+            });
+            layer[key] = json!(value);
+            layer
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [
+                layer("td", 1), layer("tt", 1),
+                layer("td", 1), layer("tt", 2),
+                layer("td", 1), layer("tt", 0),
+                layer("td", 1), layer("tt", 3),
+                layer("td", 1), layer("tt", 4)
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let mattes = composition
+            .layers
+            .iter()
+            .map(|layer| (layer.is_mask, layer.mask_layer))
+            .collect::<Vec<_>>();
+        // Each matted layer uses the matte layer above it, and normal
+        // mattes leave the layer unmatted.
+        assert_eq!(
+            mattes,
+            [
+                (true, None),
+                (false, Some((Matte::Alpha, 0))),
+                (true, None),
+                (false, Some((Matte::InvertAlpha, 2))),
+                (true, None),
+                (false, None),
+                (true, None),
+                (false, Some((Matte::Luma, 6))),
+                (true, None),
+                (false, Some((Matte::InvertLuma, 8))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_masks() {
+        let mask = |properties: serde_json::Value| {
+            let mut mask = json!({
+                "mode": "a",
+                "pt": {
+                    "a": 0,
+                    "k": {
+                        "c": true,
+                        "v": [[0, 0], [10, 0], [10, 10], [0, 10]],
+                        "i": [[0, 0], [0, 0], [0, 0], [0, 0]],
+                        "o": [[0, 0], [0, 0], [0, 0], [0, 0]]
+                    }
+                },
+                "o": { "a": 0, "k": 100 }
+            });
+            mask.as_object_mut()
+                .unwrap()
+                .extend(properties.as_object().unwrap().clone());
+            mask
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0, "hasMask": true,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "masksProperties": [
+                    mask(json!({})),
+                    mask(json!({ "inv": true, "mode": "s" })),
+                    mask(json!({ "x": { "a": 1, "k": [{ "t": 0, "s": [0] }, { "t": 30, "s": [-6] }] } })),
+                    mask(json!({ "f": { "a": 0, "k": [3, 5] } }))
+                ],
+                "shapes": []
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let masks = &composition.layers[0].masks;
+        assert_eq!(masks.len(), 4);
+        assert!(!masks[0].inverted);
+        // Inverted masks keep their mode, and cover the region outside
+        // their geometry.
+        assert!(masks[1].inverted);
+        assert_eq!(masks[1].mode, MaskMode::Subtract);
+        // Expansions default to zero, and may be animated.
+        assert!(matches!(masks[0].expansion, Value::Fixed(expansion) if expansion == 0.0));
+        assert_eq!(masks[2].expansion.evaluate(15.0), -3.0);
+        // Feathers that differ horizontally and vertically use the widest.
+        assert_eq!(masks[0].feather.evaluate(0.0), 0.0);
+        assert_eq!(masks[3].feather.evaluate(0.0), 5.0);
+    }
+
+    #[test]
+    fn test_adjustment_layer() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 12, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "o": { "a": 0, "k": 50 } },
+                "ef": [{
+                    "ty": 21,
+                    "ef": [
+                        { "ty": 10, "v": { "a": 0, "k": 0 } },
+                        { "ty": 7, "v": { "a": 0, "k": 0 } },
+                        { "ty": 2, "v": { "a": 0, "k": [0, 0, 1, 1] } },
+                        { "ty": 7, "v": { "a": 0, "k": 0 } },
+                        { "ty": 0, "v": { "a": 0, "k": 0 } },
+                        { "ty": 0, "v": { "a": 0, "k": 0 } },
+                        { "ty": 0, "v": { "a": 0, "k": 1 } }
+                    ]
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        assert!(matches!(layer.content, Content::Adjustment));
+        assert!(matches!(layer.effects[..], [Effect::Fill(_)]));
+        assert_eq!(layer.opacity.evaluate(0.0), 50.0);
+    }
+
+    #[test]
+    fn test_inline_instances() {
+        let transform = |x| {
+            json!({
+                "p": { "a": 0, "k": [x, 0] }, "s": { "a": 0, "k": [100, 100] },
+                "o": { "a": 0, "k": 50 }
+            })
+        };
+        let shape_layer = |x| {
+            json!({
+                "ty": 4, "ip": 0, "op": 60, "st": 0, "ks": transform(x),
+                "shapes": [
+                    { "ty": "el", "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [10, 10] } },
+                    { "ty": "fl", "c": { "a": 0, "k": [1, 0, 0] }, "o": { "a": 0, "k": 100 } }
+                ]
+            })
+        };
+        let instance = |id, stretch| {
+            json!({
+                "ty": 0, "refId": id, "w": 100, "h": 100, "ip": 0, "op": 60, "st": 0,
+                "sr": stretch, "ks": transform(0)
+            })
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "assets": [
+                { "id": "folder", "layers": [shape_layer(10), shape_layer(20)] },
+                { "id": "outer", "layers": [instance("folder", 1)] }
+            ],
+            "layers": [instance("outer", 1), instance("folder", 2)]
+        })
+        .to_string();
+        let options = ImportOptions {
+            inline_instances: true,
+            ..Default::default()
+        };
+        let composition = Composition::from_slice_with_options(&source, &options).unwrap();
+        // Nested instances are inlined into the instance of the outer asset.
+        let Content::Shape(outer) = &composition.layers[0].content else {
+            panic!("expected the instance to be inlined");
+        };
+        let [Shape::Group(inner, Some(inner_transform))] = &outer[..] else {
+            panic!("expected a group for the instance within the asset");
+        };
+        assert_eq!(inner_transform.opacity.evaluate(0.0), 50.0);
+        assert_eq!(inner.len(), 2);
+        let Shape::Group(shapes, Some(group)) = &inner[1] else {
+            panic!("expected a group for each layer of the asset");
+        };
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(
+            group.transform.evaluate(0.0).into_owned(),
+            Affine::translate((20.0, 0.0))
+        );
+        // Stretched instances are kept.
+        assert!(matches!(
+            composition.layers[1].content,
+            Content::Instance { .. }
+        ));
+        let composition = Composition::from_slice(&source).unwrap();
+        assert!(matches!(
+            composition.layers[0].content,
+            Content::Instance { .. }
+        ));
+    }
+
+    #[test]
+    fn test_selectors() {
+        let transform = json!({ "p": { "a": 0, "k": [0, 0] } });
+        let layer = |name, class| json!({ "ty": 3, "nm": name, "cl": class, "ip": 0, "op": 60, "st": 0, "ks": transform });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "assets": [{ "id": "card", "layers": [layer("Icon 1", "badge round"), layer("Icon 2", "")] }],
+            "layers": [
+                {
+                    "ty": 0, "nm": "Card", "refId": "card", "w": 100, "h": 100,
+                    "ip": 0, "op": 60, "st": 0, "ks": transform
+                },
+                layer("Background", "")
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let select = |source: &str| Selector::parse(source).unwrap().select(&composition);
+        assert_eq!(select("*"), ["layers/0", "layers/1"]);
+        assert_eq!(select("Card/Icon*"), ["assets/card/0", "assets/card/1"]);
+        assert_eq!(select("**/.badge.opacity"), ["assets/card/0/opacity"]);
+        assert_eq!(
+            select("Background.transform.position"),
+            ["layers/1/transform/position"]
+        );
+        assert!(select("Icon 1").is_empty());
+    }
+
+    #[test]
+    fn test_layer_styles() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0, "shapes": [],
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "sy": [
+                    { "ty": 0, "nm": "Stroke", "s": { "a": 0, "k": 4 } },
+                    { "ty": 1, "nm": "Drop Shadow", "d": { "a": 0, "k": 10 } },
+                    { "ty": 2, "nm": "Inner Shadow", "a": { "a": 0, "k": 90 } },
+                    { "ty": 3, "nm": "Outer Glow", "o": { "a": 0, "k": 50 } },
+                    { "ty": 7, "nm": "Color Overlay" }
+                ]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        assert!(layer.effects.is_empty());
+        let [LayerStyle::Stroke(stroke), LayerStyle::DropShadow(shadow), LayerStyle::InnerShadow(inner), LayerStyle::OuterGlow(glow)] =
+            &layer.styles[..]
+        else {
+            panic!("unexpected styles {:?}", layer.styles);
+        };
+        assert_eq!(stroke.evaluate(0.0).size, 4.0);
+        assert_eq!(shadow.evaluate(0.0).offset.hypot().round(), 10.0);
+        // A light from above casts the shadow down from the top edge.
+        let offset = inner.evaluate(0.0).offset;
+        assert!(offset.x.abs() < 1e-9 && (offset.y - 5.0).abs() < 1e-9);
+        assert_eq!(glow.evaluate(0.0).color.a, 128);
+        assert_eq!(
+            layer.unsupported,
+            vec![Unsupported::Effect("Color Overlay".into())]
+        );
+    }
+
+    #[test]
+    fn test_freeze() {
+        let position = json!({ "a": 1, "k": [{ "t": 0, "s": [0, 0] }, { "t": 60, "s": [60, 0] }] });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "assets": [{
+                "id": "comp",
+                "layers": [{
+                    "ty": 4, "ip": 0, "op": 60, "st": 0,
+                    "ks": { "p": position, "s": { "a": 0, "k": [100, 100] } },
+                    "shapes": [{
+                        "ty": "rc", "p": { "a": 0, "k": [0, 0] }, "r": { "a": 0, "k": 0 },
+                        "s": { "a": 1, "k": [{ "t": 0, "s": [10, 10] }, { "t": 60, "s": [70, 70] }] }
+                    }]
+                }]
+            }],
+            "layers": [
+                {
+                    "ty": 0, "refId": "comp", "w": 100, "h": 100, "ip": 0, "op": 60, "st": 10,
+                    "ks": { "p": position, "s": { "a": 0, "k": [100, 100] } }
+                },
+                {
+                    "ty": 4, "ip": 40, "op": 60, "st": 0, "shapes": [],
+                    "ks": { "p": { "a": 0, "k": [5, 5] }, "s": { "a": 0, "k": [100, 100] } }
+                }
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let frozen = composition.freeze(30.0);
+        assert_eq!(frozen.frames, composition.frames);
+        assert_eq!(frozen.layers.len(), 2);
+        let instance = &frozen.layers[0];
+        let Transform::Fixed(transform) = &instance.transform else {
+            panic!("expected a fixed transform");
+        };
+        assert_eq!(transform.translation().x, 30.0);
+        assert!(instance.is_active(0.0) && instance.is_active(59.0));
+        // The asset is frozen at the local frame of the instance.
+        let Content::Instance {
+            name, time_remap, ..
+        } = &instance.content
+        else {
+            panic!("expected an instance");
+        };
+        assert!(time_remap.is_none());
+        assert_eq!(instance.timeline(60.0).unwrap().local_frame(0.0), 0.0);
+        let asset = &frozen.assets[name];
+        assert_eq!(
+            asset[0].evaluate_transform(0.0, 60.0, 0).translation().x,
+            20.0
+        );
+        let Content::Shape(shapes) = &asset[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Geometry(geometry) = &shapes[0] else {
+            panic!("expected geometry");
+        };
+        let mut path = vec![];
+        geometry.evaluate(59.0, &mut path);
+        assert_eq!(
+            kurbo::Shape::bounding_box(&kurbo::BezPath::from_vec(path)).width(),
+            30.0
+        );
+        // Layers inactive at the frame are kept empty for their indices.
+        let inactive = &frozen.layers[1];
+        assert!(matches!(inactive.content, Content::None));
+        assert!(inactive.opacity.is_fixed());
+        assert_eq!(inactive.opacity.evaluate(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_loose_encodings() {
+        let source = json!({
+            "v": "5.5.2", "fr": "60", "ip": 0, "op": "60.0", "w": 100, "h": "100", "ddd": false,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": "0", "ind": "1", "hd": 0,
+                "ks": {
+                    "p": { "a": false, "k": ["10", 20] },
+                    "s": { "a": 0, "k": [100, 100] },
+                    "o": { "a": true, "k": [{ "t": "0", "s": ["0"] }, { "t": 60, "s": [100] }] }
+                },
+                "shapes": [{
+                    "ty": "sh",
+                    "ks": { "a": 0, "k": { "c": 1, "v": [["0", 0], [10, 0]], "i": [[0, 0], [0, 0]], "o": [[0, 0], [0, 0]] } }
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source.clone()).unwrap();
+        assert_eq!(composition.frames, 0.0..60.0);
+        assert_eq!(composition.height, 100);
+        let layer = &composition.layers[0];
+        assert_eq!(layer.evaluate_opacity(30.0, 60.0, 0), 50.0);
+        assert_eq!(
+            layer.evaluate_transform(0.0, 60.0, 0).translation(),
+            kurbo::Vec2::new(10.0, 20.0)
+        );
+        // Strict mode only accepts the encodings of the schema.
+        let bytes = serde_json::to_vec(&source).unwrap();
+        assert!(Composition::from_slice(&bytes).is_ok());
+        assert!(Composition::from_slice_strict(&bytes).is_err());
+        // Strictness only applies to the thread of the call, and not after
+        // it returns.
+        super::permissive::strict(|| {
+            let bytes = bytes.clone();
+            let other = std::thread::spawn(move || Composition::from_slice(&bytes).is_ok());
+            assert!(other.join().unwrap());
+        });
+        assert!(Composition::from_slice(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_markers() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 90, "w": 100, "h": 100,
+            "layers": [],
+            "markers": [
+                { "cm": "intro", "tm": 0, "dr": 30 },
+                { "cm": "loop", "tm": 30, "dr": 60 },
+                { "cm": "end", "tm": 90 },
+                { "cm": "untimed" }
+            ]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let markers = composition.markers();
+        assert_eq!(markers.len(), 3);
+        assert_eq!(markers[1].name, "loop");
+        assert_eq!(composition.frames_for_marker("loop"), Some(30.0..90.0));
+        // Markers without a duration cover a single frame.
+        assert_eq!(composition.frames_for_marker("end"), Some(90.0..90.0));
+        assert_eq!(composition.frames_for_marker("untimed"), None);
+    }
+
+    #[test]
+    fn test_wiggles() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ind": 1, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 0, "k": [50, 50], "x": "var $bm_rt;\n$bm_rt = wiggle(2, 10);" },
+                    "s": { "a": 0, "k": [100, 100] },
+                    "o": { "a": 0, "k": 100, "x": "wiggle(1, 40, 2)" }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        assert_eq!(layer.wiggles.len(), 2);
+        let position = |frame, seed| {
+            layer
+                .evaluate_transform(frame, 30.0, seed)
+                .translation()
+                .to_point()
+        };
+        // The same seed produces the same motion.
+        assert_eq!(position(7.0, 1), position(7.0, 1));
+        assert_ne!(position(7.0, 1), position(7.0, 2));
+        assert_ne!(position(7.0, 1), position(8.0, 1));
+        for frame in 0..60 {
+            let offset = position(frame as f64, 1) - Point::new(50.0, 50.0);
+            assert!(offset.x.abs() <= 10.0 && offset.y.abs() <= 10.0);
+            let opacity = layer.evaluate_opacity(frame as f64, 30.0, 1);
+            assert!((40.0..=100.0).contains(&opacity));
+        }
+    }
+
+    #[test]
+    fn test_roving_keyframes() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ind": 1, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 1, "k": [
+                        { "t": 0, "s": [0, 0] },
+                        { "t": 30, "s": [10, 0], "r": 1 },
+                        { "t": 35, "s": [10, 20], "r": 1 },
+                        { "t": 40, "s": [30, 20] }
+                    ] },
+                    "s": { "a": 0, "k": [100, 100] }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let layer = &composition.layers[0];
+        let position = |frame| {
+            layer
+                .evaluate_transform(frame, 30.0, 0)
+                .translation()
+                .to_point()
+        };
+        // The path is 50 long, so the roving keyframes move to the frames
+        // at 10 and 30 along it.
+        assert_eq!(position(8.0), Point::new(10.0, 0.0));
+        assert_eq!(position(16.0), Point::new(10.0, 10.0));
+        assert_eq!(position(24.0), Point::new(10.0, 20.0));
+        assert_eq!(position(40.0), Point::new(30.0, 20.0));
+    }
+
+    #[test]
+    fn test_playhead() {
+        let composition = Composition {
+            frames: 0.0..60.0,
+            frame_rate: 30.0,
+            ..Default::default()
+        };
+        // A composition played once stops on the frame before the end.
+        let mut playhead = Playhead::new(&composition, PlayMode::Once);
+        assert_eq!(playhead.advance(1.0), 30.0);
+        assert_eq!(playhead.advance(0.99), 59.0);
+        assert!(!playhead.is_complete());
+        assert_eq!(playhead.advance(0.02), 59.0);
+        assert!(playhead.is_complete());
+        assert_eq!(playhead.events(), [PlayheadEvent::Completed]);
+        assert_eq!(playhead.advance(1.0), 59.0);
+        assert!(playhead.events().is_empty());
+        // Turning back resumes playback towards the start.
+        playhead.set_forward(false);
+        assert_eq!(playhead.advance(1.5), 15.0);
+        assert_eq!(playhead.advance(1.0), 0.0);
+        assert!(playhead.is_complete());
+        // Loops report every wrap around.
+        let mut playhead = Playhead::new(&composition, PlayMode::Loop);
+        assert_eq!(playhead.advance(2.0), 0.0);
+        assert_eq!(playhead.events(), [PlayheadEvent::Looped]);
+        assert_eq!(playhead.advance(4.5), 15.0);
+        assert_eq!(playhead.events(), [PlayheadEvent::Looped; 2]);
+        assert!(!playhead.is_complete());
+        // Ping-pong reports every change of direction.
+        let mut playhead = Playhead::new(&composition, PlayMode::PingPong);
+        assert_eq!(playhead.advance(2.5), 45.0);
+        assert!(!playhead.is_forward());
+        assert_eq!(
+            playhead.events(),
+            [PlayheadEvent::Reversed { forward: false }]
+        );
+        assert_eq!(playhead.advance(2.0), 15.0);
+        assert!(playhead.is_forward());
+        assert_eq!(
+            playhead.events(),
+            [PlayheadEvent::Reversed { forward: true }]
+        );
+        playhead.seek(30.0);
+        assert_eq!(playhead.frame(), 30.0);
+        assert!(playhead.events().is_empty());
+    }
+
+    #[test]
+    fn test_axis_easing() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ind": 1, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 1, "k": [
+                        {
+                            "t": 0, "s": [0, 0],
+                            "o": { "x": [0.1, 0.2], "y": [0, 0] },
+                            "i": { "x": [0.8, 0.9], "y": 1 }
+                        },
+                        { "t": 30, "s": [30, 60] }
+                    ] },
+                    "s": { "a": 0, "k": [100, 100] }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Transform::Animated(transform) = &composition.layers[0].transform else {
+            panic!("expected an animated transform");
+        };
+        let animated::Transform {
+            position: animated::Position::Value(Value::Animated(position)),
+            ..
+        } = &**transform
+        else {
+            panic!("expected an animated position");
+        };
+        // Each keyframe keeps a single time, with the easing of each
+        // dimension.
+        assert_eq!(position.times.len(), 2);
+        let time = &position.times[0];
+        assert_eq!(time.out_tangent.map(|handle| handle.x), Some(0.1));
+        assert_eq!(time.axis_out_tangents[0].map(|handle| handle.x), Some(0.2));
+        assert_eq!(time.axis_in_tangents[0].map(|handle| handle.x), Some(0.9));
+        assert!(time.axis_out_tangents[1].is_none());
+        // Halfway through, x eases with (0.1, 0) and (0.8, 1), and y with
+        // (0.2, 0) and (0.9, 1), rather than both reaching the midpoint.
+        let point = position.evaluate(15.0);
+        assert!((point.x - 16.318).abs() < 1e-3, "{point:?}");
+        assert!((point.y - 27.363).abs() < 1e-3, "{point:?}");
+    }
+
+    #[test]
+    fn test_instance_overrides() {
+        let fill = json!({
+            "ty": 4, "ip": 0, "op": 60, "st": 0,
+            "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+            "shapes": [
+                { "ty": "fl", "c": { "a": 0, "k": [0, 0, 0], "sid": "accent" }, "o": { "a": 0, "k": 100 } }
+            ]
+        });
+        let instance = |id, slots| {
+            json!({
+                "ty": 0, "refId": id, "w": 100, "h": 100, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "slots": slots
+            })
+        };
+        let accent = |color| json!({ "accent": { "p": { "a": 0, "k": color } } });
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "slots": accent(json!([1, 0, 0])),
+            "assets": [
+                { "id": "badge", "layers": [fill] },
+                { "id": "card", "layers": [fill, instance("badge", accent(json!([0, 1, 0])))] }
+            ],
+            "layers": [instance("card", accent(json!([0, 0, 1]))), instance("card", json!({}))]
+        });
+        let mut composition = Composition::from_json(source).unwrap();
+        fn asset<'a>(composition: &'a Composition, layer: &Layer) -> &'a [Layer] {
+            let Content::Instance { name, .. } = &layer.content else {
+                panic!("expected an instance");
+            };
+            &composition.assets[name]
+        }
+        let color = |layer: &Layer| {
+            let Content::Shape(shapes) = &layer.content else {
+                panic!("expected shapes");
+            };
+            let Shape::Draw(draw) = &shapes[0] else {
+                panic!("expected a fill");
+            };
+            match draw.brush.evaluate(1.0, 0.0).into_owned() {
+                peniko::Brush::Solid(color) => color,
+                brush => panic!("expected a solid color, found {brush:?}"),
+            }
+        };
+        // Overrides apply to the asset and to the assets it instances,
+        // taking precedence over those of nested instances.
+        let overridden = asset(&composition, &composition.layers[0]);
+        assert_eq!(color(&overridden[0]), peniko::Color::rgb(0.0, 0.0, 1.0));
+        assert_eq!(
+            color(&asset(&composition, &overridden[1])[0]),
+            peniko::Color::rgb(0.0, 0.0, 1.0)
+        );
+        // Other instances keep the values of the slots of the file.
+        let plain = asset(&composition, &composition.layers[1]);
+        assert_eq!(color(&plain[0]), peniko::Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!(
+            color(&asset(&composition, &plain[1])[0]),
+            peniko::Color::rgb(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            color(&composition.assets["badge"][0]),
+            peniko::Color::rgb(1.0, 0.0, 0.0)
+        );
+        let Content::Instance {
+            name, overrides, ..
+        } = &composition.layers[0].content
+        else {
+            panic!("expected an instance");
+        };
+        assert_eq!(name, "card#1");
+        assert_eq!(overrides["accent"], [0.0, 0.0, 1.0]);
+        // Overrides take precedence over values set at runtime, which still
+        // apply to the properties that are not overridden.
+        let white = SlotValue::Color(peniko::Color::WHITE);
+        assert!(composition.set_slot("accent", white));
+        assert!(composition.apply_theme(&Theme::from_iter([("accent", white)])));
+        let overridden = asset(&composition, &composition.layers[0]);
+        let plain = asset(&composition, &composition.layers[1]);
+        assert_eq!(color(&overridden[0]), peniko::Color::rgb(0.0, 0.0, 1.0));
+        assert_eq!(
+            color(&asset(&composition, &overridden[1])[0]),
+            peniko::Color::rgb(0.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            color(&asset(&composition, &plain[1])[0]),
+            peniko::Color::rgb(0.0, 1.0, 0.0)
+        );
+        assert_eq!(color(&plain[0]), peniko::Color::WHITE);
+        // Clones keep their overrides.
+        let mut clone = composition.clone();
+        assert!(clone.set_slot("accent", white));
+        assert_eq!(
+            color(&clone.assets["card#1"][0]),
+            peniko::Color::rgb(0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_swap_asset() {
+        let source = |color| {
+            json!({
+                "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+                "assets": [{ "id": "card", "layers": [{
+                    "ty": 4, "ip": 0, "op": 60, "st": 0,
+                    "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                    "shapes": [
+                        { "ty": "rc", "p": { "a": 0, "k": [50, 50] }, "s": { "a": 0, "k": [20, 20] }, "r": { "a": 0, "k": 0 } },
+                        { "ty": "fl", "c": { "a": 0, "k": color }, "o": { "a": 0, "k": 100 } }
+                    ]
+                }] }],
+                "layers": [{
+                    "ty": 0, "refId": "card", "w": 100, "h": 100, "ip": 0, "op": 60, "st": 0,
+                    "ks": { "p": { "a": 0, "k": [10, 0] }, "s": { "a": 0, "k": [100, 100] } }
+                }]
+            })
+            .to_string()
+        };
+        let load = |color, options: &ImportOptions| {
+            Composition::from_slice_with_options(source(color), options).unwrap()
+        };
+        let mut painter = Painter::<Digest>::new();
+        let mut digest =
+            |composition: &Composition| painter.evaluate_digest(composition, 1.0, None);
+        let options = ImportOptions::default();
+        let red = load(json!([1, 0, 0]), &options);
+        let blue = load(json!([0, 0, 1]), &options);
+        // The instance renders the new layers as if they had been imported.
+        let mut swapped = red.clone();
+        let previous = swapped.swap_asset("card", blue.assets["card"].clone());
+        assert_eq!(previous.unwrap().len(), 1);
+        assert_eq!(digest(&swapped), digest(&blue));
+        assert_ne!(digest(&swapped), digest(&red));
+        // Assets that did not exist are added.
+        assert!(swapped.swap_asset("other", vec![]).is_none());
+        assert_eq!(digest(&swapped), digest(&blue));
+        // Inlined instances keep the layers of the asset at import.
+        let options = ImportOptions {
+            inline_instances: true,
+            ..Default::default()
+        };
+        let mut inlined = load(json!([1, 0, 0]), &options);
+        let before = digest(&inlined);
+        inlined.swap_asset("card", blue.assets["card"].clone());
+        assert_eq!(digest(&inlined), before);
+    }
+
+    #[test]
+    fn test_swap_overridden_asset() {
+        let fill = |name| {
+            json!({
+                "ty": 4, "nm": name, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [
+                    { "ty": "fl", "c": { "a": 0, "k": [0, 0, 0], "sid": "accent" }, "o": { "a": 0, "k": 100 } }
+                ]
+            })
+        };
+        let instance = |slots| {
+            json!({
+                "ty": 0, "refId": "card", "w": 100, "h": 100, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "slots": slots
+            })
+        };
+        let source = |name| {
+            json!({
+                "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+                "slots": { "accent": { "p": { "a": 0, "k": [1, 0, 0] } } },
+                "assets": [{ "id": "card", "layers": [fill(name)] }],
+                "layers": [
+                    instance(json!({ "accent": { "p": { "a": 0, "k": [0, 0, 1] } } })),
+                    instance(json!({}))
+                ]
+            })
+        };
+        let mut composition = Composition::from_json(source("old")).unwrap();
+        let new = Composition::from_json(source("new")).unwrap().assets["card"].clone();
+        let previous = composition.swap_asset("card", new).unwrap();
+        assert_eq!(previous[0].name, "old");
+        let rendered = |index: usize| {
+            let Content::Instance { name, source, .. } = &composition.layers[index].content else {
+                panic!("expected an instance");
+            };
+            assert_eq!(source, "card");
+            let layer = &composition.assets[name][0];
+            let Content::Shape(shapes) = &layer.content else {
+                panic!("expected shapes");
+            };
+            let Shape::Draw(draw) = &shapes[0] else {
+                panic!("expected a fill");
+            };
+            let color = match draw.brush.evaluate(1.0, 0.0).into_owned() {
+                peniko::Brush::Solid(color) => color,
+                brush => panic!("expected a solid color, found {brush:?}"),
+            };
+            (layer.name.clone(), color)
+        };
+        // Both instances render the new layers, and the overridden one
+        // keeps its override.
+        assert_eq!(
+            rendered(0),
+            ("new".into(), peniko::Color::rgb(0.0, 0.0, 1.0))
+        );
+        assert_eq!(
+            rendered(1),
+            ("new".into(), peniko::Color::rgb(1.0, 0.0, 0.0))
+        );
+        // The previous copy is replaced rather than kept alongside.
+        assert_eq!(composition.assets.len(), 2);
+    }
+
+    #[test]
+    fn test_keyframe_table() {
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 3, "ind": 1, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "o": { "a": 1, "k": [{ "t": 0, "s": [0] }, { "t": 10, "s": [100] }] },
+                    "p": { "a": 1, "k": [{ "t": 0, "s": [0, 0] }, { "t": 20, "s": [20, 40] }] },
+                    "s": { "a": 0, "k": [100, 100] }
+                }
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let opacity = PropertyFilter::Paths(vec!["layers/0/opacity".into()]);
+        let table = composition.keyframe_table(&opacity, 1);
+        let samples = table
+            .samples
+            .iter()
+            .map(|sample| (sample.frame, sample.keyframe, sample.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            samples,
+            [
+                (0.0, Some(0), vec![0.0]),
+                (5.0, None, vec![50.0]),
+                (10.0, Some(1), vec![100.0])
+            ]
+        );
+        let table = composition.keyframe_table(&PropertyFilter::All, 0);
+        assert_eq!(
+            table.to_csv(),
+            "path,frame,keyframe,value0,value1\n\
+             layers/0/transform/position,0,0,0,0\n\
+             layers/0/transform/position,20,1,20,40\n\
+             layers/0/opacity,0,0,0,\n\
+             layers/0/opacity,10,1,100,\n"
+        );
+        assert_eq!(
+            table.to_json()[1],
+            json!({
+                "path": "layers/0/transform/position", "frame": 20.0, "keyframe": 1,
+                "value": [20.0, 40.0]
+            })
+        );
+    }
+
+    #[test]
+    fn test_gradient_opacity() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [{
+                    "ty": "gf",
+                    "s": { "a": 1, "k": [{ "t": 0, "s": [0, 0] }, { "t": 10, "s": [10, 0] }] },
+                    "e": { "a": 0, "k": [100, 0] },
+                    "t": 1,
+                    "g": { "p": 2, "k": { "a": 0, "k": [0, 1, 0, 0, 1, 0, 0, 1] } },
+                    "o": { "a": 1, "k": [{ "t": 0, "s": [100] }, { "t": 10, "s": [0] }] }
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Draw(draw) = &shapes[0] else {
+            panic!("expected a draw");
+        };
+        assert!(matches!(draw.brush, Brush::Animated(_)));
+        // The opacity of the fill multiplies the alpha of every stop.
+        let opacity = draw.opacity.evaluate(5.0) / 100.0;
+        assert_eq!(opacity, 0.5);
+        let peniko::Brush::Gradient(gradient) = draw.brush.evaluate(opacity, 5.0).into_owned()
+        else {
+            panic!("expected a gradient");
+        };
+        assert!(gradient.stops.iter().all(|stop| stop.color.a == 128));
+    }
+
+    #[test]
+    fn test_gradient_stroke_opacity() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [{
+                    "ty": "gs",
+                    "s": { "a": 0, "k": [0, 0] },
+                    "e": { "a": 0, "k": [100, 0] },
+                    "t": 1,
+                    "g": { "p": 2, "k": { "a": 0, "k": [0, 1, 0, 0, 1, 0, 0, 1] } },
+                    "o": { "a": 1, "k": [{ "t": 0, "s": [100] }, { "t": 10, "s": [0] }] },
+                    "w": { "a": 0, "k": 4 },
+                    "lc": 2, "lj": 1, "ml": 4
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Draw(draw) = &shapes[0] else {
+            panic!("expected a draw");
+        };
+        assert!(draw.stroke.is_some());
+        // The opacity of the stroke multiplies the alpha of every stop.
+        assert_eq!(draw.opacity.evaluate(5.0), 50.0);
+        let opacity = draw.opacity.evaluate(5.0) / 100.0;
+        let peniko::Brush::Gradient(gradient) = draw.brush.evaluate(opacity, 5.0).into_owned()
+        else {
+            panic!("expected a gradient");
+        };
+        assert!(gradient.stops.iter().all(|stop| stop.color.a == 128));
+    }
+
+    #[test]
+    fn test_open_spline() {
+        let spline = |closed: Option<bool>| {
+            let mut spline = json!({
+                "v": [[0, 0], [10, 0], [10, 10]],
+                "i": [[0, 0], [0, 0], [0, 0]],
+                "o": [[0, 0], [0, 0], [0, 0]]
+            });
+            if let Some(closed) = closed {
+                spline["c"] = json!(closed);
+            }
+            spline
+        };
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": [{
+                    "ty": "sh",
+                    "ks": { "a": 1, "k": [
+                        { "t": 0, "s": [spline(Some(false))] },
+                        { "t": 10, "s": [spline(Some(false))] },
+                        { "t": 20, "s": [spline(Some(true))] },
+                        { "t": 30, "s": [spline(None)] }
+                    ] }
+                }, {
+                    "ty": "st",
+                    "c": { "a": 0, "k": [0, 0, 0, 1] },
+                    "o": { "a": 0, "k": 100 },
+                    "w": { "a": 0, "k": 2 }
+                }]
+            }]
+        });
+        let composition = Composition::from_json(source).unwrap();
+        let Content::Shape(shapes) = &composition.layers[0].content else {
+            panic!("expected shape content");
+        };
+        let Shape::Geometry(geometry @ Geometry::Spline(_)) = &shapes[0] else {
+            panic!("expected an animated spline");
+        };
+        let path = |frame| {
+            let mut path = vec![];
+            geometry.evaluate(frame, &mut path);
+            path
+        };
+        // An open spline has no edge back to its first vertex, even when a
+        // later keyframe is closed.
+        assert_eq!(
+            path(5.0),
+            [
+                PathEl::MoveTo(Point::new(0.0, 0.0)),
+                PathEl::LineTo(Point::new(10.0, 0.0)),
+                PathEl::LineTo(Point::new(10.0, 10.0)),
+            ]
+        );
+        assert!(!path(15.0).contains(&PathEl::ClosePath));
+        assert_eq!(path(20.0).last(), Some(&PathEl::ClosePath));
+        // A keyframe that leaves out whether it is closed continues the
+        // previous keyframe.
+        assert_eq!(path(30.0).last(), Some(&PathEl::ClosePath));
+    }
+}
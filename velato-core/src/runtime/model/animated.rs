@@ -0,0 +1,1596 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+/*!
+Representations of animated values.
+*/
+
+use super::*;
+
+use kurbo::PathEl;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub enum Position {
+    Value(Value<Point>),
+    SplitValues((Value<f64>, Value<f64>)),
+}
+
+impl Position {
+    /// Evaluates the position at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> Point {
+        match self {
+            Self::Value(value) => value.evaluate(frame),
+            Self::SplitValues((x_value, y_value)) => Point {
+                x: x_value.evaluate(frame),
+                y: y_value.evaluate(frame),
+            },
+        }
+    }
+
+    /// Returns the direction of motion at the specified frame in degrees
+    /// clockwise from the x axis, or `None` if the position is not moving.
+    ///
+    /// The direction is estimated from the positions shortly before and
+    /// after the frame rather than from the spatial tangents of the
+    /// keyframes. Outside of the keyframes, the direction is that of the
+    /// nearest end of the motion path.
+    pub fn direction(&self, frame: f64) -> Option<f64> {
+        const STEP: f64 = 0.5;
+        fn range<T: Tween>(value: &Value<T>) -> Option<(f64, f64)> {
+            match value {
+                Value::Animated(animated) => {
+                    Some((animated.times.first()?.frame, animated.times.last()?.frame))
+                }
+                _ => None,
+            }
+        }
+        let (start, end) = match self {
+            Self::Value(value) => range(value)?,
+            Self::SplitValues((x_value, y_value)) => match (range(x_value), range(y_value)) {
+                (Some(x), Some(y)) => (x.0.min(y.0), x.1.max(y.1)),
+                (x, y) => x.or(y)?,
+            },
+        };
+        // Keyframes out of order must not panic, as clamping would.
+        let frame = frame.min(end).max(start);
+        let delta =
+            self.evaluate((frame + STEP).min(end)) - self.evaluate((frame - STEP).max(start));
+        (delta.hypot2() > 1e-12).then(|| delta.atan2().to_degrees())
+    }
+}
+
+/// Animated affine transformation.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    /// Anchor point.
+    pub anchor: Value<Point>,
+    /// Translation.
+    pub position: Position,
+    /// Rotation angle.
+    pub rotation: Value<f64>,
+    /// Scale factor.
+    pub scale: Value<Vec2>,
+    /// Skew factor.
+    pub skew: Value<f64>,
+    /// Skew angle.
+    pub skew_angle: Value<f64>,
+    /// Components of a 3D transform, which is projected by a camera.
+    pub depth: Option<Box<Depth>>,
+}
+
+impl Transform {
+    /// Returns true if the transform is fixed.
+    pub fn is_fixed(&self) -> bool {
+        self.anchor.is_fixed()
+            && match &self.position {
+                Position::Value(value) => value.is_fixed(),
+                Position::SplitValues((x_value, y_value)) => {
+                    x_value.is_fixed() && y_value.is_fixed()
+                }
+            }
+            && self.rotation.is_fixed()
+            && self.scale.is_fixed()
+            && self.skew.is_fixed()
+            && self.skew_angle.is_fixed()
+            && self.depth.as_ref().map_or(true, |depth| depth.is_fixed())
+    }
+
+    /// Evaluates the transform at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> Affine {
+        self.evaluate_with_offsets(frame, |_| Vec2::ZERO)
+    }
+
+    /// Evaluates the transform at the specified frame, adding the offset
+    /// returned for each of the anchor point, position, rotation and scale
+    /// properties to their values. Rotation is offset by the x component.
+    pub fn evaluate_with_offsets(
+        &self,
+        frame: f64,
+        offset: impl Fn(LayerProperty) -> Vec2,
+    ) -> Affine {
+        let anchor = self.anchor.evaluate(frame) + offset(LayerProperty::Anchor);
+        let position = self.position.evaluate(frame) + offset(LayerProperty::Position);
+        let rotation = self.rotation.evaluate(frame) + offset(LayerProperty::Rotation).x;
+        let scale = self.scale.evaluate(frame) + offset(LayerProperty::Scale);
+        let skew = self.skew.evaluate(frame);
+        let skew_angle = self.skew_angle.evaluate(frame);
+        let skew_matrix = if skew != 0.0 {
+            const SKEW_LIMIT: f64 = 85.0;
+            let skew = -skew.clamp(-SKEW_LIMIT, SKEW_LIMIT);
+            let skew = skew.to_radians();
+            let angle = skew_angle.to_radians();
+            Affine::rotate(-angle) * Affine::skew(skew.tan(), 0.0) * Affine::rotate(angle)
+        } else {
+            Affine::IDENTITY
+        };
+        if let Some(depth) = &self.depth {
+            let scale = Vec2::new(scale.x / 100.0, scale.y / 100.0);
+            return depth.project(anchor, position, rotation, scale, skew_matrix, frame);
+        }
+        Affine::translate((position.x, position.y))
+            * Affine::rotate(rotation.to_radians())
+            * skew_matrix
+            * Affine::scale_non_uniform(scale.x / 100.0, scale.y / 100.0)
+            * Affine::translate((-anchor.x, -anchor.y))
+    }
+
+    /// Converts the animated value to its model representation.
+    pub fn into_model(self) -> super::Transform {
+        if self.is_fixed() {
+            super::Transform::Fixed(self.evaluate(0.0))
+        } else {
+            super::Transform::Animated(Box::new(self))
+        }
+    }
+}
+
+/// Animated components of a 3D transform that have no 2D counterpart.
+///
+/// Each layer is projected on its own, so the layers parented to a 3D layer
+/// are projected in the space of their parent rather than in a shared 3D
+/// space.
+#[derive(Clone, Debug)]
+pub struct Depth {
+    /// Z component of the anchor point.
+    pub anchor: Value<f64>,
+    /// Z component of the translation.
+    pub position: Value<f64>,
+    /// Z component of the scale factor.
+    pub scale: Value<f64>,
+    /// Rotation angle around the X axis.
+    pub rotation_x: Value<f64>,
+    /// Rotation angle around the Y axis.
+    pub rotation_y: Value<f64>,
+    /// Orientation angles around the X, Y and Z axes.
+    pub orientation: [Value<f64>; 3],
+    /// Cameras that project the transformed layer.
+    pub cameras: Arc<Cameras>,
+}
+
+impl Depth {
+    /// Returns true if the components are fixed.
+    pub fn is_fixed(&self) -> bool {
+        self.anchor.is_fixed()
+            && self.position.is_fixed()
+            && self.scale.is_fixed()
+            && self.rotation_x.is_fixed()
+            && self.rotation_y.is_fixed()
+            && self.orientation.iter().all(Value::is_fixed)
+            && self.cameras.is_fixed()
+    }
+
+    /// Combines the components with those shared with a 2D transform and
+    /// projects the result at the anchor point.
+    fn project(
+        &self,
+        anchor: Point,
+        position: Point,
+        rotation: f64,
+        scale: Vec2,
+        skew_matrix: Affine,
+        frame: f64,
+    ) -> Affine {
+        let rotation = [
+            self.rotation_x.evaluate(frame),
+            self.rotation_y.evaluate(frame),
+            rotation,
+        ];
+        let orientation = std::array::from_fn(|i| self.orientation[i].evaluate(frame));
+        let rotate = |v| rotate_3d(v, rotation, orientation);
+        let [a, b, c, d, ..] =
+            (skew_matrix * Affine::scale_non_uniform(scale.x, scale.y)).as_coeffs();
+        let x_axis = rotate([a, b, 0.0]);
+        let y_axis = rotate([c, d, 0.0]);
+        let z_axis = rotate([0.0, 0.0, self.scale.evaluate(frame) / 100.0]);
+        let anchor_z = self.anchor.evaluate(frame);
+        let position = [position.x, position.y, self.position.evaluate(frame)];
+        // Origin of the layer, such that the anchor point lands on the
+        // position.
+        let origin = std::array::from_fn(|i| {
+            position[i] - x_axis[i] * anchor.x - y_axis[i] * anchor.y - z_axis[i] * anchor_z
+        });
+        let camera = self.cameras.evaluate(frame);
+        camera.project(origin, x_axis, y_axis, anchor)
+    }
+}
+
+/// Rotates a vector around the Z, Y and X axes and then orients it around
+/// the Z, Y and X axes, like the transform of a 3D layer. Angles are in
+/// degrees.
+fn rotate_3d(mut v: [f64; 3], rotation: [f64; 3], orientation: [f64; 3]) -> [f64; 3] {
+    for (axis, degrees) in [
+        (2, rotation[2]),
+        (1, rotation[1]),
+        (0, rotation[0]),
+        (2, orientation[2]),
+        (1, orientation[1]),
+        (0, orientation[0]),
+    ] {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+        (v[i], v[j]) = (v[i] * cos - v[j] * sin, v[i] * sin + v[j] * cos);
+    }
+    v
+}
+
+/// Cameras that project the 3D layers of a composition.
+#[derive(Clone, Debug)]
+pub struct Cameras {
+    /// Camera layers, from top to bottom.
+    pub layers: Vec<Camera>,
+    /// Camera used when no camera layer is active.
+    pub default: fixed::Camera,
+}
+
+impl Cameras {
+    /// Returns true if the projection is fixed.
+    pub fn is_fixed(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Evaluates the topmost camera that is active at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::Camera {
+        self.layers
+            .iter()
+            .find(|camera| camera.frames.contains(&frame))
+            .map_or(self.default, |camera| {
+                camera.evaluate(self.default.center, frame)
+            })
+    }
+}
+
+/// Animated camera layer.
+///
+/// Cameras are not affected by parenting.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// Frames in which the camera is active.
+    pub frames: Range<f64>,
+    /// Position.
+    pub position: Position,
+    /// Z component of the position.
+    pub position_z: Value<f64>,
+    /// Point that the camera looks at, with its Z component. Cameras without
+    /// one look down the Z axis.
+    pub point_of_interest: Option<(Value<Point>, Value<f64>)>,
+    /// Rotation angles around the X, Y and Z axes.
+    pub rotation: [Value<f64>; 3],
+    /// Orientation angles around the X, Y and Z axes.
+    pub orientation: [Value<f64>; 3],
+    /// Distance in front of the camera of the plane that is drawn at its
+    /// original size.
+    pub zoom: Value<f64>,
+}
+
+impl Camera {
+    /// Evaluates the camera at the specified frame, centering the view on
+    /// the given point of the composition.
+    pub fn evaluate(&self, center: Point, frame: f64) -> fixed::Camera {
+        let position = self.position.evaluate(frame);
+        let position = [position.x, position.y, self.position_z.evaluate(frame)];
+        // Axes that look at the point of interest while staying upright.
+        let mut look = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        if let Some((point, z)) = &self.point_of_interest {
+            let point = point.evaluate(frame);
+            let forward = normalize([
+                point.x - position[0],
+                point.y - position[1],
+                z.evaluate(frame) - position[2],
+            ]);
+            // Looking straight up or down leaves the roll undefined, so the
+            // axes are kept.
+            if let Some(forward) = forward {
+                if let Some(right) = normalize(cross([0.0, 1.0, 0.0], forward)) {
+                    look = [right, cross(forward, right), forward];
+                }
+            }
+        }
+        let rotation = std::array::from_fn(|i| self.rotation[i].evaluate(frame));
+        let orientation = std::array::from_fn(|i| self.orientation[i].evaluate(frame));
+        let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|axis| {
+            let v = rotate_3d(axis, rotation, orientation);
+            std::array::from_fn(|i| look[0][i] * v[0] + look[1][i] * v[1] + look[2][i] * v[2])
+        });
+        fixed::Camera {
+            center,
+            zoom: self.zoom.evaluate(frame),
+            position,
+            axes,
+        }
+    }
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Returns the vector scaled to unit length, or None if it is too short to
+/// have a direction.
+fn normalize(v: [f64; 3]) -> Option<[f64; 3]> {
+    let length = v[0].hypot(v[1]).hypot(v[2]);
+    (length > f64::EPSILON).then(|| v.map(|x| x / length))
+}
+
+/// Animated ellipse.
+#[derive(Clone, Debug)]
+pub struct Ellipse {
+    /// True if the ellipse should be drawn in CCW order.
+    pub is_ccw: bool,
+    /// Position of the ellipse.
+    pub position: Value<Point>,
+    /// Size of the ellipse.
+    pub size: Value<Size>,
+}
+
+impl Ellipse {
+    pub fn is_fixed(&self) -> bool {
+        self.position.is_fixed() && self.size.is_fixed()
+    }
+
+    pub fn evaluate(&self, frame: f64) -> kurbo::Ellipse {
+        let position = self.position.evaluate(frame);
+        let size = self.size.evaluate(frame);
+        let radii = (size.width * 0.5, size.height * 0.5);
+        kurbo::Ellipse::new(position, radii, 0.0)
+    }
+}
+
+/// Animated rounded rectangle.
+#[derive(Clone, Debug)]
+pub struct Rect {
+    /// True if the rect should be drawn in CCW order.
+    pub is_ccw: bool,
+    /// Position of the rectangle.
+    pub position: Value<Point>,
+    /// Size of the rectangle.
+    pub size: Value<Size>,
+    /// Radius of the rectangle corners.
+    pub corner_radius: Value<f64>,
+}
+
+impl Rect {
+    /// Returns true if the rectangle is fixed.
+    pub fn is_fixed(&self) -> bool {
+        self.position.is_fixed() && self.size.is_fixed() && self.corner_radius.is_fixed()
+    }
+
+    /// Evaluates the rectangle at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> kurbo::RoundedRect {
+        let position = self.position.evaluate(frame);
+        let size = self.size.evaluate(frame);
+        let position = Point::new(
+            position.x - size.width * 0.5,
+            position.y - size.height * 0.5,
+        );
+        let radius = self.corner_radius.evaluate(frame);
+        kurbo::RoundedRect::new(
+            position.x,
+            position.y,
+            position.x + size.width,
+            position.y + size.height,
+            radius,
+        )
+    }
+}
+
+/// Animated star or polygon.
+#[derive(Clone, Debug)]
+pub struct Star {
+    /// True if the shape is a regular polygon rather than a star.
+    pub is_polygon: bool,
+    /// Winding direction: 1 for clockwise, -1 for counter-clockwise.
+    pub direction: f64,
+    /// Center point.
+    pub position: Value<Point>,
+    /// Distance of the inner vertices of a star from the center.
+    pub inner_radius: Value<f64>,
+    /// Roundness of the inner vertices of a star as a percentage.
+    pub inner_roundness: Value<f64>,
+    /// Distance of the outer vertices from the center.
+    pub outer_radius: Value<f64>,
+    /// Roundness of the outer vertices as a percentage.
+    pub outer_roundness: Value<f64>,
+    /// Rotation in degrees, clockwise from the top.
+    pub rotation: Value<f64>,
+    /// Number of points of the star or sides of the polygon.
+    pub points: Value<f64>,
+}
+
+impl Star {
+    /// Returns true if the star contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.position.is_fixed()
+            && self.inner_radius.is_fixed()
+            && self.inner_roundness.is_fixed()
+            && self.outer_radius.is_fixed()
+            && self.outer_roundness.is_fixed()
+            && self.rotation.is_fixed()
+            && self.points.is_fixed()
+    }
+
+    /// Evaluates the star at the given frame and emits the elements to the
+    /// specified path.
+    pub fn evaluate(&self, frame: f64, path: &mut Vec<PathEl>) {
+        let points = self.points.evaluate(frame).floor();
+        if points.is_nan() || points < 1.0 {
+            return;
+        }
+        let center = self.position.evaluate(frame);
+        let outer = (
+            self.outer_radius.evaluate(frame),
+            self.outer_roundness.evaluate(frame) / 100.0,
+        );
+        // Stars alternate between outer and inner vertices.
+        let (count, inner) = if self.is_polygon {
+            (points as usize, outer)
+        } else {
+            (
+                points as usize * 2,
+                (
+                    self.inner_radius.evaluate(frame),
+                    self.inner_roundness.evaluate(frame) / 100.0,
+                ),
+            )
+        };
+        let step = std::f64::consts::TAU / count as f64 * self.direction;
+        // The length of the tangents is proportional to the arc length
+        // between adjacent vertices of a polygon, which is half that of a
+        // star with the same number of vertices.
+        let tangent_scale = if self.is_polygon { 0.25 } else { 0.5 };
+        let start_angle = (self.rotation.evaluate(frame) - 90.0).to_radians();
+        let vertices = (0..count).map(|i| {
+            let (radius, roundness) = if i % 2 == 0 { outer } else { inner };
+            let dir = Vec2::from_angle(start_angle + step * i as f64);
+            let point = center + dir * radius;
+            let tangent = Vec2::new(-dir.y, dir.x)
+                * (radius * step.abs() * tangent_scale * roundness * self.direction);
+            (point - tangent, point, point + tangent)
+        });
+        let first = path.len();
+        let mut last: Option<(Point, Point, Point)> = None;
+        for vertex in vertices.clone().chain(vertices.take(1)) {
+            match last {
+                None => path.push(PathEl::MoveTo(vertex.1)),
+                Some(last) if last.2 == last.1 && vertex.0 == vertex.1 => {
+                    path.push(PathEl::LineTo(vertex.1));
+                }
+                Some(last) => path.push(PathEl::CurveTo(last.2, vertex.0, vertex.1)),
+            }
+            last = Some(vertex);
+        }
+        if path.len() > first {
+            path.push(PathEl::ClosePath);
+        }
+    }
+}
+
+/// Animated cubic spline.
+#[derive(Clone, Debug)]
+pub struct Spline {
+    /// True if the spline should be drawn in the reverse order of its
+    /// vertices.
+    pub is_ccw: bool,
+    /// Collection of times.
+    pub times: Vec<Time>,
+    /// Collection of splines.
+    pub values: Vec<Vec<Point>>,
+    /// True for each spline that is closed.
+    ///
+    /// Between keyframes, the spline is closed if the spline of the
+    /// preceding keyframe is closed.
+    pub closed: Vec<bool>,
+    /// Repetition of the keyframes beyond their range.
+    pub loops: Loops,
+}
+
+impl Spline {
+    /// Evaluates the spline at the given frame and emits the elements
+    /// to the specified path.
+    pub fn evaluate(&self, frame: f64, path: &mut Vec<PathEl>) -> bool {
+        let frame = self.loops.apply(&self.times, frame);
+        let Some(([ix0, ix1], t, _easing, _hold)) = Time::frames_and_weight(&self.times, frame)
+        else {
+            // TODO: evaluate whether hold frame is needed here
+            return false;
+        };
+        let (Some(from), Some(to)) = (self.values.get(ix0), self.values.get(ix1)) else {
+            return false;
+        };
+        let ix = if t >= 1.0 { ix1 } else { ix0 };
+        let is_closed = self.closed.get(ix).copied().unwrap_or(false);
+        (from.as_slice(), to.as_slice(), t).to_path(is_closed, path);
+        true
+    }
+}
+
+/// Animated repeater effect.
+#[derive(Clone, Debug)]
+pub struct Repeater {
+    /// Number of times elements should be repeated.
+    pub copies: Value<f64>,
+    /// Offset applied to each element.
+    pub offset: Value<f64>,
+    /// Anchor point.
+    pub anchor_point: Value<Point>,
+    /// Translation.
+    pub position: Value<Point>,
+    /// Rotation in degrees.
+    pub rotation: Value<f64>,
+    /// Scale.
+    pub scale: Value<Vec2>,
+    /// Opacity of the first element.
+    pub start_opacity: Value<f64>,
+    /// Opacity of the last element.
+    pub end_opacity: Value<f64>,
+    /// Offset in degrees added to the trim paths that follow the repeater
+    /// for each subsequent element.
+    pub trim_offset: Value<f64>,
+    /// Stacking order of the elements.
+    pub composite: RepeaterComposite,
+}
+
+impl Repeater {
+    /// Returns true if the repeater contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.copies.is_fixed()
+            && self.offset.is_fixed()
+            && self.anchor_point.is_fixed()
+            && self.position.is_fixed()
+            && self.rotation.is_fixed()
+            && self.scale.is_fixed()
+            && self.start_opacity.is_fixed()
+            && self.end_opacity.is_fixed()
+            && self.trim_offset.is_fixed()
+    }
+
+    /// Evaluates the repeater at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::Repeater {
+        // Far more copies than can be told apart would only stall drawing.
+        const COPIES_LIMIT: f64 = 10_000.0;
+        let copies = self.copies.evaluate(frame).round().min(COPIES_LIMIT) as usize;
+        let offset = self.offset.evaluate(frame);
+        let anchor_point = self.anchor_point.evaluate(frame);
+        let position = self.position.evaluate(frame);
+        let rotation = self.rotation.evaluate(frame);
+        let scale = self.scale.evaluate(frame);
+        let start_opacity = self.start_opacity.evaluate(frame);
+        let end_opacity = self.end_opacity.evaluate(frame);
+        let trim_offset = self.trim_offset.evaluate(frame);
+        fixed::Repeater {
+            copies,
+            offset,
+            anchor_point,
+            position,
+            rotation,
+            scale,
+            start_opacity,
+            end_opacity,
+            trim_offset,
+            composite: self.composite,
+        }
+    }
+
+    /// Converts the animated value to its model representation.
+    pub fn into_model(self) -> super::Repeater {
+        if self.is_fixed() {
+            super::Repeater::Fixed(self.evaluate(0.0))
+        } else {
+            super::Repeater::Animated(Box::new(self))
+        }
+    }
+}
+
+/// Animated path modifier.
+#[derive(Clone, Debug)]
+pub enum PathModifier {
+    /// Zig-zag modifier.
+    ZigZag(ZigZag),
+    /// Rounded corners modifier.
+    RoundedCorners(RoundedCorners),
+    /// Offset path modifier.
+    OffsetPath(OffsetPath),
+    /// Pucker and bloat modifier.
+    PuckerBloat(PuckerBloat),
+    /// Twist modifier.
+    Twist(Twist),
+    /// Trim path modifier.
+    Trim(Trim),
+}
+
+impl PathModifier {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        match self {
+            Self::ZigZag(value) => value.is_fixed(),
+            Self::RoundedCorners(value) => value.is_fixed(),
+            Self::OffsetPath(value) => value.is_fixed(),
+            Self::PuckerBloat(value) => value.is_fixed(),
+            Self::Twist(value) => value.is_fixed(),
+            Self::Trim(value) => value.is_fixed(),
+        }
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::PathModifier {
+        match self {
+            Self::ZigZag(value) => fixed::PathModifier::ZigZag(value.evaluate(frame)),
+            Self::RoundedCorners(value) => {
+                fixed::PathModifier::RoundedCorners(value.evaluate(frame))
+            }
+            Self::OffsetPath(value) => fixed::PathModifier::OffsetPath(value.evaluate(frame)),
+            Self::PuckerBloat(value) => fixed::PathModifier::PuckerBloat(value.evaluate(frame)),
+            Self::Twist(value) => fixed::PathModifier::Twist(value.evaluate(frame)),
+            Self::Trim(value) => fixed::PathModifier::Trim(value.evaluate(frame)),
+        }
+    }
+
+    /// Converts the animated value to its model representation.
+    pub fn into_model(self) -> super::PathModifier {
+        if self.is_fixed() {
+            super::PathModifier::Fixed(self.evaluate(0.0))
+        } else {
+            super::PathModifier::Animated(Box::new(self))
+        }
+    }
+}
+
+/// Animated zig-zag modifier.
+#[derive(Clone, Debug)]
+pub struct ZigZag {
+    /// Number of ridges per segment.
+    pub ridges: Value<f64>,
+    /// Distance of each ridge from the original path.
+    pub size: Value<f64>,
+    /// True if ridges are smooth waves rather than sharp corners.
+    pub is_smooth: bool,
+}
+
+impl ZigZag {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.ridges.is_fixed() && self.size.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::ZigZag {
+        fixed::ZigZag {
+            ridges: self.ridges.evaluate(frame),
+            size: self.size.evaluate(frame),
+            is_smooth: self.is_smooth,
+        }
+    }
+}
+
+/// Animated offset path modifier.
+#[derive(Clone, Debug)]
+pub struct OffsetPath {
+    /// Distance to offset the path. Positive values expand the shape.
+    pub amount: Value<f64>,
+    /// Join style for corners of the offset path.
+    pub join: kurbo::Join,
+    /// Limit for miter joins.
+    pub miter_limit: Value<f64>,
+}
+
+impl OffsetPath {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.amount.is_fixed() && self.miter_limit.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::OffsetPath {
+        fixed::OffsetPath {
+            amount: self.amount.evaluate(frame),
+            join: self.join,
+            miter_limit: self.miter_limit.evaluate(frame),
+        }
+    }
+}
+
+/// Animated pucker and bloat modifier.
+#[derive(Clone, Debug)]
+pub struct PuckerBloat {
+    /// Percentage to move vertices towards the center, with control points
+    /// moving in the opposite direction. Negative values pucker the shape.
+    pub amount: Value<f64>,
+}
+
+impl PuckerBloat {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.amount.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::PuckerBloat {
+        fixed::PuckerBloat {
+            amount: self.amount.evaluate(frame),
+        }
+    }
+}
+
+/// Animated twist modifier.
+#[derive(Clone, Debug)]
+pub struct Twist {
+    /// Rotation in degrees applied at the farthest distance from the
+    /// center.
+    pub angle: Value<f64>,
+    /// Offset of the center of rotation from the center of the path bounds.
+    pub center: Value<Vec2>,
+}
+
+impl Twist {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.angle.is_fixed() && self.center.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::Twist {
+        fixed::Twist {
+            angle: self.angle.evaluate(frame),
+            center: self.center.evaluate(frame),
+        }
+    }
+}
+
+/// Animated trim path modifier.
+#[derive(Clone, Debug)]
+pub struct Trim {
+    /// Percentage of the length of the path at which the segment starts.
+    pub start: Value<f64>,
+    /// Percentage of the length of the path at which the segment ends.
+    pub end: Value<f64>,
+    /// Offset of the segment in degrees, where a full turn moves it along
+    /// the whole path.
+    pub offset: Value<f64>,
+    /// True if the subpaths are trimmed as one sequence, rather than each
+    /// on its own.
+    pub individually: bool,
+}
+
+impl Trim {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.start.is_fixed() && self.end.is_fixed() && self.offset.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::Trim {
+        fixed::Trim {
+            start: self.start.evaluate(frame),
+            end: self.end.evaluate(frame),
+            offset: self.offset.evaluate(frame),
+            individually: self.individually,
+        }
+    }
+}
+
+/// Animated rounded corners modifier.
+#[derive(Clone, Debug)]
+pub struct RoundedCorners {
+    /// Radius of the rounded corners.
+    pub radius: Value<f64>,
+}
+
+impl RoundedCorners {
+    /// Returns true if the modifier contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.radius.is_fixed()
+    }
+
+    /// Evaluates the modifier at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::RoundedCorners {
+        fixed::RoundedCorners {
+            radius: self.radius.evaluate(frame),
+        }
+    }
+}
+
+/// Animated stroke properties.
+#[derive(Clone, Debug)]
+pub struct Stroke {
+    /// Width of the stroke.
+    pub width: Value<f64>,
+    /// Join style.
+    pub join: Value<StrokeJoin>,
+    /// Limit for miter joins.
+    pub miter_limit: Option<f64>,
+    /// Cap style.
+    pub cap: Value<StrokeCap>,
+    /// Lengths of the dash pattern in alternating dash/gap order.
+    pub dashes: Vec<Value<f64>>,
+    /// Offset into the dash pattern.
+    pub dash_offset: Value<f64>,
+}
+
+impl Stroke {
+    /// Returns true if the stroke is fixed.
+    pub fn is_fixed(&self) -> bool {
+        self.width.is_fixed()
+            && self.join.is_fixed()
+            && self.cap.is_fixed()
+            && self.dashes.iter().all(|dash| dash.is_fixed())
+            && self.dash_offset.is_fixed()
+    }
+
+    /// Evaluates the stroke at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> kurbo::Stroke {
+        let width = self.width.evaluate(frame);
+        let mut stroke = kurbo::Stroke::new(width)
+            .with_caps(self.cap.evaluate(frame).into())
+            .with_join(self.join.evaluate(frame).into());
+        if let Some(miter_limit) = self.miter_limit {
+            stroke.miter_limit = miter_limit;
+        }
+        if !self.dashes.is_empty() {
+            let mut dashes = self
+                .dashes
+                .iter()
+                .map(|dash| dash.evaluate(frame).max(0.0))
+                .collect::<Vec<_>>();
+            // Like SVG, an odd number of entries is repeated to form an even
+            // dash/gap sequence.
+            if dashes.len() % 2 == 1 {
+                dashes.extend_from_within(..);
+            }
+            // A pattern with no length would never advance.
+            if dashes.iter().sum::<f64>() > 0.0 {
+                stroke = stroke.with_dashes(self.dash_offset.evaluate(frame), dashes);
+            }
+        }
+        stroke
+    }
+
+    /// Converts the animated value to its model representation.
+    ///
+    /// Strokes without an explicit miter limit are kept animated, even if
+    /// fixed, so that the renderer can supply its own default at draw time.
+    pub fn into_model(self) -> super::Stroke {
+        if self.is_fixed() && self.miter_limit.is_some() {
+            super::Stroke::Fixed(self.evaluate(0.0))
+        } else {
+            super::Stroke::Animated(Box::new(self))
+        }
+    }
+}
+
+/// Animated linear or radial gradient.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    /// True if the gradient is radial. The type can change at keyframes.
+    pub is_radial: Value<bool>,
+    /// Starting point.
+    pub start_point: Value<Point>,
+    /// Ending point.
+    pub end_point: Value<Point>,
+    /// Distance of the focal point of a radial gradient from the start
+    /// point, as a percentage of the radius.
+    pub highlight_length: Value<f64>,
+    /// Angle in degrees of the focal point of a radial gradient, relative to
+    /// the direction from the start point to the end point.
+    pub highlight_angle: Value<f64>,
+    /// Stop offsets and color values.
+    pub stops: super::ColorStops,
+}
+
+impl Gradient {
+    /// Returns true if the value contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        self.is_radial.is_fixed()
+            && self.start_point.is_fixed()
+            && self.end_point.is_fixed()
+            && self.highlight_length.is_fixed()
+            && self.highlight_angle.is_fixed()
+            && self.stops.is_fixed()
+    }
+
+    /// Creates a fixed linear gradient from an angle and a length, as design
+    /// tools describe them.
+    ///
+    /// The gradient runs through the center point in the direction of the
+    /// angle, given in degrees clockwise from the x axis, and spans the given
+    /// length.
+    pub fn linear_from_angle(
+        center: Point,
+        angle: f64,
+        length: f64,
+        stops: fixed::ColorStops,
+    ) -> Self {
+        let axis = Vec2::from_angle(angle.to_radians()) * (length / 2.0);
+        Self::fixed(false, center - axis, center + axis, 0.0, 0.0, stops)
+    }
+
+    /// Creates a fixed radial gradient from its center point and radius.
+    pub fn radial(center: Point, radius: f64, stops: fixed::ColorStops) -> Self {
+        let end = center + Vec2::new(radius, 0.0);
+        Self::fixed(true, center, end, 0.0, 0.0, stops)
+    }
+
+    /// Converts a gradient built with peniko.
+    ///
+    /// Returns `None` for sweep gradients and for radial gradients whose
+    /// start circle is not a point inside the end circle, which have no
+    /// counterpart in Lottie. The extend mode is not kept, as gradients are
+    /// always padded.
+    pub fn from_peniko(gradient: &peniko::Gradient) -> Option<Self> {
+        let stops = gradient.stops.clone();
+        match gradient.kind {
+            peniko::GradientKind::Linear { start, end } => {
+                Some(Self::fixed(false, start, end, 0.0, 0.0, stops))
+            }
+            peniko::GradientKind::Radial {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+            } => {
+                let radius = end_radius as f64;
+                let focus = start_center - end_center;
+                if start_radius != 0.0 || radius <= 0.0 || focus.hypot() >= radius {
+                    return None;
+                }
+                let end = end_center + Vec2::new(radius, 0.0);
+                let highlight_length = focus.hypot() / radius * 100.0;
+                let highlight_angle = focus.atan2().to_degrees();
+                Some(Self::fixed(
+                    true,
+                    end_center,
+                    end,
+                    highlight_length,
+                    highlight_angle,
+                    stops,
+                ))
+            }
+            peniko::GradientKind::Sweep { .. } => None,
+        }
+    }
+
+    fn fixed(
+        is_radial: bool,
+        start_point: Point,
+        end_point: Point,
+        highlight_length: f64,
+        highlight_angle: f64,
+        stops: fixed::ColorStops,
+    ) -> Self {
+        Self {
+            is_radial: Value::Fixed(is_radial),
+            start_point: Value::Fixed(start_point),
+            end_point: Value::Fixed(end_point),
+            highlight_length: Value::Fixed(highlight_length),
+            highlight_angle: Value::Fixed(highlight_angle),
+            stops: super::ColorStops::Fixed(stops),
+        }
+    }
+
+    /// Returns the angle in degrees clockwise from the x axis of the line
+    /// from the start point to the end point at the given frame.
+    pub fn angle(&self, frame: f64) -> f64 {
+        (self.end_point.evaluate(frame) - self.start_point.evaluate(frame))
+            .atan2()
+            .to_degrees()
+    }
+
+    /// Returns the distance between the start point and the end point at the
+    /// given frame, which is the radius of radial gradients.
+    pub fn length(&self, frame: f64) -> f64 {
+        (self.end_point.evaluate(frame) - self.start_point.evaluate(frame)).hypot()
+    }
+
+    /// Evaluates the animated value at the given frame.
+    pub fn evaluate(&self, frame: f64) -> peniko::Brush {
+        let start = self.start_point.evaluate(frame);
+        let end = self.end_point.evaluate(frame);
+        let stops = self.stops.evaluate(frame).into_owned();
+        if self.is_radial.evaluate(frame) {
+            let axis = end - start;
+            let radius = axis.hypot();
+            // Keep the focal point inside the circle to avoid degenerate
+            // cones.
+            let highlight = (self.highlight_length.evaluate(frame) / 100.0).clamp(-0.99, 0.99);
+            let mut grad = if highlight == 0.0 {
+                peniko::Gradient::new_radial(start, radius as f32)
+            } else {
+                let angle = axis.atan2() + self.highlight_angle.evaluate(frame).to_radians();
+                let focus = start + Vec2::from_angle(angle) * (highlight * radius);
+                peniko::Gradient::new_two_point_radial(focus, 0.0, start, radius as f32)
+            };
+            grad.stops = stops;
+            grad.into()
+        } else {
+            let mut grad = peniko::Gradient::new_linear(start, end);
+            grad.stops = stops;
+            grad.into()
+        }
+    }
+}
+
+/// Animated color stops.
+///
+/// Each keyframe value has the layout used by Lottie: `count` color stops
+/// of offset, red, green and blue, followed by any number of opacity stops
+/// of offset and alpha, whose offsets are independent of the color stops.
+#[derive(Clone, Debug)]
+pub struct ColorStops {
+    pub frames: Vec<Time>,
+    pub values: Vec<Vec<f64>>,
+    pub count: usize,
+}
+
+impl ColorStops {
+    pub fn evaluate(&self, frame: f64) -> fixed::ColorStops {
+        self.evaluate_inner(frame).unwrap_or_default()
+    }
+
+    /// Merges the color stops and opacity stops of a value with the Lottie
+    /// layout into color stops with both color and alpha.
+    ///
+    /// There is a stop at the offset of every color stop and of every
+    /// opacity stop, with the color and alpha interpolated from the nearest
+    /// stops of the other kind.
+    pub fn merge(values: &[f64], count: usize) -> fixed::ColorStops {
+        let split = count.saturating_mul(4).min(values.len());
+        let colors = values[..split]
+            .chunks_exact(4)
+            .map(|chunk| (chunk[0], [chunk[1], chunk[2], chunk[3]]))
+            .collect::<Vec<_>>();
+        let alphas = values[split..]
+            .chunks_exact(2)
+            .map(|chunk| (chunk[0], [chunk[1]]))
+            .collect::<Vec<_>>();
+        let mut stops = colors
+            .iter()
+            .map(|&(offset, [r, g, b])| {
+                let [a] = sample_stops(&alphas, offset).unwrap_or([1.0]);
+                (offset, [r, g, b, a])
+            })
+            .collect::<Vec<_>>();
+        for &(offset, [a]) in &alphas {
+            if colors.iter().any(|color| color.0 == offset) {
+                continue;
+            }
+            if let Some([r, g, b]) = sample_stops(&colors, offset) {
+                stops.push((offset, [r, g, b, a]));
+            }
+        }
+        // The sort is stable, so coincident color stops keep their order.
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        stops
+            .into_iter()
+            .map(|(offset, [r, g, b, a])| {
+                let color = Color::rgba(
+                    r.clamp(0.0, 1.0),
+                    g.clamp(0.0, 1.0),
+                    b.clamp(0.0, 1.0),
+                    a.clamp(0.0, 1.0),
+                );
+                peniko::ColorStop::from((offset as f32, color))
+            })
+            .collect()
+    }
+
+    fn evaluate_inner(&self, frame: f64) -> Option<fixed::ColorStops> {
+        let ([ix0, ix1], t, [easing, ..], hold) = Time::frames_and_weight(&self.frames, frame)?;
+
+        let mut v0 = self.values.get(ix0)?;
+        let mut v1 = self.values.get(ix1)?;
+        // Keyframes with different numbers of stops can't be interpolated,
+        // so snap to the next keyframe once it is reached.
+        if v0.len() != v1.len() {
+            if t >= 1.0 {
+                v0 = v1;
+            } else {
+                v1 = v0;
+            }
+        }
+
+        let t = if hold { 0.0 } else { t };
+        let values = v0
+            .iter()
+            .zip(v1)
+            .map(|(v0, v1)| v0.tween(v1, t, &easing))
+            .collect::<Vec<_>>();
+        Some(Self::merge(&values, self.count))
+    }
+}
+
+/// Interpolates stops sorted by offset at the given offset, holding the
+/// first and last values outside of their range.
+fn sample_stops<const N: usize>(stops: &[(f64, [f64; N])], offset: f64) -> Option<[f64; N]> {
+    let (first, rest) = stops.split_first()?;
+    if offset <= first.0 {
+        return Some(first.1);
+    }
+    let mut last = first;
+    for stop in rest {
+        if offset <= stop.0 {
+            let t = (offset - last.0) / (stop.0 - last.0);
+            return Some(std::array::from_fn(|i| {
+                last.1[i].tween(&stop.1[i], t, &Easing::LERP)
+            }));
+        }
+        last = stop;
+    }
+    Some(last.1)
+}
+
+/// Animated brush.
+#[derive(Clone, Debug)]
+pub enum Brush {
+    /// Solid color.
+    Solid(Value<Color>),
+    /// Solid color with separately animated red, green and blue channels.
+    SplitSolid((Value<f64>, Value<f64>, Value<f64>)),
+    /// Gradient color.
+    Gradient(Gradient),
+}
+
+impl Brush {
+    /// Returns true if the value contains no animated properties.
+    pub fn is_fixed(&self) -> bool {
+        match self {
+            Self::Solid(value) => value.is_fixed(),
+            Self::SplitSolid((r, g, b)) => r.is_fixed() && g.is_fixed() && b.is_fixed(),
+            Self::Gradient(value) => value.is_fixed(),
+        }
+    }
+
+    /// Evaluates the animation at the specified time.
+    pub fn evaluate(&self, alpha: f64, frame: f64) -> fixed::Brush {
+        self.evaluate_in(alpha, frame, ColorSpace::Srgb)
+    }
+
+    /// Evaluates the animation at the specified time, interpolating
+    /// gradient stops and animated colors in the given color space.
+    ///
+    /// The stops of gradient keyframes are blended in sRGB before they are
+    /// interpolated in the color space.
+    pub fn evaluate_in(&self, alpha: f64, frame: f64, space: ColorSpace) -> fixed::Brush {
+        match self {
+            Self::Solid(value) => value
+                .evaluate_in(frame, space)
+                .with_alpha_factor(alpha as f32)
+                .into(),
+            Self::SplitSolid((r, g, b)) => Color::rgb(
+                r.evaluate(frame).clamp(0.0, 1.0),
+                g.evaluate(frame).clamp(0.0, 1.0),
+                b.evaluate(frame).clamp(0.0, 1.0),
+            )
+            .with_alpha_factor(alpha as f32)
+            .into(),
+            Self::Gradient(value) => {
+                let brush = value.evaluate(frame);
+                let brush = space.resample_brush(&brush).unwrap_or(brush);
+                fixed::brush_with_alpha(&brush, alpha)
+            }
+        }
+    }
+
+    /// Converts the animated value to its model representation.
+    pub fn into_model(self) -> super::Brush {
+        if self.is_fixed() {
+            super::Brush::Fixed(self.evaluate(1.0, 0.0))
+        } else {
+            super::Brush::Animated(Box::new(self))
+        }
+    }
+}
+
+/// Animated drop shadow effect.
+#[derive(Clone, Debug)]
+pub struct DropShadow {
+    /// Color of the shadow.
+    pub color: Value<Color>,
+    /// Opacity of the shadow, from 0 to 100.
+    pub opacity: Value<f64>,
+    /// Direction in which the shadow is cast, in degrees clockwise from
+    /// up.
+    pub direction: Value<f64>,
+    /// Distance between the content and its shadow.
+    pub distance: Value<f64>,
+    /// Radius of the blur applied to the shadow.
+    pub radius: Value<f64>,
+    /// True if the content is hidden, leaving only the shadow.
+    pub shadow_only: bool,
+}
+
+impl DropShadow {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::DropShadow {
+        let opacity = self.opacity.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        let (sin, cos) = self.direction.evaluate(frame).to_radians().sin_cos();
+        let distance = self.distance.evaluate(frame);
+        fixed::DropShadow {
+            color: self.color.evaluate(frame).with_alpha_factor(opacity as f32),
+            offset: Vec2::new(sin, -cos) * distance,
+            radius: self.radius.evaluate(frame).max(0.0),
+            shadow_only: self.shadow_only,
+        }
+    }
+}
+
+/// Animated outer glow layer style.
+#[derive(Clone, Debug)]
+pub struct OuterGlow {
+    /// Color of the glow.
+    pub color: Value<Color>,
+    /// Opacity of the glow, from 0 to 100.
+    pub opacity: Value<f64>,
+    /// Radius of the blur applied to the glow.
+    pub radius: Value<f64>,
+}
+
+impl OuterGlow {
+    /// Evaluates the style at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::OuterGlow {
+        let opacity = self.opacity.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        fixed::OuterGlow {
+            color: self.color.evaluate(frame).with_alpha_factor(opacity as f32),
+            radius: self.radius.evaluate(frame).max(0.0),
+        }
+    }
+}
+
+/// Animated stroke layer style.
+#[derive(Clone, Debug)]
+pub struct StrokeStyle {
+    /// Color of the stroke.
+    pub color: Value<Color>,
+    /// Width of the stroke outside the content.
+    pub size: Value<f64>,
+}
+
+impl StrokeStyle {
+    /// Evaluates the style at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::StrokeStyle {
+        fixed::StrokeStyle {
+            color: self.color.evaluate(frame),
+            size: self.size.evaluate(frame).max(0.0),
+        }
+    }
+}
+
+/// Animated fill effect.
+#[derive(Clone, Debug)]
+pub struct FillEffect {
+    /// Color that replaces the colors of the content.
+    pub color: Value<Color>,
+    /// Opacity of the content, from 0 to 100.
+    pub opacity: Value<f64>,
+}
+
+impl FillEffect {
+    /// Evaluates the effect at the specified frame, returning the color with
+    /// the opacity applied.
+    pub fn evaluate(&self, frame: f64) -> Color {
+        let opacity = self.opacity.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        self.color.evaluate(frame).with_alpha_factor(opacity as f32)
+    }
+}
+
+/// Animated stroke effect.
+#[derive(Clone, Debug)]
+pub struct StrokeEffect {
+    /// Index of the mask to outline, or `None` to outline all masks.
+    pub mask: Option<usize>,
+    /// Color of the stroke.
+    pub color: Value<Color>,
+    /// Width of the stroke.
+    pub width: Value<f64>,
+    /// Opacity of the stroke, from 0 to 100.
+    pub opacity: Value<f64>,
+    /// Percentage of the length of each mask at which the stroke starts.
+    pub start: Value<f64>,
+    /// Percentage of the length of each mask at which the stroke ends.
+    pub end: Value<f64>,
+    /// How the stroke combines with the content.
+    pub paint_style: StrokePaintStyle,
+}
+
+impl StrokeEffect {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::StrokeEffect {
+        let opacity = self.opacity.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        let start = self.start.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        let end = self.end.evaluate(frame).clamp(0.0, 100.0) / 100.0;
+        fixed::StrokeEffect {
+            color: self.color.evaluate(frame).with_alpha_factor(opacity as f32),
+            width: self.width.evaluate(frame).max(0.0),
+            start: start.min(end),
+            end: start.max(end),
+        }
+    }
+}
+
+/// Animated levels adjustment of a single channel.
+#[derive(Clone, Debug)]
+pub struct LevelsChannel {
+    /// Input value that maps to the output black level, from 0 to 1.
+    pub input_black: Value<f64>,
+    /// Input value that maps to the output white level, from 0 to 1.
+    pub input_white: Value<f64>,
+    /// Exponent applied to values between the input levels.
+    pub gamma: Value<f64>,
+    /// Output value for inputs at or below the input black level, from 0
+    /// to 1.
+    pub output_black: Value<f64>,
+    /// Output value for inputs at or above the input white level, from 0
+    /// to 1.
+    pub output_white: Value<f64>,
+}
+
+impl LevelsChannel {
+    /// Evaluates the adjustment at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::LevelsChannel {
+        fixed::LevelsChannel {
+            input_black: self.input_black.evaluate(frame),
+            input_white: self.input_white.evaluate(frame),
+            gamma: self.gamma.evaluate(frame),
+            output_black: self.output_black.evaluate(frame),
+            output_white: self.output_white.evaluate(frame),
+        }
+    }
+}
+
+/// Animated levels effect.
+#[derive(Clone, Debug)]
+pub struct Levels {
+    /// Adjustment applied to the red, green and blue channels.
+    pub rgb: LevelsChannel,
+    /// Adjustment applied to the red channel.
+    pub red: LevelsChannel,
+    /// Adjustment applied to the green channel.
+    pub green: LevelsChannel,
+    /// Adjustment applied to the blue channel.
+    pub blue: LevelsChannel,
+    /// Adjustment applied to the alpha channel.
+    pub alpha: LevelsChannel,
+}
+
+impl Levels {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::Levels {
+        fixed::Levels {
+            rgb: self.rgb.evaluate(frame),
+            red: self.red.evaluate(frame),
+            green: self.green.evaluate(frame),
+            blue: self.blue.evaluate(frame),
+            alpha: self.alpha.evaluate(frame),
+        }
+    }
+}
+
+/// Animated linear wipe effect.
+#[derive(Clone, Debug)]
+pub struct LinearWipe {
+    /// Percentage of the layer that is hidden.
+    pub completion: Value<f64>,
+    /// Direction in which the wipe travels, in degrees clockwise from up.
+    pub angle: Value<f64>,
+    /// Width of the soft edge of the wipe.
+    pub feather: Value<f64>,
+}
+
+impl LinearWipe {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::LinearWipe {
+        fixed::LinearWipe {
+            completion: self.completion.evaluate(frame),
+            angle: self.angle.evaluate(frame),
+            feather: self.feather.evaluate(frame).max(0.0),
+        }
+    }
+}
+
+/// Animated radial wipe effect.
+#[derive(Clone, Debug)]
+pub struct RadialWipe {
+    /// Percentage of the layer that is hidden.
+    pub completion: Value<f64>,
+    /// Angle at which the wipe starts, in degrees clockwise from up.
+    pub start_angle: Value<f64>,
+    /// Center of the wipe.
+    pub center: Value<Point>,
+    /// Direction in which the wipe travels.
+    pub direction: WipeDirection,
+    /// Width of the soft edge of the wipe.
+    pub feather: Value<f64>,
+}
+
+impl RadialWipe {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::RadialWipe {
+        fixed::RadialWipe {
+            completion: self.completion.evaluate(frame),
+            start_angle: self.start_angle.evaluate(frame),
+            center: self.center.evaluate(frame),
+            direction: self.direction,
+            feather: self.feather.evaluate(frame).max(0.0),
+        }
+    }
+}
+
+/// Animated displacement map effect.
+#[derive(Clone, Debug)]
+pub struct DisplacementMap {
+    /// Index of the layer used as the map.
+    pub layer: Option<usize>,
+    /// Channel of the map that controls horizontal displacement.
+    pub horizontal: DisplacementChannel,
+    /// Largest horizontal displacement.
+    pub max_horizontal: Value<f64>,
+    /// Channel of the map that controls vertical displacement.
+    pub vertical: DisplacementChannel,
+    /// Largest vertical displacement.
+    pub max_vertical: Value<f64>,
+}
+
+impl DisplacementMap {
+    /// Evaluates the effect at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> fixed::DisplacementMap {
+        fixed::DisplacementMap {
+            horizontal: self.horizontal,
+            max_horizontal: self.max_horizontal.evaluate(frame),
+            vertical: self.vertical,
+            max_vertical: self.max_vertical.evaluate(frame),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorStops, Gradient, Time, Value};
+    use kurbo::Point;
+    use peniko::{Brush, Color, ColorStop, GradientKind};
+
+    fn kind(gradient: &Gradient) -> GradientKind {
+        match gradient.evaluate(0.0) {
+            Brush::Gradient(gradient) => gradient.kind,
+            brush => panic!("expected a gradient, found {brush:?}"),
+        }
+    }
+
+    #[test]
+    fn test_gradient_from_angle() {
+        let stops = [(0.0, Color::BLACK), (1.0, Color::WHITE)]
+            .into_iter()
+            .map(ColorStop::from)
+            .collect();
+        let gradient = Gradient::linear_from_angle(Point::new(50.0, 50.0), 90.0, 100.0, stops);
+        let GradientKind::Linear { start, end } = kind(&gradient) else {
+            panic!("expected a linear gradient");
+        };
+        assert!((start - Point::new(50.0, 0.0)).hypot() < 1e-9);
+        assert!((end - Point::new(50.0, 100.0)).hypot() < 1e-9);
+        assert!((gradient.angle(0.0) - 90.0).abs() < 1e-9);
+        assert!((gradient.length(0.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gradient_from_peniko() {
+        let linear = peniko::Gradient::new_linear((0.0, 0.0), (10.0, 20.0));
+        let gradient = Gradient::from_peniko(&linear).unwrap();
+        assert_eq!(kind(&gradient), linear.kind);
+        // Radial gradients with a focal point become highlights.
+        let radial = peniko::Gradient::new_two_point_radial((15.0, 10.0), 0.0, (10.0, 10.0), 10.0);
+        let gradient = Gradient::from_peniko(&radial).unwrap();
+        assert_eq!(gradient.highlight_length.evaluate(0.0), 50.0);
+        let GradientKind::Radial {
+            start_center,
+            end_center,
+            end_radius,
+            ..
+        } = kind(&gradient)
+        else {
+            panic!("expected a radial gradient");
+        };
+        assert!((start_center - Point::new(15.0, 10.0)).hypot() < 1e-9);
+        assert_eq!((end_center, end_radius), (Point::new(10.0, 10.0), 10.0));
+        let sweep = peniko::Gradient::new_sweep((0.0, 0.0), 0.0, 1.0);
+        assert!(Gradient::from_peniko(&sweep).is_none());
+    }
+
+    #[test]
+    fn test_radial_highlight() {
+        // A highlight of half the radius at 90 degrees from the axis moves
+        // the focal point below the center of a gradient along the x axis.
+        let mut gradient = Gradient::radial(Point::new(10.0, 10.0), 20.0, Default::default());
+        gradient.highlight_length = Value::Fixed(50.0);
+        gradient.highlight_angle = Value::Fixed(90.0);
+        let GradientKind::Radial {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+        } = kind(&gradient)
+        else {
+            panic!("expected a radial gradient");
+        };
+        assert!((start_center - Point::new(10.0, 20.0)).hypot() < 1e-6);
+        assert_eq!(start_radius, 0.0);
+        assert_eq!((end_center, end_radius), (Point::new(10.0, 10.0), 20.0));
+        // Full highlights stay inside the circle.
+        gradient.highlight_length = Value::Fixed(100.0);
+        let GradientKind::Radial { start_center, .. } = kind(&gradient) else {
+            panic!("expected a radial gradient");
+        };
+        assert!((start_center - Point::new(10.0, 10.0)).hypot() < 20.0);
+    }
+
+    #[test]
+    fn test_alpha_stops() {
+        let stop = |stop: ColorStop| (stop.offset, stop.color);
+        // Two color stops, black to white, and three opacity stops that fade
+        // in and out at offsets of their own.
+        let values = [
+            0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, //
+            0.0, 0.0, 0.5, 1.0, 1.0, 0.0,
+        ];
+        let stops = ColorStops::merge(&values, 2);
+        assert_eq!(stops.len(), 3);
+        assert_eq!(stop(stops[0]), (0.0, Color::rgba(0.0, 0.0, 0.0, 0.0)));
+        assert_eq!(stop(stops[1]), (0.5, Color::rgba(0.5, 0.5, 0.5, 1.0)));
+        assert_eq!(stop(stops[2]), (1.0, Color::rgba(1.0, 1.0, 1.0, 0.0)));
+        // Color stops between opacity stops interpolate the alpha.
+        let values = [
+            0.0, 1.0, 0.0, 0.0, 0.25, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, //
+            0.0, 1.0, 1.0, 0.0,
+        ];
+        let stops = ColorStops::merge(&values, 3);
+        assert_eq!(stops.len(), 3);
+        assert_eq!(stop(stops[1]), (0.25, Color::rgba(0.0, 1.0, 0.0, 0.75)));
+        // Without opacity stops, colors are opaque.
+        let stops = ColorStops::merge(&values[..12], 3);
+        assert!(stops.iter().all(|stop| stop.color.a == 255));
+
+        // Keyframes are interpolated before the stops are merged, so opacity
+        // stops may move independently of color stops.
+        let animated = ColorStops {
+            frames: [0.0, 10.0]
+                .map(|frame| Time {
+                    frame,
+                    in_tangent: None,
+                    out_tangent: None,
+                    axis_in_tangents: [None; 2],
+                    axis_out_tangents: [None; 2],
+                    hold: false,
+                })
+                .to_vec(),
+            values: vec![
+                vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.2, 1.0],
+                vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.6, 0.0],
+            ],
+            count: 2,
+        };
+        let stops = animated.evaluate(5.0);
+        assert_eq!(stops.len(), 3);
+        assert_eq!(stop(stops[1]), (0.4, Color::rgba(0.4, 0.4, 0.4, 0.5)));
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::fixed;
+use peniko::{Color, ColorStop};
+
+/// Number of segments each pair of gradient stops is divided into when
+/// resampled in another color space.
+const GRADIENT_SEGMENTS: usize = 8;
+
+/// Color space in which colors are interpolated, both between the stops of
+/// gradients and between the keyframes of animated colors.
+///
+/// Lottie files store sRGB colors, and interpolating their components
+/// directly is the fastest option. Saturated gradients look closer to the
+/// output of After Effects when interpolated in linear sRGB, and Oklab keeps
+/// the perceived lightness and hue more even.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum ColorSpace {
+    /// Interpolate the sRGB components as stored.
+    #[default]
+    Srgb,
+    /// Interpolate linear light sRGB components.
+    LinearSrgb,
+    /// Interpolate in the Oklab perceptual color space.
+    Oklab,
+}
+
+impl ColorSpace {
+    /// Interpolates between two colors at the normalized time `t`.
+    ///
+    /// Alpha is always interpolated linearly.
+    pub fn interpolate(self, from: Color, to: Color, t: f64) -> Color {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        let a = lerp(from.a as f64, to.a as f64) / 255.0;
+        let [r, g, b] = match self {
+            Self::Srgb => {
+                let [from, to] = [from, to].map(srgb);
+                std::array::from_fn(|i| lerp(from[i], to[i]))
+            }
+            Self::LinearSrgb => {
+                let [from, to] = [from, to].map(|color| srgb(color).map(to_linear));
+                std::array::from_fn(|i| lerp(from[i], to[i])).map(from_linear)
+            }
+            Self::Oklab => {
+                let [from, to] = [from, to].map(|color| oklab(srgb(color).map(to_linear)));
+                linear_from_oklab(std::array::from_fn(|i| lerp(from[i], to[i]))).map(from_linear)
+            }
+        };
+        Color::rgba(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0), a)
+    }
+
+    /// Returns stops that, when interpolated in sRGB as renderers do,
+    /// approximate interpolating the given stops in this color space.
+    ///
+    /// Each pair of stops with different colors is divided into segments
+    /// with stops in between.
+    pub fn resample(self, stops: &[ColorStop]) -> fixed::ColorStops {
+        if self == Self::Srgb {
+            return stops.iter().copied().collect();
+        }
+        let mut resampled = fixed::ColorStops::new();
+        for pair in stops.windows(2) {
+            let [from, to] = [pair[0], pair[1]];
+            resampled.push(from);
+            if from.color == to.color || from.offset >= to.offset {
+                continue;
+            }
+            for segment in 1..GRADIENT_SEGMENTS {
+                let t = segment as f64 / GRADIENT_SEGMENTS as f64;
+                let offset = from.offset + (to.offset - from.offset) * t as f32;
+                let color = self.interpolate(from.color, to.color, t);
+                resampled.push(ColorStop::from((offset, color)));
+            }
+        }
+        resampled.extend(stops.last().copied());
+        resampled
+    }
+
+    /// Resamples the stops of a gradient brush in this color space.
+    pub fn resample_brush(self, brush: &fixed::Brush) -> Option<fixed::Brush> {
+        match brush {
+            fixed::Brush::Gradient(gradient) if self != Self::Srgb => {
+                let mut gradient = gradient.clone();
+                gradient.stops = self.resample(&gradient.stops);
+                Some(gradient.into())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn srgb(color: Color) -> [f64; 3] {
+    [color.r, color.g, color.b].map(|c| c as f64 / 255.0)
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts linear sRGB to Oklab, as defined by Björn Ottosson.
+fn oklab([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+fn linear_from_oklab([l, a, b]: [f64; 3]) -> [f64; 3] {
+    let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+    let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+    let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+    let [l, m, s] = [l_, m_, s_].map(|c| c * c * c);
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorSpace;
+    use peniko::{Color, ColorStop};
+
+    #[test]
+    fn test_interpolate() {
+        let red = Color::rgb8(255, 0, 0);
+        let green = Color::rgb8(0, 255, 0);
+        for space in [ColorSpace::Srgb, ColorSpace::LinearSrgb, ColorSpace::Oklab] {
+            assert_eq!(space.interpolate(red, green, 0.0), red);
+            assert_eq!(space.interpolate(red, green, 1.0), green);
+        }
+        // Raw sRGB produces a dark midpoint, which linear light avoids.
+        let mid = |space: ColorSpace| space.interpolate(red, green, 0.5);
+        assert_eq!(mid(ColorSpace::Srgb), Color::rgb8(128, 128, 0));
+        assert_eq!(mid(ColorSpace::LinearSrgb), Color::rgb8(188, 188, 0));
+        let oklab = mid(ColorSpace::Oklab);
+        assert!(oklab.r > 128 && oklab.g > 128 && oklab.b < 64);
+        // Alpha is linear in every space.
+        let clear = Color::rgba8(0, 0, 255, 0);
+        assert_eq!(ColorSpace::Oklab.interpolate(red, clear, 0.5).a, 128);
+    }
+
+    #[test]
+    fn test_resample() {
+        let stops = [
+            ColorStop::from((0.0, Color::BLACK)),
+            ColorStop::from((0.5, Color::WHITE)),
+            ColorStop::from((1.0, Color::WHITE)),
+        ];
+        assert_eq!(ColorSpace::Srgb.resample(&stops).len(), 3);
+        let resampled = ColorSpace::LinearSrgb.resample(&stops);
+        // Only the pair with different colors is divided.
+        assert_eq!(resampled.len(), 10);
+        assert_eq!(resampled[4].offset, 0.25);
+        assert_eq!(resampled[4].color, Color::rgb8(188, 188, 188));
+        assert_eq!(resampled.last(), stops.last());
+    }
+}
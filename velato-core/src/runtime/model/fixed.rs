@@ -0,0 +1,956 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+/*!
+Representations of fixed (non-animated) values.
+*/
+
+use super::{modifier, Easing, RepeaterComposite, Tween};
+use kurbo::{self, Affine, PathEl, Point, Rect, Shape as _, Vec2};
+use std::ops::Range;
+
+/// Fixed affine transformation.
+pub type Transform = kurbo::Affine;
+
+/// Fixed RGBA color.
+pub type Color = peniko::Color;
+
+/// Fixed color stops.
+pub type ColorStops = peniko::ColorStops;
+
+/// Fixed brush.
+pub type Brush = peniko::Brush;
+
+/// Fixed stroke style.
+pub type Stroke = kurbo::Stroke;
+
+/// Fixed repeater effect.
+#[derive(Clone, Debug)]
+pub struct Repeater {
+    /// Number of times to repeat.
+    pub copies: usize,
+    /// Offset of each subsequent repeated element.
+    pub offset: f64,
+    /// Anchor point.
+    pub anchor_point: Point,
+    /// Translation.
+    pub position: Point,
+    /// Rotation in degrees.
+    pub rotation: f64,
+    /// Scale.
+    pub scale: Vec2,
+    /// Opacity of the first element.
+    pub start_opacity: f64,
+    /// Opacity of the last element.
+    pub end_opacity: f64,
+    /// Offset in degrees added to the trim paths that follow the repeater
+    /// for each subsequent element.
+    pub trim_offset: f64,
+    /// Stacking order of the elements, which the opacity ramp follows from
+    /// the bottom element to the top one.
+    pub composite: RepeaterComposite,
+}
+
+impl Repeater {
+    /// Returns the transform for the given copy index.
+    pub fn transform(&self, index: usize) -> Affine {
+        let t = self.offset + index as f64;
+        Affine::translate((
+            t * self.position.x + self.anchor_point.x,
+            t * self.position.y + self.anchor_point.y,
+        )) * Affine::rotate((t * self.rotation).to_radians())
+            * Affine::scale_non_uniform(
+                (self.scale.x / 100.0).powf(t),
+                (self.scale.y / 100.0).powf(t),
+            )
+            * Affine::translate((-self.anchor_point.x, -self.anchor_point.y))
+    }
+}
+
+/// Components of an affine transformation.
+///
+/// The transformation is equivalent to a scale, followed by a horizontal
+/// skew, a rotation and finally a translation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TransformParts {
+    /// Translation.
+    pub translation: Vec2,
+    /// Rotation in degrees, clockwise.
+    pub rotation: f64,
+    /// Scale factor, where 1 is the original size.
+    pub scale: Vec2,
+    /// Horizontal skew angle in degrees.
+    pub skew: f64,
+}
+
+impl TransformParts {
+    /// Decomposes an affine transformation into its components.
+    ///
+    /// The rotation is in the range -180 to 180 degrees, and a reflection
+    /// is represented by a negative vertical scale.
+    pub fn from_affine(transform: Affine) -> Self {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        let scale_x = a.hypot(b);
+        let (rotation, scale_y, shear) = if scale_x > 0.0 {
+            let (sin, cos) = (b / scale_x, a / scale_x);
+            let scale_y = d * cos - c * sin;
+            let shear = if scale_y != 0.0 {
+                (c * cos + d * sin) / scale_y
+            } else {
+                0.0
+            };
+            (b.atan2(a), scale_y, shear)
+        } else {
+            (0.0, d, if d != 0.0 { c / d } else { 0.0 })
+        };
+        Self {
+            translation: Vec2::new(e, f),
+            rotation: rotation.to_degrees(),
+            scale: Vec2::new(scale_x, scale_y),
+            skew: shear.atan().to_degrees(),
+        }
+    }
+
+    /// Returns the affine transformation for the components.
+    pub fn to_affine(&self) -> Affine {
+        Affine::translate(self.translation)
+            * Affine::rotate(self.rotation.to_radians())
+            * Affine::skew(self.skew.to_radians().tan(), 0.0)
+            * Affine::scale_non_uniform(self.scale.x, self.scale.y)
+    }
+}
+
+impl Default for TransformParts {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::new(1.0, 1.0),
+            skew: 0.0,
+        }
+    }
+}
+
+/// Perspective camera that projects 3D layers onto a composition.
+///
+/// Coordinates follow After Effects, with the Z axis pointing away from the
+/// viewer.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Camera {
+    /// Point of the composition at the center of the view, toward which
+    /// distant layers converge.
+    pub center: Point,
+    /// Distance in front of the camera of the plane that is drawn at its
+    /// original size.
+    pub zoom: f64,
+    /// Position of the camera.
+    pub position: [f64; 3],
+    /// Directions of the right, down and forward axes of the camera.
+    pub axes: [[f64; 3]; 3],
+}
+
+impl Camera {
+    /// Returns the default camera for a composition of the given size.
+    ///
+    /// Like lottie-web, the camera looks at the center of the composition
+    /// from a distance of its diagonal, so the plane where Z is 0 is drawn
+    /// at its original size.
+    pub fn for_size(width: f64, height: f64) -> Self {
+        let zoom = width.hypot(height);
+        Self {
+            center: Point::new(width * 0.5, height * 0.5),
+            zoom,
+            position: [width * 0.5, height * 0.5, -zoom],
+            axes: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// Projects a plane in 3D space, given by its origin and the images of
+    /// its X and Y axes, onto the composition.
+    ///
+    /// A perspective projection is not affine, so the result is the affine
+    /// transformation that matches the projection around `point`, in the
+    /// coordinates of the plane. Planes behind the camera collapse to a
+    /// point.
+    pub fn project(
+        &self,
+        origin: [f64; 3],
+        x_axis: [f64; 3],
+        y_axis: [f64; 3],
+        point: Point,
+    ) -> Affine {
+        // Coordinates relative to the camera.
+        let view = |v: [f64; 3]| {
+            self.axes
+                .map(|axis| axis[0] * v[0] + axis[1] * v[1] + axis[2] * v[2])
+        };
+        let at = view(std::array::from_fn(|i| {
+            origin[i] + x_axis[i] * point.x + y_axis[i] * point.y - self.position[i]
+        }));
+        let depth = at[2];
+        if depth <= f64::EPSILON * self.zoom.max(1.0) {
+            return Affine::scale(0.0);
+        }
+        let scale = self.zoom / depth;
+        let offset = Vec2::new(at[0], at[1]);
+        // Derivative of the projection along an axis of the plane.
+        let project_axis = |axis: [f64; 3]| {
+            let axis = view(axis);
+            (Vec2::new(axis[0], axis[1]) - offset * (axis[2] / depth)) * scale
+        };
+        let x = project_axis(x_axis);
+        let y = project_axis(y_axis);
+        let projected = self.center + offset * scale;
+        let translation = projected.to_vec2() - x * point.x - y * point.y;
+        Affine::new([x.x, x.y, y.x, y.y, translation.x, translation.y])
+    }
+}
+
+/// Fixed path modifier.
+#[derive(Clone, Debug)]
+pub enum PathModifier {
+    /// Zig-zag modifier.
+    ZigZag(ZigZag),
+    /// Rounded corners modifier.
+    RoundedCorners(RoundedCorners),
+    /// Offset path modifier.
+    OffsetPath(OffsetPath),
+    /// Pucker and bloat modifier.
+    PuckerBloat(PuckerBloat),
+    /// Twist modifier.
+    Twist(Twist),
+    /// Trim path modifier.
+    Trim(Trim),
+}
+
+impl PathModifier {
+    /// Applies the modifier to the given path, emitting the result to `out`.
+    pub fn apply(&self, path: &[PathEl], out: &mut Vec<PathEl>) {
+        match self {
+            Self::ZigZag(zig_zag) => modifier::zig_zag(zig_zag, path, out),
+            Self::RoundedCorners(rounded_corners) => {
+                modifier::rounded_corners(rounded_corners, path, out);
+            }
+            Self::OffsetPath(offset_path) => modifier::offset_path(offset_path, path, out),
+            Self::PuckerBloat(pucker_bloat) => modifier::pucker_bloat(pucker_bloat, path, out),
+            Self::Twist(twist) => modifier::twist(twist, path, out),
+            Self::Trim(trim) => modifier::trim(trim, path, out),
+        }
+    }
+}
+
+/// Fixed zig-zag modifier.
+#[derive(Clone, Debug)]
+pub struct ZigZag {
+    /// Number of ridges per segment.
+    pub ridges: f64,
+    /// Distance of each ridge from the original path.
+    pub size: f64,
+    /// True if ridges are smooth waves rather than sharp corners.
+    pub is_smooth: bool,
+}
+
+/// Fixed offset path modifier.
+#[derive(Clone, Debug)]
+pub struct OffsetPath {
+    /// Distance to offset the path. Positive values expand the shape.
+    pub amount: f64,
+    /// Join style for corners of the offset path.
+    pub join: kurbo::Join,
+    /// Limit for miter joins.
+    pub miter_limit: f64,
+}
+
+/// Fixed pucker and bloat modifier.
+#[derive(Clone, Debug)]
+pub struct PuckerBloat {
+    /// Percentage to move vertices towards the center, with control points
+    /// moving in the opposite direction. Negative values pucker the shape.
+    pub amount: f64,
+}
+
+/// Fixed twist modifier.
+#[derive(Clone, Debug)]
+pub struct Twist {
+    /// Rotation in degrees applied at the farthest distance from the
+    /// center.
+    pub angle: f64,
+    /// Offset of the center of rotation from the center of the path bounds.
+    pub center: Vec2,
+}
+
+/// Fixed trim path modifier.
+#[derive(Clone, Debug)]
+pub struct Trim {
+    /// Percentage of the length of the path at which the segment starts.
+    pub start: f64,
+    /// Percentage of the length of the path at which the segment ends.
+    pub end: f64,
+    /// Offset of the segment in degrees, where a full turn moves it along
+    /// the whole path.
+    pub offset: f64,
+    /// True if the subpaths are trimmed as one sequence, rather than each
+    /// on its own.
+    pub individually: bool,
+}
+
+impl Trim {
+    /// Returns the ranges of the path that are kept, as fractions of its
+    /// length. The second range is empty unless the segment wraps around
+    /// the end of the path.
+    pub fn ranges(&self) -> [Range<f64>; 2] {
+        let mut start = self.start.clamp(0.0, 100.0) / 100.0;
+        let mut end = self.end.clamp(0.0, 100.0) / 100.0;
+        if start > end {
+            std::mem::swap(&mut start, &mut end);
+        }
+        if end - start >= 1.0 {
+            return [0.0..1.0, 0.0..0.0];
+        }
+        let shift = (self.offset / 360.0).rem_euclid(1.0);
+        start += shift;
+        end += shift;
+        if start >= 1.0 {
+            start -= 1.0;
+            end -= 1.0;
+        }
+        if end > 1.0 {
+            [start..1.0, 0.0..end - 1.0]
+        } else {
+            [start..end, 0.0..0.0]
+        }
+    }
+}
+
+/// Fixed rounded corners modifier.
+#[derive(Clone, Debug)]
+pub struct RoundedCorners {
+    /// Radius of the rounded corners.
+    pub radius: f64,
+}
+
+/// Fixed drop shadow effect.
+#[derive(Clone, Debug)]
+pub struct DropShadow {
+    /// Color of the shadow, including its opacity.
+    pub color: Color,
+    /// Offset of the shadow from the content.
+    pub offset: Vec2,
+    /// Radius of the blur applied to the shadow.
+    pub radius: f64,
+    /// True if the content is hidden, leaving only the shadow.
+    pub shadow_only: bool,
+}
+
+/// Fixed outer glow layer style.
+#[derive(Clone, Debug)]
+pub struct OuterGlow {
+    /// Color of the glow, including its opacity.
+    pub color: Color,
+    /// Radius of the blur applied to the glow.
+    pub radius: f64,
+}
+
+/// Fixed stroke layer style.
+#[derive(Clone, Debug)]
+pub struct StrokeStyle {
+    /// Color of the stroke.
+    pub color: Color,
+    /// Width of the stroke outside the content.
+    pub size: f64,
+}
+
+/// Fixed stroke effect.
+#[derive(Clone, Debug)]
+pub struct StrokeEffect {
+    /// Color of the stroke, including its opacity.
+    pub color: Color,
+    /// Width of the stroke.
+    pub width: f64,
+    /// Fraction of the length of each mask at which the stroke starts.
+    pub start: f64,
+    /// Fraction of the length of each mask at which the stroke ends.
+    pub end: f64,
+}
+
+impl StrokeEffect {
+    /// Appends the part of the given path between the start and end of the
+    /// stroke to `out`, measuring along all of its subpaths in order.
+    pub fn trim(&self, path: &[PathEl], out: &mut kurbo::BezPath) {
+        trim_path(path, self.start..self.end, out);
+    }
+}
+
+/// Accuracy of the arc lengths used to trim paths.
+const TRIM_ACCURACY: f64 = 1e-3;
+
+/// Returns the length of a path, summed over all of its subpaths.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn path_length(path: &[PathEl]) -> f64 {
+    use kurbo::ParamCurveArclen as _;
+
+    kurbo::segments(path.iter().copied())
+        .map(|segment| segment.arclen(TRIM_ACCURACY))
+        .sum()
+}
+
+/// Appends the part of the given path in the range, given as fractions of
+/// its length, to `out`, measuring along all of its subpaths in order.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn trim_path(path: &[PathEl], range: Range<f64>, out: &mut impl Extend<PathEl>) {
+    use kurbo::{ParamCurve as _, ParamCurveArclen as _};
+
+    if range.start <= 0.0 && range.end >= 1.0 {
+        out.extend(path.iter().copied());
+        return;
+    }
+    if range.start >= range.end {
+        return;
+    }
+    let segments = kurbo::segments(path.iter().copied())
+        .map(|segment| (segment, segment.arclen(TRIM_ACCURACY)))
+        .collect::<Vec<_>>();
+    let length = segments.iter().map(|(_, length)| length).sum::<f64>();
+    let (start, end) = (range.start * length, range.end * length);
+    let mut offset = 0.0;
+    let mut last = None;
+    for (segment, length) in segments {
+        let (a, b) = (offset, offset + length);
+        offset = b;
+        if b <= start || a >= end || length == 0.0 {
+            continue;
+        }
+        let t0 = if start > a {
+            segment.inv_arclen(start - a, TRIM_ACCURACY)
+        } else {
+            0.0
+        };
+        let t1 = if end < b {
+            segment.inv_arclen(end - a, TRIM_ACCURACY)
+        } else {
+            1.0
+        };
+        let segment = segment.subsegment(t0..t1);
+        if last != Some(segment.start()) {
+            out.extend([PathEl::MoveTo(segment.start())]);
+        }
+        out.extend([segment.as_path_el()]);
+        last = Some(segment.end());
+    }
+}
+
+/// Fixed levels adjustment of a single channel.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct LevelsChannel {
+    /// Input value that maps to the output black level.
+    pub input_black: f64,
+    /// Input value that maps to the output white level.
+    pub input_white: f64,
+    /// Exponent applied to values between the input levels.
+    pub gamma: f64,
+    /// Output value for inputs at or below the input black level.
+    pub output_black: f64,
+    /// Output value for inputs at or above the input white level.
+    pub output_white: f64,
+}
+
+impl LevelsChannel {
+    /// Returns the adjusted value of a channel, with both in the range 0 to
+    /// 1.
+    pub fn apply(&self, value: f64) -> f64 {
+        let range = self.input_white - self.input_black;
+        let t = if range == 0.0 {
+            if value < self.input_black {
+                0.0
+            } else {
+                1.0
+            }
+        } else {
+            ((value - self.input_black) / range).clamp(0.0, 1.0)
+        };
+        let t = if self.gamma > 0.0 {
+            t.powf(1.0 / self.gamma)
+        } else {
+            t
+        };
+        (self.output_black + (self.output_white - self.output_black) * t).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for LevelsChannel {
+    fn default() -> Self {
+        Self {
+            input_black: 0.0,
+            input_white: 1.0,
+            gamma: 1.0,
+            output_black: 0.0,
+            output_white: 1.0,
+        }
+    }
+}
+
+/// Fixed levels effect.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct Levels {
+    /// Adjustment applied to the red, green and blue channels.
+    pub rgb: LevelsChannel,
+    /// Adjustment applied to the red channel.
+    pub red: LevelsChannel,
+    /// Adjustment applied to the green channel.
+    pub green: LevelsChannel,
+    /// Adjustment applied to the blue channel.
+    pub blue: LevelsChannel,
+    /// Adjustment applied to the alpha channel.
+    pub alpha: LevelsChannel,
+}
+
+impl Levels {
+    /// Returns the adjusted color. The combined adjustment is applied before
+    /// those of the individual channels.
+    pub fn apply(&self, color: Color) -> Color {
+        let channel =
+            |value: u8, levels: &LevelsChannel| levels.apply(self.rgb.apply(value as f64 / 255.0));
+        Color::rgba(
+            channel(color.r, &self.red),
+            channel(color.g, &self.green),
+            channel(color.b, &self.blue),
+            self.alpha.apply(color.a as f64 / 255.0),
+        )
+    }
+}
+
+/// Fixed linear wipe effect.
+#[derive(Clone, Debug)]
+pub struct LinearWipe {
+    /// Percentage of the layer that is hidden.
+    pub completion: f64,
+    /// Direction in which the wipe travels, in degrees clockwise from up.
+    pub angle: f64,
+    /// Width of the soft edge of the wipe.
+    pub feather: f64,
+}
+
+impl LinearWipe {
+    /// Returns the region of the given bounds that remains visible and,
+    /// if the wipe is feathered, a gradient to use as the alpha of that
+    /// region.
+    pub fn mask(&self, bounds: Rect) -> (kurbo::BezPath, Option<peniko::Gradient>) {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let along = Vec2::new(sin, -cos);
+        let across = Vec2::new(cos, sin);
+        let corners = [
+            bounds.origin(),
+            Point::new(bounds.x1, bounds.y0),
+            Point::new(bounds.x1, bounds.y1),
+            Point::new(bounds.x0, bounds.y1),
+        ];
+        let range = |axis: Vec2| {
+            corners
+                .iter()
+                .map(|corner| corner.to_vec2().dot(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| {
+                    (min.min(x), max.max(x))
+                })
+        };
+        let (min, max) = range(along);
+        let (min_across, max_across) = range(across);
+        // The feathered edge travels from just before the bounds to just
+        // after them, so that the layer is entirely visible at 0% and
+        // entirely hidden at 100%.
+        let half_feather = self.feather / 2.0;
+        let completion = self.completion.clamp(0.0, 100.0) / 100.0;
+        let edge = min - half_feather + completion * (max - min + self.feather);
+        let start = (edge - half_feather).min(max);
+        let point = |a: f64, b: f64| (along * a + across * b).to_point();
+        let mut path = kurbo::BezPath::new();
+        path.move_to(point(start, min_across));
+        path.line_to(point(max, min_across));
+        path.line_to(point(max, max_across));
+        path.line_to(point(start, max_across));
+        path.close_path();
+        let gradient = (self.feather > 0.0).then(|| {
+            peniko::Gradient::new_linear(
+                (along * (edge - half_feather)).to_point(),
+                (along * (edge + half_feather)).to_point(),
+            )
+            .with_stops([Color::TRANSPARENT, Color::BLACK].as_slice())
+        });
+        (path, gradient)
+    }
+}
+
+/// Fixed radial wipe effect.
+#[derive(Clone, Debug)]
+pub struct RadialWipe {
+    /// Percentage of the layer that is hidden.
+    pub completion: f64,
+    /// Angle at which the wipe starts, in degrees clockwise from up.
+    pub start_angle: f64,
+    /// Center of the wipe.
+    pub center: Point,
+    /// Direction in which the wipe travels.
+    pub direction: super::WipeDirection,
+    /// Width of the soft edge of the wipe.
+    pub feather: f64,
+}
+
+impl RadialWipe {
+    /// Returns the region of the given bounds that remains visible.
+    pub fn mask(&self, bounds: Rect) -> kurbo::BezPath {
+        let hidden = self.completion.clamp(0.0, 100.0) / 100.0 * 360.0;
+        self.visible_sector(bounds, hidden, self.radius(bounds))
+    }
+
+    /// Returns regions that approximate the soft edge of a feathered wipe,
+    /// from largest to smallest.
+    ///
+    /// The regions spread the edge over the angle that spans the feather
+    /// width halfway between the center and the farthest corner of the
+    /// bounds. Filling region `i` with alpha `1 / (steps - i)` gives
+    /// coverage that ramps linearly across the edge.
+    pub fn feathered_masks(&self, bounds: Rect, steps: usize) -> Vec<kurbo::BezPath> {
+        let hidden = self.completion.clamp(0.0, 100.0) / 100.0 * 360.0;
+        let radius = self.radius(bounds);
+        let feather = (self.feather.max(0.0) / (radius / 2.0)).to_degrees();
+        (0..steps)
+            .map(|step| {
+                let t = (step as f64 + 0.5) / steps as f64;
+                let hidden = (hidden + feather * (t - 0.5)).clamp(0.0, 360.0);
+                self.visible_sector(bounds, hidden, radius)
+            })
+            .collect()
+    }
+
+    /// Returns the distance from the center to the farthest corner of the
+    /// bounds, with some slack for the approximation of arcs.
+    fn radius(&self, bounds: Rect) -> f64 {
+        [
+            bounds.origin(),
+            Point::new(bounds.x1, bounds.y0),
+            Point::new(bounds.x1, bounds.y1),
+            Point::new(bounds.x0, bounds.y1),
+        ]
+        .iter()
+        .map(|corner| corner.distance(self.center))
+        .fold(0.0, f64::max)
+            + 1.0
+    }
+
+    /// Returns the region that remains visible when the given angle, in
+    /// degrees, is hidden.
+    fn visible_sector(&self, bounds: Rect, hidden: f64, radius: f64) -> kurbo::BezPath {
+        let mut path = kurbo::BezPath::new();
+        if hidden <= 0.0 {
+            path.extend(bounds.path_elements(0.1));
+            return path;
+        }
+        if hidden >= 360.0 {
+            return path;
+        }
+        // The visible sector starts where the hidden sector ends and sweeps
+        // clockwise around the rest of the circle.
+        let start = match self.direction {
+            super::WipeDirection::Clockwise => self.start_angle + hidden,
+            super::WipeDirection::Counterclockwise => self.start_angle,
+            super::WipeDirection::Both => self.start_angle + hidden / 2.0,
+        };
+        // Arcs are measured clockwise from the x axis.
+        let arc = kurbo::Arc {
+            center: self.center,
+            radii: Vec2::new(radius, radius),
+            start_angle: (start - 90.0).to_radians(),
+            sweep_angle: (360.0 - hidden).to_radians(),
+            x_rotation: 0.0,
+        };
+        path.move_to(self.center);
+        path.line_to(arc.center + Vec2::from_angle(arc.start_angle) * radius);
+        path.extend(arc.append_iter(0.1));
+        path.close_path();
+        path
+    }
+}
+
+/// Fixed displacement map effect.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DisplacementMap {
+    /// Channel of the map that controls horizontal displacement.
+    pub horizontal: super::DisplacementChannel,
+    /// Largest horizontal displacement.
+    pub max_horizontal: f64,
+    /// Channel of the map that controls vertical displacement.
+    pub vertical: super::DisplacementChannel,
+    /// Largest vertical displacement.
+    pub max_vertical: f64,
+}
+
+impl DisplacementMap {
+    /// Returns the offset from a point of the displaced layer to the point
+    /// of its content that is shown there, where the map has the given
+    /// color.
+    ///
+    /// Channel values of one half do not displace the content, while values
+    /// of zero and one displace it by the maximum in either direction.
+    pub fn offset(&self, color: Color) -> Vec2 {
+        let offset = |channel: super::DisplacementChannel, max: f64| {
+            (channel.value(color) - 0.5) * 2.0 * max
+        };
+        Vec2::new(
+            offset(self.horizontal, self.max_horizontal),
+            offset(self.vertical, self.max_vertical),
+        )
+    }
+}
+
+// TODO: probably move this to peniko. The better option is to add an alpha
+// parameter to the draw methods in vello. This is already handled at the
+// encoding level.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn brush_with_alpha(brush: &Brush, alpha: f64) -> Brush {
+    if alpha == 1.0 {
+        brush.clone()
+    } else {
+        match brush {
+            Brush::Solid(color) => color.with_alpha_factor(alpha as f32).into(),
+            Brush::Gradient(gradient) => Brush::Gradient(peniko::Gradient {
+                kind: gradient.kind,
+                extend: gradient.extend,
+                stops: gradient
+                    .stops
+                    .iter()
+                    .map(|stop| stop.with_alpha_factor(alpha as f32))
+                    .collect(),
+            }),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Replaces the colors of a brush with black, using the luminance of each
+/// color, multiplied by its alpha, as the new alpha.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn brush_luma_to_alpha(brush: &Brush) -> Brush {
+    map_brush_colors(brush, |color| {
+        // Rec. 709 luma coefficients.
+        let luma =
+            (0.2126 * color.r as f64 + 0.7152 * color.g as f64 + 0.0722 * color.b as f64) / 255.0;
+        Color::rgba(0.0, 0.0, 0.0, luma * color.a as f64 / 255.0)
+    })
+}
+
+/// Replaces the colors of a brush with the given tint, multiplying the alpha
+/// of each color by that of the tint.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn brush_tint(brush: &Brush, tint: Color) -> Brush {
+    map_brush_colors(brush, |color| {
+        tint.with_alpha_factor(color.a as f32 / 255.0)
+    })
+}
+
+/// Applies a levels adjustment to the colors of a brush.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn brush_levels(brush: &Brush, levels: &Levels) -> Brush {
+    map_brush_colors(brush, |color| levels.apply(color))
+}
+
+/// Mixes the colors of a brush with those of a recolored copy of it, such as
+/// one returned by [`brush_tint`], by the given amount in `0..=1`.
+#[doc(hidden)] // Used by the renderer in velato.
+pub fn brush_mix(brush: &Brush, recolored: &Brush, amount: f64) -> Brush {
+    let mix = |color: Color, recolored: Color| color.tween(&recolored, amount, &Easing::LERP);
+    match (brush, recolored) {
+        (Brush::Solid(color), Brush::Solid(recolored)) => mix(*color, *recolored).into(),
+        (Brush::Gradient(gradient), Brush::Gradient(recolored)) => {
+            Brush::Gradient(peniko::Gradient {
+                kind: gradient.kind,
+                extend: gradient.extend,
+                stops: gradient
+                    .stops
+                    .iter()
+                    .zip(&recolored.stops)
+                    .map(|(stop, recolored)| peniko::ColorStop {
+                        offset: stop.offset,
+                        color: mix(stop.color, recolored.color),
+                    })
+                    .collect(),
+            })
+        }
+        _ => recolored.clone(),
+    }
+}
+
+fn map_brush_colors(brush: &Brush, f: impl Fn(Color) -> Color) -> Brush {
+    match brush {
+        Brush::Solid(color) => f(*color).into(),
+        Brush::Gradient(gradient) => Brush::Gradient(peniko::Gradient {
+            kind: gradient.kind,
+            extend: gradient.extend,
+            stops: gradient
+                .stops
+                .iter()
+                .map(|stop| peniko::ColorStop {
+                    offset: stop.offset,
+                    color: f(stop.color),
+                })
+                .collect(),
+        }),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        brush_luma_to_alpha, Brush, Color, Levels, LevelsChannel, LinearWipe, RadialWipe,
+        StrokeEffect, TransformParts,
+    };
+    use crate::model::WipeDirection;
+    use kurbo::{self, Affine, ParamCurve as _, Point, Rect, Shape as _, Vec2};
+
+    #[test]
+    fn test_decompose() {
+        let parts = TransformParts {
+            translation: Vec2::new(10.0, -5.0),
+            rotation: 30.0,
+            scale: Vec2::new(2.0, -0.5),
+            skew: 15.0,
+        };
+        let decomposed = TransformParts::from_affine(parts.to_affine());
+        assert!((decomposed.translation - parts.translation).hypot() < 1e-9);
+        assert!((decomposed.rotation - parts.rotation).abs() < 1e-9);
+        assert!((decomposed.scale - parts.scale).hypot() < 1e-9);
+        assert!((decomposed.skew - parts.skew).abs() < 1e-9);
+        assert_eq!(
+            TransformParts::from_affine(Affine::IDENTITY),
+            TransformParts::default()
+        );
+    }
+
+    #[test]
+    fn test_wipes() {
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let near = |a: Rect, b: Rect| {
+            (a.origin() - b.origin()).hypot() < 1e-6
+                && (a.size() - b.size()).to_vec2().hypot() < 1e-6
+        };
+        let linear = |completion, angle| {
+            LinearWipe {
+                completion,
+                angle,
+                feather: 0.0,
+            }
+            .mask(bounds)
+            .0
+            .bounding_box()
+        };
+        // Wipes toward the right hide the left of the layer.
+        assert!(near(linear(25.0, 90.0), Rect::new(25.0, 0.0, 100.0, 100.0)));
+        assert!(near(linear(50.0, 0.0), Rect::new(0.0, 0.0, 100.0, 50.0)));
+        assert!(linear(100.0, 90.0).area() == 0.0);
+        let radial = |completion, direction| {
+            RadialWipe {
+                completion,
+                start_angle: 0.0,
+                center: Point::new(50.0, 50.0),
+                direction,
+                feather: 0.0,
+            }
+            .mask(bounds)
+            .bounding_box()
+        };
+        assert!(near(radial(0.0, WipeDirection::Clockwise), bounds));
+        assert!(radial(100.0, WipeDirection::Clockwise).area() == 0.0);
+        // Half of a clockwise wipe from the top leaves the left half.
+        let left = radial(50.0, WipeDirection::Clockwise);
+        assert!((left.x1 - 50.0).abs() < 1e-6 && left.x0 < 0.0);
+        let right = radial(50.0, WipeDirection::Counterclockwise);
+        assert!((right.x0 - 50.0).abs() < 1e-6 && right.x1 > 100.0);
+        // Feathered regions shrink around the edge of the unfeathered one.
+        let wipe = RadialWipe {
+            completion: 25.0,
+            start_angle: 0.0,
+            center: Point::new(50.0, 50.0),
+            direction: WipeDirection::Clockwise,
+            feather: 10.0,
+        };
+        let areas = wipe
+            .feathered_masks(bounds, 4)
+            .iter()
+            .map(|path| path.area().abs())
+            .collect::<Vec<_>>();
+        let area = wipe.mask(bounds).area().abs();
+        assert!(areas.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(areas[1] > area && areas[2] < area);
+    }
+
+    #[test]
+    fn test_levels() {
+        let levels = Levels::default();
+        let color = Color::rgba8(10, 128, 250, 200);
+        assert_eq!(levels.apply(color), color);
+        let channel = LevelsChannel {
+            input_black: 0.25,
+            input_white: 0.75,
+            gamma: 2.0,
+            output_black: 0.2,
+            output_white: 0.8,
+        };
+        assert_eq!(channel.apply(0.1), 0.2);
+        assert_eq!(channel.apply(0.9), 0.8);
+        assert!((channel.apply(0.375) - (0.2 + 0.6 * 0.25f64.sqrt())).abs() < 1e-9);
+        // The combined adjustment applies before that of each channel.
+        let levels = Levels {
+            rgb: LevelsChannel {
+                output_black: 0.5,
+                ..Default::default()
+            },
+            red: LevelsChannel {
+                input_black: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let color = levels.apply(Color::rgba8(0, 0, 0, 255));
+        assert_eq!((color.r, color.g, color.b, color.a), (0, 128, 128, 255));
+    }
+
+    #[test]
+    fn test_luma_to_alpha() {
+        let alpha = |color: Color| match brush_luma_to_alpha(&color.into()) {
+            Brush::Solid(color) => {
+                assert_eq!((color.r, color.g, color.b), (0, 0, 0));
+                color.a
+            }
+            brush => panic!("expected a solid color, found {brush:?}"),
+        };
+        assert_eq!(alpha(Color::WHITE), 255);
+        assert_eq!(alpha(Color::BLACK), 0);
+        // Green contributes most to the luminance, and blue least.
+        assert_eq!(alpha(Color::rgb8(0, 255, 0)), 182);
+        assert_eq!(alpha(Color::rgb8(0, 0, 255)), 18);
+        // The alpha of the color scales its luminance.
+        assert_eq!(alpha(Color::rgba8(255, 255, 255, 128)), 128);
+    }
+
+    #[test]
+    fn test_trim_stroke() {
+        let square = Rect::new(0.0, 0.0, 10.0, 10.0)
+            .path_elements(0.1)
+            .collect::<Vec<_>>();
+        let stroke = |start, end| StrokeEffect {
+            color: Color::WHITE,
+            width: 1.0,
+            start,
+            end,
+        };
+        let mut path = kurbo::BezPath::new();
+        stroke(0.0, 1.0).trim(&square, &mut path);
+        assert_eq!(path.elements(), square.as_slice());
+        // Half of the perimeter, from the middle of the top edge to the
+        // middle of the bottom edge.
+        let mut path = kurbo::BezPath::new();
+        stroke(0.125, 0.625).trim(&square, &mut path);
+        assert!((path.perimeter(0.1) - 20.0).abs() < 1e-6);
+        let start = path.segments().next().unwrap().start();
+        assert!((start - Point::new(5.0, 0.0)).hypot() < 1e-6);
+    }
+}
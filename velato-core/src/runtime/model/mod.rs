@@ -0,0 +1,890 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::runtime::LayerProperty;
+use kurbo::{self, Affine, PathEl, Point, Shape as _, Size, Vec2};
+use peniko::{self, Color};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+mod color;
+mod modifier;
+mod spline;
+mod value;
+mod wiggle;
+
+pub mod animated;
+pub mod fixed;
+
+pub use color::ColorSpace;
+pub use value::{
+    Animated, Easing, EasingHandle, Loop, LoopMode, Loops, Rate, Slot, Time, Tween, Value, ValueRef,
+};
+pub use wiggle::Wiggle;
+
+pub(crate) use spline::SplineToPath;
+
+macro_rules! simple_value {
+    ($name:ident) => {
+        #[derive(Clone, Debug)]
+        pub enum $name {
+            Fixed(fixed::$name),
+            Animated(Box<animated::$name>),
+        }
+
+        impl $name {
+            pub fn is_fixed(&self) -> bool {
+                matches!(self, Self::Fixed(_))
+            }
+            pub fn evaluate(&self, frame: f64) -> ValueRef<'_, fixed::$name> {
+                match self {
+                    Self::Fixed(value) => ValueRef::Borrowed(value),
+                    Self::Animated(value) => ValueRef::Owned(value.evaluate(frame)),
+                }
+            }
+        }
+    };
+}
+
+simple_value!(Transform);
+simple_value!(Stroke);
+simple_value!(Repeater);
+simple_value!(ColorStops);
+simple_value!(PathModifier);
+
+#[derive(Clone, Debug)]
+pub enum Brush {
+    Fixed(fixed::Brush),
+    Animated(Box<animated::Brush>),
+}
+
+impl Brush {
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, Self::Fixed(_))
+    }
+
+    pub fn evaluate(&self, alpha: f64, frame: f64) -> ValueRef<'_, fixed::Brush> {
+        match self {
+            Self::Fixed(value) => {
+                if alpha == 1.0 {
+                    ValueRef::Borrowed(value)
+                } else {
+                    ValueRef::Owned(fixed::brush_with_alpha(value, alpha))
+                }
+            }
+            Self::Animated(value) => ValueRef::Owned(value.evaluate(alpha, frame)),
+        }
+    }
+
+    /// Evaluates the brush at the specified frame, interpolating gradient
+    /// stops and animated colors in the given color space.
+    pub fn evaluate_in(
+        &self,
+        alpha: f64,
+        frame: f64,
+        space: ColorSpace,
+    ) -> ValueRef<'_, fixed::Brush> {
+        match self {
+            Self::Fixed(value) => match space.resample_brush(value) {
+                Some(brush) => ValueRef::Owned(fixed::brush_with_alpha(&brush, alpha)),
+                None => self.evaluate(alpha, frame),
+            },
+            Self::Animated(value) => ValueRef::Owned(value.evaluate_in(alpha, frame, space)),
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::Fixed(Affine::IDENTITY)
+    }
+}
+
+impl Transform {
+    /// Evaluates the transform at the specified frame and returns its
+    /// components.
+    ///
+    /// For animated 2D transforms without skew, the rotation and scale are
+    /// taken directly from the animated properties, so rotations beyond a
+    /// full turn and negative scales are preserved.
+    pub fn decompose(&self, frame: f64) -> fixed::TransformParts {
+        match self {
+            Self::Fixed(value) => fixed::TransformParts::from_affine(*value),
+            Self::Animated(value) => {
+                let affine = value.evaluate(frame);
+                if value.skew.evaluate(frame) != 0.0 || value.depth.is_some() {
+                    return fixed::TransformParts::from_affine(affine);
+                }
+                fixed::TransformParts {
+                    translation: affine.translation(),
+                    rotation: value.rotation.evaluate(frame),
+                    scale: value.scale.evaluate(frame) / 100.0,
+                    skew: 0.0,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Geometry {
+    Fixed(Vec<PathEl>),
+    Rect(animated::Rect),
+    Ellipse(animated::Ellipse),
+    Spline(animated::Spline),
+    Star(animated::Star),
+}
+
+impl Geometry {
+    pub fn evaluate(&self, frame: f64, path: &mut Vec<PathEl>) {
+        match self {
+            Self::Fixed(value) => {
+                path.extend_from_slice(value);
+            }
+            Self::Rect(value) => {
+                let start = path.len();
+                path.extend(value.evaluate(frame).path_elements(0.1));
+                if value.is_ccw {
+                    reverse_subpaths(path, start);
+                }
+            }
+            Self::Ellipse(value) => {
+                let start = path.len();
+                path.extend(value.evaluate(frame).path_elements(0.1));
+                if value.is_ccw {
+                    reverse_subpaths(path, start);
+                }
+            }
+            Self::Spline(value) => {
+                let start = path.len();
+                value.evaluate(frame, path);
+                if value.is_ccw {
+                    reverse_subpaths(path, start);
+                }
+            }
+            Self::Star(value) => {
+                value.evaluate(frame, path);
+            }
+        }
+    }
+}
+
+/// Reverses the direction of the subpaths of a path from the given element.
+pub(crate) fn reverse_subpaths(path: &mut Vec<PathEl>, start: usize) {
+    let reversed = kurbo::BezPath::from_vec(path.split_off(start)).reverse_subpaths();
+    path.extend_from_slice(reversed.elements());
+}
+
+#[derive(Clone, Debug)]
+pub struct Draw {
+    /// Parameters for a stroked draw operation.
+    pub stroke: Option<Stroke>,
+    /// Brush for the draw operation.
+    pub brush: Brush,
+    /// Opacity of the draw operation.
+    pub opacity: Value<f64>,
+}
+
+/// Elements of a shape layer.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    /// Group of shapes with an optional transform.
+    Group(Vec<Shape>, Option<GroupTransform>),
+    /// Geometry element.
+    Geometry(Geometry),
+    /// Fill or stroke element.
+    Draw(Draw),
+    /// Repeater element.
+    Repeater(Repeater),
+    /// Modifier applied to all preceding geometry in the group.
+    Modifier(PathModifier),
+    /// Hidden element, which is kept for tooling but never rendered.
+    Hidden(Box<Shape>),
+}
+
+/// Transform and opacity for a shape group.
+#[derive(Clone, Debug)]
+pub struct GroupTransform {
+    pub transform: Transform,
+    pub opacity: Value<f64>,
+}
+
+/// Layer in an animation.
+#[derive(Clone, Debug, Default)]
+pub struct Layer {
+    /// Name of the layer.
+    pub name: String,
+    /// Classes of the layer (`cl`), separated by spaces.
+    pub class: String,
+    /// Index of the transform parent layer.
+    pub parent: Option<usize>,
+    /// Transform for the entire layer.
+    pub transform: Transform,
+    /// Opacity for the entire layer.
+    pub opacity: Value<f64>,
+    /// Width of the layer.
+    pub width: f64,
+    /// Height of the layer.
+    pub height: f64,
+    /// Blend mode for the layer.
+    pub blend_mode: Option<peniko::BlendMode>,
+    /// Range of frames in which the layer is active.
+    pub frames: Range<f64>,
+    /// Frame time stretch factor.
+    pub stretch: f64,
+    /// Starting frame for the layer (only applied to instances).
+    pub start_frame: f64,
+    /// List of masks applied to the content.
+    pub masks: Vec<Mask>,
+    /// True if the layer is used as a mask.
+    pub is_mask: bool,
+    /// True if the layer is hidden. Hidden layers are never rendered, and
+    /// layers using them as mattes are drawn unmatted, but they still parent
+    /// other layers and may be the source of displacement maps.
+    pub hidden: bool,
+    /// Matte mode and index of the matte layer.
+    pub mask_layer: Option<(Matte, usize)>,
+    /// Effects applied to the content, in order.
+    pub effects: Vec<Effect>,
+    /// Layer styles applied after the effects, in order.
+    pub styles: Vec<LayerStyle>,
+    /// Content and effects of the layer that are not rendered.
+    pub unsupported: Vec<Unsupported>,
+    /// True if the layer is blurred along its motion.
+    pub motion_blur: bool,
+    /// True if the layer only draws where the layers below it are opaque,
+    /// as with "preserve underlying transparency".
+    pub preserve_transparency: bool,
+    /// True if the layer erases the layers below it within its composition
+    /// before drawing, as with a knockout group.
+    pub knockout: bool,
+    /// Random motion added to the transform and opacity of the layer.
+    pub wiggles: Vec<Wiggle>,
+    /// True if the layer rotates to follow the direction of its motion.
+    pub auto_orient: bool,
+    /// Content of the layer.
+    pub content: Content,
+}
+
+impl Layer {
+    /// Returns true if the layer is visible at the given frame.
+    ///
+    /// Layers are visible from their start frame up to, but not including,
+    /// their end frame. Layers with zero duration are visible on their
+    /// start frame only, which is used by single frame illustrations.
+    pub fn is_active(&self, frame: f64) -> bool {
+        if self.frames.start == self.frames.end {
+            frame == self.frames.start
+        } else {
+            self.frames.contains(&frame)
+        }
+    }
+
+    /// Returns the timeline of an instance layer, or `None` if the layer
+    /// does not instance an asset.
+    ///
+    /// The frame rate is that of the composition containing the layer, and
+    /// is used to convert time remapping from seconds to frames.
+    pub fn timeline(&self, frame_rate: f64) -> Option<Timeline<'_>> {
+        match &self.content {
+            Content::Instance { time_remap, .. } => Some(Timeline {
+                start_frame: self.start_frame,
+                stretch: self.stretch,
+                time_remap: time_remap.as_ref(),
+                frame_rate,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Evaluates the transform of the layer at the given frame, including
+    /// the motion of its wiggles and its orientation along its motion path.
+    ///
+    /// Wiggles are sampled from the noise selected by the seed, and the
+    /// frame rate of the composition containing the layer converts frames
+    /// to the seconds in which their frequency is given.
+    pub fn evaluate_transform(&self, frame: f64, frame_rate: f64, seed: u64) -> Affine {
+        let wiggles = || {
+            self.wiggles
+                .iter()
+                .filter(|wiggle| wiggle.property != LayerProperty::Opacity)
+        };
+        match &self.transform {
+            Transform::Animated(transform) if self.auto_orient || wiggles().next().is_some() => {
+                let time = if frame_rate > 0.0 {
+                    frame / frame_rate
+                } else {
+                    0.0
+                };
+                let orientation = if self.auto_orient {
+                    transform.position.direction(frame).unwrap_or(0.0)
+                } else {
+                    0.0
+                };
+                transform.evaluate_with_offsets(frame, |property| {
+                    let offset = wiggles()
+                        .filter(|wiggle| wiggle.property == property)
+                        .map(|wiggle| {
+                            Vec2::new(
+                                wiggle.evaluate(seed, 0, time),
+                                wiggle.evaluate(seed, 1, time),
+                            )
+                        })
+                        .sum();
+                    if property == LayerProperty::Rotation {
+                        offset + Vec2::new(orientation, 0.0)
+                    } else {
+                        offset
+                    }
+                })
+            }
+            transform => transform.evaluate(frame).into_owned(),
+        }
+    }
+
+    /// Evaluates the opacity of the layer at the given frame, including the
+    /// motion of its wiggles. See [`Layer::evaluate_transform`].
+    pub fn evaluate_opacity(&self, frame: f64, frame_rate: f64, seed: u64) -> f64 {
+        let time = if frame_rate > 0.0 {
+            frame / frame_rate
+        } else {
+            0.0
+        };
+        let opacity = self.opacity.evaluate(frame)
+            + self
+                .wiggles
+                .iter()
+                .filter(|wiggle| wiggle.property == LayerProperty::Opacity)
+                .map(|wiggle| wiggle.evaluate(seed, 0, time))
+                .sum::<f64>();
+        if self.wiggles.is_empty() {
+            opacity
+        } else {
+            opacity.clamp(0.0, 100.0)
+        }
+    }
+
+    /// Returns the effective opacity, in `0..=1`, of each draw of the layer
+    /// at the given frame, in the order in which the draws appear in its
+    /// shapes, for debugging.
+    ///
+    /// The opacity of a draw is the product of the opacity of the layer, the
+    /// opacity of every group enclosing the draw and the opacity of the draw
+    /// itself, each applied exactly once. Repeaters, mattes and the opacity
+    /// of layers instancing the composition of this layer scale the result
+    /// further and are not included.
+    pub fn draw_opacities(&self, frame: f64, frame_rate: f64, seed: u64) -> Vec<f64> {
+        fn push_draws(shapes: &[Shape], alpha: f64, frame: f64, opacities: &mut Vec<f64>) {
+            for shape in shapes {
+                match shape {
+                    Shape::Group(shapes, group_transform) => {
+                        let group_alpha = group_transform
+                            .as_ref()
+                            .map_or(1.0, |group| group.opacity.evaluate(frame) / 100.0);
+                        push_draws(shapes, alpha * group_alpha, frame, opacities);
+                    }
+                    Shape::Draw(draw) => {
+                        opacities.push(alpha * draw.opacity.evaluate(frame) / 100.0);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let alpha = self.evaluate_opacity(frame, frame_rate, seed) / 100.0;
+        let mut opacities = vec![];
+        match &self.content {
+            Content::Shape(shapes) => push_draws(shapes, alpha, frame, &mut opacities),
+            Content::Solid { .. } => opacities.push(alpha),
+            _ => {}
+        }
+        opacities
+    }
+}
+
+/// Mapping from frames of a composition to frames of an asset instanced by
+/// one of its layers.
+#[derive(Copy, Clone, Debug)]
+pub struct Timeline<'a> {
+    /// Frame of the composition at which the asset starts.
+    pub start_frame: f64,
+    /// Frame time stretch factor.
+    pub stretch: f64,
+    /// Time in the asset, in seconds, for each frame of the composition.
+    /// Overrides the start frame and stretch.
+    pub time_remap: Option<&'a Value<f64>>,
+    /// Frames per second.
+    pub frame_rate: f64,
+}
+
+impl Timeline<'_> {
+    /// Returns the frame of the asset displayed at the given frame of the
+    /// composition.
+    ///
+    /// Each frame of the composition covers the asset from its local frame
+    /// for `1 / stretch` frames. Negative stretches play the asset in
+    /// reverse, so a frame covers the asset backwards from its local frame,
+    /// and shows the start of that interval as forward playback does. The
+    /// first frame of a reversed layer therefore shows the last frame of the
+    /// asset rather than its end.
+    pub fn local_frame(&self, frame: f64) -> f64 {
+        match self.time_remap {
+            Some(time_remap) => time_remap.evaluate(frame) * self.frame_rate,
+            None if self.stretch < 0.0 => (frame + 1.0 - self.start_frame) / self.stretch,
+            None => (frame - self.start_frame) / self.stretch,
+        }
+    }
+}
+
+/// Shutter of the virtual camera, which determines the interval over which
+/// layers with motion blur are blurred.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Shutter {
+    /// Fraction of a frame for which the shutter is open, in degrees where
+    /// 360 is a full frame.
+    pub angle: f64,
+    /// Offset of the opening of the shutter from the frame, in degrees.
+    pub phase: f64,
+}
+
+impl Shutter {
+    /// Returns the frames within the shutter interval of the given frame at
+    /// which to sample a blurred layer, spread evenly over the interval.
+    pub fn sample_frames(&self, frame: f64, samples: usize) -> impl Iterator<Item = f64> {
+        let Self { angle, phase } = *self;
+        (0..samples).map(move |i| {
+            let t = (i as f64 + 0.5) / samples as f64;
+            frame + (phase + angle * t) / 360.0
+        })
+    }
+}
+
+impl Default for Shutter {
+    fn default() -> Self {
+        // After Effects defaults to a half frame centered on the frame.
+        Self {
+            angle: 180.0,
+            phase: -90.0,
+        }
+    }
+}
+
+/// Named portion of the timeline of a composition, such as a state of an
+/// interactive animation.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Marker {
+    /// Name of the marker, from its comment.
+    pub name: String,
+    /// Frame at which the marker starts.
+    pub frame: f64,
+    /// Number of frames covered by the marker, which is zero for markers of
+    /// a single frame.
+    pub duration: f64,
+}
+
+impl Marker {
+    /// Returns the frames covered by the marker.
+    pub fn frames(&self) -> Range<f64> {
+        self.frame..self.frame + self.duration
+    }
+}
+
+/// Sound played by an audio layer of a composition.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AudioTrack {
+    /// Name of the audio layer.
+    pub name: String,
+    /// Id of the sound asset.
+    pub asset: String,
+    /// Location of the sound, as a path relative to the file or a data
+    /// URL, if the asset exists.
+    pub file: Option<String>,
+    /// Frames in which the sound plays.
+    pub frames: Range<f64>,
+    /// Frame at which the sound starts, which may be before the first frame
+    /// in which it plays.
+    pub start_frame: f64,
+    /// Stretch of the playback speed of the sound.
+    pub stretch: f64,
+}
+
+impl AudioTrack {
+    /// Returns true if the sound plays at the given frame.
+    pub fn is_active(&self, frame: f64) -> bool {
+        self.frames.contains(&frame)
+    }
+
+    /// Returns the time within the sound, in seconds, played at the given
+    /// frame.
+    pub fn offset(&self, frame: f64, frame_rate: f64) -> f64 {
+        if frame_rate > 0.0 {
+            (frame - self.start_frame) / self.stretch / frame_rate
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Matte layer mode.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Matte {
+    /// The matte layer has no effect.
+    #[default]
+    Normal,
+    /// Content is visible where the matte layer is opaque.
+    Alpha,
+    /// Content is visible where the matte layer is transparent.
+    InvertAlpha,
+    /// Content is visible where the matte layer is bright.
+    Luma,
+    /// Content is visible where the matte layer is dark.
+    InvertLuma,
+}
+
+/// Mode for combining a mask with the preceding masks of a layer.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum MaskMode {
+    /// The mask has no effect.
+    None,
+    /// Adds the mask to the covered region.
+    #[default]
+    Add,
+    /// Removes the mask from the covered region.
+    Subtract,
+    /// Restricts the covered region to the mask.
+    Intersect,
+    /// Combines with the covered region using the maximum coverage.
+    Lighten,
+    /// Combines with the covered region using the minimum coverage.
+    Darken,
+    /// Covers the regions covered by either the mask or the preceding
+    /// masks, but not both.
+    Difference,
+}
+
+/// Mask for a layer.
+#[derive(Clone, Debug)]
+pub struct Mask {
+    /// Mode for combining the mask with preceding masks.
+    pub mode: MaskMode,
+    /// True if the mask covers the region outside of its geometry.
+    pub inverted: bool,
+    /// Distance to grow the mask by. Negative values shrink the mask.
+    pub expansion: Value<f64>,
+    /// Width of the soft edge of the mask, centered on its outline.
+    pub feather: Value<f64>,
+    /// Geometry that defines the shape of the mask.
+    pub geometry: Geometry,
+    /// Opacity of the mask.
+    pub opacity: Value<f64>,
+}
+
+/// Effect applied to a layer.
+#[derive(Clone, Debug)]
+pub enum Effect {
+    /// Shadow cast by the content of the layer.
+    DropShadow(animated::DropShadow),
+    /// Replaces the colors of the content with a single color.
+    Fill(animated::FillEffect),
+    /// Adjusts the levels of each color channel of the content.
+    Levels(Box<animated::Levels>),
+    /// Outlines the masks of the layer.
+    Stroke(animated::StrokeEffect),
+    /// Transition that hides the content along a direction.
+    LinearWipe(animated::LinearWipe),
+    /// Transition that hides the content around a center point.
+    RadialWipe(animated::RadialWipe),
+    /// Moves the content by amounts sampled from the colors of another
+    /// layer.
+    DisplacementMap(animated::DisplacementMap),
+}
+
+/// Style applied to a layer after its effects.
+#[derive(Clone, Debug)]
+pub enum LayerStyle {
+    /// Outline drawn around the content.
+    Stroke(animated::StrokeStyle),
+    /// Shadow cast by the content beneath it.
+    DropShadow(animated::DropShadow),
+    /// Shadow cast onto the content, as if it were cut out of the layers
+    /// beneath.
+    InnerShadow(animated::DropShadow),
+    /// Glow beneath the content, spreading out from its edges.
+    OuterGlow(animated::OuterGlow),
+}
+
+/// Content or effect of a layer that cannot be rendered.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Unsupported {
+    /// Text content.
+    Text,
+    /// Image content, with the name of the image asset.
+    Image(String),
+    /// Effect or layer style, with its name.
+    Effect(String),
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Image(name) => write!(f, "image {name}"),
+            Self::Effect(name) => write!(f, "effect {name}"),
+        }
+    }
+}
+
+/// Direction in which a radial wipe hides the content.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum WipeDirection {
+    /// Hides the content clockwise from the start angle.
+    #[default]
+    Clockwise,
+    /// Hides the content counterclockwise from the start angle.
+    Counterclockwise,
+    /// Hides the content in both directions from the start angle.
+    Both,
+}
+
+/// Stacking order of the copies of a repeater.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum RepeaterComposite {
+    /// Draws each copy above the previous one, so the original is at the
+    /// bottom.
+    #[default]
+    Above,
+    /// Draws each copy below the previous one, so the original is on top.
+    Below,
+}
+
+/// How a stroke effect combines with the content of its layer.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum StrokePaintStyle {
+    /// Draws the stroke over the content.
+    #[default]
+    OnOriginal,
+    /// Draws the stroke in place of the content.
+    Transparent,
+    /// Shows the content only where it is covered by the stroke.
+    Reveal,
+}
+
+/// Style of the corners of a stroke.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum StrokeJoin {
+    /// Sharp corners, up to the miter limit.
+    #[default]
+    Miter,
+    /// Rounded corners.
+    Round,
+    /// Corners cut off at the outer edges of the segments.
+    Bevel,
+}
+
+impl From<StrokeJoin> for kurbo::Join {
+    fn from(value: StrokeJoin) -> Self {
+        match value {
+            StrokeJoin::Miter => Self::Miter,
+            StrokeJoin::Round => Self::Round,
+            StrokeJoin::Bevel => Self::Bevel,
+        }
+    }
+}
+
+/// Style of the ends of a stroke.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum StrokeCap {
+    /// Ends flush with the end points.
+    #[default]
+    Butt,
+    /// Rounded ends.
+    Round,
+    /// Ends extended by half the width of the stroke.
+    Square,
+}
+
+impl From<StrokeCap> for kurbo::Cap {
+    fn from(value: StrokeCap) -> Self {
+        match value {
+            StrokeCap::Butt => Self::Butt,
+            StrokeCap::Round => Self::Round,
+            StrokeCap::Square => Self::Square,
+        }
+    }
+}
+
+/// Property of the colors of a displacement map that determines the
+/// displacement along an axis.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DisplacementChannel {
+    /// The red channel.
+    Red,
+    /// The green channel.
+    Green,
+    /// The blue channel.
+    Blue,
+    /// The alpha channel.
+    Alpha,
+    /// The luminance of the color.
+    Luminance,
+    /// The hue of the color.
+    Hue,
+    /// The lightness of the color.
+    Lightness,
+    /// The saturation of the color.
+    Saturation,
+    /// Maximum displacement everywhere.
+    Full,
+    /// No displacement.
+    Half,
+    /// Maximum displacement in the opposite direction everywhere.
+    Off,
+}
+
+impl DisplacementChannel {
+    /// Returns the value of the channel for the given color, from 0 to 1.
+    pub fn value(self, color: Color) -> f64 {
+        let [r, g, b, a] = [color.r, color.g, color.b, color.a].map(|x| x as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        match self {
+            Self::Red => r,
+            Self::Green => g,
+            Self::Blue => b,
+            Self::Alpha => a,
+            // Rec. 709 luma coefficients, as used for luma mattes.
+            Self::Luminance => 0.2126 * r + 0.7152 * g + 0.0722 * b,
+            Self::Hue => {
+                let chroma = max - min;
+                let hue = if chroma == 0.0 {
+                    0.0
+                } else if max == r {
+                    ((g - b) / chroma).rem_euclid(6.0)
+                } else if max == g {
+                    (b - r) / chroma + 2.0
+                } else {
+                    (r - g) / chroma + 4.0
+                };
+                hue / 6.0
+            }
+            Self::Lightness => lightness,
+            Self::Saturation => {
+                if max == min {
+                    0.0
+                } else {
+                    (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+                }
+            }
+            Self::Full => 1.0,
+            Self::Half => 0.5,
+            Self::Off => 0.0,
+        }
+    }
+}
+
+/// Content of a layer.
+#[derive(Clone, Default, Debug)]
+pub enum Content {
+    /// Empty layer.
+    #[default]
+    None,
+    /// Asset instance with the specified name and time remapping.
+    Instance {
+        name: String,
+        /// Id of the asset in the file, which differs from `name` for
+        /// instances given their own copy of the asset.
+        source: String,
+        time_remap: Option<Value<f64>>,
+        /// Values of the slots within the asset that the instance
+        /// overrides, such as the essential properties of a composition,
+        /// as the components of each value by slot id.
+        ///
+        /// The values apply to the asset and the assets it instances, and
+        /// are interpreted according to the kind of each property as for
+        /// the slots of the file. Overrides of instances closer to the top
+        /// level take precedence. At import, instances with overrides are
+        /// given their own copy of the asset with the overrides applied.
+        overrides: BTreeMap<String, Vec<f64>>,
+    },
+    /// Collection of shapes.
+    Shape(Vec<Shape>),
+    /// Rectangle filled with a color, with its origin at the top left
+    /// corner of the layer.
+    Solid {
+        color: Color,
+        width: f64,
+        height: f64,
+    },
+    /// Adjustment layer, which applies its fill and levels effects to the
+    /// layers below it in the same composition, at its opacity. Other
+    /// effects, and the masks and mattes of the layer, do not affect the
+    /// layers below.
+    Adjustment,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Content, DisplacementChannel, Layer, Value};
+
+    #[test]
+    fn test_timeline() {
+        let mut layer = Layer {
+            start_frame: 10.0,
+            stretch: 2.0,
+            content: Content::Instance {
+                name: "asset".into(),
+                source: "asset".into(),
+                time_remap: None,
+                overrides: Default::default(),
+            },
+            ..Default::default()
+        };
+        let timeline = layer.timeline(30.0).unwrap();
+        assert_eq!(timeline.local_frame(10.0), 0.0);
+        assert_eq!(timeline.local_frame(30.0), 10.0);
+        layer.content = Content::Instance {
+            name: "asset".into(),
+            source: "asset".into(),
+            time_remap: Some(Value::Fixed(0.5)),
+            overrides: Default::default(),
+        };
+        // Remapping holds the asset at half a second.
+        let timeline = layer.timeline(30.0).unwrap();
+        assert_eq!(timeline.local_frame(30.0), 15.0);
+        layer.content = Content::Instance {
+            name: "asset".into(),
+            source: "asset".into(),
+            time_remap: None,
+            overrides: Default::default(),
+        };
+        // Reversed at double speed, the layer starts with the last frame of
+        // an asset of 60 frames and ends with the first.
+        layer.start_frame = 30.0;
+        layer.stretch = -0.5;
+        let timeline = layer.timeline(30.0).unwrap();
+        assert_eq!(timeline.local_frame(0.0), 58.0);
+        assert_eq!(timeline.local_frame(29.0), 0.0);
+        assert_eq!(timeline.local_frame(14.5), 29.0);
+        layer.content = Content::None;
+        assert!(layer.timeline(30.0).is_none());
+    }
+
+    #[test]
+    fn test_displacement_channels() {
+        use DisplacementChannel::*;
+        let color = peniko::Color::rgba8(0, 255, 0, 51);
+        let value = |channel: DisplacementChannel| channel.value(color);
+        assert_eq!(value(Red), 0.0);
+        assert_eq!(value(Green), 1.0);
+        assert_eq!(value(Alpha), 0.2);
+        assert_eq!(value(Luminance), 0.7152);
+        assert_eq!(value(Hue), 1.0 / 3.0);
+        assert_eq!(value(Lightness), 0.5);
+        assert_eq!(value(Saturation), 1.0);
+        assert_eq!(value(Half), 0.5);
+        let gray = peniko::Color::rgb8(128, 128, 128);
+        assert_eq!(Hue.value(gray), 0.0);
+        assert_eq!(Saturation.value(gray), 0.0);
+    }
+}
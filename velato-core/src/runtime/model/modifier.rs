@@ -0,0 +1,794 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Implementations of path modifiers.
+
+use super::fixed;
+use kurbo::{
+    self, offset::CubicOffset, Affine, Arc, Join, Line, ParamCurve, ParamCurveArclen,
+    ParamCurveArea, ParamCurveDeriv, PathEl, PathSeg, Point, QuadBez, Shape, Vec2,
+};
+use std::f64::consts::PI;
+
+/// Accuracy used for arc length computations.
+const ARCLEN_ACCURACY: f64 = 1e-3;
+
+/// Distance of the control points of a rounded corner from the tangent
+/// points, as a fraction of the radius. Matches the After Effects constant.
+const ROUND_CORNER_HANDLE: f64 = 0.5519;
+
+/// Tolerance used when fitting offset curves.
+const OFFSET_TOLERANCE: f64 = 0.1;
+
+/// Subpath of a path along with its closed state.
+struct Subpath<'a> {
+    elements: &'a [PathEl],
+    is_closed: bool,
+}
+
+impl Subpath<'_> {
+    fn segments(&self) -> impl Iterator<Item = PathSeg> + '_ {
+        kurbo::segments(self.elements.iter().copied())
+    }
+}
+
+/// Splits a path into subpaths at each move.
+fn subpaths(path: &[PathEl]) -> impl Iterator<Item = Subpath<'_>> {
+    let mut rest = path;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .iter()
+            .skip(1)
+            .position(|el| matches!(el, PathEl::MoveTo(_)))
+            .map(|ix| ix + 1)
+            .unwrap_or(rest.len());
+        let (elements, tail) = rest.split_at(end);
+        rest = tail;
+        let is_closed = matches!(elements.last(), Some(PathEl::ClosePath));
+        Some(Subpath {
+            elements,
+            is_closed,
+        })
+    })
+}
+
+/// Returns the unit tangent of a segment at the given parameter.
+fn tangent(seg: PathSeg, t: f64) -> Vec2 {
+    let deriv = match seg {
+        PathSeg::Line(line) => line.deriv().eval(t).to_vec2(),
+        PathSeg::Quad(quad) => quad.deriv().eval(t).to_vec2(),
+        PathSeg::Cubic(cubic) => cubic.deriv().eval(t).to_vec2(),
+    };
+    if deriv.hypot2() > 0.0 {
+        deriv.normalize()
+    } else {
+        // Degenerate handles; fall back to the chord direction.
+        let chord = seg.end() - seg.start();
+        if chord.hypot2() > 0.0 {
+            chord.normalize()
+        } else {
+            Vec2::ZERO
+        }
+    }
+}
+
+/// Point on an evaluated path along with its direction of travel.
+#[derive(Copy, Clone)]
+struct PathPoint {
+    point: Point,
+    tangent: Vec2,
+    /// Length of the path between this point and the next.
+    length: f64,
+}
+
+pub(crate) fn zig_zag(zig_zag: &fixed::ZigZag, path: &[PathEl], out: &mut Vec<PathEl>) {
+    let ridges = zig_zag.ridges.round().max(0.0) as usize;
+    if zig_zag.size == 0.0 {
+        out.extend_from_slice(path);
+        return;
+    }
+    let divisions = ridges + 1;
+    let mut points: Vec<PathPoint> = vec![];
+    for subpath in subpaths(path) {
+        points.clear();
+        let mut last_seg = None;
+        for seg in subpath.segments() {
+            let length = seg.arclen(ARCLEN_ACCURACY);
+            if length == 0.0 {
+                continue;
+            }
+            let step = length / divisions as f64;
+            for i in 0..divisions {
+                let t = if i == 0 {
+                    0.0
+                } else {
+                    seg.inv_arclen(step * i as f64, ARCLEN_ACCURACY)
+                };
+                let mut dir = tangent(seg, t);
+                if i == 0 {
+                    // Vertices point along the average of both adjacent
+                    // segments.
+                    if let Some(prev) = last_seg {
+                        let avg = tangent(prev, 1.0) + dir;
+                        if avg.hypot2() > 0.0 {
+                            dir = avg.normalize();
+                        }
+                    }
+                }
+                points.push(PathPoint {
+                    point: seg.eval(t),
+                    tangent: dir,
+                    length: step,
+                });
+            }
+            last_seg = Some(seg);
+        }
+        let Some(last_seg) = last_seg else {
+            continue;
+        };
+        if subpath.is_closed {
+            if let (Some(first), Some(first_seg)) = (points.first_mut(), subpath.segments().next())
+            {
+                let avg = tangent(last_seg, 1.0) + tangent(first_seg, 0.0);
+                if avg.hypot2() > 0.0 {
+                    first.tangent = avg.normalize();
+                }
+            }
+        } else {
+            points.push(PathPoint {
+                point: last_seg.end(),
+                tangent: tangent(last_seg, 1.0),
+                length: 0.0,
+            });
+        }
+        // Displace alternating points to either side of the path.
+        let displaced = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let normal = Vec2::new(-p.tangent.y, p.tangent.x);
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                p.point + normal * (zig_zag.size * sign)
+            })
+            .collect::<Vec<_>>();
+        let Some(&start) = displaced.first() else {
+            continue;
+        };
+        out.push(PathEl::MoveTo(start));
+        let count = displaced.len();
+        let edges = if subpath.is_closed { count } else { count - 1 };
+        for i in 0..edges {
+            let j = (i + 1) % count;
+            let to = displaced[j];
+            if zig_zag.is_smooth {
+                let handle = points[i].length * 0.5;
+                out.push(PathEl::CurveTo(
+                    displaced[i] + points[i].tangent * handle,
+                    to - points[j].tangent * handle,
+                    to,
+                ));
+            } else {
+                out.push(PathEl::LineTo(to));
+            }
+        }
+        if subpath.is_closed {
+            out.push(PathEl::ClosePath);
+        }
+    }
+}
+
+pub(crate) fn rounded_corners(
+    rounded_corners: &fixed::RoundedCorners,
+    path: &[PathEl],
+    out: &mut Vec<PathEl>,
+) {
+    if rounded_corners.radius <= 0.0 {
+        out.extend_from_slice(path);
+        return;
+    }
+    let mut segments: Vec<PathSeg> = vec![];
+    let mut corners: Vec<Option<[Point; 4]>> = vec![];
+    for subpath in subpaths(path) {
+        // Drop zero length segments so that degenerate arcs (such as the
+        // corners of a rectangle with no radius) don't hide sharp vertices.
+        segments.clear();
+        segments.extend(subpath.segments().filter(|seg| !is_degenerate(*seg)));
+        let count = segments.len();
+        if count == 0 {
+            out.extend_from_slice(subpath.elements);
+            continue;
+        }
+        // Corner at index `i` sits at the start of segment `i`.
+        corners.clear();
+        corners.extend((0..count).map(|i| {
+            if i == 0 && !subpath.is_closed {
+                return None;
+            }
+            let prev = segments[(i + count - 1) % count];
+            let next = segments[i];
+            round_corner(prev, next, rounded_corners.radius)
+        }));
+        let start = match corners[0] {
+            Some([.., to]) => to,
+            None => segments[0].start(),
+        };
+        out.push(PathEl::MoveTo(start));
+        for (i, seg) in segments.iter().enumerate() {
+            let corner = if i + 1 < count || subpath.is_closed {
+                corners[(i + 1) % count]
+            } else {
+                None
+            };
+            match (corner, seg) {
+                (Some([from, c1, c2, to]), _) => {
+                    out.push(PathEl::LineTo(from));
+                    out.push(PathEl::CurveTo(c1, c2, to));
+                }
+                (None, seg) => push_segment(*seg, out),
+            }
+        }
+        if subpath.is_closed {
+            out.push(PathEl::ClosePath);
+        }
+    }
+}
+
+/// Returns true if all points of the segment are coincident.
+fn is_degenerate(seg: PathSeg) -> bool {
+    match seg {
+        PathSeg::Line(line) => line.p0 == line.p1,
+        PathSeg::Quad(quad) => quad.p0 == quad.p1 && quad.p1 == quad.p2,
+        PathSeg::Cubic(cubic) => {
+            cubic.p0 == cubic.p1 && cubic.p1 == cubic.p2 && cubic.p2 == cubic.p3
+        }
+    }
+}
+
+/// Computes the rounding of a sharp corner between two line segments,
+/// returning the start point, control points and end point of the
+/// replacement curve.
+fn round_corner(prev: PathSeg, next: PathSeg, radius: f64) -> Option<[Point; 4]> {
+    let (PathSeg::Line(prev), PathSeg::Line(next)) = (prev, next) else {
+        return None;
+    };
+    let vertex = next.p0;
+    let into = prev.p1 - prev.p0;
+    let out_of = next.p1 - next.p0;
+    // Collinear segments have no corner to round.
+    if into.cross(out_of).abs() <= 1e-9 * into.hypot() * out_of.hypot() {
+        return None;
+    }
+    // Don't round past the midpoint of either segment so that adjacent
+    // corners never overlap.
+    let distance = radius.min(into.hypot() * 0.5).min(out_of.hypot() * 0.5);
+    let from = vertex - into.normalize() * distance;
+    let to = vertex + out_of.normalize() * distance;
+    Some([
+        from,
+        from.lerp(vertex, ROUND_CORNER_HANDLE),
+        to.lerp(vertex, ROUND_CORNER_HANDLE),
+        to,
+    ])
+}
+
+pub(crate) fn offset_path(offset_path: &fixed::OffsetPath, path: &[PathEl], out: &mut Vec<PathEl>) {
+    if offset_path.amount == 0.0 {
+        out.extend_from_slice(path);
+        return;
+    }
+    // Curves can't be fitted more accurately than the precision of their
+    // coordinates, so the tolerance grows with the magnitude of the path.
+    let bounds = path.bounding_box();
+    let magnitude = [
+        bounds.x0,
+        bounds.y0,
+        bounds.x1,
+        bounds.y1,
+        offset_path.amount,
+    ]
+    .iter()
+    .fold(0.0, |max: f64, x| max.max(x.abs()));
+    if !magnitude.is_finite() {
+        out.extend_from_slice(path);
+        return;
+    }
+    let tolerance = OFFSET_TOLERANCE.max(magnitude * 1e-9);
+    let mut segments: Vec<PathSeg> = vec![];
+    let mut pieces: Vec<Vec<PathSeg>> = vec![];
+    let mut joins: Vec<Vec<PathEl>> = vec![];
+    for subpath in subpaths(path) {
+        segments.clear();
+        segments.extend(subpath.segments().filter(|seg| !is_degenerate(*seg)));
+        let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+            out.extend_from_slice(subpath.elements);
+            continue;
+        };
+        // Offsets are applied along the left normal so flip the direction
+        // for counter-clockwise subpaths to make positive amounts expand.
+        let area = segments.iter().map(|seg| seg.signed_area()).sum::<f64>()
+            + Line::new(last.end(), first.start()).signed_area();
+        let distance = if area > 0.0 {
+            -offset_path.amount
+        } else {
+            offset_path.amount
+        };
+        pieces.clear();
+        pieces.extend(
+            segments
+                .iter()
+                .map(|seg| offset_segment(*seg, distance, tolerance)),
+        );
+        let count = segments.len();
+        let join_count = if subpath.is_closed && count > 1 {
+            count
+        } else {
+            count - 1
+        };
+        joins.clear();
+        for i in 0..join_count {
+            let j = (i + 1) % count;
+            let (Some(mut prev), Some(mut next)) =
+                (pieces[i].last().copied(), pieces[j].first().copied())
+            else {
+                joins.push(vec![]);
+                continue;
+            };
+            let mut join = vec![];
+            offset_join(
+                offset_path,
+                tolerance,
+                segments[j].start(),
+                distance,
+                &mut prev,
+                &mut next,
+                &mut join,
+            );
+            *pieces[i].last_mut().unwrap() = prev;
+            *pieces[j].first_mut().unwrap() = next;
+            joins.push(join);
+        }
+        out.push(PathEl::MoveTo(pieces[0][0].start()));
+        for (i, piece) in pieces.iter().enumerate() {
+            for seg in piece {
+                push_segment(*seg, out);
+            }
+            if let Some(join) = joins.get(i) {
+                out.extend_from_slice(join);
+            }
+        }
+        if subpath.is_closed {
+            out.push(PathEl::ClosePath);
+        }
+    }
+}
+
+pub(crate) fn pucker_bloat(
+    pucker_bloat: &fixed::PuckerBloat,
+    path: &[PathEl],
+    out: &mut Vec<PathEl>,
+) {
+    let percent = pucker_bloat.amount / 100.0;
+    if percent == 0.0 {
+        out.extend_from_slice(path);
+        return;
+    }
+    for subpath in subpaths(path) {
+        let Some(center) = centroid(&subpath) else {
+            out.extend_from_slice(subpath.elements);
+            continue;
+        };
+        // Vertices move towards the center while control points move away
+        // from it.
+        let vertex = |p: Point| p.lerp(center, percent);
+        let handle = |p: Point| p.lerp(center, -percent);
+        let mut start = Point::ZERO;
+        let mut last = Point::ZERO;
+        for el in subpath.elements {
+            match *el {
+                PathEl::MoveTo(p) => {
+                    out.push(PathEl::MoveTo(vertex(p)));
+                    start = p;
+                    last = p;
+                }
+                PathEl::LineTo(p) => {
+                    out.push(PathEl::CurveTo(handle(last), handle(p), vertex(p)));
+                    last = p;
+                }
+                PathEl::QuadTo(c, p) => {
+                    let cubic = QuadBez::new(last, c, p).raise();
+                    out.push(PathEl::CurveTo(
+                        handle(cubic.p1),
+                        handle(cubic.p2),
+                        vertex(p),
+                    ));
+                    last = p;
+                }
+                PathEl::CurveTo(c1, c2, p) => {
+                    out.push(PathEl::CurveTo(handle(c1), handle(c2), vertex(p)));
+                    last = p;
+                }
+                PathEl::ClosePath => {
+                    if last != start {
+                        out.push(PathEl::CurveTo(handle(last), handle(start), vertex(start)));
+                    }
+                    out.push(PathEl::ClosePath);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the average of the vertices of a subpath.
+fn centroid(subpath: &Subpath) -> Option<Point> {
+    let mut vertices = subpath
+        .elements
+        .iter()
+        .filter_map(|el| el.end_point())
+        .collect::<Vec<_>>();
+    // The last vertex of a closed subpath often repeats the first.
+    if subpath.is_closed && vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+    if vertices.is_empty() {
+        return None;
+    }
+    let sum = vertices.iter().fold(Vec2::ZERO, |sum, p| sum + p.to_vec2());
+    Some((sum / vertices.len() as f64).to_point())
+}
+
+pub(crate) fn twist(twist: &fixed::Twist, path: &[PathEl], out: &mut Vec<PathEl>) {
+    let bounds = path.bounding_box();
+    let center = bounds.center() + twist.center;
+    // Rotation is proportional to the distance from the center, reaching
+    // the full angle at the farthest corner of the bounds.
+    let max_distance = [
+        bounds.origin(),
+        Point::new(bounds.x1, bounds.y0),
+        Point::new(bounds.x0, bounds.y1),
+        Point::new(bounds.x1, bounds.y1),
+    ]
+    .iter()
+    .map(|corner| corner.distance(center))
+    .fold(0.0, f64::max);
+    if twist.angle == 0.0 || max_distance == 0.0 {
+        out.extend_from_slice(path);
+        return;
+    }
+    let angle = twist.angle.to_radians();
+    let rotate = |p: Point| {
+        let offset = p - center;
+        let theta = angle * offset.hypot() / max_distance;
+        center + Vec2::from_angle(offset.atan2() + theta) * offset.hypot()
+    };
+    let mut last = Point::ZERO;
+    let mut start = Point::ZERO;
+    for el in path {
+        match *el {
+            PathEl::MoveTo(p) => {
+                out.push(PathEl::MoveTo(rotate(p)));
+                start = p;
+                last = p;
+            }
+            // Lines are converted to curves so that they can bend.
+            PathEl::LineTo(p) => {
+                out.push(PathEl::CurveTo(
+                    rotate(last.lerp(p, 1.0 / 3.0)),
+                    rotate(last.lerp(p, 2.0 / 3.0)),
+                    rotate(p),
+                ));
+                last = p;
+            }
+            PathEl::QuadTo(c, p) => {
+                out.push(PathEl::QuadTo(rotate(c), rotate(p)));
+                last = p;
+            }
+            PathEl::CurveTo(c1, c2, p) => {
+                out.push(PathEl::CurveTo(rotate(c1), rotate(c2), rotate(p)));
+                last = p;
+            }
+            PathEl::ClosePath => {
+                if last != start {
+                    out.push(PathEl::CurveTo(
+                        rotate(last.lerp(start, 1.0 / 3.0)),
+                        rotate(last.lerp(start, 2.0 / 3.0)),
+                        rotate(start),
+                    ));
+                }
+                out.push(PathEl::ClosePath);
+                last = start;
+            }
+        }
+    }
+}
+
+pub(crate) fn trim(trim: &fixed::Trim, path: &[PathEl], out: &mut Vec<PathEl>) {
+    let ranges = trim.ranges();
+    if trim.individually {
+        for range in ranges {
+            fixed::trim_path(path, range, out);
+        }
+        return;
+    }
+    for subpath in subpaths(path) {
+        for range in ranges.clone() {
+            fixed::trim_path(subpath.elements, range, out);
+        }
+    }
+}
+
+/// Offsets a segment by the given distance along its left normal.
+fn offset_segment(seg: PathSeg, distance: f64, tolerance: f64) -> Vec<PathSeg> {
+    let cubic = match seg {
+        PathSeg::Line(line) => {
+            let dir = tangent(seg, 0.0);
+            let offset = Vec2::new(-dir.y, dir.x) * distance;
+            return vec![PathSeg::Line(Line::new(line.p0 + offset, line.p1 + offset))];
+        }
+        PathSeg::Quad(quad) => quad.raise(),
+        PathSeg::Cubic(cubic) => cubic,
+    };
+    // Fitting squares coordinates, which overflows for huge curves, so
+    // curves are fitted with coordinates of at most one.
+    let scale = [cubic.p0, cubic.p1, cubic.p2, cubic.p3]
+        .iter()
+        .fold(distance.abs(), |max, p| max.max(p.x.abs()).max(p.y.abs()))
+        .max(1.0);
+    let unit = Affine::scale(scale.recip()) * cubic;
+    let offset = CubicOffset::new_regularized(unit, distance / scale, tolerance / scale);
+    kurbo::fit_to_bezpath(&offset, tolerance / scale)
+        .segments()
+        .map(|seg| Affine::scale(scale) * seg)
+        .collect()
+}
+
+/// Connects two adjacent offset segments that meet at `vertex` of the
+/// original path, either by trimming them where they overlap or by
+/// bridging the gap between them with the requested join.
+fn offset_join(
+    offset_path: &fixed::OffsetPath,
+    tolerance: f64,
+    vertex: Point,
+    distance: f64,
+    prev: &mut PathSeg,
+    next: &mut PathSeg,
+    out: &mut Vec<PathEl>,
+) {
+    let from = prev.end();
+    let to = next.start();
+    if (to - from).hypot() <= tolerance * 1e-3 {
+        return;
+    }
+    let from_dir = tangent(*prev, 1.0);
+    let to_dir = tangent(*next, 0.0);
+    // Turning towards the offset side causes the segments to overlap.
+    if from_dir.cross(to_dir) * distance > 0.0 {
+        if let Some((prev_t, next_t)) = overlap(*prev, *next) {
+            *prev = prev.subsegment(0.0..prev_t);
+            *next = next.subsegment(next_t..1.0);
+        } else {
+            out.push(PathEl::LineTo(to));
+        }
+        return;
+    }
+    match offset_path.join {
+        Join::Round => {
+            let start_angle = (from - vertex).atan2();
+            let mut sweep_angle = (to - vertex).atan2() - start_angle;
+            if sweep_angle > PI {
+                sweep_angle -= 2.0 * PI;
+            } else if sweep_angle < -PI {
+                sweep_angle += 2.0 * PI;
+            }
+            let arc = Arc {
+                center: vertex,
+                radii: Vec2::new(distance.abs(), distance.abs()),
+                start_angle,
+                sweep_angle,
+                x_rotation: 0.0,
+            };
+            out.extend(arc.append_iter(tolerance));
+        }
+        Join::Miter => {
+            let miter = Line::new(from, from + from_dir)
+                .crossing_point(Line::new(to, to + to_dir))
+                .filter(|p| (*p - vertex).hypot() <= offset_path.miter_limit * distance.abs());
+            if let Some(miter) = miter {
+                out.push(PathEl::LineTo(miter));
+            }
+            out.push(PathEl::LineTo(to));
+        }
+        Join::Bevel => out.push(PathEl::LineTo(to)),
+    }
+}
+
+/// Finds the parameters where two overlapping segments intersect. Only
+/// supported when at least one of them is a line.
+fn overlap(prev: PathSeg, next: PathSeg) -> Option<(f64, f64)> {
+    if let PathSeg::Line(line) = next {
+        prev.intersect_line(line)
+            .into_iter()
+            .max_by(|a, b| a.segment_t.total_cmp(&b.segment_t))
+            .map(|hit| (hit.segment_t, hit.line_t))
+    } else if let PathSeg::Line(line) = prev {
+        next.intersect_line(line)
+            .into_iter()
+            .max_by(|a, b| a.line_t.total_cmp(&b.line_t))
+            .map(|hit| (hit.line_t, hit.segment_t))
+    } else {
+        None
+    }
+}
+
+/// Appends a segment to a path that already ends at the segment start.
+fn push_segment(seg: PathSeg, out: &mut Vec<PathEl>) {
+    out.push(match seg {
+        PathSeg::Line(line) => PathEl::LineTo(line.p1),
+        PathSeg::Quad(quad) => PathEl::QuadTo(quad.p1, quad.p2),
+        PathSeg::Cubic(cubic) => PathEl::CurveTo(cubic.p1, cubic.p2, cubic.p3),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixed::{OffsetPath, PuckerBloat, RoundedCorners, Trim, Twist, ZigZag};
+    use kurbo::{Join, PathEl, Point, Rect, Shape, Vec2};
+
+    #[test]
+    fn test_zig_zag_square() {
+        let path = Rect::new(0.0, 0.0, 100.0, 100.0)
+            .path_elements(0.1)
+            .collect::<Vec<_>>();
+        let mut out = vec![];
+        let zig_zag = ZigZag {
+            ridges: 1.0,
+            size: 5.0,
+            is_smooth: false,
+        };
+        super::zig_zag(&zig_zag, &path, &mut out);
+        // 4 sides with 2 points each, closed.
+        let lines = out
+            .iter()
+            .filter(|el| matches!(el, PathEl::LineTo(_)))
+            .count();
+        assert_eq!(lines, 8);
+        assert!(matches!(out.last(), Some(PathEl::ClosePath)));
+        // The midpoint of the top edge is pushed off the edge.
+        let PathEl::LineTo(mid) = out[1] else {
+            panic!("expected line");
+        };
+        assert!((mid.x - 50.0).abs() < 1e-6);
+        assert!((mid.y.abs() - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rounded_corners_square() {
+        let path = Rect::new(0.0, 0.0, 100.0, 100.0)
+            .to_rounded_rect(0.0)
+            .path_elements(0.1)
+            .collect::<Vec<_>>();
+        let mut out = vec![];
+        let rounded_corners = RoundedCorners { radius: 10.0 };
+        super::rounded_corners(&rounded_corners, &path, &mut out);
+        let curves = out
+            .iter()
+            .filter(|el| matches!(el, PathEl::CurveTo(..)))
+            .count();
+        assert_eq!(curves, 4);
+        assert!(matches!(out.last(), Some(PathEl::ClosePath)));
+        // Every corner is cut back by the radius.
+        for el in &out {
+            let (PathEl::LineTo(p) | PathEl::CurveTo(_, _, p)) = el else {
+                continue;
+            };
+            let on_corner = |v: f64| v == 0.0 || v == 100.0;
+            assert!(!(on_corner(p.x) && on_corner(p.y)), "{p:?} not rounded");
+        }
+        assert!(out.contains(&PathEl::LineTo(Point::new(90.0, 0.0))));
+    }
+
+    #[test]
+    fn test_offset_path_square() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let path = rect.path_elements(0.1).collect::<Vec<_>>();
+        let offset = |amount, join| {
+            let mut out = vec![];
+            let offset_path = OffsetPath {
+                amount,
+                join,
+                miter_limit: 4.0,
+            };
+            super::offset_path(&offset_path, &path, &mut out);
+            out.as_slice().bounding_box()
+        };
+        let expanded = offset(10.0, Join::Miter);
+        assert!((expanded.width() - 120.0).abs() < 1e-6);
+        assert!((expanded.x0 + 10.0).abs() < 1e-6);
+        let expanded = offset(10.0, Join::Round);
+        assert!((expanded.width() - 120.0).abs() < 1e-6);
+        let contracted = offset(-10.0, Join::Round);
+        assert!((contracted.width() - 80.0).abs() < 1e-6);
+        assert!((contracted.x0 - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pucker_bloat_square() {
+        let path = Rect::new(0.0, 0.0, 100.0, 100.0)
+            .path_elements(0.1)
+            .collect::<Vec<_>>();
+        let mut out = vec![];
+        let pucker_bloat = PuckerBloat { amount: 50.0 };
+        super::pucker_bloat(&pucker_bloat, &path, &mut out);
+        // Corners move halfway to the center.
+        assert_eq!(out[0], PathEl::MoveTo(Point::new(25.0, 25.0)));
+        // Edges become curves bulging outwards.
+        let PathEl::CurveTo(c1, c2, p) = out[1] else {
+            panic!("expected curve");
+        };
+        assert_eq!(p, Point::new(75.0, 25.0));
+        assert_eq!(c1, Point::new(-25.0, -25.0));
+        assert_eq!(c2, Point::new(125.0, -25.0));
+    }
+
+    #[test]
+    fn test_twist_square() {
+        let path = Rect::new(0.0, 0.0, 100.0, 100.0)
+            .path_elements(0.1)
+            .collect::<Vec<_>>();
+        let mut out = vec![];
+        let twist = Twist {
+            angle: 90.0,
+            center: Vec2::ZERO,
+        };
+        super::twist(&twist, &path, &mut out);
+        // Corners are the farthest points so they rotate by the full angle.
+        let PathEl::MoveTo(start) = out[0] else {
+            panic!("expected move");
+        };
+        assert!(start.distance(Point::new(100.0, 0.0)) < 1e-6);
+    }
+
+    #[test]
+    fn test_trim_squares() {
+        let square = |x| {
+            Rect::new(x, 0.0, x + 100.0, 100.0)
+                .path_elements(0.1)
+                .collect::<Vec<_>>()
+        };
+        let trim = |start, end, offset, individually| Trim {
+            start,
+            end,
+            offset,
+            individually,
+        };
+        assert_eq!(trim(75.0, 25.0, 0.0, false).ranges()[0], 0.25..0.75);
+        // Offsets past the end wrap around to the start of the path.
+        let wrapped = trim(0.0, 25.0, 315.0, false);
+        assert_eq!(wrapped.ranges(), [0.875..1.0, 0.0..0.125]);
+        let mut out = vec![];
+        super::trim(&wrapped, &square(0.0), &mut out);
+        assert_eq!(
+            out,
+            [
+                PathEl::MoveTo(Point::new(0.0, 50.0)),
+                PathEl::LineTo(Point::new(0.0, 0.0)),
+                PathEl::MoveTo(Point::new(0.0, 0.0)),
+                PathEl::LineTo(Point::new(50.0, 0.0)),
+            ]
+        );
+        // Simultaneous trims keep half of each square, while individual
+        // trims keep the first of the two.
+        let squares = [square(0.0), square(200.0)].concat();
+        let moves = |individually| {
+            let mut out = vec![];
+            super::trim(&trim(0.0, 50.0, 0.0, individually), &squares, &mut out);
+            let bounds = kurbo::BezPath::from_vec(out.clone()).bounding_box();
+            let moves = out.iter().filter(|el| matches!(el, PathEl::MoveTo(_)));
+            (moves.count(), bounds.width())
+        };
+        assert_eq!(moves(false), (2, 300.0));
+        assert_eq!(moves(true), (1, 100.0));
+    }
+}
@@ -0,0 +1,738 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+/// Fixed or animated value.
+#[derive(Clone, Debug)]
+pub enum Value<T: Tween> {
+    /// Fixed value.
+    Fixed(T),
+    /// Animated value, boxed so that fixed values stay small.
+    Animated(Box<Animated<T>>),
+    /// Value bound to a slot, which can be replaced at runtime.
+    Slot(Box<Slot<T>>),
+}
+
+impl<T: Tween> Value<T> {
+    /// Returns true if the value is fixed.
+    ///
+    /// Values bound to a slot are never fixed, as the slot may change.
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, Self::Fixed(_))
+    }
+
+    /// Returns the value at a specified frame.
+    pub fn evaluate(&self, frame: f64) -> T {
+        match self {
+            Self::Fixed(fixed) => fixed.clone(),
+            Self::Animated(animated) => animated.evaluate(frame),
+            Self::Slot(slot) => slot.read().evaluate(frame),
+        }
+    }
+
+    /// Applies a function to the fixed value or to the value of every
+    /// keyframe.
+    ///
+    /// Interpolated values are only transformed consistently if the
+    /// function is affine, such as a scale or an offset.
+    pub fn map(self, f: impl Fn(T) -> T) -> Self {
+        match self {
+            Self::Fixed(value) => Self::Fixed(f(value)),
+            Self::Animated(mut animated) => {
+                animated.values = animated.values.into_iter().map(f).collect();
+                Self::Animated(animated)
+            }
+            Self::Slot(slot) => {
+                let id = slot.id.clone();
+                let is_overridden = slot.is_overridden();
+                let mapped = Slot::new(id, slot.into_inner().map(f));
+                mapped.overridden.store(is_overridden, Ordering::Relaxed);
+                Self::Slot(Box::new(mapped))
+            }
+        }
+    }
+}
+
+/// Frames over which derivatives are measured, which is a power of two so
+/// that the difference of linear values divides exactly.
+const DERIVATIVE_STEP: f64 = 1.0 / 1024.0;
+
+impl<T: Rate> Value<T> {
+    /// Returns the rate of change of the value at a specified frame, in
+    /// units per frame, for matching the velocity of the animation when
+    /// handing off to another motion such as a spring.
+    ///
+    /// This is the rate at which the value approaches the frame during
+    /// forward playback, so the rate at the last keyframe is that of the
+    /// segment that ends there. Multiply by the frame rate for units per
+    /// second.
+    pub fn derivative(&self, frame: f64) -> T::Rate {
+        let value = self.evaluate(frame);
+        value.rate(&self.evaluate(frame - DERIVATIVE_STEP), DERIVATIVE_STEP)
+    }
+}
+
+/// Value of a property that references a slot by id.
+///
+/// The value is shared through a lock so that slots can be set on a
+/// composition that is being rendered.
+#[derive(Debug)]
+pub struct Slot<T: Tween> {
+    /// Id of the slot.
+    pub id: String,
+    value: RwLock<Value<T>>,
+    /// True if the value is an override of the instance containing the
+    /// slot, which takes precedence over values set at runtime.
+    overridden: AtomicBool,
+}
+
+impl<T: Tween> Slot<T> {
+    /// Creates a slot with an initial value.
+    pub fn new(id: String, value: Value<T>) -> Self {
+        Self {
+            id,
+            value: RwLock::new(value),
+            overridden: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true if the value of the slot is an override of the instance
+    /// containing it, which [`Composition::set_slot`](crate::Composition::set_slot)
+    /// and themes leave in place.
+    pub fn is_overridden(&self) -> bool {
+        self.overridden.load(Ordering::Relaxed)
+    }
+
+    /// Replaces the value of the slot with an override of the instance
+    /// containing it.
+    pub(crate) fn set_override(&self, value: Value<T>) {
+        self.set(value);
+        self.overridden.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of the slot.
+    pub fn get(&self) -> Value<T> {
+        self.read().clone()
+    }
+
+    /// Replaces the value of the slot.
+    pub fn set(&self, value: Value<T>) {
+        *self.value.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+
+    /// Returns the value of the slot, consuming it.
+    pub fn into_inner(self) -> Value<T> {
+        self.value
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Value<T>> {
+        // Values are replaced whole, so a panic while holding the lock
+        // cannot leave a partial value behind.
+        self.value.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Tween> Clone for Slot<T> {
+    fn clone(&self) -> Self {
+        let slot = Self::new(self.id.clone(), self.get());
+        slot.overridden
+            .store(self.is_overridden(), Ordering::Relaxed);
+        slot
+    }
+}
+
+impl<T: Tween + Default> Default for Value<T> {
+    fn default() -> Self {
+        Self::Fixed(T::default())
+    }
+}
+
+/// Borrowed or owned value.
+#[derive(Clone, Debug)]
+pub enum ValueRef<'a, T> {
+    Borrowed(&'a T),
+    Owned(T),
+}
+
+impl<'a, T> AsRef<T> for ValueRef<'a, T> {
+    fn as_ref(&self) -> &T {
+        match self {
+            Self::Borrowed(value) => value,
+            Self::Owned(value) => value,
+        }
+    }
+}
+
+impl<'a, T: Clone> ValueRef<'a, T> {
+    pub fn into_owned(self) -> T {
+        match self {
+            Self::Borrowed(value) => value.clone(),
+            Self::Owned(value) => value,
+        }
+    }
+}
+
+/// Cubic bezier easing between two keyframes.
+#[derive(Copy, Clone, Debug)]
+pub struct Easing {
+    /// Control point leaving the first keyframe.
+    pub o: EasingHandle,
+    /// Control point entering the second keyframe.
+    pub i: EasingHandle,
+}
+
+impl Easing {
+    /// Linear easing.
+    pub const LERP: Easing = Easing {
+        o: EasingHandle { x: 0.0, y: 0.0 },
+        i: EasingHandle { x: 1.0, y: 1.0 },
+    };
+
+    /// Evaluates the easing curve at the normalized time `t`, returning the
+    /// normalized progress between the two keyframes.
+    ///
+    /// The curve is the cubic bezier from `(0, 0)` to `(1, 1)` with control
+    /// points `o` and `i`. Progress may fall outside `0..=1` for curves that
+    /// overshoot.
+    pub fn evaluate(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        // Handles on the diagonal, such as those of keyframes without
+        // easing, make the curve linear.
+        if t == 0.0 || t == 1.0 || (self.o.x == self.o.y && self.i.x == self.i.y) {
+            return t;
+        }
+        // The time axis must be monotonic for the curve to be a function.
+        let x1 = self.o.x.clamp(0.0, 1.0);
+        let x2 = self.i.x.clamp(0.0, 1.0);
+        let s = solve_bezier_param(x1, x2, t);
+        cubic_bezier(self.o.y, self.i.y, s)
+    }
+
+    /// Samples the easing curve at `n` evenly spaced times in `0..=1`.
+    ///
+    /// Returns `(time, progress)` pairs, which is useful for drawing curve
+    /// previews or checking easing behavior without rendering.
+    pub fn sample(&self, n: usize) -> Vec<(f64, f64)> {
+        match n {
+            0 => vec![],
+            1 => vec![(0.0, self.evaluate(0.0))],
+            _ => (0..n)
+                .map(|i| {
+                    let t = i as f64 / (n - 1) as f64;
+                    (t, self.evaluate(t))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Evaluates one axis of a cubic bezier from 0 to 1 with the given inner
+/// control values.
+fn cubic_bezier(c1: f64, c2: f64, s: f64) -> f64 {
+    let u = 1.0 - s;
+    3.0 * u * u * s * c1 + 3.0 * u * s * s * c2 + s * s * s
+}
+
+/// Finds the curve parameter at which the time axis reaches `x`.
+fn solve_bezier_param(x1: f64, x2: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-9;
+    // Newton's method converges quickly for well-behaved curves.
+    let mut s = x;
+    for _ in 0..8 {
+        let err = cubic_bezier(x1, x2, s) - x;
+        if err.abs() < EPSILON {
+            return s;
+        }
+        let u = 1.0 - s;
+        let slope = 3.0 * u * u * x1 + 6.0 * u * s * (x2 - x1) + 3.0 * s * s * (1.0 - x2);
+        if slope.abs() < EPSILON {
+            break;
+        }
+        s -= err / slope;
+        if !(0.0..=1.0).contains(&s) {
+            break;
+        }
+    }
+    // Fall back to bisection, which always converges on a monotonic curve.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = x;
+    for _ in 0..64 {
+        let err = cubic_bezier(x1, x2, s) - x;
+        if err.abs() < EPSILON {
+            break;
+        }
+        if err < 0.0 {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = 0.5 * (lo + hi);
+    }
+    s
+}
+
+/// Control point of an easing curve, in normalized time and progress.
+#[derive(Copy, Clone, Debug)]
+pub struct EasingHandle {
+    /// Normalized time.
+    pub x: f64,
+    /// Normalized progress.
+    pub y: f64,
+}
+
+/// Time for a particular keyframe, represented as a frame number.
+#[derive(Copy, Clone, Debug)]
+pub struct Time {
+    /// Frame number.
+    pub frame: f64,
+    /// Easing tangent going into the next keyframe
+    pub in_tangent: Option<EasingHandle>,
+    /// Easing tangent leaving the current keyframe
+    pub out_tangent: Option<EasingHandle>,
+    /// Easing tangents going into the next keyframe for the second and
+    /// third dimensions of values that ease each dimension separately.
+    /// Dimensions without one use `in_tangent`.
+    pub axis_in_tangents: [Option<EasingHandle>; 2],
+    /// Easing tangents leaving the current keyframe for the second and
+    /// third dimensions. Dimensions without one use `out_tangent`.
+    pub axis_out_tangents: [Option<EasingHandle>; 2],
+    /// Whether it's a hold frame.
+    pub hold: bool,
+}
+
+impl Time {
+    /// Returns the frame indices and interpolation weight for the given frame,
+    /// the easing of each dimension, and whether to hold the frame
+    pub(crate) fn frames_and_weight(
+        times: &[Time],
+        frame: f64,
+    ) -> Option<([usize; 2], f64, [Easing; 3], bool)> {
+        if times.is_empty() {
+            return None;
+        }
+        use core::cmp::Ordering::*;
+        let ix = match times.binary_search_by(|x| {
+            if x.frame < frame {
+                Less
+            } else if x.frame > frame {
+                Greater
+            } else {
+                Equal
+            }
+        }) {
+            Ok(ix) => ix,
+            Err(ix) => ix.saturating_sub(1),
+        };
+        let ix0 = ix.min(times.len() - 1);
+        let ix1 = (ix0 + 1).min(times.len() - 1);
+
+        let t0 = times[ix0];
+        let t1 = times[ix1];
+        let easing = |out_tangent: Option<EasingHandle>, in_tangent: Option<EasingHandle>| Easing {
+            o: out_tangent.unwrap_or(Easing::LERP.o),
+            i: in_tangent.unwrap_or(Easing::LERP.i),
+        };
+        // Both handles of a segment belong to the keyframe that starts it.
+        let first = easing(t0.out_tangent, t0.in_tangent);
+        let axis = |axis: usize| {
+            easing(
+                t0.axis_out_tangents[axis].or(t0.out_tangent),
+                t0.axis_in_tangents[axis].or(t0.in_tangent),
+            )
+        };
+        let easings = [first, axis(0), axis(1)];
+        let hold = t0.hold;
+        // Segments without duration, such as at or after the last keyframe,
+        // hold their first value.
+        let t = if t1.frame > t0.frame {
+            (frame - t0.frame) / (t1.frame - t0.frame)
+        } else {
+            0.0
+        };
+        Some(([ix0, ix1], t.clamp(0.0, 1.0), easings, hold))
+    }
+}
+
+/// Repetition of keyframes beyond their range.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LoopMode {
+    /// Restarts from the beginning of the repeated keyframes.
+    Cycle,
+    /// Alternates between playing the repeated keyframes forwards and
+    /// backwards.
+    PingPong,
+}
+
+impl LoopMode {
+    /// Maps a frame to the range of the repeated keyframes.
+    fn wrap(self, start: f64, end: f64, frame: f64) -> f64 {
+        let duration = end - start;
+        if duration <= 0.0 {
+            return frame;
+        }
+        match self {
+            Self::Cycle => start + (frame - start).rem_euclid(duration),
+            Self::PingPong => {
+                let offset = (frame - start).rem_euclid(duration * 2.0);
+                if offset > duration {
+                    end - (offset - duration)
+                } else {
+                    start + offset
+                }
+            }
+        }
+    }
+}
+
+/// Repetition of keyframes before or after their range, such as from the
+/// `loopIn` and `loopOut` expressions.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Loop {
+    /// How the keyframes are repeated.
+    pub mode: LoopMode,
+    /// Number of keyframe segments that are repeated, counted from the
+    /// first keyframe before the range and from the last keyframe after
+    /// it. All segments are repeated if this is zero.
+    pub segments: u32,
+}
+
+/// Repetition of keyframes on both sides of their range.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Loops {
+    /// Repetition before the first keyframe.
+    pub before: Option<Loop>,
+    /// Repetition after the last keyframe.
+    pub after: Option<Loop>,
+}
+
+impl Loops {
+    /// Maps a frame outside of the keyframes to the frame that it repeats.
+    pub fn apply(&self, times: &[Time], frame: f64) -> f64 {
+        let len = times.len();
+        if len < 2 {
+            return frame;
+        }
+        let segments = |repeat: Loop| match repeat.segments as usize {
+            0 => len - 1,
+            segments => segments.min(len - 1),
+        };
+        match (self.before, self.after) {
+            (Some(repeat), _) if frame < times[0].frame => {
+                let end = times[segments(repeat)].frame;
+                repeat.mode.wrap(times[0].frame, end, frame)
+            }
+            (_, Some(repeat)) if frame > times[len - 1].frame => {
+                let start = times[len - 1 - segments(repeat)].frame;
+                repeat.mode.wrap(start, times[len - 1].frame, frame)
+            }
+            _ => frame,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Animated<T: Tween> {
+    pub times: Vec<Time>,
+    pub values: Vec<T>,
+    /// Repetition of the keyframes beyond their range.
+    pub loops: Loops,
+}
+
+impl<T: Tween> Animated<T> {
+    /// Returns the value at the specified frame.
+    pub fn evaluate(&self, frame: f64) -> T {
+        self.evaluate_inner(frame).unwrap_or_default()
+    }
+
+    fn evaluate_inner(&self, frame: f64) -> Option<T> {
+        let frame = self.loops.apply(&self.times, frame);
+        let ([ix0, ix1], t, easings, hold) = Time::frames_and_weight(&self.times, frame)?;
+        let t = if hold { 0.0 } else { t };
+
+        let v1 = self.values.get(ix0)?;
+        let v2 = self.values.get(ix1)?;
+
+        Some(v1.tween_axes(v2, t, &easings))
+    }
+}
+
+impl Value<peniko::Color> {
+    /// Returns the color at a specified frame, interpolating between
+    /// keyframes in the given color space.
+    pub fn evaluate_in(&self, frame: f64, space: super::ColorSpace) -> peniko::Color {
+        match self {
+            Self::Fixed(color) => *color,
+            Self::Animated(animated) => animated.evaluate_in(frame, space),
+            Self::Slot(slot) => slot.read().evaluate_in(frame, space),
+        }
+    }
+}
+
+impl Animated<peniko::Color> {
+    /// Returns the color at the specified frame, interpolating between
+    /// keyframes in the given color space.
+    pub fn evaluate_in(&self, frame: f64, space: super::ColorSpace) -> peniko::Color {
+        let frame = self.loops.apply(&self.times, frame);
+        let Some(([ix0, ix1], t, _, hold)) = Time::frames_and_weight(&self.times, frame) else {
+            return Default::default();
+        };
+        let t = if hold { 0.0 } else { t };
+        match (self.values.get(ix0), self.values.get(ix1)) {
+            (Some(from), Some(to)) => space.interpolate(*from, *to, t),
+            _ => Default::default(),
+        }
+    }
+}
+
+/// Something that can be interpolated with an easing function.
+pub trait Tween: Clone + Default {
+    /// Interpolates between `self` and `other` at the normalized time `t`.
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self;
+
+    /// Interpolates between `self` and `other` at the normalized time `t`,
+    /// with a separate easing for each of up to three dimensions.
+    ///
+    /// By default, the whole value is interpolated with the easing of the
+    /// first dimension.
+    fn tween_axes(&self, other: &Self, t: f64, easings: &[Easing; 3]) -> Self {
+        self.tween(other, t, &easings[0])
+    }
+}
+
+impl Tween for f64 {
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self {
+        self + (other - self) * easing.evaluate(t)
+    }
+}
+
+/// Values with a rate of change, such as numbers and points.
+pub trait Rate: Tween {
+    /// Type of the rate of change, such as a vector for points.
+    type Rate;
+
+    /// Returns the rate of change from `from` to `self` over `frames`.
+    fn rate(&self, from: &Self, frames: f64) -> Self::Rate;
+}
+
+impl Rate for f64 {
+    type Rate = f64;
+
+    fn rate(&self, from: &Self, frames: f64) -> f64 {
+        (self - from) / frames
+    }
+}
+
+impl Rate for kurbo::Point {
+    type Rate = kurbo::Vec2;
+
+    fn rate(&self, from: &Self, frames: f64) -> kurbo::Vec2 {
+        (*self - *from) / frames
+    }
+}
+
+impl Rate for kurbo::Vec2 {
+    type Rate = kurbo::Vec2;
+
+    fn rate(&self, from: &Self, frames: f64) -> kurbo::Vec2 {
+        (*self - *from) / frames
+    }
+}
+
+impl Rate for kurbo::Size {
+    type Rate = kurbo::Vec2;
+
+    fn rate(&self, from: &Self, frames: f64) -> kurbo::Vec2 {
+        (self.to_vec2() - from.to_vec2()) / frames
+    }
+}
+
+impl Tween for bool {
+    fn tween(&self, other: &Self, t: f64, _easing: &Easing) -> Self {
+        step(self, other, t)
+    }
+}
+
+impl Tween for super::StrokeJoin {
+    fn tween(&self, other: &Self, t: f64, _easing: &Easing) -> Self {
+        step(self, other, t)
+    }
+}
+
+impl Tween for super::StrokeCap {
+    fn tween(&self, other: &Self, t: f64, _easing: &Easing) -> Self {
+        step(self, other, t)
+    }
+}
+
+/// Interpolates values that have nothing in between by snapping at the end
+/// of the segment.
+fn step<T: Copy>(from: &T, to: &T, t: f64) -> T {
+    if t >= 1.0 {
+        *to
+    } else {
+        *from
+    }
+}
+
+impl Tween for kurbo::Point {
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self {
+        Self::new(
+            self.x.tween(&other.x, t, easing),
+            self.y.tween(&other.y, t, easing),
+        )
+    }
+
+    fn tween_axes(&self, other: &Self, t: f64, easings: &[Easing; 3]) -> Self {
+        Self::new(
+            self.x.tween(&other.x, t, &easings[0]),
+            self.y.tween(&other.y, t, &easings[1]),
+        )
+    }
+}
+
+impl Tween for kurbo::Vec2 {
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self {
+        Self::new(
+            self.x.tween(&other.x, t, easing),
+            self.y.tween(&other.y, t, easing),
+        )
+    }
+
+    fn tween_axes(&self, other: &Self, t: f64, easings: &[Easing; 3]) -> Self {
+        Self::new(
+            self.x.tween(&other.x, t, &easings[0]),
+            self.y.tween(&other.y, t, &easings[1]),
+        )
+    }
+}
+
+impl Tween for kurbo::Size {
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self {
+        Self::new(
+            self.width.tween(&other.width, t, easing),
+            self.height.tween(&other.height, t, easing),
+        )
+    }
+
+    fn tween_axes(&self, other: &Self, t: f64, easings: &[Easing; 3]) -> Self {
+        Self::new(
+            self.width.tween(&other.width, t, &easings[0]),
+            self.height.tween(&other.height, t, &easings[1]),
+        )
+    }
+}
+
+impl Tween for peniko::Color {
+    fn tween(&self, other: &Self, t: f64, easing: &Easing) -> Self {
+        let r = (self.r as f64 / 255.0).tween(&(other.r as f64 / 255.0), t, easing);
+        let g = (self.g as f64 / 255.0).tween(&(other.g as f64 / 255.0), t, easing);
+        let b = (self.b as f64 / 255.0).tween(&(other.b as f64 / 255.0), t, easing);
+        let a = (self.a as f64 / 255.0).tween(&(other.a as f64 / 255.0), t, easing);
+        peniko::Color::rgba(r, g, b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animated, Easing, EasingHandle, Time, Value};
+    use kurbo::{Point, Vec2};
+
+    #[test]
+    fn test_lerp_sample() {
+        let samples = Easing::LERP.sample(5);
+        assert_eq!(samples.len(), 5);
+        for (t, progress) in samples {
+            assert!((t - progress).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_ease_in_out_sample() {
+        let easing = Easing {
+            o: EasingHandle { x: 0.42, y: 0.0 },
+            i: EasingHandle { x: 0.58, y: 1.0 },
+        };
+        let samples = easing.sample(11);
+        assert_eq!(samples.first(), Some(&(0.0, 0.0)));
+        assert_eq!(samples.last(), Some(&(1.0, 1.0)));
+        assert!((samples[5].1 - 0.5).abs() < 1e-6);
+        assert!(samples[2].1 < 0.2);
+        assert!(samples.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn test_derivative() {
+        let time = |frame, hold| Time {
+            frame,
+            in_tangent: None,
+            out_tangent: None,
+            axis_in_tangents: [None; 2],
+            axis_out_tangents: [None; 2],
+            hold,
+        };
+        let value = Value::Animated(Box::new(Animated {
+            times: vec![time(0.0, false), time(10.0, true), time(20.0, false)],
+            values: vec![0.0, 100.0, 50.0],
+            loops: Default::default(),
+        }));
+        assert_eq!(value.derivative(5.0), 10.0);
+        // The last segment into a keyframe sets the rate at the keyframe.
+        assert_eq!(value.derivative(10.0), 10.0);
+        // Hold keyframes and frames outside the keyframes do not change.
+        assert_eq!(value.derivative(15.0), 0.0);
+        assert_eq!(value.derivative(-5.0), 0.0);
+        assert_eq!(value.derivative(25.0), 0.0);
+        assert_eq!(Value::Fixed(1.0).derivative(5.0), 0.0);
+
+        let point = Value::Animated(Box::new(Animated {
+            times: vec![time(0.0, false), time(4.0, false)],
+            values: vec![Point::ZERO, Point::new(8.0, -4.0)],
+            loops: Default::default(),
+        }));
+        assert_eq!(point.derivative(2.0), Vec2::new(2.0, -1.0));
+    }
+
+    #[test]
+    fn test_value_size() {
+        // Most values in a file are fixed, so they should not pay for the
+        // keyframes of animated values.
+        assert_eq!(
+            std::mem::size_of::<Value<f64>>(),
+            2 * std::mem::size_of::<f64>()
+        );
+    }
+
+    #[test]
+    fn test_axis_easing() {
+        let handle = |x, y| Some(EasingHandle { x, y });
+        let time = |frame, out_tangent, axis_out_tangents| Time {
+            frame,
+            in_tangent: handle(0.58, 1.0),
+            out_tangent,
+            axis_in_tangents: [None; 2],
+            axis_out_tangents,
+            hold: false,
+        };
+        let value = Animated {
+            times: vec![
+                time(0.0, handle(0.42, 0.0), [handle(0.0, 0.0), None]),
+                time(10.0, None, [None; 2]),
+            ],
+            values: vec![Point::ZERO, Point::new(100.0, 100.0)],
+            loops: Default::default(),
+        };
+        // The first dimension eases in and out, and the second only out.
+        let point = value.evaluate(2.5);
+        assert!((point.x - 12.916).abs() < 1e-3, "{point:?}");
+        let ease_out = Easing {
+            o: EasingHandle { x: 0.0, y: 0.0 },
+            i: EasingHandle { x: 0.58, y: 1.0 },
+        };
+        assert!((point.y - 100.0 * ease_out.evaluate(0.25)).abs() < 1e-9);
+        assert!(point.y > 25.0);
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::runtime::LayerProperty;
+
+/// Random motion added to a layer property by a `wiggle` expression.
+///
+/// The motion is sampled from smooth value noise, which depends only on the
+/// time, the key of the wiggle and a seed chosen by the renderer, so the
+/// same seed always produces the same motion.
+#[derive(Clone, Debug)]
+pub struct Wiggle {
+    /// Wiggled property of the layer.
+    pub property: LayerProperty,
+    /// Number of wiggles per second.
+    pub frequency: f64,
+    /// Largest offset from the value of the property.
+    pub amplitude: f64,
+    /// Number of octaves of noise that are added together.
+    pub octaves: u32,
+    /// Amplitude of each octave relative to the previous one.
+    pub amplitude_multiplier: f64,
+    /// Distinguishes the noise of the wiggles of a composition, so that
+    /// wiggled properties do not move in unison.
+    pub key: u64,
+}
+
+impl Wiggle {
+    /// Returns the offset of one dimension of the property at the given
+    /// time in seconds.
+    pub fn evaluate(&self, seed: u64, dimension: u32, time: f64) -> f64 {
+        let seed = mix(mix(seed ^ self.key) ^ u64::from(dimension));
+        let mut frequency = self.frequency;
+        let mut amplitude = self.amplitude;
+        let mut offset = 0.0;
+        // More octaves than this add nothing visible.
+        for octave in 0..self.octaves.min(16) {
+            offset += amplitude * noise(seed.wrapping_add(u64::from(octave)), time * frequency);
+            frequency *= 2.0;
+            amplitude *= self.amplitude_multiplier;
+        }
+        offset
+    }
+}
+
+/// One dimensional value noise in the range `-1..=1`, interpolating random
+/// values at integer positions.
+fn noise(seed: u64, x: f64) -> f64 {
+    if !x.is_finite() {
+        return 0.0;
+    }
+    let start = x.floor();
+    let t = x - start;
+    let t = t * t * (3.0 - 2.0 * t);
+    let value = |i: f64| {
+        let bits = mix(seed ^ (i as i64 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        // The top 53 bits give a uniform number in 0..1.
+        (bits >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    };
+    let a = value(start);
+    let b = value(start + 1.0);
+    a + (b - a) * t
+}
+
+/// Scrambles the bits of a number, as in the `SplitMix64` generator.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
@@ -0,0 +1,217 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{animated, Layer, Time, Transform, Tween, Value};
+use super::Composition;
+
+/// Animated property of a layer that can be observed.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LayerProperty {
+    /// Anchor point of the layer transform.
+    Anchor,
+    /// Position of the layer transform. For positions with separate
+    /// dimensions, the keyframes of the x coordinate are used.
+    Position,
+    /// Rotation of the layer transform.
+    Rotation,
+    /// Scale of the layer transform.
+    Scale,
+    /// Opacity of the layer.
+    Opacity,
+}
+
+/// Selects a keyframe of an observed property.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KeyframeSelector {
+    /// Keyframe at the given index.
+    Index(usize),
+    /// Final keyframe of the property.
+    Last,
+}
+
+/// Notification that playback crossed an observed keyframe.
+#[derive(Clone, Debug)]
+pub struct KeyframeEvent<'a> {
+    /// Name of the layer containing the property.
+    pub layer: &'a str,
+    /// Observed property.
+    pub property: LayerProperty,
+    /// Index of the keyframe that was crossed.
+    pub keyframe: usize,
+    /// Frame of the keyframe.
+    pub frame: f64,
+}
+
+struct Watch {
+    layer: String,
+    property: LayerProperty,
+    keyframe: KeyframeSelector,
+    callback: Box<dyn FnMut(&KeyframeEvent)>,
+}
+
+/// Invokes callbacks when playback crosses keyframes of named layer
+/// properties.
+///
+/// This allows applications to measure progress through an animated flow,
+/// such as when a checkmark finishes scaling in, without polling values on
+/// every frame. Only top level layers of a composition are observed.
+#[derive(Default)]
+pub struct KeyframeObserver {
+    watches: Vec<Watch>,
+    last_frame: Option<f64>,
+}
+
+impl KeyframeObserver {
+    /// Creates a new observer with no callbacks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a callback for when playback reaches a keyframe of the
+    /// given property on all layers with the given name.
+    pub fn watch(
+        &mut self,
+        layer: impl Into<String>,
+        property: LayerProperty,
+        keyframe: KeyframeSelector,
+        callback: impl FnMut(&KeyframeEvent) + 'static,
+    ) {
+        self.watches.push(Watch {
+            layer: layer.into(),
+            property,
+            keyframe,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Forgets the previously observed frame so that the next update does
+    /// not report any crossings.
+    pub fn reset(&mut self) {
+        self.last_frame = None;
+    }
+
+    /// Advances playback to the given frame, invoking callbacks for every
+    /// observed keyframe after the previous frame and up to and including
+    /// this one.
+    ///
+    /// A decrease in frame is treated as the animation looping back to the
+    /// start.
+    pub fn update(&mut self, composition: &Composition, frame: f64) {
+        let Some(last_frame) = self.last_frame.replace(frame) else {
+            return;
+        };
+        if last_frame == frame {
+            return;
+        }
+        let crossed = |t: f64| {
+            if frame > last_frame {
+                t > last_frame && t <= frame
+            } else {
+                t > last_frame || t <= frame
+            }
+        };
+        for watch in &mut self.watches {
+            for layer in composition.layers.iter() {
+                if layer.name != watch.layer {
+                    continue;
+                }
+                let Some(times) = keyframe_times(layer, watch.property) else {
+                    continue;
+                };
+                let index = match watch.keyframe {
+                    KeyframeSelector::Index(index) => index,
+                    KeyframeSelector::Last => times.len().saturating_sub(1),
+                };
+                let Some(time) = times.get(index) else {
+                    continue;
+                };
+                if crossed(time.frame) {
+                    (watch.callback)(&KeyframeEvent {
+                        layer: &layer.name,
+                        property: watch.property,
+                        keyframe: index,
+                        frame: time.frame,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Returns the keyframes of an animated layer property.
+fn keyframe_times(layer: &Layer, property: LayerProperty) -> Option<&[Time]> {
+    fn times<T: Tween>(value: &Value<T>) -> Option<&[Time]> {
+        match value {
+            Value::Fixed(_) | Value::Slot(_) => None,
+            Value::Animated(animated) => Some(&animated.times),
+        }
+    }
+    if property == LayerProperty::Opacity {
+        return times(&layer.opacity);
+    }
+    let Transform::Animated(transform) = &layer.transform else {
+        return None;
+    };
+    match property {
+        LayerProperty::Anchor => times(&transform.anchor),
+        LayerProperty::Position => match &transform.position {
+            animated::Position::Value(value) => times(value),
+            animated::Position::SplitValues((x, _)) => times(x),
+        },
+        LayerProperty::Rotation => times(&transform.rotation),
+        LayerProperty::Scale => times(&transform.scale),
+        LayerProperty::Opacity => times(&layer.opacity),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyframeObserver, KeyframeSelector, LayerProperty};
+    use crate::model::{Animated, Layer, Time, Value};
+    use crate::Composition;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_last_keyframe() {
+        let time = |frame| Time {
+            frame,
+            in_tangent: None,
+            out_tangent: None,
+            axis_in_tangents: [None; 2],
+            axis_out_tangents: [None; 2],
+            hold: false,
+        };
+        let composition = Composition {
+            frames: 0.0..30.0,
+            frame_rate: 30.0,
+            layers: vec![Layer {
+                name: "checkmark".into(),
+                opacity: Value::Animated(Box::new(Animated {
+                    times: vec![time(0.0), time(10.0), time(20.0)],
+                    values: vec![0.0, 50.0, 100.0],
+                    loops: Default::default(),
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let events = Rc::new(RefCell::new(vec![]));
+        let mut observer = KeyframeObserver::new();
+        let sink = events.clone();
+        observer.watch(
+            "checkmark",
+            LayerProperty::Opacity,
+            KeyframeSelector::Last,
+            move |event| sink.borrow_mut().push((event.keyframe, event.frame)),
+        );
+        for frame in [0.0, 15.0, 25.0, 29.0] {
+            observer.update(&composition, frame);
+        }
+        assert_eq!(*events.borrow(), [(2, 20.0)]);
+        // Looping around crosses the keyframe again.
+        observer.update(&composition, 5.0);
+        observer.update(&composition, 20.0);
+        assert_eq!(events.borrow().len(), 2);
+    }
+}
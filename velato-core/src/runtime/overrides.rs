@@ -0,0 +1,103 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{Content, Layer};
+use super::{slots, Composition};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Gives every instance with overrides its own copy of the asset it
+/// instances, with the overridden slots set.
+///
+/// Copies are named after the asset followed by `#` and a number, and
+/// the assets that they instance are copied in turn, so that overrides
+/// apply to nested instances.
+pub(crate) fn apply(composition: &mut Composition) {
+    let mut assets = std::mem::take(&mut composition.assets);
+    let mut names = assets.keys().cloned().collect::<Vec<_>>();
+    // Sort by name so that copies are named deterministically.
+    names.sort();
+    for name in names {
+        let Some(mut layers) = assets.remove(&name) else {
+            continue;
+        };
+        let mut stack = vec![name.clone()];
+        redirect(&mut layers, &BTreeMap::new(), &mut assets, &mut stack);
+        assets.insert(name, layers);
+    }
+    redirect(
+        &mut composition.layers,
+        &BTreeMap::new(),
+        &mut assets,
+        &mut vec![],
+    );
+    composition.assets = assets;
+}
+
+/// Gives the instances with overrides new copies of their assets, after
+/// the assets have been changed.
+///
+/// Instances are pointed back to the assets they were imported with and
+/// the previous copies are dropped before copying again.
+pub(crate) fn rebuild(composition: &mut Composition) {
+    let mut copies = HashSet::new();
+    for layers in composition
+        .assets
+        .values_mut()
+        .chain(std::iter::once(&mut composition.layers))
+    {
+        for layer in layers {
+            if let Content::Instance { name, source, .. } = &mut layer.content {
+                if name != source {
+                    copies.insert(std::mem::replace(name, source.clone()));
+                }
+            }
+        }
+    }
+    composition.assets.retain(|name, _| !copies.contains(name));
+    apply(composition);
+}
+
+/// Points the instances among the layers that have overrides, including
+/// those inherited from the instances containing them, to copies of their
+/// assets.
+///
+/// The stack holds the names of the assets being copied, to stop at
+/// recursive instances.
+fn redirect(
+    layers: &mut [Layer],
+    inherited: &BTreeMap<String, Vec<f64>>,
+    assets: &mut HashMap<String, Vec<Layer>>,
+    stack: &mut Vec<String>,
+) {
+    for layer in layers {
+        let Content::Instance {
+            name, overrides, ..
+        } = &mut layer.content
+        else {
+            continue;
+        };
+        // Overrides of the containing instances take precedence.
+        let mut values = overrides.clone();
+        values.extend(
+            inherited
+                .iter()
+                .map(|(id, value)| (id.clone(), value.clone())),
+        );
+        if values.is_empty() || stack.contains(name) {
+            continue;
+        }
+        let Some(mut copy) = assets.get(name).cloned() else {
+            continue;
+        };
+        slots::override_in_layers(&copy, &values);
+        stack.push(name.clone());
+        redirect(&mut copy, &values, assets, stack);
+        stack.pop();
+        let copy_name = (1..)
+            .map(|n| format!("{name}#{n}"))
+            .find(|copy_name| !assets.contains_key(copy_name))
+            .unwrap();
+        assets.insert(copy_name.clone(), copy);
+        *name = copy_name;
+    }
+}
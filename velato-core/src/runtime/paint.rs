@@ -0,0 +1,3041 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::{Canvas, Composition, Digest};
+use crate::model::*;
+use kurbo::{Affine, BezPath, Cap, Join, PathEl, Point, Rect, Shape as _, Vec2};
+use peniko::{BlendMode, Color, ColorStop, Compose, Fill, Font, GradientKind, Mix};
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::ops::Range;
+
+/// Policy for replacing miter joins with bevel joins on sharp corners.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub enum JoinFallback {
+    /// Bevel only when the miter length exceeds the stroke's miter limit.
+    #[default]
+    MiterLimit,
+    /// Additionally bevel any corner whose interior angle, in degrees, is
+    /// smaller than the given threshold.
+    Angle(f64),
+}
+
+impl JoinFallback {
+    /// Returns the effective miter limit for a stroke with the given limit.
+    fn apply(self, miter_limit: f64) -> f64 {
+        match self {
+            Self::MiterLimit => miter_limit,
+            Self::Angle(degrees) => {
+                // The ratio of miter length to stroke width for a corner
+                // with interior angle theta is 1 / sin(theta / 2).
+                let half_angle = (degrees.clamp(0.0, 180.0) * 0.5).to_radians();
+                if half_angle <= 0.0 {
+                    miter_limit
+                } else {
+                    miter_limit.min(half_angle.sin().recip())
+                }
+            }
+        }
+    }
+}
+
+/// Options that change how compositions are rendered.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct RenderOptions {
+    /// Mirrors the composition horizontally around its center, such as for
+    /// right-to-left layouts.
+    pub mirror_x: bool,
+    /// Mirrors the composition vertically around its center.
+    pub mirror_y: bool,
+    /// Number of times that layers with motion blur are rendered over the
+    /// shutter interval of each frame and averaged. Motion blur is disabled
+    /// with fewer than two samples.
+    ///
+    /// Each sample renders the layer again, so the cost of blurred layers
+    /// grows linearly with the number of samples.
+    pub motion_blur_samples: usize,
+    /// Color space in which gradient stops and animated colors are
+    /// interpolated.
+    ///
+    /// Spaces other than sRGB divide gradients into additional stops, which
+    /// costs some encoding time for each gradient drawn.
+    pub color_space: ColorSpace,
+}
+
+impl RenderOptions {
+    /// Returns the transform that applies these options to a composition,
+    /// to be applied before the transform passed to the renderer.
+    ///
+    /// The composition is mirrored as a whole, so layer anchors and
+    /// positions are mirrored together with the content they place.
+    pub fn transform(&self, composition: &Composition) -> Affine {
+        if !self.mirror_x && !self.mirror_y {
+            return Affine::IDENTITY;
+        }
+        let center = Vec2::new(composition.width as f64, composition.height as f64) * 0.5;
+        let scale = |mirror| if mirror { -1.0 } else { 1.0 };
+        let mirror = Affine::translate(center)
+            * Affine::scale_non_uniform(scale(self.mirror_x), scale(self.mirror_y))
+            * Affine::translate(-center);
+        // Mirror in the space of the composition, before the base transform
+        // that was folded into its layers.
+        let base = composition.base_transform;
+        if base.determinant() == 0.0 {
+            return mirror;
+        }
+        base * mirror * base.inverse()
+    }
+}
+
+/// Stroke parameters supplied by the renderer rather than the animation.
+#[derive(Copy, Clone, Debug)]
+struct StrokeDefaults {
+    miter_limit: f64,
+    join_fallback: JoinFallback,
+}
+
+impl Default for StrokeDefaults {
+    fn default() -> Self {
+        Self {
+            // Matches the After Effects default.
+            miter_limit: 4.0,
+            join_fallback: JoinFallback::default(),
+        }
+    }
+}
+
+/// Evaluates a composition and paints it onto a [`Canvas`].
+///
+/// This is the evaluation walk shared by every consumer of frames: velato's
+/// renderer paints into vello scenes, and a [`Digest`] observes what would
+/// be drawn without a renderer. A painter retains scratch buffers sized for
+/// the most complex frame it has painted, and cached instances only for the
+/// frame currently being painted.
+pub struct Painter<C> {
+    batch: Batch,
+    mask_elements: Vec<PathEl>,
+    stroke_defaults: StrokeDefaults,
+    options: RenderOptions,
+    instance_caching: bool,
+    group_isolation: bool,
+    displacement_maps: bool,
+    /// Scratch buffers for the content of displacement maps.
+    map_batch: Batch,
+    placeholders: bool,
+    placeholder_font: Option<Font>,
+    /// True while rendering the samples of a layer with motion blur, which
+    /// are not blurred again.
+    sampling_motion: bool,
+    /// True while a knockout layer erases the layers below it.
+    knocking_out: bool,
+    /// Seed of the noise sampled by wiggles.
+    seed: u64,
+    /// Painted precomposition instances for the current frame, keyed by
+    /// asset name, local frame and alpha.
+    instance_cache: HashMap<(String, u64, u64), C>,
+}
+
+impl<C: Canvas> Default for Painter<C> {
+    fn default() -> Self {
+        Self {
+            batch: Batch::default(),
+            mask_elements: vec![],
+            stroke_defaults: StrokeDefaults::default(),
+            options: RenderOptions::default(),
+            instance_caching: false,
+            group_isolation: false,
+            displacement_maps: false,
+            map_batch: Batch::default(),
+            placeholders: false,
+            placeholder_font: None,
+            sampling_motion: false,
+            knocking_out: false,
+            seed: 0,
+            instance_cache: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Canvas> Painter<C> {
+    /// Creates a new painter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the miter limit used for strokes that do not specify one.
+    pub fn set_default_miter_limit(&mut self, miter_limit: f64) {
+        self.stroke_defaults.miter_limit = miter_limit;
+    }
+
+    /// Returns the miter limit used for strokes that do not specify one.
+    pub fn default_miter_limit(&self) -> f64 {
+        self.stroke_defaults.miter_limit
+    }
+
+    /// Sets the policy for falling back to bevel joins on sharp corners.
+    pub fn set_join_fallback(&mut self, join_fallback: JoinFallback) {
+        self.stroke_defaults.join_fallback = join_fallback;
+    }
+
+    /// Returns the policy for falling back to bevel joins on sharp corners.
+    pub fn join_fallback(&self) -> JoinFallback {
+        self.stroke_defaults.join_fallback
+    }
+
+    /// Sets the options that change how compositions are rendered.
+    pub fn set_render_options(&mut self, options: RenderOptions) {
+        self.options = options;
+    }
+
+    /// Returns the options that change how compositions are rendered.
+    pub fn render_options(&self) -> RenderOptions {
+        self.options
+    }
+
+    /// Sets whether identical precomposition instances are shared within a
+    /// frame.
+    ///
+    /// When enabled, each precomposition is painted once per frame for every
+    /// distinct local time and opacity at which it appears, into a fragment
+    /// of the canvas that is appended with the transform of each instance.
+    /// This skips evaluating repeated precompositions, such as confetti, at
+    /// the cost of holding the fragments in memory for the duration of the
+    /// frame. Whether appending a fragment is cheaper than painting it again
+    /// depends on the canvas.
+    pub fn set_instance_caching(&mut self, enabled: bool) {
+        self.instance_caching = enabled;
+        if !enabled {
+            self.instance_cache = HashMap::new();
+        }
+    }
+
+    /// Returns true if identical precomposition instances are shared within
+    /// a frame.
+    pub fn instance_caching(&self) -> bool {
+        self.instance_caching
+    }
+
+    /// Sets whether semi-transparent shape groups are isolated.
+    ///
+    /// When enabled, a group with opacity that contains multiple fills or
+    /// strokes is rendered at full opacity into a separate layer which is
+    /// then composited with the group opacity. This matches After Effects,
+    /// where a stroke overlapping the fill of the same group does not
+    /// darken the overlap, but requires an additional layer per group.
+    pub fn set_group_isolation(&mut self, enabled: bool) {
+        self.group_isolation = enabled;
+    }
+
+    /// Returns true if semi-transparent shape groups are isolated.
+    pub fn group_isolation(&self) -> bool {
+        self.group_isolation
+    }
+
+    /// Sets whether displacement map effects are applied.
+    ///
+    /// vello cannot sample the pixels of another layer, so displacement is
+    /// approximated by moving the points of the paths of a shape layer by
+    /// amounts sampled from the colors of the map layer at each point. The
+    /// map is sampled in the space of the displaced layer, ignoring its own
+    /// transform, and must itself be a shape layer. Each point is tested
+    /// against every draw of the map, so this is much more expensive than
+    /// other effects and disabled by default.
+    pub fn set_displacement_maps(&mut self, enabled: bool) {
+        self.displacement_maps = enabled;
+    }
+
+    /// Returns true if displacement map effects are applied.
+    pub fn displacement_maps(&self) -> bool {
+        self.displacement_maps
+    }
+
+    /// Sets whether unsupported content is drawn as placeholders.
+    ///
+    /// This is a debugging aid for reviewing animations. When enabled,
+    /// layers with text, images, or effects that cannot be rendered are
+    /// covered by a crossed-out rectangle, so that missing content is not
+    /// mistaken for an intentional gap. Placeholders are labeled with the
+    /// name of the layer and what is missing if a font is set with
+    /// [`Painter::set_placeholder_font`].
+    pub fn set_placeholders(&mut self, enabled: bool) {
+        self.placeholders = enabled;
+    }
+
+    /// Returns true if unsupported content is drawn as placeholders.
+    pub fn placeholders(&self) -> bool {
+        self.placeholders
+    }
+
+    /// Sets the font used to label placeholders. Canvases have no built-in
+    /// font, so placeholders are unlabeled without one.
+    pub fn set_placeholder_font(&mut self, font: Option<Font>) {
+        self.placeholder_font = font;
+    }
+
+    /// Returns the font used to label placeholders.
+    pub fn placeholder_font(&self) -> Option<&Font> {
+        self.placeholder_font.as_ref()
+    }
+
+    /// Sets the seed of the noise that moves properties with a `wiggle`
+    /// expression.
+    ///
+    /// Wiggles depend only on the seed and the frame, so renderers with the
+    /// same seed produce the same motion on every run. The default seed is
+    /// zero.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the seed of the noise that moves properties with a `wiggle`
+    /// expression.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns a painter for another kind of canvas with the same settings,
+    /// and empty scratch buffers and instance cache.
+    pub fn for_canvas<D: Canvas>(&self) -> Painter<D> {
+        Painter {
+            stroke_defaults: self.stroke_defaults,
+            options: self.options,
+            instance_caching: self.instance_caching,
+            group_isolation: self.group_isolation,
+            displacement_maps: self.displacement_maps,
+            placeholders: self.placeholders,
+            placeholder_font: self.placeholder_font.clone(),
+            seed: self.seed,
+            ..Painter::default()
+        }
+    }
+
+    /// Paints the animation at a given frame onto a canvas.
+    ///
+    /// Frames before the start of the animation display the first frame.
+    /// Frames at or after the end display the last frame, so the end of the
+    /// frame range can be used to show the final state of the animation.
+    pub fn paint(
+        &mut self,
+        animation: &Composition,
+        frame: f64,
+        transform: Affine,
+        alpha: f64,
+        canvas: &mut C,
+    ) {
+        let frame = clamp_frame(&animation.frames, frame);
+        let transform = transform * self.options.transform(animation);
+        self.batch.clear();
+        self.instance_cache.clear();
+        self.push_root(animation, transform, canvas);
+        for (index, layer) in animation.layers.iter().enumerate().rev() {
+            if layer.is_mask {
+                continue;
+            }
+            self.render_root_layer(animation, index, transform, alpha, frame, canvas);
+        }
+        canvas.pop_layer();
+    }
+
+    /// Paints the animation as [`paint`](Self::paint) does, except that
+    /// each root layer is painted alone onto `scratch`.
+    ///
+    /// After each layer, `painted` is called with the canvas, the scratch
+    /// canvas, the index of the layer and the bounds, in canvas coordinates,
+    /// of everything it drew, and should move the layer from the scratch
+    /// canvas onto the canvas. Precomposition instances are not shared,
+    /// since the bounds of every draw are needed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn paint_layers(
+        &mut self,
+        animation: &Composition,
+        frame: f64,
+        transform: Affine,
+        alpha: f64,
+        canvas: &mut C,
+        scratch: &mut C,
+        mut painted: impl FnMut(&mut C, &mut C, usize, Option<Rect>),
+    ) {
+        let frame = clamp_frame(&animation.frames, frame);
+        let transform = transform * self.options.transform(animation);
+        self.batch.clear();
+        self.instance_cache.clear();
+        self.push_root(animation, transform, canvas);
+        self.batch.bounds = Some(None);
+        for (index, layer) in animation.layers.iter().enumerate().rev() {
+            if layer.is_mask {
+                continue;
+            }
+            self.render_root_layer(animation, index, transform, alpha, frame, scratch);
+            let bounds = self.batch.bounds.replace(None).flatten();
+            painted(canvas, scratch, index, bounds);
+        }
+        self.batch.bounds = None;
+        canvas.pop_layer();
+    }
+
+    /// Paints the animation at a given frame onto a canvas, using a second
+    /// animation as a matte of the given mode.
+    ///
+    /// Both animations are evaluated at the same frame and with the same
+    /// transform, and a matte whose frame range ends earlier holds its last
+    /// frame, as [`paint`](Self::paint) does. This is useful for transitions
+    /// where a wipe animation reveals arbitrary content.
+    #[allow(clippy::too_many_arguments)]
+    pub fn paint_with_matte(
+        &mut self,
+        content: &Composition,
+        matte: &Composition,
+        mode: Matte,
+        frame: f64,
+        transform: Affine,
+        alpha: f64,
+        canvas: &mut C,
+    ) {
+        let clip = Rect::new(0.0, 0.0, content.width as _, content.height as _);
+        let clip_transform = transform * content.base_transform;
+        // Extra layer to isolate blending for the matte
+        canvas.push_layer(Mix::Normal, 1.0, clip_transform, &clip);
+        let luma_to_alpha = self.batch.luma_to_alpha;
+        self.batch.luma_to_alpha |= matches!(mode, Matte::Luma | Matte::InvertLuma);
+        self.paint(matte, frame, transform, 1.0, canvas);
+        self.batch.luma_to_alpha = luma_to_alpha;
+        canvas.push_layer(matte_blend(mode), 1.0, clip_transform, &clip);
+        self.paint(content, frame, transform, alpha, canvas);
+        canvas.pop_layer();
+        canvas.pop_layer();
+    }
+
+    /// Pushes the layer that clips the composition to its bounds.
+    fn push_root(&mut self, animation: &Composition, transform: Affine, canvas: &mut C) {
+        canvas.push_layer(
+            root_mix(animation),
+            1.0,
+            transform * animation.base_transform,
+            &Rect::new(0.0, 0.0, animation.width as _, animation.height as _),
+        );
+    }
+
+    /// Renders a root layer with the adjustment layers above it, unless it
+    /// is hidden.
+    fn render_root_layer(
+        &mut self,
+        animation: &Composition,
+        index: usize,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        if animation.layers[index].hidden {
+            return;
+        }
+        let recolors = self.push_adjustments(animation, &animation.layers, index, frame);
+        let layer = &animation.layers[index];
+        self.render_layer(
+            animation,
+            &animation.layers,
+            layer,
+            transform,
+            alpha,
+            frame,
+            canvas,
+        );
+        self.batch.recolors.truncate(recolors);
+    }
+
+    /// Renders a layer with its matte, masks and effects.
+    ///
+    /// Hidden layers are skipped by the callers rather than here, since a
+    /// hidden track matte still mattes the layer it applies to.
+    #[allow(clippy::too_many_arguments)]
+    fn render_layer(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        if !layer.is_active(frame) {
+            return;
+        }
+        let parent_transform = transform;
+        let full_rect = Rect::new(0.0, 0.0, animation.width as f64, animation.height as f64);
+        // Root layers include the base transform while the layers of
+        // instances do not, so cover the composition in both spaces.
+        let full_rect = full_rect.union(animation.base_transform.transform_rect_bbox(full_rect));
+        let samples = self.options.motion_blur_samples;
+        if layer.motion_blur && samples > 1 && !self.sampling_motion {
+            // vello has no blur, so motion blur is the average of the layer
+            // at times spread over the shutter interval, composited
+            // additively like soft shadows.
+            self.sampling_motion = true;
+            canvas.push_layer(Mix::Normal, 1.0, parent_transform, &full_rect);
+            let weight = 1.0 / samples as f32;
+            for frame in animation.shutter.sample_frames(frame, samples) {
+                canvas.push_layer(Compose::Plus, weight, parent_transform, &full_rect);
+                self.render_layer(
+                    animation,
+                    layer_set,
+                    layer,
+                    parent_transform,
+                    alpha,
+                    frame,
+                    canvas,
+                );
+                canvas.pop_layer();
+            }
+            canvas.pop_layer();
+            self.sampling_motion = false;
+            return;
+        }
+        if layer.knockout && !self.knocking_out {
+            // Knockout layers erase the layers below them within their
+            // composition with their own coverage, then draw normally, so
+            // that they composite onto transparency.
+            self.knocking_out = true;
+            canvas.push_layer(Compose::DestOut, 1.0, parent_transform, &full_rect);
+            self.render_layer(
+                animation,
+                layer_set,
+                layer,
+                parent_transform,
+                alpha,
+                frame,
+                canvas,
+            );
+            canvas.pop_layer();
+            self.knocking_out = false;
+        }
+        let transform = self.compute_transform(
+            layer_set,
+            layer,
+            parent_transform,
+            frame,
+            animation.frame_rate,
+        );
+        let composite = layer_composite(layer);
+        if let Some(mode) = composite {
+            canvas.push_layer(mode, 1.0, parent_transform, &full_rect);
+        }
+        if let Some((matte, mask_index)) = layer.mask_layer {
+            // Extra layer to isolate blending for the matte
+            canvas.push_layer(Mix::Normal, 1.0, parent_transform, &full_rect);
+            if let Some(mask) = layer_set.get(mask_index) {
+                // vello has no luminance masks, so luma mattes are drawn with
+                // the luminance of each brush as its alpha. This is exact
+                // unless translucent parts of the matte overlap.
+                let luma_to_alpha = self.batch.luma_to_alpha;
+                self.batch.luma_to_alpha |= matches!(matte, Matte::Luma | Matte::InvertLuma);
+                self.render_layer(
+                    animation,
+                    layer_set,
+                    mask,
+                    parent_transform,
+                    alpha,
+                    frame,
+                    canvas,
+                );
+                self.batch.luma_to_alpha = luma_to_alpha;
+            }
+            // The content is composited onto the matte so that only the
+            // parts covered (or uncovered) by the matte remain.
+            canvas.push_layer(matte_blend(matte), 1.0, parent_transform, &full_rect);
+        }
+        let alpha = alpha * layer.evaluate_opacity(frame, animation.frame_rate, self.seed) / 100.0;
+        let wipe_layers = push_wipes(layer, animation, transform, frame, canvas);
+        let mask_layers = self.push_masks(
+            &layer.masks,
+            transform,
+            parent_transform,
+            &full_rect,
+            frame,
+            canvas,
+        );
+        let hide_content = self.render_effects(
+            animation,
+            layer_set,
+            layer,
+            parent_transform,
+            transform,
+            alpha,
+            frame,
+            &full_rect,
+            canvas,
+        );
+        self.render_styles(
+            animation,
+            layer_set,
+            layer,
+            parent_transform,
+            transform,
+            alpha,
+            frame,
+            &full_rect,
+            canvas,
+        );
+        if !hide_content {
+            // Fill and levels effects recolor the content, but not its
+            // shadows. The last recolor is applied first, so effects are
+            // pushed in reverse to apply them in order and before those of
+            // enclosing layers.
+            let recolors = self.batch.recolors.len();
+            for effect in layer.effects.iter().rev() {
+                match effect {
+                    Effect::Fill(fill) => {
+                        let color = fill.evaluate(frame);
+                        self.batch.recolors.push(Recolor::Tint(color));
+                    }
+                    Effect::Levels(levels) => {
+                        let levels = levels.evaluate(frame);
+                        self.batch.recolors.push(Recolor::Levels(levels));
+                    }
+                    _ => {}
+                }
+            }
+            let reveal_layers = self.push_reveals(layer, transform, frame, canvas);
+            let inner_shadows = has_inner_shadows(layer);
+            if inner_shadows {
+                canvas.push_layer(Mix::Normal, 1.0, parent_transform, &full_rect);
+            }
+            self.render_content(animation, layer_set, layer, transform, alpha, frame, canvas);
+            if inner_shadows {
+                self.render_inner_shadows(
+                    animation,
+                    layer_set,
+                    layer,
+                    parent_transform,
+                    transform,
+                    frame,
+                    &full_rect,
+                    canvas,
+                );
+                canvas.pop_layer();
+            }
+            for _ in 0..reveal_layers {
+                canvas.pop_layer();
+            }
+            self.batch.recolors.truncate(recolors);
+        }
+        for _ in 0..mask_layers {
+            canvas.pop_layer();
+        }
+        // Strokes outline the masks, so they are drawn outside of them.
+        self.render_strokes(layer, transform, alpha, frame, canvas);
+        let isolation_layers =
+            layer.mask_layer.is_some() as usize * 2 + composite.is_some() as usize;
+        for _ in 0..wipe_layers + isolation_layers {
+            canvas.pop_layer();
+        }
+        // Placeholders are drawn over everything else so that they are not
+        // hidden by the masks of the layer, but not into mattes.
+        if self.placeholders && !layer.is_mask && !layer.unsupported.is_empty() {
+            self.render_placeholder(animation, layer, transform, canvas);
+        }
+    }
+
+    /// Draws a crossed-out rectangle over the bounds of a layer with
+    /// unsupported content.
+    fn render_placeholder(
+        &mut self,
+        animation: &Composition,
+        layer: &Layer,
+        transform: Affine,
+        canvas: &mut C,
+    ) {
+        let bounds = layer_bounds(layer, animation);
+        if let Some(damage) = &mut self.batch.bounds {
+            let rect = transform.transform_rect_bbox(
+                bounds.inflate(PLACEHOLDER_STROKE_WIDTH, PLACEHOLDER_STROKE_WIDTH),
+            );
+            *damage = Some(damage.map_or(rect, |damage| damage.union(rect)));
+        }
+        let fill = PLACEHOLDER_COLOR.with_alpha_factor(0.25);
+        canvas.fill(Fill::NonZero, transform, fill, None, &bounds);
+        let mut path = BezPath::new();
+        path.extend(bounds.path_elements(0.1));
+        path.move_to(bounds.origin());
+        path.line_to((bounds.x1, bounds.y1));
+        path.move_to((bounds.x1, bounds.y0));
+        path.line_to((bounds.x0, bounds.y1));
+        let style = fixed::Stroke::new(PLACEHOLDER_STROKE_WIDTH);
+        canvas.stroke(&style, transform, PLACEHOLDER_COLOR, None, &path);
+        let Some(font) = &self.placeholder_font else {
+            return;
+        };
+        let missing = layer
+            .unsupported
+            .iter()
+            .map(|unsupported| unsupported.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let label = if layer.name.is_empty() {
+            missing
+        } else {
+            format!("{}: {missing}", layer.name)
+        };
+        let margin = PLACEHOLDER_STROKE_WIDTH * 2.0;
+        let origin = bounds.origin() + Vec2::new(margin, margin + PLACEHOLDER_FONT_SIZE as f64);
+        // Labels are clipped to the placeholder, which bounds its damage.
+        canvas.push_layer(Mix::Clip, 1.0, transform, &bounds);
+        let label_transform = transform * Affine::translate(origin.to_vec2());
+        canvas.draw_text(
+            font,
+            PLACEHOLDER_FONT_SIZE,
+            label_transform,
+            PLACEHOLDER_COLOR,
+            &label,
+        );
+        canvas.pop_layer();
+    }
+
+    /// Draws the effects of a layer beneath its content and returns true if
+    /// the content should be hidden.
+    #[allow(clippy::too_many_arguments)]
+    fn render_effects(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        parent_transform: Affine,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        clip: &Rect,
+        canvas: &mut C,
+    ) -> bool {
+        let mut hide_content = false;
+        if parent_transform.determinant() == 0.0 {
+            return hide_content;
+        }
+        for effect in &layer.effects {
+            match effect {
+                Effect::DropShadow(shadow) => {
+                    let shadow = shadow.evaluate(frame);
+                    hide_content |= shadow.shadow_only;
+                    self.render_shadow(
+                        animation,
+                        layer_set,
+                        layer,
+                        parent_transform,
+                        transform,
+                        alpha,
+                        frame,
+                        &shadow,
+                        clip,
+                        canvas,
+                    );
+                }
+                Effect::Stroke(stroke) => {
+                    hide_content |= stroke.paint_style == StrokePaintStyle::Transparent;
+                }
+                // Wipes are applied to the whole layer by `push_wipes`, and
+                // fills, levels and displacement to the content only.
+                Effect::LinearWipe(_)
+                | Effect::RadialWipe(_)
+                | Effect::Fill(_)
+                | Effect::Levels(_)
+                | Effect::DisplacementMap(_) => {}
+            }
+        }
+        hide_content
+    }
+
+    /// Draws a copy of the content of a layer in the color of a shadow,
+    /// offset in the space of the parent.
+    ///
+    /// vello has no blur, so soft shadows are the average of copies of the
+    /// content spread over the blur radius, composited additively.
+    #[allow(clippy::too_many_arguments)]
+    fn render_shadow(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        parent_transform: Affine,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        shadow: &fixed::DropShadow,
+        clip: &Rect,
+        canvas: &mut C,
+    ) {
+        // Shadows are offset in the space of the parent so that they are
+        // unaffected by the rotation and scale of the layer itself.
+        let local_transform = parent_transform.inverse() * transform;
+        let shadow_transform =
+            |offset| parent_transform * Affine::translate(offset) * local_transform;
+        self.batch.recolors.push(Recolor::Tint(shadow.color));
+        if shadow.radius == 0.0 {
+            let transform = shadow_transform(shadow.offset);
+            self.render_content(animation, layer_set, layer, transform, alpha, frame, canvas);
+        } else {
+            canvas.push_layer(Mix::Normal, 1.0, parent_transform, clip);
+            let weight = 1.0 / (SHADOW_SAMPLES * SHADOW_SAMPLES) as f32;
+            let t = |k: usize| 2.0 * k as f64 / (SHADOW_SAMPLES - 1) as f64 - 1.0;
+            for i in 0..SHADOW_SAMPLES {
+                for j in 0..SHADOW_SAMPLES {
+                    let offset = shadow.offset + Vec2::new(t(i), t(j)) * shadow.radius;
+                    canvas.push_layer(Compose::Plus, weight, parent_transform, clip);
+                    let transform = shadow_transform(offset);
+                    self.render_content(
+                        animation, layer_set, layer, transform, alpha, frame, canvas,
+                    );
+                    canvas.pop_layer();
+                }
+            }
+            canvas.pop_layer();
+        }
+        self.batch.recolors.pop();
+    }
+
+    /// Draws the layer styles that are beneath the content of a layer:
+    /// shadows, glows and strokes.
+    #[allow(clippy::too_many_arguments)]
+    fn render_styles(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        parent_transform: Affine,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        clip: &Rect,
+        canvas: &mut C,
+    ) {
+        if parent_transform.determinant() == 0.0 {
+            return;
+        }
+        for style in &layer.styles {
+            let shadow = match style {
+                LayerStyle::DropShadow(shadow) => shadow.evaluate(frame),
+                LayerStyle::OuterGlow(glow) => {
+                    let glow = glow.evaluate(frame);
+                    fixed::DropShadow {
+                        color: glow.color,
+                        offset: Vec2::ZERO,
+                        radius: glow.radius,
+                        shadow_only: false,
+                    }
+                }
+                LayerStyle::Stroke(stroke) => {
+                    // Outlines are centered on the edges of the content,
+                    // which covers their inner half.
+                    let stroke = stroke.evaluate(frame);
+                    if stroke.size > 0.0 {
+                        self.batch.outline = Some(stroke.size * 2.0);
+                        self.batch.recolors.push(Recolor::Tint(stroke.color));
+                        self.render_content(
+                            animation, layer_set, layer, transform, alpha, frame, canvas,
+                        );
+                        self.batch.recolors.pop();
+                        self.batch.outline = None;
+                    }
+                    continue;
+                }
+                // Inner shadows are drawn over the content by
+                // `render_inner_shadows`.
+                LayerStyle::InnerShadow(_) => continue,
+            };
+            self.render_shadow(
+                animation,
+                layer_set,
+                layer,
+                parent_transform,
+                transform,
+                alpha,
+                frame,
+                &shadow,
+                clip,
+                canvas,
+            );
+        }
+    }
+
+    /// Draws the inner shadows of a layer over its content, which must be
+    /// isolated so that they only cover the content.
+    #[allow(clippy::too_many_arguments)]
+    fn render_inner_shadows(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        parent_transform: Affine,
+        transform: Affine,
+        frame: f64,
+        clip: &Rect,
+        canvas: &mut C,
+    ) {
+        if parent_transform.determinant() == 0.0 {
+            return;
+        }
+        for style in &layer.styles {
+            let LayerStyle::InnerShadow(shadow) = style else {
+                continue;
+            };
+            let shadow = shadow.evaluate(frame);
+            // The shadow color fills the content, except where an offset
+            // copy of the content erases it.
+            canvas.push_layer(Compose::SrcAtop, 1.0, parent_transform, clip);
+            canvas.fill(Fill::NonZero, parent_transform, shadow.color, None, clip);
+            canvas.push_layer(Compose::DestOut, 1.0, parent_transform, clip);
+            let eraser = fixed::DropShadow {
+                color: Color::BLACK,
+                ..shadow
+            };
+            self.render_shadow(
+                animation,
+                layer_set,
+                layer,
+                parent_transform,
+                transform,
+                1.0,
+                frame,
+                &eraser,
+                clip,
+                canvas,
+            );
+            canvas.pop_layer();
+            canvas.pop_layer();
+        }
+    }
+
+    /// Pushes clips that show the content of a layer only beneath its
+    /// revealing strokes and returns the number of layers pushed.
+    fn push_reveals(
+        &mut self,
+        layer: &Layer,
+        transform: Affine,
+        frame: f64,
+        canvas: &mut C,
+    ) -> usize {
+        let mut count = 0;
+        for effect in &layer.effects {
+            let Effect::Stroke(effect) = effect else {
+                continue;
+            };
+            if effect.paint_style != StrokePaintStyle::Reveal {
+                continue;
+            }
+            let stroke = effect.evaluate(frame);
+            let path = self.stroke_effect_path(layer, effect, &stroke, frame);
+            let outline = kurbo::stroke(
+                path.iter(),
+                &stroke_effect_style(&stroke),
+                &Default::default(),
+                0.1,
+            );
+            canvas.push_layer(
+                Mix::Clip,
+                stroke.color.a as f32 / 255.0,
+                transform,
+                &outline,
+            );
+            count += 1;
+        }
+        count
+    }
+
+    /// Draws the strokes of a layer that are painted over or in place of
+    /// its content.
+    fn render_strokes(
+        &mut self,
+        layer: &Layer,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        // Draws are rendered in reverse, so later strokes are pushed first
+        // to draw them on top.
+        for effect in layer.effects.iter().rev() {
+            let Effect::Stroke(effect) = effect else {
+                continue;
+            };
+            if effect.paint_style == StrokePaintStyle::Reveal {
+                continue;
+            }
+            let stroke = effect.evaluate(frame);
+            let path = self.stroke_effect_path(layer, effect, &stroke, frame);
+            let geometry_start = self.batch.geometries.len();
+            self.batch.push_path(&path, transform);
+            self.batch.push_draw_data(DrawData {
+                stroke: Some(stroke_effect_style(&stroke)),
+                brush: stroke.color.into(),
+                alpha,
+                geometry: geometry_start..self.batch.geometries.len(),
+            });
+        }
+        if !self.batch.draws.is_empty() {
+            self.batch.render(canvas);
+            self.batch.clear();
+        }
+    }
+
+    /// Returns the outline of the masks selected by a stroke effect, trimmed
+    /// to the extent of the stroke.
+    fn stroke_effect_path(
+        &mut self,
+        layer: &Layer,
+        effect: &animated::StrokeEffect,
+        stroke: &fixed::StrokeEffect,
+        frame: f64,
+    ) -> BezPath {
+        let masks = match effect.mask {
+            Some(index) => layer.masks.get(index..=index).unwrap_or_default(),
+            None => &layer.masks,
+        };
+        let mut path = BezPath::new();
+        for mask in masks {
+            mask.geometry.evaluate(frame, &mut self.mask_elements);
+            stroke.trim(&self.mask_elements, &mut path);
+            self.mask_elements.clear();
+        }
+        path
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_content(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        layer: &Layer,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        match &layer.content {
+            // Adjustment layers recolor the layers below them instead.
+            Content::None | Content::Adjustment => {}
+            Content::Instance { name, .. } => {
+                if let (Some(asset_layers), Some(timeline)) = (
+                    animation.assets.get(name),
+                    layer.timeline(animation.frame_rate),
+                ) {
+                    let frame = timeline.local_frame(frame);
+                    let bounds = layer_bounds(layer, animation);
+                    // Fragments are always encoded with their original
+                    // colors and strokes, so instances within luma mattes,
+                    // shadows, stroke styles or recolored layers are not
+                    // cached. Neither are instances while tracking
+                    // damage, which requires the bounds of every draw.
+                    if self.instance_caching
+                        && !self.batch.luma_to_alpha
+                        && self.batch.recolors.is_empty()
+                        && self.batch.outline.is_none()
+                        && self.batch.bounds.is_none()
+                    {
+                        let key = (name.clone(), frame.to_bits(), alpha.to_bits());
+                        // Take the fragment out of the cache while rendering
+                        // since nested instances may also be cached.
+                        let fragment = match self.instance_cache.remove(&key) {
+                            Some(fragment) => fragment,
+                            None => {
+                                let mut fragment = canvas.fragment();
+                                self.render_instance(
+                                    animation,
+                                    asset_layers,
+                                    bounds,
+                                    Affine::IDENTITY,
+                                    alpha,
+                                    frame,
+                                    &mut fragment,
+                                );
+                                fragment
+                            }
+                        };
+                        canvas.append(&fragment, Some(transform));
+                        self.instance_cache.insert(key, fragment);
+                    } else {
+                        self.render_instance(
+                            animation,
+                            asset_layers,
+                            bounds,
+                            transform,
+                            alpha,
+                            frame,
+                            canvas,
+                        );
+                    }
+                }
+            }
+            Content::Shape(shapes) => {
+                self.render_shapes(shapes, transform, alpha, frame);
+                if self.displacement_maps {
+                    self.displace(layer_set, layer, transform, frame);
+                }
+                self.batch.render(canvas);
+                self.batch.clear();
+            }
+            Content::Solid {
+                color,
+                width,
+                height,
+            } => {
+                self.render_solid(*color, *width, *height, transform, alpha);
+                self.batch.render(canvas);
+                self.batch.clear();
+            }
+        }
+    }
+
+    /// Batches the rectangle of a solid color layer, so that it is drawn
+    /// like a filled shape within mattes, shadows and color effects.
+    fn render_solid(
+        &mut self,
+        color: Color,
+        width: f64,
+        height: f64,
+        transform: Affine,
+        alpha: f64,
+    ) {
+        let start = self.batch.geometries.len();
+        let rect = Rect::new(0.0, 0.0, width, height);
+        self.batch.push_path(&rect.to_path(0.1), transform);
+        self.batch.push_draw_data(DrawData {
+            stroke: None,
+            brush: color.into(),
+            alpha,
+            geometry: start..self.batch.geometries.len(),
+        });
+    }
+
+    /// Pushes layers that restrict subsequent drawing to the region covered
+    /// by the given masks and returns the number of layers pushed.
+    ///
+    /// Masks are combined in order, starting from an empty region, or the
+    /// full region if the first mask subtracts or intersects. Lighten and
+    /// darken are treated as add and intersect, which is exact unless masks
+    /// are semi-transparent or feathered.
+    fn push_masks(
+        &mut self,
+        masks: &[Mask],
+        transform: Affine,
+        clip_transform: Affine,
+        clip: &Rect,
+        frame: f64,
+        canvas: &mut C,
+    ) -> usize {
+        let masks = masks.iter().filter(|mask| mask.mode != MaskMode::None);
+        let Some(first) = masks.clone().next() else {
+            return 0;
+        };
+        if masks.clone().count() == 1
+            && !first.inverted
+            && first.feather.evaluate(frame) <= 0.0
+            && matches!(
+                first.mode,
+                MaskMode::Add | MaskMode::Intersect | MaskMode::Lighten | MaskMode::Difference
+            )
+        {
+            // A single mask is equivalent to a clip.
+            let alpha = first.opacity.evaluate(frame) / 100.0;
+            self.eval_mask(first, frame, 0.0);
+            canvas.push_layer(
+                Mix::Clip,
+                alpha as f32,
+                transform,
+                &self.mask_elements.as_slice(),
+            );
+            self.mask_elements.clear();
+            return 1;
+        }
+        // Build the coverage of the masks in the alpha channel of an
+        // isolated layer, then draw the content onto it.
+        canvas.push_layer(Mix::Normal, 1.0, clip_transform, clip);
+        if matches!(first.mode, MaskMode::Subtract | MaskMode::Intersect) {
+            canvas.fill(Fill::NonZero, clip_transform, Color::BLACK, None, clip);
+        }
+        for mask in masks {
+            let compose = match mask.mode {
+                MaskMode::None => continue,
+                MaskMode::Add | MaskMode::Lighten => None,
+                MaskMode::Subtract => Some(Compose::DestOut),
+                MaskMode::Intersect | MaskMode::Darken => Some(Compose::DestIn),
+                MaskMode::Difference => Some(Compose::Xor),
+            };
+            if let Some(compose) = compose {
+                canvas.push_layer(compose, 1.0, clip_transform, clip);
+            }
+            self.fill_mask(mask, transform, clip_transform, clip, frame, canvas);
+            if compose.is_some() {
+                canvas.pop_layer();
+            }
+        }
+        canvas.push_layer(Compose::SrcIn, 1.0, clip_transform, clip);
+        2
+    }
+
+    /// Fills the region covered by a mask with its opacity.
+    ///
+    /// vello has no blur, so feathered masks are approximated by filling a
+    /// series of offset outlines spanning the feather width, which together
+    /// form a linear ramp of coverage across the edge.
+    fn fill_mask(
+        &mut self,
+        mask: &Mask,
+        transform: Affine,
+        clip_transform: Affine,
+        clip: &Rect,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        let alpha = mask.opacity.evaluate(frame) / 100.0;
+        let feather = mask.feather.evaluate(frame);
+        let fill_region = |this: &mut Self, canvas: &mut C, offset: f64, alpha: f64| {
+            this.eval_mask(mask, frame, offset);
+            let fill = if mask.inverted {
+                // Surround the mask with the full region, in the coordinate
+                // space of the mask, so that even-odd filling covers
+                // everything outside it.
+                if transform.determinant() != 0.0 {
+                    let to_mask = transform.inverse() * clip_transform;
+                    this.mask_elements
+                        .extend(clip.path_elements(0.1).map(|el| to_mask * el));
+                }
+                Fill::EvenOdd
+            } else {
+                Fill::NonZero
+            };
+            let color = Color::BLACK.with_alpha_factor(alpha as f32);
+            canvas.fill(fill, transform, color, None, &this.mask_elements.as_slice());
+            this.mask_elements.clear();
+        };
+        if feather <= 0.0 {
+            fill_region(self, canvas, 0.0, alpha);
+            return;
+        }
+        canvas.push_layer(Mix::Normal, alpha as f32, clip_transform, clip);
+        // Fill from the largest region to the smallest, which is the
+        // outermost outline unless the mask is inverted.
+        let direction = if mask.inverted { -1.0 } else { 1.0 };
+        for step in 0..FEATHER_STEPS {
+            let t = (step as f64 + 0.5) / FEATHER_STEPS as f64;
+            let offset = direction * feather * (0.5 - t);
+            // Each fill covers the remaining fraction of the ramp so that
+            // the combined coverage grows linearly.
+            fill_region(self, canvas, offset, 1.0 / (FEATHER_STEPS - step) as f64);
+        }
+        canvas.pop_layer();
+    }
+
+    /// Evaluates the geometry of a mask, including its expansion and an
+    /// additional offset, into `mask_elements`.
+    fn eval_mask(&mut self, mask: &Mask, frame: f64, offset: f64) {
+        let expansion = mask.expansion.evaluate(frame) + offset;
+        if expansion == 0.0 {
+            mask.geometry.evaluate(frame, &mut self.mask_elements);
+            return;
+        }
+        mask.geometry
+            .evaluate(frame, &mut self.batch.modify_elements);
+        let offset = fixed::PathModifier::OffsetPath(fixed::OffsetPath {
+            amount: expansion,
+            join: Join::Miter,
+            // Matches the After Effects default.
+            miter_limit: 4.0,
+        });
+        offset.apply(&self.batch.modify_elements, &mut self.mask_elements);
+        self.batch.modify_elements.clear();
+    }
+
+    /// Moves the points of the paths in the batch by the displacement map
+    /// effects of a layer.
+    fn displace(&mut self, layer_set: &[Layer], layer: &Layer, transform: Affine, frame: f64) {
+        for effect in &layer.effects {
+            let Effect::DisplacementMap(effect) = effect else {
+                continue;
+            };
+            let Some(map_layer) = effect.layer.and_then(|index| layer_set.get(index)) else {
+                continue;
+            };
+            let Content::Shape(shapes) = &map_layer.content else {
+                continue;
+            };
+            if !map_layer.is_active(frame) {
+                continue;
+            }
+            let map = effect.evaluate(frame);
+            // The map is collected into its own batch with its original
+            // colors and without isolated groups.
+            std::mem::swap(&mut self.batch, &mut self.map_batch);
+            let group_isolation = std::mem::replace(&mut self.group_isolation, false);
+            self.render_shapes(shapes, Affine::IDENTITY, 1.0, frame);
+            self.group_isolation = group_isolation;
+            std::mem::swap(&mut self.batch, &mut self.map_batch);
+            let sampler = MapSampler::new(&self.map_batch);
+            self.map_batch.clear();
+            self.batch
+                .displace(transform, |point| map.offset(sampler.sample(point)));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_instance(
+        &mut self,
+        animation: &Composition,
+        asset_layers: &[Layer],
+        bounds: Rect,
+        transform: Affine,
+        alpha: f64,
+        frame: f64,
+        canvas: &mut C,
+    ) {
+        // Layers of a precomposition blend with each other, but not with
+        // the layers below the instance, so they are isolated.
+        let isolate = has_blend_modes(asset_layers);
+        if isolate {
+            canvas.push_layer(Mix::Normal, 1.0, transform, &bounds);
+        }
+        for (index, asset_layer) in asset_layers.iter().enumerate().rev() {
+            if asset_layer.is_mask || asset_layer.hidden {
+                continue;
+            }
+            let recolors = self.push_adjustments(animation, asset_layers, index, frame);
+            self.render_layer(
+                animation,
+                asset_layers,
+                asset_layer,
+                transform,
+                alpha,
+                frame,
+                canvas,
+            );
+            self.batch.recolors.truncate(recolors);
+        }
+        if isolate {
+            canvas.pop_layer();
+        }
+    }
+
+    fn render_shapes(&mut self, shapes: &[Shape], transform: Affine, alpha: f64, frame: f64) {
+        // Groups are walked with an explicit stack rather than recursion, so
+        // deeply nested files cannot overflow the call stack.
+        let mut stack = vec![ShapeScope::new(shapes, transform, alpha, None, &self.batch)];
+        while let Some(scope) = stack.last_mut() {
+            let Some(shape) = scope.shapes.next() else {
+                if let Some(alpha) = scope.isolated {
+                    // Draws are rendered in reverse, so the start of an
+                    // isolated group is recorded last.
+                    self.batch.draws.push(DrawCommand::BeginGroup { alpha });
+                }
+                stack.pop();
+                continue;
+            };
+            let (transform, alpha) = (scope.transform, scope.alpha);
+            let (geometry_start, draw_start) = (scope.geometry_start, scope.draw_start);
+            match shape {
+                Shape::Group(shapes, group_transform) => {
+                    let (group_transform, group_alpha) =
+                        if let Some(GroupTransform { transform, opacity }) = group_transform {
+                            (
+                                transform.evaluate(frame).into_owned(),
+                                opacity.evaluate(frame) / 100.0,
+                            )
+                        } else {
+                            (Affine::IDENTITY, 1.0)
+                        };
+                    let transform = transform * group_transform;
+                    let scope = if self.group_isolation && group_alpha < 1.0 && may_overlap(shapes)
+                    {
+                        self.batch.draws.push(DrawCommand::EndGroup);
+                        ShapeScope::new(shapes, transform, alpha, Some(group_alpha), &self.batch)
+                    } else {
+                        ShapeScope::new(shapes, transform, alpha * group_alpha, None, &self.batch)
+                    };
+                    stack.push(scope);
+                }
+                Shape::Geometry(geometry) => {
+                    self.batch.push_geometry(geometry, transform, frame);
+                }
+                Shape::Draw(draw) => {
+                    self.batch.push_draw(
+                        draw,
+                        alpha,
+                        geometry_start,
+                        frame,
+                        &self.stroke_defaults,
+                        self.options.color_space,
+                    );
+                }
+                Shape::Repeater(repeater) => {
+                    let repeater = repeater.evaluate(frame);
+                    self.batch
+                        .repeat(repeater.as_ref(), geometry_start, draw_start);
+                }
+                Shape::Modifier(modifier) => {
+                    let modifier = modifier.evaluate(frame);
+                    self.batch.modify(modifier.as_ref(), geometry_start);
+                }
+                Shape::Hidden(_) => {}
+            }
+        }
+    }
+
+    /// Pushes the color effects of the active adjustment layers above the
+    /// layer at the given index onto the recolors of the batch, and returns
+    /// the previous number of recolors.
+    fn push_adjustments(
+        &mut self,
+        animation: &Composition,
+        layer_set: &[Layer],
+        index: usize,
+        frame: f64,
+    ) -> usize {
+        let len = self.batch.recolors.len();
+        // The nearest adjustment layer is applied first, so it is pushed
+        // last.
+        for layer in &layer_set[..index] {
+            if !matches!(layer.content, Content::Adjustment)
+                || layer.hidden
+                || !layer.is_active(frame)
+            {
+                continue;
+            }
+            let opacity = layer.evaluate_opacity(frame, animation.frame_rate, self.seed) / 100.0;
+            let recolors: Vec<_> = layer
+                .effects
+                .iter()
+                .rev()
+                .filter_map(|effect| match effect {
+                    Effect::Fill(fill) => Some(Recolor::Tint(fill.evaluate(frame))),
+                    Effect::Levels(levels) => Some(Recolor::Levels(levels.evaluate(frame))),
+                    _ => None,
+                })
+                .collect();
+            if opacity > 0.0 && !recolors.is_empty() {
+                self.batch
+                    .recolors
+                    .push(Recolor::Adjustment { recolors, opacity });
+            }
+        }
+        len
+    }
+
+    /// Computes the transform for a single layer. This currently chases the
+    /// full transform chain each time. If it becomes a bottleneck, we can
+    /// implement caching.
+    fn compute_transform(
+        &self,
+        layer_set: &[Layer],
+        layer: &Layer,
+        global_transform: Affine,
+        frame: f64,
+        frame_rate: f64,
+    ) -> Affine {
+        let mut transform = layer.evaluate_transform(frame, frame_rate, self.seed);
+        let mut parent_index = layer.parent;
+        let mut count = 0usize;
+        while let Some(index) = parent_index {
+            // We don't check for cycles at import time, so this heuristic
+            // prevents infinite loops.
+            if count >= layer_set.len() {
+                break;
+            }
+            if let Some(parent) = layer_set.get(index) {
+                parent_index = parent.parent;
+                transform = parent.evaluate_transform(frame, frame_rate, self.seed) * transform;
+                count += 1;
+            } else {
+                break;
+            }
+        }
+        global_transform * transform
+    }
+}
+
+/// Returns true if the shapes may produce more than one draw.
+fn may_overlap(shapes: &[Shape]) -> bool {
+    let mut count = 0;
+    let mut stack = vec![shapes.iter()];
+    while let Some(shapes) = stack.last_mut() {
+        let Some(shape) = shapes.next() else {
+            stack.pop();
+            continue;
+        };
+        match shape {
+            Shape::Group(shapes, _) => stack.push(shapes.iter()),
+            Shape::Draw(_) => count += 1,
+            // Repeated draws overlap each other.
+            Shape::Repeater(_) => count += 2,
+            _ => {}
+        }
+        if count > 1 {
+            return true;
+        }
+    }
+    false
+}
+
+impl Painter<Digest> {
+    /// Evaluates the animation at a given frame without rendering it and
+    /// returns a checksum of what would be drawn.
+    ///
+    /// This paints onto a [`Digest`], so it runs exactly the evaluation of
+    /// rendering, including transforms, animated values, geometry, mattes,
+    /// masks, effects and layer styles, but is cheap and needs no GPU. The
+    /// checksum is stable for a given build, which makes this suitable for
+    /// checking that evaluation is deterministic and does not panic across
+    /// entire catalogs of animations.
+    pub fn evaluate_only(&mut self, animation: &Composition, frame: f64) -> u64 {
+        self.evaluate_digest(animation, frame, None)
+    }
+
+    /// Evaluates the animation at a given frame as
+    /// [`evaluate_only`](Self::evaluate_only) does, and returns a digest of
+    /// the result that is stable across platforms.
+    ///
+    /// Floating point numbers are rounded to the nearest multiple of
+    /// `quantum` before they are accumulated, so that builds for different
+    /// targets agree despite differences in the last bits of libm functions
+    /// or fused operations. A quantum of `1.0 / 1024.0` suits values in
+    /// pixels, and `None` accumulates exact values. Values that fall close
+    /// to a rounding boundary may still round differently, so compare
+    /// digests over many frames rather than relying on a single one.
+    pub fn evaluate_digest(
+        &mut self,
+        animation: &Composition,
+        frame: f64,
+        quantum: Option<f64>,
+    ) -> u64 {
+        let mut digest = Digest::new(quantum);
+        self.paint(animation, frame, Affine::IDENTITY, 1.0, &mut digest);
+        digest.finish()
+    }
+}
+
+/// Shapes of a group being walked by [`Painter::render_shapes`].
+struct ShapeScope<'a> {
+    shapes: std::slice::Iter<'a, Shape>,
+    transform: Affine,
+    alpha: f64,
+    /// Alpha of the group if it is composited as a whole.
+    isolated: Option<f64>,
+    /// Start of the geometries of the group, which bounds its draws.
+    geometry_start: usize,
+    /// Start of the draws of the group, for repeaters.
+    draw_start: usize,
+}
+
+impl<'a> ShapeScope<'a> {
+    fn new(
+        shapes: &'a [Shape],
+        transform: Affine,
+        alpha: f64,
+        isolated: Option<f64>,
+        batch: &Batch,
+    ) -> Self {
+        Self {
+            shapes: shapes.iter(),
+            transform,
+            alpha,
+            isolated,
+            geometry_start: batch.geometries.len(),
+            draw_start: batch.draws.len(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum DrawCommand {
+    Draw(DrawData),
+    /// Start of an isolated group, composited with the given alpha.
+    BeginGroup {
+        alpha: f64,
+    },
+    /// End of an isolated group.
+    EndGroup,
+}
+
+#[derive(Clone, Debug)]
+struct DrawData {
+    stroke: Option<fixed::Stroke>,
+    brush: fixed::Brush,
+    alpha: f64,
+    /// Range into `ShapeBatch::geometries`
+    geometry: Range<usize>,
+}
+
+impl DrawData {
+    fn new(
+        draw: &Draw,
+        alpha: f64,
+        geometry: Range<usize>,
+        frame: f64,
+        defaults: &StrokeDefaults,
+        color_space: ColorSpace,
+    ) -> Self {
+        Self {
+            stroke: draw
+                .stroke
+                .as_ref()
+                .map(|stroke| eval_stroke(stroke, frame, defaults)),
+            brush: draw.brush.evaluate_in(1.0, frame, color_space).into_owned(),
+            alpha: alpha * draw.opacity.evaluate(frame) / 100.0,
+            geometry,
+        }
+    }
+}
+
+fn eval_stroke(stroke: &Stroke, frame: f64, defaults: &StrokeDefaults) -> fixed::Stroke {
+    let mut evaluated = stroke.evaluate(frame).into_owned();
+    if let Stroke::Animated(animated) = stroke {
+        if animated.miter_limit.is_none() {
+            evaluated.miter_limit = defaults.miter_limit;
+        }
+    }
+    if evaluated.join == Join::Miter {
+        evaluated.miter_limit = defaults.join_fallback.apply(evaluated.miter_limit);
+    }
+    evaluated
+}
+
+/// Pushes layers that restrict subsequent drawing to the regions left
+/// visible by the wipe effects of a layer and returns the number of layers
+/// pushed.
+///
+/// Wipes are evaluated over the bounds of the layer, which for layers
+/// without a size are those of the composition.
+fn push_wipes(
+    layer: &Layer,
+    animation: &Composition,
+    transform: Affine,
+    frame: f64,
+    canvas: &mut impl Canvas,
+) -> usize {
+    let bounds = layer_bounds(layer, animation);
+    let mut count = 0;
+    for effect in &layer.effects {
+        match effect {
+            Effect::LinearWipe(wipe) => match wipe.evaluate(frame).mask(bounds) {
+                (path, None) => {
+                    canvas.push_layer(Mix::Clip, 1.0, transform, &path);
+                    count += 1;
+                }
+                (path, Some(gradient)) => {
+                    // The content is composited onto the gradient so that
+                    // it fades across the feathered edge.
+                    canvas.push_layer(Mix::Normal, 1.0, transform, &path);
+                    canvas.fill(Fill::NonZero, transform, &gradient, None, &path);
+                    canvas.push_layer(Compose::SrcIn, 1.0, transform, &path);
+                    count += 2;
+                }
+            },
+            Effect::RadialWipe(wipe) => {
+                let wipe = wipe.evaluate(frame);
+                if wipe.feather <= 0.0 {
+                    let path = wipe.mask(bounds);
+                    canvas.push_layer(Mix::Clip, 1.0, transform, &path);
+                    count += 1;
+                    continue;
+                }
+                // vello has no blur, so the content is composited onto a
+                // series of sectors spanning the feather, like feathered
+                // masks.
+                let masks = wipe.feathered_masks(bounds, FEATHER_STEPS);
+                let clip = &masks[0];
+                canvas.push_layer(Mix::Normal, 1.0, transform, clip);
+                for (step, path) in masks.iter().enumerate() {
+                    let alpha = 1.0 / (FEATHER_STEPS - step) as f32;
+                    let color = Color::BLACK.with_alpha_factor(alpha);
+                    canvas.fill(Fill::NonZero, transform, color, None, path);
+                }
+                canvas.push_layer(Compose::SrcIn, 1.0, transform, clip);
+                count += 2;
+            }
+            Effect::DropShadow(_)
+            | Effect::Fill(_)
+            | Effect::Levels(_)
+            | Effect::Stroke(_)
+            | Effect::DisplacementMap(_) => {}
+        }
+    }
+    count
+}
+
+/// Returns the mode of the layer that composites a layer onto the layers
+/// below it, if it is not drawn directly.
+///
+/// Layers that preserve transparency only keep the parts over opaque parts
+/// of the layers below them, which combines with their blend mode.
+fn layer_composite(layer: &Layer) -> Option<BlendMode> {
+    match (layer.blend_mode, layer.preserve_transparency) {
+        (Some(mode), true) if mode.compose == Compose::SrcOver => {
+            Some(BlendMode::new(mode.mix, Compose::SrcAtop))
+        }
+        (Some(mode), _) => Some(mode),
+        (None, true) => Some(Compose::SrcAtop.into()),
+        (None, false) => None,
+    }
+}
+
+/// Returns true if any of the layers blends with the layers below it.
+fn has_blend_modes(layers: &[Layer]) -> bool {
+    layers.iter().any(|layer| layer.blend_mode.is_some())
+}
+
+/// Returns true if the layer has inner shadows, which are drawn onto its
+/// content in isolation.
+fn has_inner_shadows(layer: &Layer) -> bool {
+    layer
+        .styles
+        .iter()
+        .any(|style| matches!(style, LayerStyle::InnerShadow(_)))
+}
+
+/// Returns the mode of the layer that clips a composition, which also
+/// isolates it when its layers blend, so that they blend as if the
+/// composition were rendered onto transparency.
+fn root_mix(animation: &Composition) -> Mix {
+    if has_blend_modes(&animation.layers) {
+        Mix::Normal
+    } else {
+        Mix::Clip
+    }
+}
+
+/// Returns the bounds of a layer in its own space, which are those of the
+/// composition for layers without a size.
+fn layer_bounds(layer: &Layer, animation: &Composition) -> Rect {
+    if layer.width > 0.0 && layer.height > 0.0 {
+        Rect::new(0.0, 0.0, layer.width, layer.height)
+    } else {
+        Rect::new(0.0, 0.0, animation.width as f64, animation.height as f64)
+    }
+}
+
+/// Color of placeholders for unsupported content.
+const PLACEHOLDER_COLOR: Color = Color::rgb8(255, 0, 255);
+
+/// Width of the outline of placeholders.
+const PLACEHOLDER_STROKE_WIDTH: f64 = 2.0;
+
+/// Size of the font used to label placeholders.
+const PLACEHOLDER_FONT_SIZE: f32 = 12.0;
+
+/// Returns the style of a stroke effect, which is painted with a round
+/// brush.
+fn stroke_effect_style(stroke: &fixed::StrokeEffect) -> fixed::Stroke {
+    fixed::Stroke::new(stroke.width)
+        .with_caps(Cap::Round)
+        .with_join(Join::Round)
+}
+
+/// Number of outlines used to approximate the soft edge of a feathered mask.
+const FEATHER_STEPS: usize = 8;
+
+/// Number of copies along each axis used to approximate the blur of a soft
+/// shadow.
+const SHADOW_SAMPLES: usize = 3;
+
+/// Blend mode of the content of a layer over its matte.
+fn matte_blend(matte: Matte) -> BlendMode {
+    match matte {
+        Matte::Normal => Mix::Normal.into(),
+        Matte::Alpha | Matte::Luma => Compose::SrcIn.into(),
+        Matte::InvertAlpha | Matte::InvertLuma => Compose::SrcOut.into(),
+    }
+}
+
+/// Clamps a frame to the range of frames displayed by an animation.
+///
+/// The last displayed frame is one before the end, or the start frame for
+/// animations that are shorter than a single frame.
+fn clamp_frame(frames: &Range<f64>, frame: f64) -> f64 {
+    if frame >= frames.end {
+        (frames.end - 1.0).max(frames.start)
+    } else {
+        frame.max(frames.start)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct GeometryData {
+    /// Range into `ShapeBatch::elements`
+    elements: Range<usize>,
+    transform: Affine,
+    /// Offset in degrees added to trim paths, which differs between the
+    /// copies of a repeater.
+    trim_phase: f64,
+}
+
+/// Adjustment to the colors of draws.
+#[derive(Clone, Debug)]
+enum Recolor {
+    /// Replaces colors with the given color, multiplying their alpha.
+    Tint(Color),
+    /// Adjusts the levels of each channel.
+    Levels(fixed::Levels),
+    /// Applies the effects of an adjustment layer, mixed with the original
+    /// colors by the opacity of the layer. The last effect is applied first.
+    Adjustment {
+        recolors: Vec<Recolor>,
+        opacity: f64,
+    },
+}
+
+impl Recolor {
+    fn apply(&self, brush: &fixed::Brush) -> fixed::Brush {
+        match self {
+            Self::Tint(color) => fixed::brush_tint(brush, *color),
+            Self::Levels(levels) => fixed::brush_levels(brush, levels),
+            Self::Adjustment { recolors, opacity } => {
+                let adjusted = recolors
+                    .iter()
+                    .rev()
+                    .fold(brush.clone(), |adjusted, recolor| recolor.apply(&adjusted));
+                fixed::brush_mix(brush, &adjusted, *opacity)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Batch {
+    elements: Vec<PathEl>,
+    geometries: Vec<GeometryData>,
+    draws: Vec<DrawCommand>,
+    repeat_geometries: Vec<GeometryData>,
+    repeat_draws: Vec<DrawCommand>,
+    modify_elements: Vec<PathEl>,
+    /// Length of geometries at time of most recent draw. This is
+    /// used to prevent merging into already used geometries.
+    drawn_geometry: usize,
+    /// True if draws should use the luminance of their brush as alpha,
+    /// for rendering luma mattes.
+    luma_to_alpha: bool,
+    /// Stack of adjustments to the colors of draws, for rendering shadows
+    /// and color effects. The last adjustment is applied first.
+    recolors: Vec<Recolor>,
+    /// Width added to the outlines that replace fills and strokes, for
+    /// rendering stroke layer styles.
+    outline: Option<f64>,
+    /// Union of the bounds of rendered draws, if tracking damage.
+    bounds: Option<Option<Rect>>,
+}
+
+impl Batch {
+    fn push_geometry(&mut self, geometry: &Geometry, transform: Affine, frame: f64) {
+        // Merge with the previous geometry if possible. There are two
+        // conditions:
+        // 1. The previous geometry has not yet been referenced by a draw
+        // 2. The geometries have the same transform
+        if self.drawn_geometry < self.geometries.len()
+            && self.geometries.last().map(|last| last.transform) == Some(transform)
+        {
+            geometry.evaluate(frame, &mut self.elements);
+            self.geometries.last_mut().unwrap().elements.end = self.elements.len();
+        } else {
+            let start = self.elements.len();
+            geometry.evaluate(frame, &mut self.elements);
+            let end = self.elements.len();
+            self.geometries.push(GeometryData {
+                elements: start..end,
+                transform,
+                trim_phase: 0.0,
+            });
+        }
+    }
+
+    fn push_draw(
+        &mut self,
+        draw: &Draw,
+        alpha: f64,
+        geometry_start: usize,
+        frame: f64,
+        stroke_defaults: &StrokeDefaults,
+        color_space: ColorSpace,
+    ) {
+        let data = DrawData::new(
+            draw,
+            alpha,
+            geometry_start..self.geometries.len(),
+            frame,
+            stroke_defaults,
+            color_space,
+        );
+        self.push_draw_data(data);
+    }
+
+    /// Pushes a path as a geometry that is never merged with others.
+    fn push_path(&mut self, path: &BezPath, transform: Affine) {
+        let start = self.elements.len();
+        self.elements.extend(path.iter());
+        self.geometries.push(GeometryData {
+            elements: start..self.elements.len(),
+            transform,
+            trim_phase: 0.0,
+        });
+    }
+
+    fn push_draw_data(&mut self, mut data: DrawData) {
+        for recolor in self.recolors.iter().rev() {
+            data.brush = recolor.apply(&data.brush);
+        }
+        if self.luma_to_alpha {
+            data.brush = fixed::brush_luma_to_alpha(&data.brush);
+        }
+        if let Some(width) = self.outline {
+            let width = data.stroke.as_ref().map_or(0.0, |stroke| stroke.width) + width;
+            data.stroke = Some(fixed::Stroke::new(width).with_join(Join::Round));
+        }
+        self.draws.push(DrawCommand::Draw(data));
+        self.drawn_geometry = self.geometries.len();
+    }
+
+    fn repeat(&mut self, repeater: &fixed::Repeater, geometry_start: usize, draw_start: usize) {
+        // First move the relevant ranges of geometries and draws into side
+        // buffers
+        self.repeat_geometries
+            .extend(self.geometries.drain(geometry_start..));
+        self.repeat_draws.extend(self.draws.drain(draw_start..));
+        // Next, repeat the geometries and apply the offset transform, with
+        // the geometries of each copy in a contiguous range
+        let count = self.repeat_geometries.len();
+        for i in 0..repeater.copies {
+            let transform = repeater.transform(i);
+            for geometry in self.repeat_geometries.iter() {
+                let mut geometry = geometry.clone();
+                geometry.transform *= transform;
+                geometry.trim_phase += repeater.trim_offset * (repeater.offset + i as f64);
+                self.geometries.push(geometry);
+            }
+        }
+        // Finally, repeat the draws of each copy from the bottom one up,
+        // taking into account opacity and the range of the geometries of
+        // the copy
+        let start_alpha = repeater.start_opacity / 100.0;
+        let end_alpha = repeater.end_opacity / 100.0;
+        let delta_alpha = if repeater.copies > 1 {
+            // See note in Skottie: AE does not cover the full opacity range
+            (end_alpha - start_alpha) / repeater.copies as f64
+        } else {
+            0.0
+        };
+        for level in 0..repeater.copies {
+            let i = match repeater.composite {
+                RepeaterComposite::Above => level,
+                RepeaterComposite::Below => repeater.copies - 1 - level,
+            };
+            let alpha = start_alpha + delta_alpha * level as f64;
+            if alpha <= 0.0 {
+                continue;
+            }
+            let copy_start = geometry_start + i * count;
+            for mut command in self.repeat_draws.iter().cloned() {
+                if let DrawCommand::Draw(draw) = &mut command {
+                    draw.alpha *= alpha;
+                    draw.geometry = copy_start + (draw.geometry.start - geometry_start)
+                        ..copy_start + (draw.geometry.end - geometry_start);
+                }
+                self.draws.push(command);
+            }
+        }
+        // Clear the side buffers
+        self.repeat_geometries.clear();
+        self.repeat_draws.clear();
+        // Prevent merging until new geometries are pushed
+        self.drawn_geometry = self.geometries.len();
+    }
+
+    fn modify(&mut self, modifier: &fixed::PathModifier, geometry_start: usize) {
+        if let fixed::PathModifier::Trim(trim) = modifier {
+            if trim.individually {
+                self.trim_sequence(trim, geometry_start);
+                return;
+            }
+        }
+        // Modified elements are appended rather than replaced in place since
+        // repeated geometries may share element ranges.
+        for geometry in &mut self.geometries[geometry_start..] {
+            let start = self.elements.len();
+            let source = geometry.elements.clone();
+            self.modify_elements.clear();
+            match modifier {
+                // Each copy of a repeater trims with its own phase.
+                fixed::PathModifier::Trim(trim) if geometry.trim_phase != 0.0 => {
+                    let trim = fixed::Trim {
+                        offset: trim.offset + geometry.trim_phase,
+                        ..trim.clone()
+                    };
+                    fixed::PathModifier::Trim(trim)
+                        .apply(&self.elements[source], &mut self.modify_elements);
+                }
+                _ => modifier.apply(&self.elements[source], &mut self.modify_elements),
+            }
+            self.elements.extend_from_slice(&self.modify_elements);
+            geometry.elements = start..self.elements.len();
+        }
+        // Prevent merging new geometries into modified ones
+        self.drawn_geometry = self.geometries.len();
+    }
+
+    /// Trims the geometries as one sequence, in the order they were
+    /// pushed, measuring each in its own coordinate space.
+    fn trim_sequence(&mut self, trim: &fixed::Trim, geometry_start: usize) {
+        let lengths = self.geometries[geometry_start..]
+            .iter()
+            .map(|geometry| fixed::path_length(&self.elements[geometry.elements.clone()]))
+            .collect::<Vec<_>>();
+        let total = lengths.iter().sum::<f64>();
+        let ranges = trim
+            .ranges()
+            .map(|range| range.start * total..range.end * total);
+        let mut offset = 0.0;
+        for (geometry, length) in self.geometries[geometry_start..].iter_mut().zip(lengths) {
+            let start = self.elements.len();
+            let source = geometry.elements.clone();
+            self.modify_elements.clear();
+            if length > 0.0 {
+                for range in ranges.clone() {
+                    let range = (range.start - offset) / length..(range.end - offset) / length;
+                    fixed::trim_path(
+                        &self.elements[source.clone()],
+                        range,
+                        &mut self.modify_elements,
+                    );
+                }
+            }
+            offset += length;
+            self.elements.extend_from_slice(&self.modify_elements);
+            geometry.elements = start..self.elements.len();
+        }
+        // Prevent merging new geometries into trimmed ones
+        self.drawn_geometry = self.geometries.len();
+    }
+
+    /// Moves every point of the geometries, in the space given by the
+    /// transform, to the point of the content that the offset function
+    /// selects for it.
+    fn displace(&mut self, transform: Affine, offset: impl Fn(Point) -> Vec2) {
+        if transform.determinant() == 0.0 {
+            return;
+        }
+        let inverse = transform.inverse();
+        // Displaced elements are appended rather than replaced in place
+        // since repeated geometries may share element ranges.
+        for geometry in &mut self.geometries {
+            let local = inverse * geometry.transform;
+            if local.determinant() == 0.0 {
+                continue;
+            }
+            let local_inverse = local.inverse();
+            let displace = |point: Point| {
+                let point = local * point;
+                local_inverse * (point - offset(point))
+            };
+            let start = self.elements.len();
+            for index in geometry.elements.clone() {
+                let element = match self.elements[index] {
+                    PathEl::MoveTo(p) => PathEl::MoveTo(displace(p)),
+                    PathEl::LineTo(p) => PathEl::LineTo(displace(p)),
+                    PathEl::QuadTo(p1, p2) => PathEl::QuadTo(displace(p1), displace(p2)),
+                    PathEl::CurveTo(p1, p2, p3) => {
+                        PathEl::CurveTo(displace(p1), displace(p2), displace(p3))
+                    }
+                    PathEl::ClosePath => PathEl::ClosePath,
+                };
+                self.elements.push(element);
+            }
+            geometry.elements = start..self.elements.len();
+        }
+        // Prevent merging new geometries into displaced ones
+        self.drawn_geometry = self.geometries.len();
+    }
+
+    fn render(&mut self, canvas: &mut impl Canvas) {
+        // Process all draws in reverse
+        for (i, command) in self.draws.iter().enumerate().rev() {
+            let draw = match command {
+                DrawCommand::Draw(draw) => draw,
+                DrawCommand::BeginGroup { alpha } => {
+                    let bounds = self.group_bounds(i);
+                    canvas.push_layer(Mix::Normal, *alpha as f32, Affine::IDENTITY, &bounds);
+                    continue;
+                }
+                DrawCommand::EndGroup => {
+                    canvas.pop_layer();
+                    continue;
+                }
+            };
+            // Some nastiness to avoid cloning the brush if unnecessary
+            let modified_brush = if draw.alpha != 1.0 {
+                Some(fixed::brush_with_alpha(&draw.brush, draw.alpha))
+            } else {
+                None
+            };
+            let brush = modified_brush.as_ref().unwrap_or(&draw.brush);
+            if self.bounds.is_some() {
+                if let Some(rect) = self.draw_bounds(draw) {
+                    let bounds = self.bounds.flatten();
+                    self.bounds = Some(Some(bounds.map_or(rect, |bounds| bounds.union(rect))));
+                }
+            }
+            for geometry in self.geometries[draw.geometry.clone()].iter() {
+                let path = &self.elements[geometry.elements.clone()];
+                let transform = geometry.transform;
+                if let Some(stroke) = draw.stroke.as_ref() {
+                    canvas.stroke(stroke, transform, brush, None, &path);
+                } else {
+                    canvas.fill(Fill::NonZero, transform, brush, None, &path);
+                }
+            }
+        }
+    }
+
+    /// Computes the bounds, in canvas coordinates, of the draws in the
+    /// isolated group starting at the given index.
+    fn group_bounds(&self, begin: usize) -> Rect {
+        let mut bounds: Option<Rect> = None;
+        let mut depth = 0usize;
+        for command in self.draws[..begin].iter().rev() {
+            let draw = match command {
+                DrawCommand::Draw(draw) => draw,
+                DrawCommand::BeginGroup { .. } => {
+                    depth += 1;
+                    continue;
+                }
+                DrawCommand::EndGroup => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                    continue;
+                }
+            };
+            if let Some(rect) = self.draw_bounds(draw) {
+                bounds = Some(bounds.map_or(rect, |bounds| bounds.union(rect)));
+            }
+        }
+        bounds.unwrap_or_default()
+    }
+
+    /// Computes the bounds, in canvas coordinates, of a draw.
+    fn draw_bounds(&self, draw: &DrawData) -> Option<Rect> {
+        let mut bounds: Option<Rect> = None;
+        for geometry in &self.geometries[draw.geometry.clone()] {
+            let path = &self.elements[geometry.elements.clone()];
+            let mut rect = path.bounding_box();
+            if let Some(stroke) = draw.stroke.as_ref() {
+                // Conservatively account for joins and caps.
+                rect = rect.inflate(
+                    stroke.width * stroke.miter_limit.max(1.0),
+                    stroke.width * stroke.miter_limit.max(1.0),
+                );
+            }
+            let rect = geometry.transform.transform_rect_bbox(rect);
+            bounds = Some(bounds.map_or(rect, |bounds| bounds.union(rect)));
+        }
+        bounds
+    }
+
+    fn clear(&mut self) {
+        self.elements.clear();
+        self.geometries.clear();
+        self.draws.clear();
+        self.repeat_geometries.clear();
+        self.repeat_draws.clear();
+        self.modify_elements.clear();
+        self.drawn_geometry = 0;
+    }
+}
+
+/// Tolerance used to outline the strokes of displacement maps.
+const MAP_STROKE_TOLERANCE: f64 = 0.25;
+
+/// Region covered by a single draw of a displacement map.
+struct MapRegion {
+    /// Outline of the region in the space of the map.
+    path: BezPath,
+    bounds: Rect,
+    /// Transform from the space of the map to the space of the brush.
+    brush_transform: Affine,
+    brush: fixed::Brush,
+}
+
+/// Colors of a displacement map, sampled by point.
+struct MapSampler {
+    /// Regions of the map in the order they are drawn.
+    regions: Vec<MapRegion>,
+}
+
+impl MapSampler {
+    fn new(batch: &Batch) -> Self {
+        let mut regions = vec![];
+        for command in batch.draws.iter().rev() {
+            let DrawCommand::Draw(draw) = command else {
+                continue;
+            };
+            let brush = fixed::brush_with_alpha(&draw.brush, draw.alpha);
+            for geometry in &batch.geometries[draw.geometry.clone()] {
+                if geometry.transform.determinant() == 0.0 {
+                    continue;
+                }
+                let elements = batch.elements[geometry.elements.clone()].iter().copied();
+                let mut path = match draw.stroke.as_ref() {
+                    Some(stroke) => {
+                        kurbo::stroke(elements, stroke, &Default::default(), MAP_STROKE_TOLERANCE)
+                    }
+                    None => elements.collect(),
+                };
+                path.apply_affine(geometry.transform);
+                regions.push(MapRegion {
+                    bounds: path.bounding_box(),
+                    path,
+                    brush_transform: geometry.transform.inverse(),
+                    brush: brush.clone(),
+                });
+            }
+        }
+        Self { regions }
+    }
+
+    /// Returns the color of the map at a point.
+    ///
+    /// The colors of transparent parts of the map are mid gray, which does
+    /// not displace the content unless its alpha channel is used.
+    fn sample(&self, point: Point) -> Color {
+        let mut premultiplied = [0.0; 4];
+        for region in &self.regions {
+            if !region.bounds.contains(point) || region.path.winding(point) == 0 {
+                continue;
+            }
+            let color = brush_color_at(&region.brush, region.brush_transform * point);
+            let alpha = color.a as f64 / 255.0;
+            let source = [color.r, color.g, color.b].map(|x| x as f64 / 255.0 * alpha);
+            for (i, source) in source.into_iter().chain([alpha]).enumerate() {
+                premultiplied[i] = source + premultiplied[i] * (1.0 - alpha);
+            }
+        }
+        let [r, g, b, a] = premultiplied;
+        let gray = 0.5 * (1.0 - a);
+        Color::rgba(r + gray, g + gray, b + gray, a)
+    }
+}
+
+/// Returns the color of a brush at a point in the space of the brush.
+///
+/// Radial gradients are treated as if their focal point were at their
+/// center.
+fn brush_color_at(brush: &fixed::Brush, point: Point) -> Color {
+    let gradient = match brush {
+        fixed::Brush::Solid(color) => return *color,
+        fixed::Brush::Gradient(gradient) => gradient,
+        fixed::Brush::Image(_) => return Color::TRANSPARENT,
+    };
+    let t = match gradient.kind {
+        GradientKind::Linear { start, end } => {
+            let axis = end - start;
+            let length = axis.hypot2();
+            if length == 0.0 {
+                0.0
+            } else {
+                (point - start).dot(axis) / length
+            }
+        }
+        GradientKind::Radial {
+            end_center,
+            end_radius,
+            ..
+        } => {
+            if end_radius <= 0.0 {
+                0.0
+            } else {
+                point.distance(end_center) / end_radius as f64
+            }
+        }
+        GradientKind::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        } => {
+            let angle = (point - center).atan2().rem_euclid(std::f64::consts::TAU);
+            let sweep = (end_angle - start_angle) as f64;
+            if sweep == 0.0 {
+                0.0
+            } else {
+                (angle - start_angle as f64) / sweep
+            }
+        }
+    };
+    stops_color(&gradient.stops, t.clamp(0.0, 1.0) as f32)
+}
+
+/// Returns the color of gradient stops at an offset.
+fn stops_color(stops: &[ColorStop], offset: f32) -> Color {
+    let Some(first) = stops.first() else {
+        return Color::TRANSPARENT;
+    };
+    if offset <= first.offset {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if offset > end.offset {
+            continue;
+        }
+        let range = end.offset - start.offset;
+        let t = if range > 0.0 {
+            (offset - start.offset) / range
+        } else {
+            1.0
+        };
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        return Color::rgba8(
+            mix(start.color.r, end.color.r),
+            mix(start.color.g, end.color.g),
+            mix(start.color.b, end.color.b),
+            mix(start.color.a, end.color.a),
+        );
+    }
+    stops[stops.len() - 1].color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FEATHER_STEPS;
+    use super::{layer_composite, Digest, DrawCommand, DrawData, Painter, RenderOptions};
+    use crate::model::{animated, Animated, Brush, Content, Draw, Geometry, Layer, Shape};
+    use crate::model::{fixed, ColorSpace, DisplacementChannel, Effect, RepeaterComposite, Time};
+    use crate::model::{Mask, MaskMode, Matte, Transform, Value};
+    use crate::{Canvas, Composition};
+    use kurbo::{Affine, PathEl, Point, Rect, Shape as _, Size, Stroke, Vec2};
+    use peniko::{BlendMode, BrushRef, Color, Compose, Fill, Font, Mix};
+
+    const FRAMES: f64 = 100_000.0;
+
+    fn layer(content: Content, transform: Affine) -> Layer {
+        Layer {
+            transform: Transform::Fixed(transform),
+            opacity: Value::Fixed(100.0),
+            frames: 0.0..FRAMES,
+            stretch: 1.0,
+            content,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a rectangle of a given size centered at a position and filled
+    /// with a color.
+    fn rect(position: Value<Point>, size: Size, color: fixed::Color) -> Vec<Shape> {
+        vec![
+            Shape::Geometry(Geometry::Rect(animated::Rect {
+                is_ccw: false,
+                position,
+                size: Value::Fixed(size),
+                corner_radius: Value::Fixed(0.0),
+            })),
+            Shape::Draw(Draw {
+                stroke: None,
+                brush: Brush::Fixed(color.into()),
+                opacity: Value::Fixed(100.0),
+            }),
+        ]
+    }
+
+    /// Returns a red 10 by 10 rectangle centered at a position.
+    fn square(position: Value<Point>) -> Vec<Shape> {
+        rect(
+            position,
+            Size::new(10.0, 10.0),
+            fixed::Color::rgb8(255, 0, 0),
+        )
+    }
+
+    /// Returns a linear keyframe time.
+    fn time(frame: f64) -> Time {
+        Time {
+            frame,
+            in_tangent: None,
+            out_tangent: None,
+            axis_in_tangents: [None; 2],
+            axis_out_tangents: [None; 2],
+            hold: false,
+        }
+    }
+
+    /// Canvas that records the bounds, in canvas coordinates, of the clips
+    /// of its layers and of its fills.
+    #[derive(Default)]
+    struct Recorder {
+        layers: Vec<(BlendMode, Rect)>,
+        fills: Vec<Rect>,
+    }
+
+    impl Canvas for Recorder {
+        fn fragment(&self) -> Self {
+            Self::default()
+        }
+
+        fn push_layer(
+            &mut self,
+            blend: impl Into<BlendMode>,
+            _alpha: f32,
+            transform: Affine,
+            clip: &impl kurbo::Shape,
+        ) {
+            let bounds = transform.transform_rect_bbox(clip.bounding_box());
+            self.layers.push((blend.into(), bounds));
+        }
+
+        fn pop_layer(&mut self) {}
+
+        fn fill<'b>(
+            &mut self,
+            _style: Fill,
+            transform: Affine,
+            _brush: impl Into<BrushRef<'b>>,
+            _brush_transform: Option<Affine>,
+            shape: &impl kurbo::Shape,
+        ) {
+            self.fills
+                .push(transform.transform_rect_bbox(shape.bounding_box()));
+        }
+
+        fn stroke<'b>(
+            &mut self,
+            _style: &Stroke,
+            _transform: Affine,
+            _brush: impl Into<BrushRef<'b>>,
+            _brush_transform: Option<Affine>,
+            _shape: &impl kurbo::Shape,
+        ) {
+        }
+
+        fn append(&mut self, fragment: &Self, _transform: Option<Affine>) {
+            self.layers.extend_from_slice(&fragment.layers);
+            self.fills.extend_from_slice(&fragment.fills);
+        }
+
+        fn draw_text(&mut self, _: &Font, _: f32, _: Affine, _: Color, _: &str) {}
+    }
+
+    /// Paints a red square over the composition, restricted by a mask, and
+    /// returns what was drawn.
+    fn paint_masked(mask: Mask, frame: f64) -> Recorder {
+        let square = rect(
+            Value::Fixed(Point::new(50.0, 50.0)),
+            Size::new(100.0, 100.0),
+            fixed::Color::rgb8(255, 0, 0),
+        );
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![Layer {
+                masks: vec![mask],
+                ..layer(Content::Shape(square), Affine::IDENTITY)
+            }],
+            ..Default::default()
+        };
+        let mut recorder = Recorder::default();
+        Painter::new().paint(&composition, frame, Affine::IDENTITY, 1.0, &mut recorder);
+        recorder
+    }
+
+    fn mask(rect: Rect) -> Mask {
+        Mask {
+            mode: MaskMode::Add,
+            inverted: false,
+            expansion: Value::Fixed(0.0),
+            feather: Value::Fixed(0.0),
+            geometry: Geometry::Fixed(rect.path_elements(0.1).collect()),
+            opacity: Value::Fixed(100.0),
+        }
+    }
+
+    #[test]
+    fn test_mask_expansion() {
+        let expanded = Mask {
+            expansion: Value::Animated(Box::new(Animated {
+                times: vec![time(0.0), time(10.0)],
+                values: vec![0.0, -10.0],
+                loops: Default::default(),
+            })),
+            ..mask(Rect::new(20.0, 20.0, 60.0, 60.0))
+        };
+        let clip = |frame| paint_masked(expanded.clone(), frame).layers[1];
+        // The mask clips the content, after the clip of the composition.
+        assert_eq!(
+            clip(0.0),
+            (Mix::Clip.into(), Rect::new(20.0, 20.0, 60.0, 60.0))
+        );
+        // Negative expansions shrink the mask.
+        assert_eq!(clip(5.0).1, Rect::new(25.0, 25.0, 55.0, 55.0));
+        assert_eq!(clip(10.0).1, Rect::new(30.0, 30.0, 50.0, 50.0));
+        // Positive expansions grow it.
+        let grown = Mask {
+            expansion: Value::Fixed(4.0),
+            ..mask(Rect::new(20.0, 20.0, 60.0, 60.0))
+        };
+        assert_eq!(
+            paint_masked(grown, 0.0).layers[1].1,
+            Rect::new(16.0, 16.0, 64.0, 64.0)
+        );
+    }
+
+    #[test]
+    fn test_mask_feather() {
+        let feathered = Mask {
+            feather: Value::Fixed(8.0),
+            ..mask(Rect::new(20.0, 20.0, 60.0, 60.0))
+        };
+        let recorder = paint_masked(feathered, 0.0);
+        // Besides the clip of the composition, nothing is clipped: the mask
+        // is filled into a layer that the content is then drawn into.
+        assert!(recorder.layers[1..]
+            .iter()
+            .all(|(blend, _)| *blend != Mix::Clip.into()));
+        let (mask, content) = recorder.fills.split_at(FEATHER_STEPS);
+        assert_eq!(content, [Rect::new(0.0, 0.0, 100.0, 100.0)]);
+        // The outlines of the ramp shrink from the outer edge of the feather
+        // to its inner edge, centered on the outline of the mask.
+        assert!(mask.windows(2).all(|pair| pair[0].contains_rect(pair[1])));
+        assert!(Rect::new(16.0, 16.0, 64.0, 64.0).contains_rect(mask[0]));
+        assert!(mask[FEATHER_STEPS - 1].contains_rect(Rect::new(24.0, 24.0, 56.0, 56.0)));
+        let center = mask[FEATHER_STEPS / 2];
+        assert!((center.x0 - 20.0).abs() < 1.0, "{center:?}");
+    }
+
+    #[test]
+    fn test_matte_composition() {
+        let content = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![layer(
+                Content::Shape(rect(
+                    Value::Fixed(Point::new(50.0, 50.0)),
+                    Size::new(100.0, 100.0),
+                    fixed::Color::rgb8(0, 0, 255),
+                )),
+                Affine::IDENTITY,
+            )],
+            ..Default::default()
+        };
+        // A wipe that moves right over ten frames.
+        let wipe = square(Value::Animated(Box::new(Animated {
+            times: vec![time(0.0), time(10.0)],
+            values: vec![Point::new(0.0, 50.0), Point::new(100.0, 50.0)],
+            loops: Default::default(),
+        })));
+        let matte = Composition {
+            frames: 0.0..10.0,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![layer(Content::Shape(wipe), Affine::IDENTITY)],
+            ..Default::default()
+        };
+        let paint = |mode, frame| {
+            let mut recorder = Recorder::default();
+            Painter::new().paint_with_matte(
+                &content,
+                &matte,
+                mode,
+                frame,
+                Affine::IDENTITY,
+                1.0,
+                &mut recorder,
+            );
+            recorder
+        };
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+        // The matte is drawn into an isolated layer, and the content is
+        // composited onto it.
+        let alpha = paint(Matte::Alpha, 5.0);
+        assert_eq!(
+            alpha.layers,
+            [
+                (Mix::Normal.into(), bounds),
+                (Mix::Clip.into(), bounds),
+                (Compose::SrcIn.into(), bounds),
+                (Mix::Clip.into(), bounds),
+            ]
+        );
+        assert_eq!(alpha.fills, [Rect::new(45.0, 45.0, 55.0, 55.0), bounds]);
+        // Inverted mattes keep the content outside of the matte.
+        let inverted = paint(Matte::InvertAlpha, 5.0);
+        assert_eq!(inverted.layers[2], (Compose::SrcOut.into(), bounds));
+        assert_eq!(inverted.fills, alpha.fills);
+        // Past the end of its frames, the matte holds its last frame while
+        // the content keeps playing.
+        assert_eq!(
+            paint(Matte::Alpha, 50.0).fills,
+            [Rect::new(85.0, 45.0, 95.0, 55.0), bounds]
+        );
+    }
+
+    #[test]
+    fn test_instance_cache() {
+        let dot = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let instance = || Content::Instance {
+            name: "dot".into(),
+            source: "dot".into(),
+            time_remap: None,
+            overrides: Default::default(),
+        };
+        let composition = Composition {
+            frames: 0.0..FRAMES,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            assets: [(
+                "dot".to_string(),
+                vec![layer(Content::Shape(dot), Affine::IDENTITY)],
+            )]
+            .into_iter()
+            .collect(),
+            layers: vec![
+                layer(instance(), Affine::IDENTITY),
+                layer(instance(), Affine::translate((20.0, 0.0))),
+            ],
+            ..Default::default()
+        };
+        let mut painter = Painter::<Digest>::new();
+        painter.set_instance_caching(true);
+        for frame in 0..10 {
+            painter.evaluate_only(&composition, frame as f64);
+            // Both instances share a single cached fragment.
+            assert_eq!(painter.instance_cache.len(), 1);
+        }
+        painter.set_instance_caching(false);
+        assert!(painter.instance_cache.is_empty());
+    }
+
+    #[test]
+    fn test_displacement_map() {
+        // The map is red over the right half of the content, and transparent
+        // elsewhere.
+        let map = layer(
+            Content::Shape(rect(
+                Value::Fixed(Point::new(100.0, 50.0)),
+                Size::new(100.0, 100.0),
+                fixed::Color::RED,
+            )),
+            Affine::translate((500.0, 0.0)),
+        );
+        let shapes = rect(
+            Value::Fixed(Point::new(50.0, 50.0)),
+            Size::new(100.0, 20.0),
+            fixed::Color::BLUE,
+        );
+        let content = Layer {
+            effects: vec![Effect::DisplacementMap(animated::DisplacementMap {
+                layer: Some(0),
+                horizontal: DisplacementChannel::Red,
+                max_horizontal: Value::Fixed(10.0),
+                vertical: DisplacementChannel::Alpha,
+                max_vertical: Value::Fixed(10.0),
+            })],
+            ..layer(Content::Shape(shapes.clone()), Affine::IDENTITY)
+        };
+        let layers = [map, content];
+        let transform = Affine::translate((5.0, 0.0));
+        let mut painter = Painter::<Digest>::new();
+        painter.render_shapes(&shapes, transform, 1.0, 0.0);
+        painter.displace(&layers, &layers[1], transform, 0.0);
+        let geometry = painter.batch.geometries[0].clone();
+        let mut points = painter.batch.elements[geometry.elements]
+            .iter()
+            .filter_map(|element| element.end_point())
+            .map(|point| (point.x.round(), point.y.round()))
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        points.dedup();
+        // Points over the map move left and up, while points outside of it
+        // move down.
+        assert_eq!(
+            points,
+            [(0.0, 50.0), (0.0, 70.0), (90.0, 30.0), (90.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_only() {
+        let dot = square(Value::Animated(Box::new(Animated {
+            times: vec![time(10.0), time(20.0)],
+            values: vec![Point::new(0.0, 0.0), Point::new(100.0, 100.0)],
+            loops: Default::default(),
+        })));
+        let composition = Composition {
+            frames: 0.0..30.0,
+            frame_rate: 60.0,
+            width: 100,
+            height: 100,
+            layers: vec![layer(Content::Shape(dot), Affine::IDENTITY)],
+            ..Default::default()
+        };
+        let mut painter = Painter::<Digest>::new();
+        let mut checksum = |frame| painter.evaluate_only(&composition, frame);
+        assert_eq!(checksum(15.0), checksum(15.0));
+        assert_ne!(checksum(15.0), checksum(16.0));
+        // The dot is still before its first keyframe.
+        assert_eq!(checksum(0.0), checksum(5.0));
+        // A frozen composition shows the same frame throughout.
+        let frozen = composition.freeze(15.0);
+        let expected = painter.evaluate_only(&composition, 15.0);
+        assert_eq!(painter.evaluate_only(&frozen, 0.0), expected);
+        assert_eq!(painter.evaluate_only(&frozen, 29.0), expected);
+        // Digests are painted by the walk that renders, so knockouts and
+        // blend modes change them like they change the rendered frame.
+        let mut knockout = composition.clone();
+        knockout.layers[0].knockout = true;
+        assert_ne!(painter.evaluate_only(&knockout, 15.0), expected);
+        let mut multiply = composition.clone();
+        multiply.layers[0].blend_mode = Some(Mix::Multiply.into());
+        assert_ne!(painter.evaluate_only(&multiply, 15.0), expected);
+
+        // A difference in the last bits of a transform only changes exact
+        // digests.
+        let mut nudged = composition.clone();
+        nudged.layers[0].transform =
+            crate::model::Transform::Fixed(Affine::translate((1e-12, 0.0)));
+        let mut digest = |composition, quantum| painter.evaluate_digest(composition, 15.0, quantum);
+        assert_ne!(digest(&composition, None), digest(&nudged, None));
+        let quantum = Some(1.0 / 1024.0);
+        assert_eq!(digest(&composition, quantum), digest(&nudged, quantum));
+        assert_ne!(digest(&composition, None), digest(&composition, quantum));
+    }
+
+    #[test]
+    fn test_nested_group_opacity() {
+        let half = || {
+            Some(crate::model::GroupTransform {
+                transform: crate::model::Transform::Fixed(Affine::IDENTITY),
+                opacity: Value::Fixed(50.0),
+            })
+        };
+        let draw = Shape::Draw(Draw {
+            stroke: None,
+            brush: Brush::Fixed(fixed::Color::rgb8(255, 0, 0).into()),
+            opacity: Value::Fixed(50.0),
+        });
+        let shapes = vec![Shape::Group(vec![Shape::Group(vec![draw], half())], half())];
+        let layer = layer(Content::Shape(shapes.clone()), Affine::IDENTITY);
+        for isolation in [false, true] {
+            let mut painter = Painter::<Digest>::new();
+            painter.set_group_isolation(isolation);
+            painter.render_shapes(&shapes, Affine::IDENTITY, 1.0, 0.0);
+            // A single draw is never isolated, so the group opacities are
+            // folded into it.
+            let alphas = painter
+                .batch
+                .draws
+                .iter()
+                .map(|command| match command {
+                    DrawCommand::Draw(draw) => draw.alpha,
+                    command => panic!("unexpected {command:?}"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(alphas, vec![0.125]);
+            assert_eq!(layer.draw_opacities(0.0, 60.0, 0), alphas);
+        }
+    }
+
+    #[test]
+    fn test_group_isolation() {
+        let geometry = square(Value::Fixed(Point::new(5.0, 5.0))).remove(0);
+        let draw = |stroke| {
+            Shape::Draw(Draw {
+                stroke,
+                brush: Brush::Fixed(fixed::Color::rgb8(255, 0, 0).into()),
+                opacity: Value::Fixed(100.0),
+            })
+        };
+        let stroke = animated::Stroke {
+            width: Value::Fixed(2.0),
+            join: Value::Fixed(crate::model::StrokeJoin::Miter),
+            miter_limit: Some(4.0),
+            cap: Value::Fixed(crate::model::StrokeCap::Butt),
+            dashes: vec![],
+            dash_offset: Value::Fixed(0.0),
+        };
+        let group = |opacity, shapes| {
+            Shape::Group(
+                shapes,
+                Some(crate::model::GroupTransform {
+                    transform: Transform::Fixed(Affine::IDENTITY),
+                    opacity: Value::Fixed(opacity),
+                }),
+            )
+        };
+        let commands = |isolation, shapes: &[Shape]| {
+            let mut painter = Painter::<Digest>::new();
+            painter.set_group_isolation(isolation);
+            painter.render_shapes(shapes, Affine::IDENTITY, 1.0, 0.0);
+            // Commands are recorded from front to back, and drawn in
+            // reverse.
+            painter
+                .batch
+                .draws
+                .iter()
+                .rev()
+                .map(|command| match command {
+                    DrawCommand::Draw(draw) => format!("draw {}", draw.alpha),
+                    DrawCommand::BeginGroup { alpha } => format!("begin {alpha}"),
+                    DrawCommand::EndGroup => "end".to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+        // A semi-transparent group whose stroke overlaps its fill.
+        let outlined = [group(
+            50.0,
+            vec![
+                geometry.clone(),
+                draw(Some(stroke.into_model())),
+                draw(None),
+            ],
+        )];
+        // Without isolation, each draw is faded on its own, so they darken
+        // where they overlap.
+        assert_eq!(commands(false, &outlined), ["draw 0.5", "draw 0.5"]);
+        // With isolation, the draws are composited at full opacity, then
+        // faded together.
+        assert_eq!(
+            commands(true, &outlined),
+            ["begin 0.5", "draw 1", "draw 1", "end"]
+        );
+        // Opaque groups are never isolated.
+        let opaque = [group(100.0, vec![geometry.clone(), draw(None), draw(None)])];
+        assert_eq!(commands(true, &opaque), ["draw 1", "draw 1"]);
+    }
+
+    #[test]
+    fn test_adjustment_layer() {
+        let shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+        let adjustment = |opacity| Layer {
+            opacity: Value::Fixed(opacity),
+            effects: vec![Effect::Fill(animated::FillEffect {
+                color: Value::Fixed(fixed::Color::rgb8(0, 0, 255)),
+                opacity: Value::Fixed(100.0),
+            })],
+            ..layer(Content::Adjustment, Affine::IDENTITY)
+        };
+        let brushes = |opacity| {
+            let composition = Composition {
+                frames: 0.0..FRAMES,
+                frame_rate: 60.0,
+                layers: vec![
+                    adjustment(opacity),
+                    layer(Content::Shape(shapes.clone()), Affine::IDENTITY),
+                ],
+                ..Default::default()
+            };
+            let mut painter = Painter::<Digest>::new();
+            // Only the layers below an adjustment layer are recolored.
+            assert_eq!(
+                painter.push_adjustments(&composition, &composition.layers, 0, 0.0),
+                0
+            );
+            assert!(painter.batch.recolors.is_empty());
+            painter.push_adjustments(&composition, &composition.layers, 1, 0.0);
+            painter.render_shapes(&shapes, Affine::IDENTITY, 1.0, 0.0);
+            painter
+                .batch
+                .draws
+                .iter()
+                .map(|command| match command {
+                    DrawCommand::Draw(draw) => draw.brush.clone(),
+                    command => panic!("unexpected {command:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(brushes(100.0), vec![fixed::Color::rgb8(0, 0, 255).into()]);
+        assert_eq!(brushes(50.0), vec![fixed::Color::rgb8(128, 0, 128).into()]);
+        assert_eq!(brushes(0.0), vec![fixed::Color::rgb8(255, 0, 0).into()]);
+    }
+
+    #[test]
+    fn test_gradient_color_space() {
+        let mut gradient = peniko::Gradient::new_linear((0.0, 0.0), (10.0, 0.0));
+        gradient
+            .stops
+            .push((0.0, fixed::Color::rgb8(255, 0, 0)).into());
+        gradient
+            .stops
+            .push((1.0, fixed::Color::rgb8(0, 0, 255)).into());
+        let mut shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+        shapes[1] = Shape::Draw(Draw {
+            stroke: None,
+            brush: Brush::Fixed(gradient.into()),
+            opacity: Value::Fixed(100.0),
+        });
+        let stops = |color_space| {
+            let mut painter = Painter::<Digest>::new();
+            painter.set_render_options(RenderOptions {
+                color_space,
+                ..Default::default()
+            });
+            painter.render_shapes(&shapes, Affine::IDENTITY, 1.0, 0.0);
+            match &painter.batch.draws[..] {
+                [DrawCommand::Draw(DrawData {
+                    brush: peniko::Brush::Gradient(gradient),
+                    ..
+                })] => gradient.stops.clone(),
+                draws => panic!("expected a gradient, found {draws:?}"),
+            }
+        };
+        assert_eq!(stops(ColorSpace::Srgb).len(), 2);
+        let oklab = stops(ColorSpace::Oklab);
+        assert!(oklab.len() > 2);
+        assert_eq!(oklab.first(), stops(ColorSpace::Srgb).first());
+        assert_eq!(oklab.last(), stops(ColorSpace::Srgb).last());
+    }
+
+    #[test]
+    fn test_repeater_trim_phase() {
+        let shapes = |trim_offset, individually| {
+            let mut shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+            shapes.splice(
+                1..1,
+                [
+                    Shape::Repeater(crate::model::Repeater::Fixed(fixed::Repeater {
+                        copies: 4,
+                        offset: 0.0,
+                        anchor_point: Point::ZERO,
+                        position: Point::ZERO,
+                        rotation: 0.0,
+                        scale: Vec2::new(100.0, 100.0),
+                        start_opacity: 100.0,
+                        end_opacity: 100.0,
+                        trim_offset,
+                        composite: RepeaterComposite::Above,
+                    })),
+                    Shape::Modifier(crate::model::PathModifier::Fixed(
+                        fixed::PathModifier::Trim(fixed::Trim {
+                            start: 0.0,
+                            end: 25.0,
+                            offset: 0.0,
+                            individually,
+                        }),
+                    )),
+                ],
+            );
+            shapes
+        };
+        let starts = |trim_offset, individually| {
+            let mut painter = Painter::<Digest>::new();
+            painter.render_shapes(
+                &shapes(trim_offset, individually),
+                Affine::IDENTITY,
+                1.0,
+                0.0,
+            );
+            let batch = &painter.batch;
+            batch
+                .geometries
+                .iter()
+                .map(
+                    |geometry| match batch.elements[geometry.elements.clone()].first() {
+                        Some(PathEl::MoveTo(point)) => Some(*point),
+                        _ => None,
+                    },
+                )
+                .collect::<Vec<_>>()
+        };
+        // Without a phase every copy keeps the same side of the rectangle.
+        let same = starts(0.0, false);
+        assert_eq!(same.len(), 4);
+        assert!(same
+            .iter()
+            .all(|start| start.is_some() && *start == same[0]));
+        // A quarter turn per copy moves each on to the next side.
+        let phased = starts(90.0, false);
+        assert_eq!(phased[0], same[0]);
+        for (i, start) in phased.iter().enumerate() {
+            assert!(start.is_some());
+            assert!(phased[..i].iter().all(|other| other != start));
+        }
+        // Trimming the copies as a sequence keeps only the first.
+        assert_eq!(starts(90.0, true), [same[0], None, None, None]);
+    }
+
+    #[test]
+    fn test_repeater_composite() {
+        let shapes = |composite| {
+            let mut shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+            shapes.push(Shape::Repeater(crate::model::Repeater::Fixed(
+                fixed::Repeater {
+                    copies: 3,
+                    offset: 0.0,
+                    anchor_point: Point::ZERO,
+                    position: Point::new(10.0, 0.0),
+                    rotation: 0.0,
+                    scale: Vec2::new(100.0, 100.0),
+                    start_opacity: 40.0,
+                    end_opacity: 100.0,
+                    trim_offset: 0.0,
+                    composite,
+                },
+            )));
+            shapes
+        };
+        // Returns the offset and alpha of each drawn copy, from the bottom.
+        let stack = |composite| {
+            let mut painter = Painter::<Digest>::new();
+            painter.render_shapes(&shapes(composite), Affine::IDENTITY, 1.0, 0.0);
+            let batch = &painter.batch;
+            batch
+                .draws
+                .iter()
+                .map(|command| match command {
+                    DrawCommand::Draw(draw) => {
+                        assert_eq!(draw.geometry.len(), 1);
+                        let geometry = &batch.geometries[draw.geometry.start];
+                        let offset = geometry.transform.translation().x;
+                        (offset, (draw.alpha * 100.0).round())
+                    }
+                    _ => panic!("expected a draw"),
+                })
+                .collect::<Vec<_>>()
+        };
+        // Each copy is drawn once, with the opacity ramp from the bottom up.
+        assert_eq!(
+            stack(RepeaterComposite::Above),
+            [(0.0, 40.0), (10.0, 60.0), (20.0, 80.0)]
+        );
+        assert_eq!(
+            stack(RepeaterComposite::Below),
+            [(20.0, 40.0), (10.0, 60.0), (0.0, 80.0)]
+        );
+    }
+
+    #[test]
+    fn test_deep_groups() {
+        // Deeper than the call stack of a test thread allows for recursion.
+        let mut shapes = square(Value::Fixed(Point::new(5.0, 5.0)));
+        for _ in 0..5_000 {
+            shapes = vec![Shape::Group(shapes, None)];
+        }
+        let mut painter = Painter::<Digest>::new();
+        painter.render_shapes(&shapes, Affine::IDENTITY, 1.0, 0.0);
+        assert_eq!(painter.batch.draws.len(), 1);
+        assert_eq!(painter.batch.geometries.len(), 1);
+    }
+
+    #[test]
+    fn test_layer_composite() {
+        let multiply = Some(Mix::Multiply.into());
+        let mode = |preserve_transparency, blend_mode| {
+            layer_composite(&Layer {
+                preserve_transparency,
+                blend_mode,
+                ..layer(Content::None, Affine::IDENTITY)
+            })
+        };
+        assert_eq!(
+            mode(true, multiply),
+            Some(BlendMode::new(Mix::Multiply, Compose::SrcAtop))
+        );
+        let add = Some(BlendMode::new(Mix::Normal, Compose::Plus));
+        assert_eq!(mode(true, add), add);
+        assert_eq!(mode(false, None), None);
+    }
+}
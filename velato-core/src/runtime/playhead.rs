@@ -0,0 +1,170 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Composition;
+use std::ops::Range;
+
+/// How a [`Playhead`] continues when it reaches an end of the composition.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum PlayMode {
+    /// Plays once and stops at the end.
+    Once,
+    /// Starts over from the other end.
+    #[default]
+    Loop,
+    /// Reverses direction.
+    PingPong,
+}
+
+/// Boundary crossed by a [`Playhead`], reported by [`Playhead::events`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PlayheadEvent {
+    /// Playback wrapped around from one end of the composition to the other
+    /// to start another loop.
+    Looped,
+    /// Playback reversed at an end of the composition, and now plays
+    /// forward if `forward` is true.
+    Reversed {
+        /// Whether playback now plays forward.
+        forward: bool,
+    },
+    /// Playback reached the end of a composition played once and stopped.
+    Completed,
+}
+
+/// Position of playback within a composition, advanced by elapsed time.
+///
+/// The frames of a composition exclude their end, which is also the first
+/// frame of the next loop, so the last displayed frame is one before the
+/// end. A composition played once completes when the last frame has been
+/// displayed for its full duration, and the playhead then stays on that
+/// frame.
+#[derive(Clone, Debug)]
+pub struct Playhead {
+    frames: Range<f64>,
+    frame_rate: f64,
+    mode: PlayMode,
+    /// Position within the frames, which reaches the end only after the
+    /// last frame has been displayed.
+    position: f64,
+    forward: bool,
+    complete: bool,
+    events: Vec<PlayheadEvent>,
+}
+
+impl Playhead {
+    /// Creates a playhead at the start of a composition, playing forward.
+    pub fn new(composition: &Composition, mode: PlayMode) -> Self {
+        Self {
+            frames: composition.frames.clone(),
+            frame_rate: composition.frame_rate,
+            mode,
+            position: composition.frames.start,
+            forward: true,
+            complete: false,
+            events: vec![],
+        }
+    }
+
+    /// Returns the frame to display.
+    pub fn frame(&self) -> f64 {
+        let last = (self.frames.end - 1.0).max(self.frames.start);
+        self.position.clamp(self.frames.start, last)
+    }
+
+    /// Returns the play mode.
+    pub fn mode(&self) -> PlayMode {
+        self.mode
+    }
+
+    /// Returns true if playback plays forward.
+    pub fn is_forward(&self) -> bool {
+        self.forward
+    }
+
+    /// Sets the direction of playback, which resumes a completed playhead
+    /// that is turned back.
+    pub fn set_forward(&mut self, forward: bool) {
+        if forward != self.forward {
+            self.forward = forward;
+            self.complete = false;
+        }
+    }
+
+    /// Returns true if a composition played once has reached its end, or
+    /// its start when played in reverse.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns the boundaries crossed by the most recent call to
+    /// [`advance`](Self::advance), in order.
+    pub fn events(&self) -> &[PlayheadEvent] {
+        &self.events
+    }
+
+    /// Moves the playhead to the given frame, clamped to the frames of the
+    /// composition, and resumes playback if it was complete.
+    pub fn seek(&mut self, frame: f64) {
+        self.position = frame.clamp(self.frames.start, self.frames.end.max(self.frames.start));
+        self.complete = false;
+        self.events.clear();
+    }
+
+    /// Advances playback by the given time, in seconds, and returns the
+    /// frame to display.
+    pub fn advance(&mut self, seconds: f64) -> f64 {
+        self.events.clear();
+        if self.complete {
+            return self.frame();
+        }
+        let duration = self.frames.end - self.frames.start;
+        if duration <= 0.0 {
+            // Nothing moves within an empty composition, which is complete
+            // as soon as it is played once.
+            if self.mode == PlayMode::Once {
+                self.complete = true;
+                self.events.push(PlayheadEvent::Completed);
+            }
+            return self.frame();
+        }
+        let mut remaining = if self.frame_rate > 0.0 && seconds > 0.0 {
+            seconds * self.frame_rate
+        } else {
+            0.0
+        };
+        while remaining > 0.0 {
+            let (target, other) = if self.forward {
+                (self.frames.end, self.frames.start)
+            } else {
+                (self.frames.start, self.frames.end)
+            };
+            let distance = (target - self.position).abs();
+            if remaining < distance {
+                self.position += if self.forward { remaining } else { -remaining };
+                break;
+            }
+            remaining -= distance;
+            match self.mode {
+                PlayMode::Once => {
+                    self.position = target;
+                    self.complete = true;
+                    self.events.push(PlayheadEvent::Completed);
+                    break;
+                }
+                PlayMode::Loop => {
+                    self.position = other;
+                    self.events.push(PlayheadEvent::Looped);
+                }
+                PlayMode::PingPong => {
+                    self.position = target;
+                    self.forward = !self.forward;
+                    self.events.push(PlayheadEvent::Reversed {
+                        forward: self.forward,
+                    });
+                }
+            }
+        }
+        self.frame()
+    }
+}
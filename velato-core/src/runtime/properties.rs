@@ -0,0 +1,459 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{
+    animated, Brush, ColorStops, Content, Effect, Geometry, Layer, LayerStyle, PathModifier,
+    Repeater, Shape, Stroke, Time, Transform, Tween, Value,
+};
+use super::Composition;
+
+/// Keyframe range of an animated property in a composition.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AnimatedProperty {
+    /// Slash separated path to the property, such as
+    /// `layers/0/shapes/1/transform/position`. Layers of precomposed assets
+    /// are found under `assets/<name>/<index>`.
+    pub path: String,
+    /// Frame of the first keyframe.
+    pub first_keyframe: f64,
+    /// Frame of the last keyframe.
+    pub last_keyframe: f64,
+    /// Number of keyframes.
+    pub keyframe_count: usize,
+    /// Frames of every keyframe, in order.
+    pub keyframes: Vec<f64>,
+}
+
+/// Selects the animated properties to consider, by path.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum PropertyFilter {
+    /// Every animated property.
+    #[default]
+    All,
+    /// Properties at or below any of the given paths, such as `layers/0` for
+    /// all properties of the first layer or `layers/2/transform/position`
+    /// for a single property.
+    Paths(Vec<String>),
+}
+
+impl PropertyFilter {
+    /// Returns true if the property with the given path is selected.
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Paths(paths) => paths.iter().any(|prefix| {
+                let prefix = prefix.trim_end_matches('/');
+                path.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            }),
+        }
+    }
+}
+
+/// Returns the animated properties of every layer in a composition.
+///
+/// Frames are in the local time of the layer containing the property, so
+/// properties of assets are not adjusted for the timing of their instances.
+pub(crate) fn collect(composition: &Composition) -> Vec<AnimatedProperty> {
+    let mut properties = vec![];
+    visit(composition, &mut properties);
+    properties
+}
+
+/// Receives the properties of a composition along with their paths.
+pub(crate) trait Visitor {
+    /// Visits the value of the property named `name` within `path`.
+    fn value<T: Tween + 'static>(&mut self, path: &str, name: &str, value: &Value<T>);
+
+    /// Visits the keyframes of a property that is not a [`Value`], such as
+    /// a spline.
+    fn times(&mut self, path: String, times: &[Time]);
+}
+
+impl Visitor for Vec<AnimatedProperty> {
+    fn value<T: Tween + 'static>(&mut self, path: &str, name: &str, value: &Value<T>) {
+        if let Value::Animated(animated) = value {
+            self.times(format!("{path}/{name}"), &animated.times);
+        }
+    }
+
+    fn times(&mut self, path: String, times: &[Time]) {
+        let (Some(first), Some(last)) = (times.first(), times.last()) else {
+            return;
+        };
+        self.push(AnimatedProperty {
+            path,
+            first_keyframe: first.frame,
+            last_keyframe: last.frame,
+            keyframe_count: times.len(),
+            keyframes: times.iter().map(|time| time.frame).collect(),
+        });
+    }
+}
+
+/// Visits the properties of every layer in a composition, including the
+/// layers of its assets.
+pub(crate) fn visit(composition: &Composition, visitor: &mut impl Visitor) {
+    visit_layers(&composition.layers, "layers", visitor);
+    // Sort by name so the result is deterministic.
+    let mut assets = composition.assets.iter().collect::<Vec<_>>();
+    assets.sort_by_key(|(name, _)| *name);
+    for (name, layers) in assets {
+        visit_layers(layers, &format!("assets/{name}"), visitor);
+    }
+}
+
+/// Visits the properties of a list of layers, whose paths start with the
+/// given prefix.
+pub(crate) fn visit_layers(layers: &[Layer], prefix: &str, visitor: &mut impl Visitor) {
+    for (i, layer) in layers.iter().enumerate() {
+        push_layer(&format!("{prefix}/{i}"), layer, visitor);
+    }
+}
+
+fn push_value<T: Tween + 'static>(
+    path: &str,
+    name: &str,
+    value: &Value<T>,
+    visitor: &mut impl Visitor,
+) {
+    visitor.value(path, name, value);
+}
+
+fn push_layer(path: &str, layer: &Layer, visitor: &mut impl Visitor) {
+    push_transform(&format!("{path}/transform"), &layer.transform, visitor);
+    push_value(path, "opacity", &layer.opacity, visitor);
+    for (i, mask) in layer.masks.iter().enumerate() {
+        let path = format!("{path}/masks/{i}");
+        push_value(&path, "expansion", &mask.expansion, visitor);
+        push_value(&path, "feather", &mask.feather, visitor);
+        push_geometry(&format!("{path}/geometry"), &mask.geometry, visitor);
+        push_value(&path, "opacity", &mask.opacity, visitor);
+    }
+    for (i, effect) in layer.effects.iter().enumerate() {
+        push_effect(&format!("{path}/effects/{i}"), effect, visitor);
+    }
+    for (i, style) in layer.styles.iter().enumerate() {
+        push_style(&format!("{path}/styles/{i}"), style, visitor);
+    }
+    match &layer.content {
+        Content::None | Content::Solid { .. } | Content::Adjustment => {}
+        Content::Instance { time_remap, .. } => {
+            if let Some(time_remap) = time_remap {
+                push_value(path, "time_remap", time_remap, visitor);
+            }
+        }
+        Content::Shape(shapes) => push_shapes(&format!("{path}/shapes"), shapes, visitor),
+    }
+}
+
+fn push_transform(path: &str, transform: &Transform, visitor: &mut impl Visitor) {
+    let Transform::Animated(transform) = transform else {
+        return;
+    };
+    push_value(path, "anchor", &transform.anchor, visitor);
+    match &transform.position {
+        animated::Position::Value(value) => push_value(path, "position", value, visitor),
+        animated::Position::SplitValues((x, y)) => {
+            push_value(path, "position/x", x, visitor);
+            push_value(path, "position/y", y, visitor);
+        }
+    }
+    push_value(path, "rotation", &transform.rotation, visitor);
+    push_value(path, "scale", &transform.scale, visitor);
+    push_value(path, "skew", &transform.skew, visitor);
+    push_value(path, "skew_angle", &transform.skew_angle, visitor);
+    if let Some(depth) = &transform.depth {
+        push_value(path, "anchor/z", &depth.anchor, visitor);
+        push_value(path, "position/z", &depth.position, visitor);
+        push_value(path, "scale/z", &depth.scale, visitor);
+        push_value(path, "rotation_x", &depth.rotation_x, visitor);
+        push_value(path, "rotation_y", &depth.rotation_y, visitor);
+        for (axis, value) in ["x", "y", "z"].iter().zip(&depth.orientation) {
+            push_value(path, &format!("orientation/{axis}"), value, visitor);
+        }
+    }
+}
+
+fn push_shapes(path: &str, shapes: &[Shape], visitor: &mut impl Visitor) {
+    for (i, shape) in shapes.iter().enumerate() {
+        push_shape(&format!("{path}/{i}"), shape, visitor);
+    }
+}
+
+fn push_shape(path: &str, shape: &Shape, visitor: &mut impl Visitor) {
+    match shape {
+        Shape::Group(shapes, transform) => {
+            if let Some(transform) = transform {
+                push_transform(&format!("{path}/transform"), &transform.transform, visitor);
+                push_value(path, "opacity", &transform.opacity, visitor);
+            }
+            push_shapes(&format!("{path}/shapes"), shapes, visitor);
+        }
+        Shape::Geometry(geometry) => push_geometry(path, geometry, visitor),
+        Shape::Draw(draw) => {
+            if let Some(Stroke::Animated(stroke)) = &draw.stroke {
+                let path = format!("{path}/stroke");
+                push_value(&path, "width", &stroke.width, visitor);
+                push_value(&path, "join", &stroke.join, visitor);
+                push_value(&path, "cap", &stroke.cap, visitor);
+                for (i, dash) in stroke.dashes.iter().enumerate() {
+                    push_value(&path, &format!("dashes/{i}"), dash, visitor);
+                }
+                push_value(&path, "dash_offset", &stroke.dash_offset, visitor);
+            }
+            if let Brush::Animated(brush) = &draw.brush {
+                push_brush(path, brush, visitor);
+            }
+            push_value(path, "opacity", &draw.opacity, visitor);
+        }
+        Shape::Repeater(Repeater::Animated(repeater)) => {
+            push_value(path, "copies", &repeater.copies, visitor);
+            push_value(path, "offset", &repeater.offset, visitor);
+            push_value(path, "anchor_point", &repeater.anchor_point, visitor);
+            push_value(path, "position", &repeater.position, visitor);
+            push_value(path, "rotation", &repeater.rotation, visitor);
+            push_value(path, "scale", &repeater.scale, visitor);
+            push_value(path, "start_opacity", &repeater.start_opacity, visitor);
+            push_value(path, "end_opacity", &repeater.end_opacity, visitor);
+            push_value(path, "trim_offset", &repeater.trim_offset, visitor);
+        }
+        Shape::Modifier(PathModifier::Animated(modifier)) => {
+            push_modifier(path, modifier, visitor);
+        }
+        Shape::Repeater(Repeater::Fixed(_)) | Shape::Modifier(PathModifier::Fixed(_)) => {}
+        // Hidden items keep the paths they would have if visible.
+        Shape::Hidden(shape) => push_shape(path, shape, visitor),
+    }
+}
+
+fn push_geometry(path: &str, geometry: &Geometry, visitor: &mut impl Visitor) {
+    match geometry {
+        Geometry::Fixed(_) => {}
+        Geometry::Rect(rect) => {
+            push_value(path, "position", &rect.position, visitor);
+            push_value(path, "size", &rect.size, visitor);
+            push_value(path, "corner_radius", &rect.corner_radius, visitor);
+        }
+        Geometry::Ellipse(ellipse) => {
+            push_value(path, "position", &ellipse.position, visitor);
+            push_value(path, "size", &ellipse.size, visitor);
+        }
+        Geometry::Spline(spline) => {
+            visitor.times(format!("{path}/path"), &spline.times);
+        }
+        Geometry::Star(star) => {
+            push_value(path, "position", &star.position, visitor);
+            push_value(path, "inner_radius", &star.inner_radius, visitor);
+            push_value(path, "inner_roundness", &star.inner_roundness, visitor);
+            push_value(path, "outer_radius", &star.outer_radius, visitor);
+            push_value(path, "outer_roundness", &star.outer_roundness, visitor);
+            push_value(path, "rotation", &star.rotation, visitor);
+            push_value(path, "points", &star.points, visitor);
+        }
+    }
+}
+
+fn push_brush(path: &str, brush: &animated::Brush, visitor: &mut impl Visitor) {
+    let path = format!("{path}/brush");
+    match brush {
+        animated::Brush::Solid(value) => push_value(&path, "color", value, visitor),
+        animated::Brush::SplitSolid((r, g, b)) => {
+            push_value(&path, "red", r, visitor);
+            push_value(&path, "green", g, visitor);
+            push_value(&path, "blue", b, visitor);
+        }
+        animated::Brush::Gradient(gradient) => {
+            push_value(&path, "is_radial", &gradient.is_radial, visitor);
+            push_value(&path, "start_point", &gradient.start_point, visitor);
+            push_value(&path, "end_point", &gradient.end_point, visitor);
+            push_value(
+                &path,
+                "highlight_length",
+                &gradient.highlight_length,
+                visitor,
+            );
+            push_value(&path, "highlight_angle", &gradient.highlight_angle, visitor);
+            if let ColorStops::Animated(stops) = &gradient.stops {
+                visitor.times(format!("{path}/stops"), &stops.frames);
+            }
+        }
+    }
+}
+
+fn push_modifier(path: &str, modifier: &animated::PathModifier, visitor: &mut impl Visitor) {
+    match modifier {
+        animated::PathModifier::ZigZag(zig_zag) => {
+            push_value(path, "ridges", &zig_zag.ridges, visitor);
+            push_value(path, "size", &zig_zag.size, visitor);
+        }
+        animated::PathModifier::RoundedCorners(rounded_corners) => {
+            push_value(path, "radius", &rounded_corners.radius, visitor);
+        }
+        animated::PathModifier::OffsetPath(offset_path) => {
+            push_value(path, "amount", &offset_path.amount, visitor);
+            push_value(path, "miter_limit", &offset_path.miter_limit, visitor);
+        }
+        animated::PathModifier::PuckerBloat(pucker_bloat) => {
+            push_value(path, "amount", &pucker_bloat.amount, visitor);
+        }
+        animated::PathModifier::Twist(twist) => {
+            push_value(path, "angle", &twist.angle, visitor);
+            push_value(path, "center", &twist.center, visitor);
+        }
+        animated::PathModifier::Trim(trim) => {
+            push_value(path, "start", &trim.start, visitor);
+            push_value(path, "end", &trim.end, visitor);
+            push_value(path, "offset", &trim.offset, visitor);
+        }
+    }
+}
+
+fn push_style(path: &str, style: &LayerStyle, visitor: &mut impl Visitor) {
+    match style {
+        LayerStyle::Stroke(stroke) => {
+            push_value(path, "color", &stroke.color, visitor);
+            push_value(path, "size", &stroke.size, visitor);
+        }
+        LayerStyle::DropShadow(shadow) | LayerStyle::InnerShadow(shadow) => {
+            push_value(path, "color", &shadow.color, visitor);
+            push_value(path, "opacity", &shadow.opacity, visitor);
+            push_value(path, "direction", &shadow.direction, visitor);
+            push_value(path, "distance", &shadow.distance, visitor);
+            push_value(path, "radius", &shadow.radius, visitor);
+        }
+        LayerStyle::OuterGlow(glow) => {
+            push_value(path, "color", &glow.color, visitor);
+            push_value(path, "opacity", &glow.opacity, visitor);
+            push_value(path, "radius", &glow.radius, visitor);
+        }
+    }
+}
+
+fn push_effect(path: &str, effect: &Effect, visitor: &mut impl Visitor) {
+    match effect {
+        Effect::DropShadow(shadow) => {
+            push_value(path, "color", &shadow.color, visitor);
+            push_value(path, "opacity", &shadow.opacity, visitor);
+            push_value(path, "direction", &shadow.direction, visitor);
+            push_value(path, "distance", &shadow.distance, visitor);
+            push_value(path, "radius", &shadow.radius, visitor);
+        }
+        Effect::Fill(fill) => {
+            push_value(path, "color", &fill.color, visitor);
+            push_value(path, "opacity", &fill.opacity, visitor);
+        }
+        Effect::Levels(levels) => {
+            for (name, channel) in [
+                ("rgb", &levels.rgb),
+                ("red", &levels.red),
+                ("green", &levels.green),
+                ("blue", &levels.blue),
+                ("alpha", &levels.alpha),
+            ] {
+                let path = format!("{path}/{name}");
+                push_value(&path, "input_black", &channel.input_black, visitor);
+                push_value(&path, "input_white", &channel.input_white, visitor);
+                push_value(&path, "gamma", &channel.gamma, visitor);
+                push_value(&path, "output_black", &channel.output_black, visitor);
+                push_value(&path, "output_white", &channel.output_white, visitor);
+            }
+        }
+        Effect::Stroke(stroke) => {
+            push_value(path, "color", &stroke.color, visitor);
+            push_value(path, "width", &stroke.width, visitor);
+            push_value(path, "opacity", &stroke.opacity, visitor);
+            push_value(path, "start", &stroke.start, visitor);
+            push_value(path, "end", &stroke.end, visitor);
+        }
+        Effect::LinearWipe(wipe) => {
+            push_value(path, "completion", &wipe.completion, visitor);
+            push_value(path, "angle", &wipe.angle, visitor);
+            push_value(path, "feather", &wipe.feather, visitor);
+        }
+        Effect::RadialWipe(wipe) => {
+            push_value(path, "completion", &wipe.completion, visitor);
+            push_value(path, "start_angle", &wipe.start_angle, visitor);
+            push_value(path, "center", &wipe.center, visitor);
+            push_value(path, "feather", &wipe.feather, visitor);
+        }
+        Effect::DisplacementMap(map) => {
+            push_value(path, "max_horizontal", &map.max_horizontal, visitor);
+            push_value(path, "max_vertical", &map.max_vertical, visitor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnimatedProperty, PropertyFilter};
+    use crate::Composition;
+    use serde_json::json;
+
+    #[test]
+    fn test_animated_properties() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 120, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 120, "st": 0,
+                "ks": {
+                    "p": { "a": 0, "k": [0, 0] },
+                    "r": { "a": 1, "k": [{ "t": 10, "s": [0] }, { "t": 90, "s": [90] }] },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": [{
+                    "ty": "gr",
+                    "it": [
+                        {
+                            "ty": "el",
+                            "p": { "a": 0, "k": [0, 0] },
+                            "s": { "a": 1, "k": [
+                                { "t": 0, "s": [10, 10] },
+                                { "t": 30, "s": [20, 20] },
+                                { "t": 110, "s": [10, 10] }
+                            ] }
+                        },
+                        {
+                            "ty": "fl",
+                            "c": { "a": 0, "k": [1, 0, 0, 1] },
+                            "o": { "a": 0, "k": 100 }
+                        }
+                    ]
+                }]
+            }]
+        })
+        .to_string();
+        let composition = Composition::from_slice(source).unwrap();
+        let properties = composition.animated_properties().collect::<Vec<_>>();
+        assert_eq!(
+            properties,
+            [
+                AnimatedProperty {
+                    path: "layers/0/transform/rotation".into(),
+                    first_keyframe: 10.0,
+                    last_keyframe: 90.0,
+                    keyframe_count: 2,
+                    keyframes: vec![10.0, 90.0],
+                },
+                AnimatedProperty {
+                    path: "layers/0/shapes/0/shapes/0/size".into(),
+                    first_keyframe: 0.0,
+                    last_keyframe: 110.0,
+                    keyframe_count: 3,
+                    keyframes: vec![0.0, 30.0, 110.0],
+                },
+            ]
+        );
+        let nearest = |frame, filter| composition.nearest_keyframe(frame, filter);
+        assert_eq!(nearest(50.0, PropertyFilter::All), Some(30.0));
+        assert_eq!(nearest(20.0, PropertyFilter::All), Some(10.0));
+        let rotation = PropertyFilter::Paths(vec!["layers/0/transform".into()]);
+        assert_eq!(nearest(60.0, rotation.clone()), Some(90.0));
+        // Equidistant keyframes resolve to the earlier one.
+        assert_eq!(nearest(50.0, rotation.clone()), Some(10.0));
+        // Paths match whole components.
+        assert!(!rotation.matches("layers/0/transformer"));
+        let missing = PropertyFilter::Paths(vec!["layers/1".into()]);
+        assert_eq!(nearest(50.0, missing), None);
+    }
+}
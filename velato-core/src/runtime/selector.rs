@@ -0,0 +1,339 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{Content, Layer};
+use super::Composition;
+use crate::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Step of a [`Selector`] that matches one level of layers.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SelectorSegment {
+    /// Layers with exactly the given name.
+    Name(String),
+    /// Layers whose name matches the pieces of a pattern in order, with any
+    /// text between them, written with `*` between the pieces.
+    Pattern(Vec<String>),
+    /// Any layer, written as `*`.
+    Any,
+    /// Any number of levels of layers, including none, written as `**`.
+    Descendants,
+    /// Layers with the given class among their classes, written as
+    /// `.class`.
+    Class(String),
+}
+
+impl SelectorSegment {
+    fn matches(&self, layer: &Layer) -> bool {
+        match self {
+            Self::Name(name) => layer.name == *name,
+            Self::Pattern(pieces) => matches_pattern(pieces, &layer.name),
+            Self::Any | Self::Descendants => true,
+            Self::Class(class) => layer.class.split_whitespace().any(|c| c == class),
+        }
+    }
+}
+
+/// Address of layers, and optionally of a property of those layers, in a
+/// composition.
+///
+/// Selectors are written as segments separated by `/`, each matching a
+/// layer by name within the asset instanced by the layer matched by the
+/// previous segment, such as `Card/Icon` for the layer named `Icon` within
+/// the precomposition instanced by the top level layer `Card`. Within a
+/// name, `*` matches any text, a segment of `*` matches any layer and a
+/// segment of `**` matches any number of levels. A segment starting with
+/// `.` matches layers by class (`cl`) instead, such as `.button`.
+///
+/// A property may follow the last segment after a `.`, with its path
+/// relative to the layer as in [`AnimatedProperty`](super::AnimatedProperty)
+/// and separated by `.`, such as `Card.transform.position` or
+/// `**/.badge.opacity`. Any of `/`, `.`, `*` and `\` is taken literally when
+/// preceded by `\`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Selector {
+    segments: Vec<SelectorSegment>,
+    property: Option<String>,
+}
+
+impl Selector {
+    /// Parses a selector.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let error = |reason| Error::Selector(source.to_string(), reason);
+        if source.is_empty() {
+            return Err(error("empty selector"));
+        }
+        // Characters of each segment, with whether they were escaped.
+        let mut raw_segments = vec![vec![]];
+        let mut chars = source.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let c = chars.next().ok_or_else(|| error("trailing escape"))?;
+                    raw_segments.last_mut().unwrap().push((c, true));
+                }
+                '/' => raw_segments.push(vec![]),
+                c => raw_segments.last_mut().unwrap().push((c, false)),
+            }
+        }
+        let last = raw_segments.len() - 1;
+        let mut segments = vec![];
+        let mut property = None;
+        for (i, raw) in raw_segments.iter().enumerate() {
+            let split = raw
+                .iter()
+                .skip(1)
+                .position(|&c| c == ('.', false))
+                .map(|position| position + 1);
+            let head = &raw[..split.unwrap_or(raw.len())];
+            if let Some(split) = split {
+                if i != last {
+                    return Err(error("a property must follow the last segment"));
+                }
+                let mut components = vec![];
+                for component in raw[split + 1..].split(|&c| c == ('.', false)) {
+                    if component.is_empty() {
+                        return Err(error("empty property"));
+                    }
+                    if component.contains(&('*', false)) {
+                        return Err(error("wildcards are not supported in properties"));
+                    }
+                    components.push(text(component));
+                }
+                property = Some(components.join("/"));
+            }
+            let segment = match head {
+                [] => return Err(error("empty segment")),
+                [('*', false)] => SelectorSegment::Any,
+                [('*', false), ('*', false)] => SelectorSegment::Descendants,
+                [('.', false), class @ ..] => {
+                    if class.is_empty() {
+                        return Err(error("empty class"));
+                    }
+                    if class.contains(&('*', false)) {
+                        return Err(error("wildcards are not supported in classes"));
+                    }
+                    SelectorSegment::Class(text(class))
+                }
+                name if name.contains(&('*', false)) => {
+                    SelectorSegment::Pattern(name.split(|&c| c == ('*', false)).map(text).collect())
+                }
+                name => SelectorSegment::Name(text(name)),
+            };
+            segments.push(segment);
+        }
+        Ok(Self { segments, property })
+    }
+
+    /// Returns the segments of the selector, from the outermost layer.
+    pub fn segments(&self) -> &[SelectorSegment] {
+        &self.segments
+    }
+
+    /// Returns the path of the selected property relative to each layer,
+    /// separated by `/`, or `None` if the selector selects layers.
+    pub fn property(&self) -> Option<&str> {
+        self.property.as_deref()
+    }
+
+    /// Returns true if the selector matches a layer, given the layers that
+    /// instance its asset, from the top level layer to the layer itself.
+    pub fn matches(&self, layers: &[&Layer]) -> bool {
+        matches_segments(&self.segments, layers)
+    }
+
+    /// Returns the paths of the layers or properties of a composition that
+    /// the selector matches, in the form of
+    /// [`AnimatedProperty::path`](super::AnimatedProperty::path), such as
+    /// for a [`PropertyFilter::Paths`](super::PropertyFilter::Paths).
+    ///
+    /// Layers of assets are found under `assets/<name>/<index>`, and appear
+    /// once however many instances match.
+    pub fn select(&self, composition: &Composition) -> Vec<String> {
+        let mut paths = vec![];
+        let mut visit = Visit {
+            composition,
+            selector: self,
+            chain: vec![],
+            assets: vec![],
+            paths: &mut paths,
+        };
+        visit.layers(&composition.layers, "layers");
+        paths
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Self, Error> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            match segment {
+                SelectorSegment::Name(name) => write_escaped(f, name)?,
+                SelectorSegment::Pattern(pieces) => {
+                    for (i, piece) in pieces.iter().enumerate() {
+                        if i > 0 {
+                            f.write_str("*")?;
+                        }
+                        write_escaped(f, piece)?;
+                    }
+                }
+                SelectorSegment::Any => f.write_str("*")?,
+                SelectorSegment::Descendants => f.write_str("**")?,
+                SelectorSegment::Class(class) => {
+                    f.write_str(".")?;
+                    write_escaped(f, class)?;
+                }
+            }
+        }
+        if let Some(property) = &self.property {
+            for component in property.split('/') {
+                f.write_str(".")?;
+                write_escaped(f, component)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Walks the layers of a composition through the assets they instance.
+struct Visit<'a, 'b> {
+    composition: &'a Composition,
+    selector: &'b Selector,
+    chain: Vec<&'a Layer>,
+    /// Names of the assets being walked, to stop at recursive instances.
+    assets: Vec<&'a str>,
+    paths: &'b mut Vec<String>,
+}
+
+impl<'a> Visit<'a, '_> {
+    fn layers(&mut self, layers: &'a [Layer], prefix: &str) {
+        for (i, layer) in layers.iter().enumerate() {
+            self.chain.push(layer);
+            if self.selector.matches(&self.chain) {
+                let path = match &self.selector.property {
+                    Some(property) => format!("{prefix}/{i}/{property}"),
+                    None => format!("{prefix}/{i}"),
+                };
+                if !self.paths.contains(&path) {
+                    self.paths.push(path);
+                }
+            }
+            if let Content::Instance { name, .. } = &layer.content {
+                if let Some((name, asset)) = self.composition.assets.get_key_value(name) {
+                    if !self.assets.contains(&name.as_str()) {
+                        self.assets.push(name);
+                        self.layers(asset, &format!("assets/{name}"));
+                        self.assets.pop();
+                    }
+                }
+            }
+            self.chain.pop();
+        }
+    }
+}
+
+fn matches_segments(segments: &[SelectorSegment], layers: &[&Layer]) -> bool {
+    match (segments, layers) {
+        ([], []) => true,
+        ([SelectorSegment::Descendants, rest @ ..], _) => {
+            (0..=layers.len()).any(|skip| matches_segments(rest, &layers[skip..]))
+        }
+        ([segment, rest @ ..], [layer, layers @ ..]) => {
+            segment.matches(layer) && matches_segments(rest, layers)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the text contains the pieces in order, starting with the
+/// first and ending with the last.
+fn matches_pattern(pieces: &[String], text: &str) -> bool {
+    let [first, middle @ .., last] = pieces else {
+        return pieces
+            .first()
+            .map_or(text.is_empty(), |piece| text == piece);
+    };
+    let Some(mut rest) = text.strip_prefix(first.as_str()) else {
+        return false;
+    };
+    for piece in middle {
+        let Some(start) = rest.find(piece.as_str()) else {
+            return false;
+        };
+        rest = &rest[start + piece.len()..];
+    }
+    rest.ends_with(last.as_str())
+}
+
+fn text(chars: &[(char, bool)]) -> String {
+    chars.iter().map(|(c, _)| c).collect()
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        if matches!(c, '/' | '.' | '*' | '\\') {
+            f.write_str("\\")?;
+        }
+        write!(f, "{c}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches_pattern, Selector, SelectorSegment};
+
+    #[test]
+    fn test_parse() {
+        let selector = Selector::parse("Card/Icon*/**/.badge.transform.position").unwrap();
+        assert_eq!(
+            selector.segments(),
+            [
+                SelectorSegment::Name("Card".into()),
+                SelectorSegment::Pattern(vec!["Icon".into(), "".into()]),
+                SelectorSegment::Descendants,
+                SelectorSegment::Class("badge".into()),
+            ]
+        );
+        assert_eq!(selector.property(), Some("transform/position"));
+        let selector = Selector::parse(r"v1\.2/\*").unwrap();
+        assert_eq!(
+            selector.segments(),
+            [
+                SelectorSegment::Name("v1.2".into()),
+                SelectorSegment::Name("*".into()),
+            ]
+        );
+        assert_eq!(selector.property(), None);
+        for source in ["Card.opacity", r"a\/b/c*d/**", "*/.x", r"v1\.2/\*"] {
+            let selector = Selector::parse(source).unwrap();
+            assert_eq!(selector.to_string(), source);
+            assert_eq!(source.parse::<Selector>().unwrap(), selector);
+        }
+        for source in ["", "a//b", "a.b/c", "a.", "a\\", ".", "a.*", ".a*"] {
+            assert!(Selector::parse(source).is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_patterns() {
+        let pattern = |source: &str| source.split('*').map(String::from).collect::<Vec<_>>();
+        assert!(matches_pattern(&pattern("Icon*"), "Icon 1"));
+        assert!(matches_pattern(&pattern("*1"), "Icon 1"));
+        assert!(matches_pattern(&pattern("I*n*1"), "Icon 1"));
+        assert!(!matches_pattern(&pattern("I*n*2"), "Icon 1"));
+        assert!(matches_pattern(&pattern("Ic*on"), "Icon"));
+        assert!(!matches_pattern(&pattern("Icon*on"), "Icon"));
+    }
+}
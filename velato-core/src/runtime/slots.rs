@@ -0,0 +1,443 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{Easing, Layer, Slot, Time, Tween, Value};
+use super::properties::{self, Visitor};
+use super::Composition;
+use kurbo::{Point, Size};
+use peniko::Color;
+use std::any::Any;
+use std::collections::BTreeMap;
+
+/// Value assigned to a slot with [`Composition::set_slot`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SlotValue {
+    /// Color of a fill, stroke or effect.
+    Color(Color),
+    /// Number, such as an opacity, a rotation or a stroke width.
+    Number(f64),
+    /// Point or vector, such as a position, a scale or a size.
+    Point(Point),
+}
+
+impl SlotValue {
+    /// Parses a CSS color, such as design tokens that arrive as strings.
+    ///
+    /// Accepts hexadecimal colors of the forms `#rgb`, `#rgba`, `#rrggbb`
+    /// and `#rrggbbaa`, the functions `rgb()` and `rgba()` with channels
+    /// from 0 to 255 or percentages, and the names of SVG colors, all
+    /// ignoring case.
+    pub fn parse_color(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if value == "transparent" {
+            return Some(Self::Color(Color::TRANSPARENT));
+        }
+        if let Some(args) = value
+            .strip_prefix("rgba(")
+            .or_else(|| value.strip_prefix("rgb("))
+        {
+            return parse_rgb(args.strip_suffix(')')?).map(Self::Color);
+        }
+        Color::parse(&value).map(Self::Color)
+    }
+
+    /// Formats a color as a hexadecimal string of the form `#rrggbb`, or
+    /// `#rrggbbaa` if it is translucent, or returns `None` for other kinds
+    /// of values.
+    pub fn to_hex(&self) -> Option<String> {
+        let Self::Color(color) = self else {
+            return None;
+        };
+        let mut hex = format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b);
+        if color.a != 255 {
+            hex.push_str(&format!("{:02x}", color.a));
+        }
+        Some(hex)
+    }
+
+    /// Interpolates between two values of the same kind, or snaps to the
+    /// second value at the end for values of different kinds.
+    pub fn blend(self, other: Self, t: f64) -> Self {
+        let easing = &Easing::LERP;
+        match (self, other) {
+            (Self::Color(a), Self::Color(b)) => Self::Color(a.tween(&b, t, easing)),
+            (Self::Number(a), Self::Number(b)) => Self::Number(a.tween(&b, t, easing)),
+            (Self::Point(a), Self::Point(b)) => Self::Point(a.tween(&b, t, easing)),
+            _ if t >= 1.0 => other,
+            _ => self,
+        }
+    }
+}
+
+/// Parses the arguments of a CSS `rgb()` or `rgba()` color, separated by
+/// commas or by spaces with an optional slash before the alpha.
+fn parse_rgb(args: &str) -> Option<Color> {
+    let args = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>();
+    let channel = |arg: &str, scale: f64| -> Option<f64> {
+        let value = match arg.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+            None => arg.parse::<f64>().ok()? / scale,
+        };
+        value.is_finite().then(|| value.clamp(0.0, 1.0))
+    };
+    let (r, g, b, a) = match args[..] {
+        [r, g, b] => (r, g, b, "1"),
+        [r, g, b, a] => (r, g, b, a),
+        _ => return None,
+    };
+    Some(Color::rgba(
+        channel(r, 255.0)?,
+        channel(g, 255.0)?,
+        channel(b, 255.0)?,
+        channel(a, 1.0)?,
+    ))
+}
+
+/// Set of slot values that are applied together, such as the colors of a
+/// light or dark appearance.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Theme {
+    values: BTreeMap<String, SlotValue>,
+}
+
+impl Theme {
+    /// Creates a new theme with no values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of the slot with the given id.
+    pub fn set(&mut self, id: impl Into<String>, value: SlotValue) {
+        self.values.insert(id.into(), value);
+    }
+
+    /// Sets the slot with the given id to a color parsed with
+    /// [`SlotValue::parse_color`], and returns false if the color is
+    /// invalid.
+    pub fn set_color(&mut self, id: impl Into<String>, color: &str) -> bool {
+        match SlotValue::parse_color(color) {
+            Some(value) => {
+                self.set(id, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the value of the slot with the given id.
+    pub fn get(&self, id: &str) -> Option<SlotValue> {
+        self.values.get(id).copied()
+    }
+
+    /// Returns an iterator over the ids and values of the slots of the
+    /// theme.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, SlotValue)> + '_ {
+        self.values.iter().map(|(id, value)| (id.as_str(), *value))
+    }
+
+    /// Interpolates between two themes, where `t` is clamped to `0..=1`.
+    ///
+    /// Slots that are only in one of the themes keep their value.
+    pub fn blend(&self, other: &Theme, t: f64) -> Theme {
+        let t = t.clamp(0.0, 1.0);
+        let mut values = self.values.clone();
+        for (id, value) in &other.values {
+            values
+                .entry(id.clone())
+                .and_modify(|from| *from = from.blend(*value, t))
+                .or_insert(*value);
+        }
+        Theme { values }
+    }
+}
+
+impl<K: Into<String>> FromIterator<(K, SlotValue)> for Theme {
+    fn from_iter<I: IntoIterator<Item = (K, SlotValue)>>(iter: I) -> Self {
+        Self {
+            values: iter
+                .into_iter()
+                .map(|(id, value)| (id.into(), value))
+                .collect(),
+        }
+    }
+}
+
+/// Source of the new value of a slot.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum SlotSource<'a> {
+    /// Value set at runtime, which only applies to properties of the same
+    /// kind.
+    Value(SlotValue),
+    /// Components of the static value of a slot in the file, which are
+    /// interpreted according to the kind of each property.
+    Components(&'a [f64]),
+}
+
+impl SlotSource<'_> {
+    fn number(self) -> Option<f64> {
+        match self {
+            Self::Value(SlotValue::Number(value)) => Some(value),
+            Self::Components(&[value]) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn color(self) -> Option<Color> {
+        match self {
+            Self::Value(SlotValue::Color(value)) => Some(value),
+            // Alpha is ignored, as for the colors of properties.
+            Self::Components(&[r, g, b, ..]) => Some(Color::rgb(r, g, b)),
+            _ => None,
+        }
+    }
+
+    fn point(self) -> Option<Point> {
+        match self {
+            Self::Value(SlotValue::Point(value)) => Some(value),
+            Self::Components(&[x, y, ..]) => Some(Point::new(x, y)),
+            _ => None,
+        }
+    }
+}
+
+/// Sets the value of every property bound to the slot with the given id,
+/// returning true if any property was set.
+///
+/// Properties overridden by the instances containing them are left alone.
+pub(crate) fn set(composition: &Composition, id: &str, source: SlotSource) -> bool {
+    let mut setter = Setter {
+        source: |slot: &str| (slot == id).then_some(source),
+        is_override: false,
+        found: false,
+    };
+    properties::visit(composition, &mut setter);
+    setter.found
+}
+
+/// Overrides the value of every property of the layers, excluding the
+/// layers of the assets they instance, that is bound to a slot with a new
+/// value.
+pub(crate) fn override_in_layers(layers: &[Layer], values: &BTreeMap<String, Vec<f64>>) {
+    let mut setter = Setter {
+        source: |slot: &str| {
+            values
+                .get(slot)
+                .map(|components| SlotSource::Components(components))
+        },
+        is_override: true,
+        found: false,
+    };
+    properties::visit_layers(layers, "", &mut setter);
+}
+
+/// Sets the value of every property bound to a slot of the theme in a
+/// single pass, returning true if any property was set.
+///
+/// Properties overridden by the instances containing them are left alone.
+pub(crate) fn apply(composition: &Composition, theme: &Theme) -> bool {
+    let mut setter = Setter {
+        source: |slot: &str| theme.get(slot).map(SlotSource::Value),
+        is_override: false,
+        found: false,
+    };
+    properties::visit(composition, &mut setter);
+    setter.found
+}
+
+struct Setter<F> {
+    /// Returns the new value of the slot with the given id, if any.
+    source: F,
+    /// True to set overrides of instances rather than runtime values.
+    is_override: bool,
+    found: bool,
+}
+
+impl<F> Setter<F> {
+    fn set<T: Tween + 'static>(&mut self, slot: &dyn Any, value: Option<T>) {
+        let (Some(slot), Some(value)) = (slot.downcast_ref::<Slot<T>>(), value) else {
+            return;
+        };
+        if self.is_override {
+            slot.set_override(Value::Fixed(value));
+        } else if !slot.is_overridden() {
+            slot.set(Value::Fixed(value));
+        } else {
+            return;
+        }
+        self.found = true;
+    }
+}
+
+impl<'a, F: FnMut(&str) -> Option<SlotSource<'a>>> Visitor for Setter<F> {
+    fn value<T: Tween + 'static>(&mut self, _path: &str, _name: &str, value: &Value<T>) {
+        let Value::Slot(slot) = value else {
+            return;
+        };
+        let Some(source) = (self.source)(&slot.id) else {
+            return;
+        };
+        let slot: &dyn Any = slot.as_ref();
+        self.set(slot, source.number());
+        self.set(slot, source.color());
+        self.set(slot, source.point());
+        self.set(slot, source.point().map(|point| point.to_vec2()));
+        self.set(
+            slot,
+            source.point().map(|point| Size::new(point.x, point.y)),
+        );
+    }
+
+    fn times(&mut self, _path: String, _times: &[Time]) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SlotValue, Theme};
+    use crate::model::{Brush, Content, Shape};
+    use crate::Composition;
+    use kurbo::Point;
+    use peniko::Color;
+    use serde_json::json;
+
+    #[test]
+    fn test_slots() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "slots": {
+                "accent": { "p": { "a": 0, "k": [0, 0, 1, 1] } },
+                "label": { "p": { "a": 0, "k": { "t": "text" } } }
+            },
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "p": { "a": 0, "k": [10, 10], "sid": "origin" },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": [{
+                    "ty": "fl",
+                    "c": { "a": 0, "k": [1, 0, 0, 1], "sid": "accent" },
+                    "o": { "a": 0, "k": 100, "sid": "alpha" }
+                }]
+            }]
+        })
+        .to_string();
+        let mut composition = Composition::from_slice(source).unwrap();
+        let draw = |composition: &Composition| {
+            let Content::Shape(shapes) = &composition.layers[0].content else {
+                panic!("expected shapes");
+            };
+            let Shape::Draw(draw) = &shapes[0] else {
+                panic!("expected a fill");
+            };
+            draw.clone()
+        };
+        let color = |composition: &Composition| match draw(composition)
+            .brush
+            .evaluate(1.0, 0.0)
+            .into_owned()
+        {
+            peniko::Brush::Solid(color) => color,
+            brush => panic!("expected a solid color, found {brush:?}"),
+        };
+        let opacity = |composition: &Composition| draw(composition).opacity.evaluate(0.0);
+        let position = |composition: &Composition| {
+            composition.layers[0]
+                .transform
+                .evaluate(0.0)
+                .into_owned()
+                .translation()
+        };
+        // The value of the slot in the file replaces that of the property.
+        assert!(matches!(draw(&composition).brush, Brush::Animated(_)));
+        assert_eq!(color(&composition), Color::rgb(0.0, 0.0, 1.0));
+        assert!(composition.set_slot("accent", SlotValue::Color(Color::WHITE)));
+        assert_eq!(color(&composition), Color::WHITE);
+        assert!(composition.set_slot("alpha", SlotValue::Number(50.0)));
+        assert_eq!(opacity(&composition), 50.0);
+        assert_eq!(position(&composition).x, 10.0);
+        assert!(composition.set_slot("origin", SlotValue::Point(Point::new(30.0, 40.0))));
+        assert_eq!(position(&composition).y, 40.0);
+        // Values of another kind, and unknown slots, are ignored.
+        assert!(!composition.set_slot("alpha", SlotValue::Color(Color::WHITE)));
+        assert!(!composition.set_slot("missing", SlotValue::Number(1.0)));
+        // Clones have their own slots.
+        let mut clone = composition.clone();
+        assert!(clone.set_slot("alpha", SlotValue::Number(10.0)));
+        assert_eq!(opacity(&clone), 10.0);
+        assert_eq!(opacity(&composition), 50.0);
+    }
+
+    #[test]
+    fn test_theme_blend() {
+        let source = json!({
+            "v": "5.5.2", "fr": 60, "ip": 0, "op": 60, "w": 100, "h": 100,
+            "layers": [{
+                "ty": 4, "ip": 0, "op": 60, "st": 0,
+                "ks": {
+                    "o": { "a": 0, "k": 100, "sid": "alpha" },
+                    "p": { "a": 0, "k": [0, 0] },
+                    "s": { "a": 0, "k": [100, 100] }
+                },
+                "shapes": []
+            }]
+        });
+        let mut composition = Composition::from_json(source).unwrap();
+        let light = Theme::from_iter([
+            ("alpha", SlotValue::Number(100.0)),
+            ("accent", SlotValue::Color(Color::BLACK)),
+        ]);
+        let dark = Theme::from_iter([
+            ("alpha", SlotValue::Number(20.0)),
+            ("accent", SlotValue::Color(Color::WHITE)),
+            ("width", SlotValue::Number(2.0)),
+        ]);
+        let blend = light.blend(&dark, 0.25);
+        assert_eq!(blend.get("alpha"), Some(SlotValue::Number(80.0)));
+        let Some(SlotValue::Color(accent)) = blend.get("accent") else {
+            panic!("expected a color");
+        };
+        assert!((60..=68).contains(&accent.r));
+        // Slots in a single theme keep their value.
+        assert_eq!(blend.get("width"), Some(SlotValue::Number(2.0)));
+        assert_eq!(light.blend(&dark, 2.0), dark);
+
+        assert!(composition.apply_theme_blend(&light, &dark, 0.25));
+        assert_eq!(composition.layers[0].opacity.evaluate(0.0), 80.0);
+        assert!(!composition.apply_theme(&Theme::new()));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        let parse = |value| match SlotValue::parse_color(value) {
+            Some(SlotValue::Color(color)) => color,
+            value => panic!("expected a color, found {value:?}"),
+        };
+        assert_eq!(parse("#FF8000"), Color::rgb8(255, 128, 0));
+        assert_eq!(parse("#f80"), Color::rgb8(255, 136, 0));
+        assert_eq!(parse("#ff800080"), Color::rgba8(255, 128, 0, 128));
+        assert_eq!(parse(" CornflowerBlue "), Color::CORNFLOWER_BLUE);
+        assert_eq!(parse("transparent"), Color::TRANSPARENT);
+        assert_eq!(parse("rgb(255, 128, 0)"), Color::rgb8(255, 128, 0));
+        assert_eq!(parse("rgba(255,128,0,0.5)").a, 128);
+        assert_eq!(parse("rgb(100% 50% 0% / 25%)").a, 64);
+        for invalid in ["", "#12", "rgb(1, 2)", "rgb(a, b, c)", "notacolor"] {
+            assert_eq!(SlotValue::parse_color(invalid), None, "{invalid}");
+        }
+
+        let hex = |value| SlotValue::parse_color(value).unwrap().to_hex().unwrap();
+        assert_eq!(hex("Orange"), "#ffa500");
+        assert_eq!(hex("rgba(0, 0, 255, 0.5)"), "#0000ff80");
+        assert_eq!(SlotValue::Number(1.0).to_hex(), None);
+
+        let mut theme = Theme::new();
+        assert!(theme.set_color("accent", "#00ff00"));
+        assert!(!theme.set_color("accent", "green-ish"));
+        assert_eq!(
+            theme.get("accent"),
+            Some(SlotValue::Color(Color::rgb8(0, 255, 0)))
+        );
+    }
+}
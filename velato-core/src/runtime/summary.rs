@@ -0,0 +1,213 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{animated, fixed, Brush, Content, Layer, Shape, Value};
+use super::Composition;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Maximum number of colors reported in a summary.
+const MAX_COLORS: usize = 5;
+
+/// Textual description of a composition, suitable for accessibility labels
+/// and alt-text generation.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    /// Duration of the animation in seconds.
+    pub duration: f64,
+    /// Frames per second.
+    pub frame_rate: f64,
+    /// Width of the animation.
+    pub width: usize,
+    /// Height of the animation.
+    pub height: usize,
+    /// Names of the visible top level layers, in rendering order from front
+    /// to back.
+    pub layer_names: Vec<String>,
+    /// Names of the markers, in timeline order.
+    pub markers: Vec<String>,
+    /// Most frequently used colors, most common first.
+    pub colors: Vec<fixed::Color>,
+}
+
+impl Summary {
+    pub(crate) fn new(composition: &Composition) -> Self {
+        let frames = composition.frames.end - composition.frames.start;
+        let duration = if composition.frame_rate > 0.0 {
+            frames.max(0.0) / composition.frame_rate
+        } else {
+            0.0
+        };
+        let layer_names = composition
+            .layers
+            .iter()
+            .filter(|layer| !layer.is_mask && !layer.hidden && !layer.name.is_empty())
+            .map(|layer| layer.name.clone())
+            .collect();
+        let mut markers = composition.markers().to_vec();
+        markers.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+        let markers = markers
+            .into_iter()
+            .filter(|marker| !marker.name.is_empty())
+            .map(|marker| marker.name)
+            .collect();
+        let mut counts = HashMap::new();
+        for layer in composition
+            .layers
+            .iter()
+            .chain(composition.assets.values().flatten())
+            .filter(|layer| !layer.hidden)
+        {
+            count_layer_colors(layer, &mut counts);
+        }
+        let mut colors = counts.into_iter().collect::<Vec<_>>();
+        // Sort by frequency, then by value so the result is deterministic.
+        colors.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        let colors = colors
+            .into_iter()
+            .take(MAX_COLORS)
+            .map(|(color, _)| color)
+            .collect();
+        Self {
+            duration,
+            frame_rate: composition.frame_rate,
+            width: composition.width,
+            height: composition.height,
+            layer_names,
+            markers,
+            colors,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Animation, {:.1} seconds at {} frames per second, {}x{} pixels.",
+            self.duration, self.frame_rate, self.width, self.height
+        )?;
+        if !self.layer_names.is_empty() {
+            write!(f, " Layers: {}.", self.layer_names.join(", "))?;
+        }
+        if !self.markers.is_empty() {
+            write!(f, " Markers: {}.", self.markers.join(", "))?;
+        }
+        if !self.colors.is_empty() {
+            write!(f, " Dominant colors:")?;
+            for (i, color) in self.colors.iter().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                write!(f, "{sep}#{:02x}{:02x}{:02x}", color.r, color.g, color.b)?;
+            }
+            write!(f, ".")?;
+        }
+        Ok(())
+    }
+}
+
+fn count_layer_colors(layer: &Layer, counts: &mut HashMap<fixed::Color, usize>) {
+    match &layer.content {
+        Content::Shape(shapes) => count_shape_colors(shapes, counts),
+        Content::Solid { color, .. } => add_color(*color, counts),
+        _ => {}
+    }
+}
+
+fn count_shape_colors(shapes: &[Shape], counts: &mut HashMap<fixed::Color, usize>) {
+    for shape in shapes {
+        match shape {
+            Shape::Group(shapes, _) => count_shape_colors(shapes, counts),
+            Shape::Draw(draw) => match &draw.brush {
+                Brush::Fixed(brush) => count_brush_colors(brush, counts),
+                Brush::Animated(brush) => match brush.as_ref() {
+                    animated::Brush::Solid(value) => {
+                        let slot;
+                        let colors: &[fixed::Color] = match value {
+                            Value::Fixed(color) => std::slice::from_ref(color),
+                            Value::Animated(animated) => &animated.values,
+                            Value::Slot(value) => {
+                                slot = value.get().evaluate(0.0);
+                                std::slice::from_ref(&slot)
+                            }
+                        };
+                        for color in colors {
+                            add_color(*color, counts);
+                        }
+                    }
+                    animated::Brush::SplitSolid(_) => {
+                        count_brush_colors(&brush.evaluate(1.0, 0.0), counts);
+                    }
+                    animated::Brush::Gradient(gradient) => {
+                        count_brush_colors(&gradient.evaluate(0.0), counts);
+                    }
+                },
+            },
+            _ => {}
+        }
+    }
+}
+
+fn count_brush_colors(brush: &fixed::Brush, counts: &mut HashMap<fixed::Color, usize>) {
+    match brush {
+        fixed::Brush::Solid(color) => add_color(*color, counts),
+        fixed::Brush::Gradient(gradient) => {
+            for stop in gradient.stops.iter() {
+                add_color(stop.color, counts);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn add_color(color: fixed::Color, counts: &mut HashMap<fixed::Color, usize>) {
+    // Transparency is not meaningful in a textual description.
+    let color = fixed::Color::rgb8(color.r, color.g, color.b);
+    *counts.entry(color).or_default() += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::fixed;
+    use crate::Composition;
+    use serde_json::json;
+
+    #[test]
+    fn test_summary() {
+        let fill =
+            |color| json!({ "ty": "fl", "c": { "a": 0, "k": color }, "o": { "a": 0, "k": 100 } });
+        let layer = |name, shapes| {
+            json!({
+                "ty": 4, "nm": name, "ip": 0, "op": 60, "st": 0,
+                "ks": { "p": { "a": 0, "k": [0, 0] }, "s": { "a": 0, "k": [100, 100] } },
+                "shapes": shapes
+            })
+        };
+        let mut hidden = layer("Guides", json!([fill(json!([0, 1, 0]))]));
+        hidden["hd"] = json!(true);
+        let source = json!({
+            "v": "5.5.2", "fr": 30, "ip": 0, "op": 60, "w": 200, "h": 100,
+            "layers": [
+                layer("Logo", json!([fill(json!([1, 0, 0])), fill(json!([1, 0, 0]))])),
+                hidden,
+                layer("Background", json!([fill(json!([0, 0, 1]))]))
+            ],
+            "markers": [
+                { "cm": "outro", "tm": 30, "dr": 30 },
+                { "cm": "intro", "tm": 0, "dr": 30 }
+            ]
+        });
+        let summary = Composition::from_json(source).unwrap().summary();
+        assert_eq!(summary.duration, 2.0);
+        assert_eq!((summary.width, summary.height), (200, 100));
+        // Hidden layers are not described, nor are their colors.
+        assert_eq!(summary.layer_names, ["Logo", "Background"]);
+        assert_eq!(summary.markers, ["intro", "outro"]);
+        assert_eq!(summary.colors[0], fixed::Color::rgb8(255, 0, 0));
+        assert_eq!(
+            summary.to_string(),
+            "Animation, 2.0 seconds at 30 frames per second, 200x100 pixels. \
+             Layers: Logo, Background. Markers: intro, outro. \
+             Dominant colors: #ff0000, #0000ff."
+        );
+    }
+}
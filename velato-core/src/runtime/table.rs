@@ -0,0 +1,164 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::model::{Time, Tween, Value};
+use super::properties::{self, PropertyFilter, Visitor};
+use super::Composition;
+use kurbo::{Point, Size, Vec2};
+use peniko::Color;
+use std::any::Any;
+use std::fmt::Write as _;
+
+/// Value of an animated property at a frame, in a [`KeyframeTable`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyframeSample {
+    /// Slash separated path to the property, as in
+    /// [`AnimatedProperty::path`](super::AnimatedProperty::path).
+    pub path: String,
+    /// Frame in the local time of the layer containing the property.
+    pub frame: f64,
+    /// Index of the keyframe at this frame, or `None` for samples between
+    /// keyframes.
+    pub keyframe: Option<usize>,
+    /// Components of the value, such as the coordinates of a point or the
+    /// red, green, blue and alpha of a color from 0 to 1.
+    pub value: Vec<f64>,
+}
+
+/// Values of animated properties sampled at their keyframes, created by
+/// [`Composition::keyframe_table`].
+///
+/// This shows how the keyframes of a file are interpreted, such as to
+/// compare the motion between keyframes with the graph editor of After
+/// Effects.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct KeyframeTable {
+    /// Samples of each property in order of frame, with the properties in
+    /// the order of [`Composition::animated_properties`].
+    pub samples: Vec<KeyframeSample>,
+}
+
+impl KeyframeTable {
+    /// Formats the table as CSV, with a row for each sample and a column
+    /// for each component of the values.
+    pub fn to_csv(&self) -> String {
+        let components = self
+            .samples
+            .iter()
+            .map(|sample| sample.value.len())
+            .max()
+            .unwrap_or(0);
+        let mut csv = String::from("path,frame,keyframe");
+        for i in 0..components {
+            let _ = write!(csv, ",value{i}");
+        }
+        csv.push('\n');
+        for sample in &self.samples {
+            let _ = write!(csv, "{},{},", sample.path, sample.frame);
+            if let Some(keyframe) = sample.keyframe {
+                let _ = write!(csv, "{keyframe}");
+            }
+            for i in 0..components {
+                csv.push(',');
+                if let Some(component) = sample.value.get(i) {
+                    let _ = write!(csv, "{component}");
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Formats the table as a JSON array with an object for each sample.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.samples
+            .iter()
+            .map(|sample| {
+                serde_json::json!({
+                    "path": sample.path,
+                    "frame": sample.frame,
+                    "keyframe": sample.keyframe,
+                    "value": sample.value,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Samples the animated properties of a composition at their keyframes and
+/// at `steps` evenly spaced frames between each pair of keyframes.
+pub(crate) fn sample(
+    composition: &Composition,
+    filter: &PropertyFilter,
+    steps: usize,
+) -> KeyframeTable {
+    let mut sampler = Sampler {
+        filter,
+        steps,
+        table: KeyframeTable::default(),
+    };
+    properties::visit(composition, &mut sampler);
+    sampler.table
+}
+
+struct Sampler<'a> {
+    filter: &'a PropertyFilter,
+    steps: usize,
+    table: KeyframeTable,
+}
+
+impl Visitor for Sampler<'_> {
+    fn value<T: Tween + 'static>(&mut self, path: &str, name: &str, value: &Value<T>) {
+        let Value::Animated(animated) = value else {
+            return;
+        };
+        let path = format!("{path}/{name}");
+        if !self.filter.matches(&path) {
+            return;
+        }
+        let times = &animated.times;
+        for (i, time) in times.iter().enumerate() {
+            let mut push = |frame: f64, keyframe| {
+                let value = components(&value.evaluate(frame));
+                if let Some(value) = value {
+                    self.table.samples.push(KeyframeSample {
+                        path: path.clone(),
+                        frame,
+                        keyframe,
+                        value,
+                    });
+                }
+            };
+            push(time.frame, Some(i));
+            let Some(next) = times.get(i + 1) else {
+                continue;
+            };
+            for step in 1..=self.steps {
+                let t = step as f64 / (self.steps + 1) as f64;
+                push(time.frame + (next.frame - time.frame) * t, None);
+            }
+        }
+    }
+
+    fn times(&mut self, _path: String, _times: &[Time]) {}
+}
+
+/// Returns the components of a value that has them, such as a number or a
+/// point.
+fn components(value: &dyn Any) -> Option<Vec<f64>> {
+    if let Some(value) = value.downcast_ref::<f64>() {
+        Some(vec![*value])
+    } else if let Some(point) = value.downcast_ref::<Point>() {
+        Some(vec![point.x, point.y])
+    } else if let Some(vector) = value.downcast_ref::<Vec2>() {
+        Some(vec![vector.x, vector.y])
+    } else if let Some(size) = value.downcast_ref::<Size>() {
+        Some(vec![size.width, size.height])
+    } else {
+        value.downcast_ref::<Color>().map(|color| {
+            [color.r, color.g, color.b, color.a]
+                .map(|channel| channel as f64 / 255.0)
+                .to_vec()
+        })
+    }
+}
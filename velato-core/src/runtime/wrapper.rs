@@ -0,0 +1,87 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::Error;
+use serde_json::Value;
+
+/// Parses a document that contains an animation, possibly surrounded by a
+/// prefix such as a JSONP callback, and returns the animation object.
+///
+/// The animation is located with the given JSON pointer, or if none is
+/// given, as the first object in the document that looks like an
+/// animation.
+pub(super) fn unwrap_animation(source: &[u8], pointer: Option<&str>) -> Result<Value, Error> {
+    let mut document = match serde_json::from_slice::<Value>(source) {
+        Ok(document) => document,
+        // Strip anything outside of the outermost braces, such as
+        // `callback(...);` or `)]}'`.
+        Err(error) => {
+            let start = source.iter().position(|&b| b == b'{');
+            let end = source.iter().rposition(|&b| b == b'}');
+            match (start, end) {
+                (Some(start), Some(end)) if start < end => {
+                    serde_json::from_slice(&source[start..=end]).map_err(|_| error)?
+                }
+                _ => return Err(error.into()),
+            }
+        }
+    };
+    let animation = match pointer {
+        Some(pointer) => document.pointer_mut(pointer).map(Value::take),
+        None => find_animation(document.take()),
+    };
+    animation.ok_or(Error::AnimationNotFound)
+}
+
+/// Returns true if the value has the required properties of an animation.
+fn is_animation(value: &Value) -> bool {
+    value.get("layers").is_some_and(Value::is_array)
+        && ["ip", "op", "fr"]
+            .iter()
+            .all(|key| value.get(key).is_some_and(Value::is_number))
+}
+
+/// Searches a document for an animation, in depth first order. Animations
+/// encoded as strings within the document are also found.
+fn find_animation(value: Value) -> Option<Value> {
+    if is_animation(&value) {
+        return Some(value);
+    }
+    match value {
+        Value::Object(map) => map.into_iter().find_map(|(_, value)| find_animation(value)),
+        Value::Array(values) => values.into_iter().find_map(find_animation),
+        Value::String(string) if string.trim_start().starts_with('{') => {
+            find_animation(serde_json::from_str(&string).ok()?)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unwrap_animation;
+    use serde_json::json;
+
+    #[test]
+    fn test_unwrap() {
+        let animation = json!({ "ip": 0, "op": 60, "fr": 30, "layers": [] });
+        let envelope = json!({ "meta": { "id": 7 }, "data": { "animation": animation } });
+        let source = envelope.to_string();
+        assert_eq!(
+            unwrap_animation(source.as_bytes(), None).unwrap(),
+            animation
+        );
+        assert_eq!(
+            unwrap_animation(source.as_bytes(), Some("/data/animation")).unwrap(),
+            animation
+        );
+        assert!(unwrap_animation(source.as_bytes(), Some("/meta/name")).is_err());
+        let jsonp = format!("callback({source});");
+        assert_eq!(unwrap_animation(jsonp.as_bytes(), None).unwrap(), animation);
+        let nested = json!({ "animation": animation.to_string() }).to_string();
+        assert_eq!(
+            unwrap_animation(nested.as_bytes(), None).unwrap(),
+            animation
+        );
+    }
+}
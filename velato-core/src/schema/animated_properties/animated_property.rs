@@ -3,14 +3,15 @@
 
 use super::keyframe::Keyframe;
 use crate::schema::helpers::int_boolean::BoolInt;
-use serde::de::DeserializeOwned;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// An animatable property that holds an array of numbers.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AnimatedProperty<StaticType> {
     /// Property Index
-    #[serde(rename = "ix")]
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_index: Option<f64>,
     /// Whether the property is animated.
@@ -26,16 +27,21 @@ pub struct AnimatedProperty<StaticType> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub slot_id: Option<String>,
     #[serde(rename = "k")]
-    #[serde(bound = "StaticType: Serialize + DeserializeOwned")]
+    #[serde(bound = "StaticType: Serialize + permissive::Value")]
     pub value: AnimatedPropertyK<StaticType>,
 }
 
 /// The possible values of "k" in an [`AnimatedProperty`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum AnimatedPropertyK<StaticType> {
     /// Array of keyframes
     AnimatedValue(Vec<Keyframe>),
     /// Static value
-    Static(StaticType),
+    Static(
+        #[serde(deserialize_with = "permissive::value")]
+        #[serde(bound(deserialize = "StaticType: permissive::Value"))]
+        StaticType,
+    ),
 }
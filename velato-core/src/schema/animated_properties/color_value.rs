@@ -0,0 +1,26 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::animated_property::AnimatedProperty;
+use super::split_color::SplitColor;
+use crate::schema::helpers::color::Color;
+use serde::{Deserialize, Serialize};
+
+/// An animatable property that holds a Color.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ColorValue {
+    #[serde(flatten)]
+    pub animated_property: AnimatedProperty<Color>,
+}
+
+/// The possible values of a fill or stroke color.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AnyColorValue {
+    /// Color
+    Color(ColorValue),
+    /// Color with split channels
+    SplitColor(SplitColor),
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::multi_dimensional::MultiDimensional;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Represents colors and offsets in a gradient.
@@ -12,11 +13,12 @@ use serde::{Deserialize, Serialize};
 /// - With alpha, same as above but at the end of the list there is a sequence
 ///   of offset, alpha
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GradientColors {
     /// Colors in the gradient.
     #[serde(rename = "k")]
     pub colors: MultiDimensional,
     /// Number of colors in k.
-    #[serde(rename = "p")]
+    #[serde(rename = "p", deserialize_with = "permissive::number")]
     pub count: usize,
 }
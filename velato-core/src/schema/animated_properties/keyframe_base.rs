@@ -3,14 +3,16 @@
 
 use super::keyframe_bezier_handle::KeyframeBezierHandle;
 use crate::schema::helpers::int_boolean::BoolInt;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// A Keyframes specifies the value at a specific time and the interpolation
 /// function to reach the next keyframe.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KeyframeBase {
     /// Time
-    #[serde(rename = "t")]
+    #[serde(rename = "t", deserialize_with = "permissive::number")]
     pub time: f64,
     /// Hold
     #[serde(rename = "h")]
@@ -1,10 +1,12 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Represents a keyframe bezier handle.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KeyframeBezierHandle {
     /// X-coordinate of the handle.
     /// - 0 means start time of the keyframe.
@@ -20,10 +22,11 @@ pub struct KeyframeBezierHandle {
 
 /// Represents a component of the keyframe.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum KeyframeComponent {
     /// Array of component values.
-    ArrayOfValues(Vec<f64>),
+    ArrayOfValues(#[serde(deserialize_with = "permissive::numbers")] Vec<f64>),
     /// Single component value.
-    SingleValue(f64),
+    SingleValue(#[serde(deserialize_with = "permissive::number")] f64),
 }
@@ -12,5 +12,6 @@ pub mod position;
 pub mod position_keyframe;
 pub mod shape_keyframe;
 pub mod shape_property;
+pub mod split_color;
 pub mod split_vector;
 pub mod value;
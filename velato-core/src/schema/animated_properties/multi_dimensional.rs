@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::animated_property::AnimatedProperty;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// An animatable property that holds an array of numbers
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MultiDimensional {
     #[serde(flatten)]
     pub animated_property: AnimatedProperty<Vec<f64>>,
@@ -13,7 +15,7 @@ pub struct MultiDimensional {
     /// Number of components in the value arrays.
     /// If present values will be truncated or expanded to match this length
     /// when accessed from expressions.
-    #[serde(rename = "l")]
+    #[serde(rename = "l", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length: Option<f64>,
 }
@@ -3,13 +3,15 @@
 
 use super::position_keyframe::PositionKeyframe;
 use crate::schema::helpers::int_boolean::BoolInt;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// An animatable property to represent a position in space
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Position {
     /// The index of the property.
-    #[serde(rename = "ix")]
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_index: Option<f64>,
     /// Whether the property is animated
@@ -19,10 +21,14 @@ pub struct Position {
     #[serde(rename = "x")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expression: Option<String>,
+    /// One of the ID in the file's slots
+    #[serde(rename = "sid")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot_id: Option<String>,
     /// Number of components in the value arrays.
     /// If present, values will be truncated or expanded to match this length
     /// when accessed from expressions.
-    #[serde(rename = "l")]
+    #[serde(rename = "l", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length: Option<f64>,
     /// The value variant (Animated or Static).
@@ -32,8 +38,9 @@ pub struct Position {
 
 /// The possible values of "k" in a [`Position`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum PositionValueK {
     Animated(Vec<PositionKeyframe>),
-    Static(Vec<f64>),
+    Static(#[serde(deserialize_with = "permissive::numbers")] Vec<f64>),
 }
@@ -2,19 +2,35 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::keyframe::Keyframe;
+use crate::schema::helpers::int_boolean::BoolInt;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Position Keyframe
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PositionKeyframe {
     #[serde(flatten)]
     pub keyframe: Keyframe,
     /// In-Tangent for values (e.g., moving position around a curved path).
-    #[serde(rename = "ti")]
+    #[serde(
+        rename = "ti",
+        deserialize_with = "permissive::option_numbers",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_in_tangent: Option<Vec<f64>>,
     /// Out-Tangent for values (e.g., moving position around a curved path).
-    #[serde(rename = "to")]
+    #[serde(
+        rename = "to",
+        deserialize_with = "permissive::option_numbers",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_out_tangent: Option<Vec<f64>>,
+    /// Whether the keyframe is roving, with its time derived from the
+    /// neighbouring keyframes so the motion has a constant speed.
+    #[serde(rename = "r")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roving: Option<BoolInt>,
 }
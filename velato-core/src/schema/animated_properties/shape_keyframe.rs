@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// Keyframe holding Bezier objects
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShapeKeyframe {
     #[serde(flatten)]
     pub base: KeyframeBase,
@@ -6,12 +6,14 @@ use crate::schema::helpers::int_boolean::BoolInt;
 use serde::{Deserialize, Serialize};
 
 use super::shape_keyframe::ShapeKeyframe;
+use crate::schema::helpers::permissive;
 
 /// An animatable property that holds a Bezier
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShapeProperty {
     /// The index of the property.
-    #[serde(rename = "ix")]
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_index: Option<f64>,
     /// Whether the property is animated
@@ -29,6 +31,7 @@ pub struct ShapeProperty {
 
 /// The possible values of "k" in a [`ShapeProperty`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum ShapePropertyK {
     Animated(Vec<ShapeKeyframe>),
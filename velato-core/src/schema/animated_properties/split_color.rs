@@ -0,0 +1,27 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::value::FloatValue;
+use crate::schema::helpers::permissive;
+use serde::{Deserialize, Serialize};
+
+/// An animatable color that is split into individually animated channels.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SplitColor {
+    /// Flag that is true for colors with individually animated channels.
+    #[serde(rename = "s", deserialize_with = "permissive::boolean")]
+    pub split: bool,
+
+    /// Red channel, in the range 0 to 1.
+    #[serde(rename = "r")]
+    pub r: FloatValue,
+
+    /// Green channel, in the range 0 to 1.
+    #[serde(rename = "g")]
+    pub g: FloatValue,
+
+    /// Blue channel, in the range 0 to 1.
+    #[serde(rename = "b")]
+    pub b: FloatValue,
+}
@@ -2,14 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::value::FloatValue;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// An animatable property that is split into individually animated components.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SplitVector {
     /// Flag that is true for multidimensionals with individually animated
     /// components.
-    #[serde(rename = "s")]
+    #[serde(rename = "s", deserialize_with = "permissive::boolean")]
     pub split: bool,
 
     /// X component.
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// An animatable property that holds a float.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FloatValue {
     #[serde(flatten)]
     pub animated_property: AnimatedProperty<f64>,
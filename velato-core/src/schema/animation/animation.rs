@@ -1,12 +1,19 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::schema::{assets::AnyAsset, helpers::int_boolean::BoolInt, layers::AnyLayer};
+use super::motion_blur::MotionBlur;
+use super::slot::Slot;
+use crate::schema::helpers::permissive;
+use crate::schema::{
+    assets::AnyAsset, helpers::int_boolean::BoolInt, helpers::marker::Marker, layers::AnyLayer,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Display;
 
 /// Top level object, describing the animation
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Animation {
     /// Lottie file version
     #[serde(rename = "v")]
@@ -17,20 +24,20 @@ pub struct Animation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// Framerate in frames per second
-    #[serde(rename = "fr")]
+    #[serde(rename = "fr", deserialize_with = "permissive::number")]
     pub frame_rate: f64,
     /// "In Point", which frame the animation starts at (usually 0)
-    #[serde(rename = "ip")]
+    #[serde(rename = "ip", deserialize_with = "permissive::number")]
     pub in_point: f64,
     /// "Out Point", which frame the animation stops/loops at, which makes this
     /// the duration in frames when `ip` is 0
-    #[serde(rename = "op")]
+    #[serde(rename = "op", deserialize_with = "permissive::number")]
     pub out_point: f64,
     /// Width of the animation
-    #[serde(rename = "w")]
+    #[serde(rename = "w", deserialize_with = "permissive::number")]
     pub width: usize,
     /// Height of the animation
-    #[serde(rename = "h")]
+    #[serde(rename = "h", deserialize_with = "permissive::number")]
     pub height: usize,
     /// Whether the animation has 3D layers
     #[serde(rename = "ddd", default)]
@@ -42,6 +49,16 @@ pub struct Animation {
     /// List of layers
     #[serde(default)]
     pub layers: Vec<AnyLayer>,
+    /// Motion blur settings for layers with motion blur enabled
+    #[serde(rename = "mb")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub motion_blur: Option<MotionBlur>,
+    /// Properties referenced by the `sid` of animated properties, by id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<BTreeMap<String, Slot>>,
+    /// Named portions of the timeline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markers: Option<Vec<Marker>>,
 }
 
 impl Animation {
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 /// Base class for layer holders
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Composition {
     /// An array of Layers
     #[serde(rename = "layers")]
@@ -1,9 +1,10 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-// todo motion-blur
 #[allow(clippy::module_inception)]
 pub mod animation;
 // todo user-metadata
 pub mod composition;
+pub mod motion_blur;
+pub mod slot;
 // todo metadata
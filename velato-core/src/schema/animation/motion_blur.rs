@@ -0,0 +1,35 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::helpers::permissive;
+use serde::{Deserialize, Serialize};
+
+/// Motion blur settings
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MotionBlur {
+    /// Shutter angle in degrees
+    #[serde(rename = "sa", deserialize_with = "permissive::option_number", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shutter_angle: Option<f64>,
+    /// Shutter phase in degrees
+    #[serde(rename = "sp", deserialize_with = "permissive::option_number", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shutter_phase: Option<f64>,
+    /// Samples per frame
+    #[serde(
+        rename = "spf",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples_per_frame: Option<f64>,
+    /// Adaptive sample limit
+    #[serde(
+        rename = "asl",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_sample_limit: Option<f64>,
+}
@@ -0,0 +1,48 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::animated_property::AnimatedProperty;
+use crate::schema::helpers::permissive;
+use serde::de::Deserializer;
+use serde::{Deserialize, Serialize};
+
+/// Property that animated properties reference by its id, so that it can
+/// be themed
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Slot {
+    /// Value of the slot, or `None` for values other than numbers and
+    /// arrays of numbers, such as text documents and images
+    #[serde(rename = "p", deserialize_with = "property_or_none", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<AnimatedProperty<AnySlotValue>>,
+}
+
+/// Static value of a slot
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AnySlotValue {
+    /// Number, such as an opacity
+    Number(#[serde(deserialize_with = "permissive::number")] f64),
+    /// Array of numbers, such as a color or a position
+    Vector(#[serde(deserialize_with = "permissive::numbers")] Vec<f64>),
+}
+
+impl permissive::Value for AnySlotValue {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as Deserialize>::deserialize(deserializer)
+    }
+}
+
+/// Deserializes a slot property, ignoring properties of other types so that
+/// they do not prevent the animation from loading.
+pub fn property_or_none<'de, D>(
+    deserializer: D,
+) -> Result<Option<AnimatedProperty<AnySlotValue>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(AnimatedProperty::deserialize(value).ok())
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 ///
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Asset {
     /// Unique identifier used by layers when referencing this asset
     #[serde(rename = "id")]
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 ///
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FileAsset {
     #[serde(flatten)]
     pub asset: Asset,
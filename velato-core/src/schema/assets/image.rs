@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize, Serializer};
 
 /// External image
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Image {
     #[serde(flatten)]
     pub file_asset: FileAsset,
@@ -4,17 +4,19 @@
 use super::asset::Asset;
 use crate::schema::animation::composition::Composition;
 use crate::schema::helpers::int_boolean::BoolInt;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Asset containing an animation that can be referenced by layers.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Precomposition {
     #[serde(flatten)]
     pub asset: Asset,
     #[serde(flatten)]
     pub composition: Composition,
     /// Framerate in frames per second
-    #[serde(rename = "fr")]
+    #[serde(rename = "fr", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frame_rate: Option<f64>,
     /// Extra composition
@@ -6,6 +6,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// Layer and shape blend mode
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum BlendMode {
     Normal = 0,
     Multiply = 1,
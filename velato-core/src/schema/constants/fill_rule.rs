@@ -6,6 +6,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// Rule used to handle multiple shapes rendered with the same fill object
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FillRule {
     /// Everything is colored (You can think of this as an OR)
     NonZero = 1,
@@ -0,0 +1,31 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Type of a gradient
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum GradientType {
+    Linear = 1,
+    Radial = 2,
+}
+
+/// Type of a gradient, which some files keyframe
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AnyGradientType {
+    Fixed(GradientType),
+    /// Keyframes holding the numeric value of a gradient type
+    Animated(FloatValue),
+}
+
+impl From<GradientType> for AnyGradientType {
+    fn from(value: GradientType) -> Self {
+        Self::Fixed(value)
+    }
+}
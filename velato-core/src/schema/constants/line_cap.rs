@@ -0,0 +1,35 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Style at the end of a stoked line
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum LineCap {
+    ///
+    Butt = 1,
+    ///
+    Round = 2,
+    ///
+    Square = 3,
+}
+
+/// Style of a line cap, which some files keyframe
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AnyLineCap {
+    Fixed(LineCap),
+    /// Keyframes holding the numeric value of a line cap style
+    Animated(FloatValue),
+}
+
+impl From<LineCap> for AnyLineCap {
+    fn from(value: LineCap) -> Self {
+        Self::Fixed(value)
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Style at a sharp corner of a stoked line
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum LineJoin {
+    Miter = 1,
+    Round = 2,
+    Bevel = 3,
+}
+
+/// Style of a line join, which some files keyframe
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AnyLineJoin {
+    Fixed(LineJoin),
+    /// Keyframes holding the numeric value of a line join style
+    Animated(FloatValue),
+}
+
+impl From<LineJoin> for AnyLineJoin {
+    fn from(value: LineJoin) -> Self {
+        Self::Fixed(value)
+    }
+}
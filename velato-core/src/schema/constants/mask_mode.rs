@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 /// How masks interact with each other. See <https://helpx.adobe.com/after-effects/using/alpha-channels-masks-mattes.html>.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MaskMode {
     #[serde(rename = "n")]
     None,
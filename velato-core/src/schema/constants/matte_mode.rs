@@ -6,6 +6,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// How a layer should mask another layer
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MatteMode {
     Normal = 0,
     Alpha = 1,
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 /// Type of a dash item in a stroked line
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum StrokeDashType {
     #[serde(rename = "d")]
     Dash,
@@ -7,6 +7,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// range selector.
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum TextShape {
     Square = 1,
     RampUp = 2,
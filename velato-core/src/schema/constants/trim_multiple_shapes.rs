@@ -6,9 +6,10 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// How to handle multiple shapes in trim path
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum TrimMultipleShapes {
-    ///
-    Individually = 1,
-    ///
-    Simultaneously = 2,
+    /// Each shape is trimmed on its own
+    Simultaneously = 1,
+    /// Shapes are trimmed as one sequence
+    Individually = 2,
 }
@@ -0,0 +1,51 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::effect_value::EffectValue;
+use crate::schema::helpers::int_boolean::BoolInt;
+use crate::schema::helpers::permissive;
+use crate::schema::helpers::visual_object::VisualObject;
+use serde::{Deserialize, Serialize};
+
+/// Effect type of a fill.
+pub const FILL_EFFECT: u32 = 21;
+
+/// Effect type of a stroke.
+pub const STROKE_EFFECT: u32 = 22;
+
+/// Effect type of levels with individual controls for each channel.
+pub const LEVELS_EFFECT: u32 = 24;
+
+/// Effect type of a drop shadow.
+pub const DROP_SHADOW_EFFECT: u32 = 25;
+
+/// Effect type of a radial wipe.
+pub const RADIAL_WIPE_EFFECT: u32 = 26;
+
+/// Effect type of a displacement map.
+pub const DISPLACEMENT_MAP_EFFECT: u32 = 27;
+
+/// Match name of a linear wipe, which has no dedicated effect type.
+pub const LINEAR_WIPE_MATCH_NAME: &str = "ADBE Linear Wipe";
+
+/// Layer effect
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Effect {
+    #[serde(flatten)]
+    pub visual_object: VisualObject,
+    /// Effect type
+    #[serde(rename = "ty")]
+    pub effect_type: u32,
+    /// Effect Index
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<f64>,
+    /// Whether the effect is enabled
+    #[serde(rename = "en")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<BoolInt>,
+    /// Values of the effect, in an order that depends on the effect type
+    #[serde(rename = "ef", default)]
+    pub values: Vec<EffectValue>,
+}
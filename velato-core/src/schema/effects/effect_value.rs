@@ -0,0 +1,37 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
+use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::helpers::permissive;
+use crate::schema::helpers::visual_object::VisualObject;
+use serde::{Deserialize, Serialize};
+
+/// Value of an effect
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct EffectValue {
+    #[serde(flatten)]
+    pub visual_object: VisualObject,
+    /// Effect value type, such as 0 for a slider or 2 for a color
+    #[serde(rename = "ty")]
+    pub value_type: u32,
+    /// Effect Index
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<f64>,
+    /// Value, which is absent for values that are ignored
+    #[serde(rename = "v")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<EffectValueK>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum EffectValueK {
+    /// Colors and points
+    Vector(MultiDimensional),
+    /// Sliders, angles, checkboxes and the like
+    Scalar(FloatValue),
+}
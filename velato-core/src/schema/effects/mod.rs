@@ -0,0 +1,5 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+pub mod effect;
+pub mod effect_value;
@@ -1,27 +1,29 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// This represents a cubic bezier path.
 /// Note that for interpolation to work correctly all bezier values in a
 /// property's keyframe must have the same number of points.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Bezier {
     /// Whether the bezier forms a closed loop
-    #[serde(rename = "c")]
+    #[serde(rename = "c", deserialize_with = "permissive::option_boolean", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub closed: Option<bool>,
 
     /// Points along the curve
-    #[serde(rename = "v")]
+    #[serde(rename = "v", deserialize_with = "permissive::points")]
     pub vertices: Vec<[f64; 2]>,
 
     /// Cubic control points, incoming tangent
-    #[serde(rename = "i")]
+    #[serde(rename = "i", deserialize_with = "permissive::points")]
     pub in_tangents: Vec<[f64; 2]>,
 
     /// Cubic control points, outgoing tangent
-    #[serde(rename = "o")]
+    #[serde(rename = "o", deserialize_with = "permissive::points")]
     pub out_tangents: Vec<[f64; 2]>,
 }
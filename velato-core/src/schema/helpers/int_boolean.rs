@@ -0,0 +1,41 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::permissive::{self, BoolVisitor};
+use serde::{Deserialize, Deserializer};
+use serde_repr::Serialize_repr;
+
+/// Represents boolean value… 0 is false, 1 is true.
+///
+/// Booleans encoded as `true` and `false` are also accepted, except in
+/// [strict](permissive::strict) mode.
+#[derive(Serialize_repr, PartialEq, Default, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum BoolInt {
+    /// 0 = False
+    #[default]
+    False = 0,
+    /// 1 = True
+    True = 1,
+}
+
+impl<'de> Deserialize<'de> for BoolInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = if permissive::is_strict() {
+            match u8::deserialize(deserializer)? {
+                0 => false,
+                1 => true,
+                value => {
+                    return Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Unsigned(value as u64),
+                        &"0 or 1",
+                    ))
+                }
+            }
+        } else {
+            deserializer.deserialize_any(BoolVisitor)?
+        };
+        Ok(if value { Self::True } else { Self::False })
+    }
+}
@@ -1,11 +1,13 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Defines named portions of the composition
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Marker {
     /// Comment
     #[serde(rename = "cm")]
@@ -13,12 +15,12 @@ pub struct Marker {
     pub comment: Option<String>,
 
     /// Time
-    #[serde(rename = "tm")]
+    #[serde(rename = "tm", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time: Option<f64>,
 
     /// Duration
-    #[serde(rename = "dr")]
+    #[serde(rename = "dr", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<f64>,
 }
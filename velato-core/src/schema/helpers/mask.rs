@@ -1,9 +1,11 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
 use crate::schema::animated_properties::shape_property::ShapeProperty;
 use crate::schema::animated_properties::value::FloatValue;
 use crate::schema::constants::mask_mode::MaskMode;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// A layer can have an array of masks that clip the contents of the layer to a
@@ -14,6 +16,7 @@ use serde::{Deserialize, Serialize};
 /// With mattes, you use a layer to define the clipping area, while with masks
 /// you use an animated bezier curve.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Mask {
     /// Name, as seen from editors and the like
     #[serde(rename = "nm")]
@@ -26,7 +29,11 @@ pub struct Mask {
     pub match_name: Option<String>,
 
     /// Inverted
-    #[serde(rename = "inv")]
+    #[serde(
+        rename = "inv",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub inverted: Option<bool>,
 
@@ -49,4 +56,9 @@ pub struct Mask {
     #[serde(rename = "x")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expand: Option<FloatValue>,
+
+    /// Feather
+    #[serde(rename = "f")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feather: Option<MultiDimensional>,
 }
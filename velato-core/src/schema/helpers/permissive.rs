@@ -0,0 +1,264 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Deserializers that accept the loose encodings found in files exported by
+//! some tools, such as numbers encoded as strings and booleans encoded as
+//! `0` or `1`.
+//!
+//! Within [`strict`], every adapter deserializes exactly as the schema is
+//! written, which is useful for validating files.
+
+use serde::de::{self, DeserializeOwned, Deserializer, Unexpected, Visitor};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with loose encodings rejected by the adapters of this thread.
+///
+/// The setting is thread-local rather than passed to the adapters, as serde
+/// derives offer no way to thread it through the schema, and is restored
+/// when `f` returns or unwinds.
+pub fn strict<T>(f: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            STRICT.with(|strict| strict.set(self.0));
+        }
+    }
+    let _restore = Restore(STRICT.with(|strict| strict.replace(true)));
+    f()
+}
+
+/// Returns true if loose encodings are rejected.
+pub fn is_strict() -> bool {
+    STRICT.with(Cell::get)
+}
+
+/// Numbers that may be encoded as strings.
+pub trait Number: DeserializeOwned {
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+impl Number for f64 {
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl Number for usize {
+    fn from_f64(value: f64) -> Option<Self> {
+        (value >= 0.0 && value.fract() == 0.0 && value <= usize::MAX as f64)
+            .then_some(value as usize)
+    }
+}
+
+/// Values of animated properties, which are numbers or arrays of numbers.
+pub trait Value: Sized {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl Value for f64 {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        number(deserializer)
+    }
+}
+
+impl Value for Vec<f64> {
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        numbers(deserializer)
+    }
+}
+
+/// Deserializes a value of an animated property.
+pub fn value<'de, D: Deserializer<'de>, T: Value>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize(deserializer)
+}
+
+/// Wrapper that deserializes a number that may be encoded as a string.
+struct Loose<T>(T);
+
+impl<'de, T: Number> Deserialize<'de> for Loose<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if is_strict() {
+            return T::deserialize(deserializer).map(Loose);
+        }
+        deserializer
+            .deserialize_any(NumberVisitor(PhantomData))
+            .map(Loose)
+    }
+}
+
+struct NumberVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Number> Visitor<'de> for NumberVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a string containing a number")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<T, E> {
+        T::from_f64(value).ok_or_else(|| E::invalid_value(Unexpected::Float(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        T::from_f64(value as f64).ok_or_else(|| E::invalid_value(Unexpected::Signed(value), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        T::from_f64(value as f64)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite())
+            .and_then(T::from_f64)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
+
+/// Wrapper that deserializes a boolean that may be encoded as a number.
+struct LooseBool(bool);
+
+impl<'de> Deserialize<'de> for LooseBool {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if is_strict() {
+            return bool::deserialize(deserializer).map(LooseBool);
+        }
+        deserializer.deserialize_any(BoolVisitor).map(LooseBool)
+    }
+}
+
+/// Accepts `true`, `false`, `0` and `1`, and the same as strings.
+pub(crate) struct BoolVisitor;
+
+impl<'de> Visitor<'de> for BoolVisitor {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean, 0 or 1")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<bool, E> {
+        Ok(value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<bool, E> {
+        match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<bool, E> {
+        match value {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<bool, E> {
+        match value {
+            0.0 => Ok(false),
+            1.0 => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Float(value), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<bool, E> {
+        match value.trim() {
+            "0" | "false" => Ok(false),
+            "1" | "true" => Ok(true),
+            _ => Err(E::invalid_value(Unexpected::Str(value), &self)),
+        }
+    }
+}
+
+/// Deserializes a number that may be encoded as a string.
+pub fn number<'de, D: Deserializer<'de>, T: Number>(deserializer: D) -> Result<T, D::Error> {
+    Loose::deserialize(deserializer).map(|Loose(value)| value)
+}
+
+/// Deserializes an optional number that may be encoded as a string.
+pub fn option_number<'de, D: Deserializer<'de>, T: Number>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    Option::<Loose<T>>::deserialize(deserializer).map(|value| value.map(|Loose(value)| value))
+}
+
+/// Deserializes an array of numbers that may be encoded as strings.
+pub fn numbers<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+    Vec::<Loose<f64>>::deserialize(deserializer)
+        .map(|values| values.into_iter().map(|Loose(value)| value).collect())
+}
+
+/// Deserializes an optional array of numbers that may be encoded as strings.
+pub fn option_numbers<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<f64>>, D::Error> {
+    Option::<Vec<Loose<f64>>>::deserialize(deserializer)
+        .map(|values| values.map(|values| values.into_iter().map(|Loose(value)| value).collect()))
+}
+
+/// Deserializes an array of points whose coordinates may be encoded as
+/// strings.
+pub fn points<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<[f64; 2]>, D::Error> {
+    Vec::<[Loose<f64>; 2]>::deserialize(deserializer).map(|points| {
+        points
+            .into_iter()
+            .map(|[Loose(x), Loose(y)]| [x, y])
+            .collect()
+    })
+}
+
+/// Deserializes a boolean that may be encoded as a number.
+pub fn boolean<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    LooseBool::deserialize(deserializer).map(|LooseBool(value)| value)
+}
+
+/// Deserializes an optional boolean that may be encoded as a number.
+pub fn option_boolean<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    Option::<LooseBool>::deserialize(deserializer).map(|value| value.map(|LooseBool(value)| value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{boolean, number, numbers, strict};
+    use serde_json::json;
+
+    #[test]
+    fn test_loose_encodings() {
+        let index = |value| number::<_, usize>(value);
+        let number = |value| number::<_, f64>(value);
+        assert_eq!(number(json!(1.5)).unwrap(), 1.5);
+        assert_eq!(number(json!(" 100 ")).unwrap(), 100.0);
+        assert!(number(json!("1px")).is_err());
+        assert!(number(json!("NaN")).is_err());
+        assert_eq!(index(json!("3")).unwrap(), 3);
+        assert!(index(json!(-1)).is_err());
+        assert_eq!(numbers(json!([1, "2", 3.5])).unwrap(), vec![1.0, 2.0, 3.5]);
+        assert!(boolean(json!(1)).unwrap());
+        assert!(!boolean(json!("false")).unwrap());
+        assert!(boolean(json!(2)).is_err());
+        // Strict mode accepts only the encodings of the schema.
+        strict(|| {
+            assert!(number(json!("100")).is_err());
+            assert!(boolean(json!(1)).is_err());
+            assert_eq!(number(json!(100)).unwrap(), 100.0);
+        });
+        assert_eq!(number(json!("100")).unwrap(), 100.0);
+    }
+}
@@ -10,6 +10,7 @@ use crate::schema::animated_properties::value::FloatValue;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Transform {
     /// Position (relative to its parent) around which transformations are
     /// applied (ie: center for rotation / scale)
@@ -28,6 +29,23 @@ pub struct Transform {
     #[serde(rename = "r")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rotation: Option<AnyTransformR>,
+    /// Rotation around the X axis in degrees, for 3D layers
+    #[serde(rename = "rx")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation_x: Option<FloatValue>,
+    /// Rotation around the Y axis in degrees, for 3D layers
+    #[serde(rename = "ry")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation_y: Option<FloatValue>,
+    /// Rotation around the Z axis in degrees, for 3D layers (equivalent to
+    /// "r")
+    #[serde(rename = "rz")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation_z: Option<FloatValue>,
+    /// Orientation in degrees around the X, Y and Z axes, for 3D layers
+    #[serde(rename = "or")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<MultiDimensional>,
     /// Skew amount as an angle in degrees
     #[serde(rename = "sk")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,6 +63,7 @@ pub struct Transform {
 
 /// The possible values of "p" in a [`Transform`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum AnyTransformP {
     /// Position / Translation
@@ -55,6 +74,7 @@ pub enum AnyTransformP {
 
 /// The possible values of "r" in a [`Transform`].
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum AnyTransformR {
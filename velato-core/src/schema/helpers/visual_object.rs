@@ -4,6 +4,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, PartialEq, Default, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VisualObject {
     /// Name, as seen from editors and the like
     #[serde(rename = "nm")]
@@ -0,0 +1,24 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::VisualLayer;
+use serde::{Deserialize, Serialize};
+
+#[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum LayerId {
+    Adjustment = 12,
+}
+
+/// No contents, applies its effects to the layers below it
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AdjustmentLayer {
+    #[serde(flatten)]
+    pub properties: VisualLayer,
+
+    /// Layer type, must be 12
+    #[serde(rename = "ty")]
+    pub layer_type: LayerId,
+}
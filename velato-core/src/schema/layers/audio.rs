@@ -0,0 +1,59 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::helpers::permissive;
+use serde::{Deserialize, Serialize};
+
+#[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum LayerId {
+    Audio = 6,
+}
+
+/// Plays a sound asset. Audio layers have no transform, so they only share
+/// the timing of visual layers.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AudioLayer {
+    /// Layer type, must be 6
+    #[serde(rename = "ty")]
+    pub layer_type: LayerId,
+    /// Name, as seen from editors and the like
+    #[serde(rename = "nm")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether the layer is hidden
+    #[serde(
+        rename = "hd",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+    /// Layer index for parenting
+    #[serde(
+        rename = "ind",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Time Stretch
+    #[serde(rename = "sr", deserialize_with = "permissive::option_number", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_stretch: Option<f64>,
+    /// Frame when the layer starts playing
+    #[serde(rename = "ip", deserialize_with = "permissive::number")]
+    pub in_point: f64,
+    /// Frame when the layer stops playing
+    #[serde(rename = "op", deserialize_with = "permissive::number")]
+    pub out_point: f64,
+    /// Start Time
+    #[serde(rename = "st", deserialize_with = "permissive::number")]
+    pub start_time: f64,
+    /// ID of the sound asset
+    #[serde(rename = "refId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound_id: Option<String>,
+}
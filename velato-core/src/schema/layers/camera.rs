@@ -0,0 +1,31 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::visual::VisualLayer;
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+
+#[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum LayerId {
+    Camera = 13,
+}
+
+/// 3D camera. The anchor point of the transform is the point of interest
+/// that the camera looks at.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct CameraLayer {
+    #[serde(flatten)]
+    pub properties: VisualLayer,
+
+    /// Layer type, must be 13
+    #[serde(rename = "ty")]
+    pub layer_type: LayerId,
+
+    /// Distance from the camera to the plane that is drawn at its original
+    /// size
+    #[serde(rename = "pe")]
+    pub perspective: FloatValue,
+}
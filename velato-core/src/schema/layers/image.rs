@@ -0,0 +1,28 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::visual::VisualLayer;
+use serde::{Deserialize, Serialize};
+
+#[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum LayerId {
+    Image = 2,
+}
+
+/// Renders an image
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ImageLayer {
+    #[serde(flatten)]
+    pub properties: VisualLayer,
+
+    /// Layer type, must be 2
+    #[serde(rename = "ty")]
+    pub layer_type: LayerId,
+
+    /// ID of the image as specified in the assets
+    #[serde(rename = "refId")]
+    pub image_id: String,
+}
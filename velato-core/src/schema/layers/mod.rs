@@ -1,23 +1,34 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub mod adjustment;
+pub mod audio;
+pub mod camera;
 pub mod enumerations;
+pub mod image;
 pub mod null;
 pub mod precomposition;
 pub mod shape;
 pub mod solid_color;
+pub mod text;
 pub mod visual;
 
 use self::solid_color::SolidColorLayer;
 use self::visual::VisualLayer;
+use adjustment::AdjustmentLayer;
+use audio::AudioLayer;
+use camera::CameraLayer;
+use image::ImageLayer;
 use null::NullLayer;
 use precomposition::PrecompositionLayer;
 use serde::{Deserialize, Serialize};
 use shape::ShapeLayer;
+use text::TextLayer;
 
 /// There are several layer types, which is specified by the 'ty' attribute. All
 /// layers share the properties in `layers::common::Properties`.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum AnyLayer {
     /// Renders a Precomposition
@@ -27,22 +38,25 @@ pub enum AnyLayer {
     SolidColor(SolidColorLayer),
 
     /// Renders an Image
-    // todo Image
+    Image(ImageLayer),
 
     /// No contents, only used for parenting
 
     /// Has an array of shapes
     Shape(ShapeLayer),
-    // Renders Text
-    // todo Text
+    /// Renders Text
+    Text(TextLayer),
+    /// 3D camera
+    Camera(CameraLayer),
     Null(NullLayer),
-    // unimplemented - Audio(AudioLayer),
+    /// Applies its effects to the layers below it
+    Adjustment(AdjustmentLayer),
+    /// Plays a sound
+    Audio(AudioLayer),
     // unimplemented - VideoPlaceholder(VideoPlaceholderLayer)
     // unimplemented - Video(VideoLayer)
     // unimplemented - ImagePlaceholder(ImagePlaceholderLayer)
     // unimplemented - Guide(GuideLayer)
-    // unimplemented - Adjustment(AdjustmentLayer)
-    // unimplemented - Camera(CameraLayer)
     // unimplemented - Light(LightLayer)
     // unimplemented - Data(DataLayer)
 }
@@ -163,6 +177,7 @@ mod tests {
                         property_index: None,
                         animated: Some(BoolInt::False),
                         expression: None,
+                        slot_id: None,
                         length: None,
                         value: PositionValueK::Static(vec![256.0, 256.0]),
                     }),
@@ -170,6 +185,7 @@ mod tests {
                         property_index: None,
                         animated: Some(BoolInt::False),
                         expression: None,
+                        slot_id: None,
                         length: None,
                         value: PositionValueK::Static(vec![256.0, 256.0]),
                     }),
@@ -192,6 +208,10 @@ mod tests {
                             value: AnimatedPropertyK::Static(0.0),
                         },
                     })),
+                    rotation_x: None,
+                    rotation_y: None,
+                    rotation_z: None,
+                    orientation: None,
                     opacity: Some(FloatValue {
                         animated_property: AnimatedProperty {
                             animated: Some(BoolInt::False),
@@ -210,10 +230,14 @@ mod tests {
                 matte_mode: None,
                 matte_target: None,
                 masks_properties: None,
+                effects: None,
+                styles: None,
                 rotate_to_match_anim_pos_path: None,
                 matte_layer_index: None,
                 has_mask: None,
                 motion_blur: None,
+                preserve_transparency: None,
+                knockout: None,
                 blend_mode: None,
                 css_class: None,
                 id: None,
@@ -250,10 +274,12 @@ mod tests {
                         css_class: None,
                         xml_id: None,
                     },
+                    direction: None,
                     position: Position {
                         property_index: None,
                         animated: Some(BoolInt::False),
                         expression: None,
+                        slot_id: None,
                         length: None,
                         value: PositionValueK::Static(vec![303.9044776119403, 324.9671641791045]),
                     },
@@ -5,12 +5,14 @@ use super::VisualLayer;
 use serde::{Deserialize, Serialize};
 
 #[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum LayerId {
     Null = 3,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct NullLayer {
     #[serde(flatten)]
     pub properties: VisualLayer,
@@ -4,10 +4,14 @@
 use serde::{Deserialize, Serialize};
 
 use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::animation::slot::Slot;
+use std::collections::BTreeMap;
 
 use super::visual::VisualLayer;
+use crate::schema::helpers::permissive;
 
 #[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum LayerId {
     Precomposition = 0,
@@ -15,6 +19,7 @@ pub enum LayerId {
 
 /// Renders a Precomposition
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PrecompositionLayer {
     #[serde(flatten)]
     pub properties: VisualLayer,
@@ -25,15 +30,19 @@ pub struct PrecompositionLayer {
     #[serde(rename = "refId")]
     pub precomp_id: String,
     /// Width of the clipping rect
-    #[serde(rename = "w")]
+    #[serde(rename = "w", deserialize_with = "permissive::number")]
     pub width: f64,
     /// Height of the clipping rect
-    #[serde(rename = "h")]
+    #[serde(rename = "h", deserialize_with = "permissive::number")]
     pub height: f64,
     /// Time Remapping
     #[serde(rename = "tm")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_remap: Option<FloatValue>,
+    /// Values of the slots within the precomp that this layer overrides,
+    /// such as the essential properties of a composition, by id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<BTreeMap<String, Slot>>,
 }
 
 #[cfg(test)]
@@ -8,6 +8,7 @@ use crate::schema::shapes::AnyShape;
 use serde::{Deserialize, Serialize};
 
 #[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum LayerId {
     Shape = 4,
@@ -15,6 +16,7 @@ pub enum LayerId {
 
 /// Has an array of shapes
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShapeLayer {
     #[serde(flatten)]
     pub properties: VisualLayer,
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::visual::VisualLayer;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 #[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum LayerId {
     SolidColor = 1,
@@ -12,6 +14,7 @@ pub enum LayerId {
 
 /// Has an array of shapes
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SolidColorLayer {
     #[serde(flatten)]
     pub properties: VisualLayer,
@@ -25,9 +28,9 @@ pub struct SolidColorLayer {
     #[serde(rename = "sc")]
     pub color: String,
 
-    #[serde(rename = "sh")]
+    #[serde(rename = "sh", deserialize_with = "permissive::number")]
     pub height: f64,
 
-    #[serde(rename = "sw")]
+    #[serde(rename = "sw", deserialize_with = "permissive::number")]
     pub width: f64,
 }
@@ -0,0 +1,24 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::visual::VisualLayer;
+use serde::{Deserialize, Serialize};
+
+#[derive(serde_repr::Deserialize_repr, serde_repr::Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(u8)]
+pub enum LayerId {
+    Text = 5,
+}
+
+/// Renders text. The text itself is not yet parsed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TextLayer {
+    #[serde(flatten)]
+    pub properties: VisualLayer,
+
+    /// Layer type, must be 5
+    #[serde(rename = "ty")]
+    pub layer_type: LayerId,
+}
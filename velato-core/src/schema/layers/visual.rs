@@ -3,13 +3,17 @@
 
 use crate::schema::constants::blend_mode::BlendMode;
 use crate::schema::constants::matte_mode::MatteMode;
+use crate::schema::effects::effect::Effect;
 use crate::schema::helpers::int_boolean::BoolInt;
 use crate::schema::helpers::mask::Mask;
+use crate::schema::helpers::permissive;
 use crate::schema::helpers::transform::Transform;
+use crate::schema::styles::AnyLayerStyle;
 use serde::{Deserialize, Serialize};
 
 /// Common properties between layers
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VisualLayer {
     /// Name, as seen from editors and the like
     #[serde(rename = "nm")]
@@ -25,29 +29,41 @@ pub struct VisualLayer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub three_dimensional: Option<BoolInt>,
     /// Whether the layer is hidden
-    #[serde(rename = "hd", default)]
+    #[serde(
+        rename = "hd",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hidden: Option<bool>,
     /// Layer index for parenting
-    #[serde(rename = "ind")]
+    #[serde(
+        rename = "ind",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub index: Option<usize>,
     /// Parent index for parenting
-    #[serde(rename = "parent")]
+    #[serde(
+        rename = "parent",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_index: Option<usize>,
     /// Time Stretch
-    #[serde(rename = "sr")]
+    #[serde(rename = "sr", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_stretch: Option<f64>,
     /// Frame when the layer becomes visible
-    #[serde(rename = "ip")]
+    #[serde(rename = "ip", deserialize_with = "permissive::number")]
     pub in_point: f64,
     /// Frame when the layer becomes invisible
-    #[serde(rename = "op")]
+    #[serde(rename = "op", deserialize_with = "permissive::number")]
     pub out_point: f64,
     /// Start Time
-    #[serde(rename = "st")]
+    #[serde(rename = "st", deserialize_with = "permissive::number")]
     pub start_time: f64,
     /// Matte mode
     #[serde(rename = "tt")]
@@ -61,15 +77,14 @@ pub struct VisualLayer {
     #[serde(rename = "masksProperties")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub masks_properties: Option<Vec<Mask>>,
-    // TODO:
     /// Effects for the layer
-    //#[serde(rename = "ef")]
-    //#[serde(skip_serializing_if = "Option::is_none")]
-    //pub effects: Option<Vec<()>>,
+    #[serde(rename = "ef")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effects: Option<Vec<Effect>>,
     /// Layer styles
-    //#[serde(rename = "sy")]
-    //#[serde(skip_serializing_if = "Option::is_none")]
-    //pub styles: Option<Vec<()>>,
+    #[serde(rename = "sy")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub styles: Option<Vec<AnyLayerStyle>>,
     /// Layer transform
     #[serde(rename = "ks")]
     pub transform: Transform,
@@ -79,17 +94,33 @@ pub struct VisualLayer {
     pub rotate_to_match_anim_pos_path: Option<BoolInt>,
     /// Index of the layer used as matte, if omitted assume the layer above the
     /// current one
-    #[serde(rename = "tp")]
+    #[serde(rename = "tp", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub matte_layer_index: Option<f64>,
     /// Whether the layer has masks applied
-    #[serde(rename = "hasMask", default)]
+    #[serde(
+        rename = "hasMask",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_mask: Option<bool>,
     /// Whether motion blur is enabled for the layer
-    #[serde(rename = "mb", default)]
+    #[serde(
+        rename = "mb",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub motion_blur: Option<bool>,
+    /// If 1, the layer only draws where the layers below it are opaque
+    #[serde(rename = "pt", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserve_transparency: Option<BoolInt>,
+    /// If 1, the layer knocks out the layers below it within its composition
+    #[serde(rename = "ko", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub knockout: Option<BoolInt>,
     /// Blend Mode
     #[serde(rename = "bm")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -9,8 +9,7 @@ pub mod animated_properties;
 pub mod animation;
 pub mod assets;
 pub mod constants;
-// todo effect-values
-// todo effects
+pub mod effects;
 pub mod helpers;
 pub mod layers;
 pub mod shapes;
@@ -52,6 +51,9 @@ mod tests {
         three_dimensional: Some(BoolInt::False),
         layers: vec![],
         assets: None,
+        motion_blur: None,
+        slots: None,
+        markers: None,
     });
 
     #[test]
@@ -78,4 +80,32 @@ mod tests {
     fn test_can_serialize() {
         serde_json::to_value(&*LOTTIE).unwrap();
     }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_round_trip() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        // Xorshift with a fixed seed so that failures are reproducible.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut parsed = 0;
+        for _ in 0..64 {
+            let bytes = (0..4096)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect::<Vec<_>>();
+            let animation = Animation::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let json = serde_json::to_string(&animation).unwrap();
+            // Random values may be ambiguous between variants of untagged
+            // enums, so not every animation is expected to parse back.
+            if serde_json::from_str::<Animation>(&json).is_ok() {
+                parsed += 1;
+            }
+        }
+        assert!(parsed >= 48, "only {parsed} of 64 animations parsed");
+    }
 }
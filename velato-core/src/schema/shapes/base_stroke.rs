@@ -3,22 +3,24 @@
 
 use super::stroke_dash::StrokeDash;
 use crate::schema::animated_properties::value::FloatValue;
-use crate::schema::constants::line_cap::LineCap;
-use crate::schema::constants::line_join::LineJoin;
+use crate::schema::constants::line_cap::AnyLineCap;
+use crate::schema::constants::line_join::AnyLineJoin;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct BaseStroke {
     /// Line Cap
     #[serde(rename = "lc")]
-    pub line_cap: Option<LineCap>,
+    pub line_cap: Option<AnyLineCap>,
 
     /// Line Join
     #[serde(rename = "lj")]
-    pub line_join: Option<LineJoin>,
+    pub line_join: Option<AnyLineJoin>,
 
     /// Miter Limit
-    #[serde(rename = "ml")]
+    #[serde(rename = "ml", deserialize_with = "permissive::option_number", default)]
     pub miter_limit: Option<f64>,
 
     /// Animatable alternative to miter limit
@@ -4,12 +4,18 @@
 use super::shape_element::ShapeElement;
 use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
 use crate::schema::animated_properties::position::Position;
+use crate::schema::constants::shape_direction::ShapeDirection;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct EllipseShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
+    /// Direction the shape is drawn as, mostly relevant when using trim path
+    #[serde(rename = "d")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<ShapeDirection>,
     /// Position
     #[serde(rename = "p")]
     pub position: Position,
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use super::shape_element::ShapeElement;
-use crate::schema::animated_properties::color_value::ColorValue;
+use crate::schema::animated_properties::color_value::AnyColorValue;
 use crate::schema::constants::fill_rule::FillRule;
 use crate::schema::shapes::FloatValue;
 use serde::{Deserialize, Serialize};
 
 /// Solid fill color
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FillShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
@@ -20,7 +21,7 @@ pub struct FillShape {
 
     /// Color
     #[serde(rename = "c")]
-    pub color: ColorValue,
+    pub color: AnyColorValue,
 
     /// Fill Rule
     #[serde(rename = "r")]
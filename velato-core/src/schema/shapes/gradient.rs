@@ -4,11 +4,12 @@
 use crate::schema::animated_properties::gradient_colors::GradientColors;
 use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
 use crate::schema::animated_properties::value::FloatValue;
-use crate::schema::constants::gradient_type::GradientType;
+use crate::schema::constants::gradient_type::AnyGradientType;
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// Represents a gradient.
 pub struct Gradient {
     /// Describes the starting point for the gradient.
@@ -21,7 +22,7 @@ pub struct Gradient {
 
     /// Indicates the type of the gradient.
     #[serde(rename = "t")]
-    pub gradient_type: Option<GradientType>,
+    pub gradient_type: Option<AnyGradientType>,
 
     /// Represents the highlight length as a percentage between start and end
     /// points.
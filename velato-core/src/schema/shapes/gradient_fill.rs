@@ -8,6 +8,7 @@ use crate::schema::constants::fill_rule::FillRule;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GradientFillShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
@@ -0,0 +1,133 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::base_stroke::BaseStroke;
+use super::gradient::Gradient;
+use super::shape_element::ShapeElement;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct GradientStrokeShape {
+    #[serde(flatten)]
+    pub shape_element: ShapeElement,
+
+    #[serde(flatten)]
+    pub base_stroke: BaseStroke,
+
+    #[serde(flatten)]
+    pub gradient: Gradient,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GradientStrokeShape;
+    use crate::schema::{
+        animated_properties::{
+            animated_property::{AnimatedProperty, AnimatedPropertyK},
+            gradient_colors::GradientColors,
+            multi_dimensional::MultiDimensional,
+            value::FloatValue,
+        },
+        constants::{gradient_type::GradientType, line_cap::LineCap, line_join::LineJoin},
+        helpers::{int_boolean::BoolInt, visual_object::VisualObject},
+        shapes::{base_stroke::BaseStroke, gradient::Gradient, shape_element::ShapeElement},
+    };
+    use once_cell::sync::Lazy;
+    use serde_json::json;
+
+    static JSON: Lazy<serde_json::Value> = Lazy::new(|| {
+        json!(
+            {
+                "nm": "Gradient Stroke",
+                "o": { "a": 0, "k": 50 },
+                "w": { "a": 0, "k": 4 },
+                "lc": 2,
+                "lj": 1,
+                "ml": 4,
+                "s": { "a": 0, "k": [0, 0] },
+                "e": { "a": 0, "k": [100, 0] },
+                "t": 1,
+                "g": {
+                    "p": 2,
+                    "k": { "a": 0, "k": [0, 1, 0, 0, 1, 0, 0, 1] }
+                }
+            }
+        )
+    });
+
+    fn multi(value: Vec<f64>) -> MultiDimensional {
+        MultiDimensional {
+            animated_property: AnimatedProperty {
+                property_index: None,
+                animated: Some(BoolInt::False),
+                expression: None,
+                slot_id: None,
+                value: AnimatedPropertyK::Static(value),
+            },
+            length: None,
+        }
+    }
+
+    fn float(value: f64) -> FloatValue {
+        FloatValue {
+            animated_property: AnimatedProperty {
+                property_index: None,
+                animated: Some(BoolInt::False),
+                expression: None,
+                slot_id: None,
+                value: AnimatedPropertyK::Static(value),
+            },
+        }
+    }
+
+    static SHAPE: Lazy<GradientStrokeShape> = Lazy::new(|| GradientStrokeShape {
+        shape_element: ShapeElement {
+            visual_object: VisualObject {
+                name: Some("Gradient Stroke".to_string()),
+                match_name: None,
+            },
+            hidden: None,
+            blend_mode: None,
+            property_index: None,
+            css_class: None,
+            xml_id: None,
+            index: None,
+        },
+        base_stroke: BaseStroke {
+            line_cap: Some(LineCap::Round.into()),
+            line_join: Some(LineJoin::Miter.into()),
+            miter_limit: Some(4.0),
+            miter_limit_anim: None,
+            opacity: float(50.0),
+            width: float(4.0),
+            dashes: None,
+        },
+        gradient: Gradient {
+            start_point: multi(vec![0.0, 0.0]),
+            end_point: multi(vec![100.0, 0.0]),
+            gradient_type: Some(GradientType::Linear.into()),
+            highlight_length: None,
+            highlight_angle: None,
+            colors: GradientColors {
+                colors: multi(vec![0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]),
+                count: 2,
+            },
+        },
+    });
+
+    #[test]
+    fn test_deserialize() {
+        let actual = serde_json::from_value(JSON.to_owned());
+
+        match actual {
+            Ok(actual) => assert_eq!(*SHAPE, actual),
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn test_can_serialize() {
+        serde_json::to_value(&*SHAPE).unwrap();
+    }
+}
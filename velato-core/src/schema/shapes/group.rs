@@ -3,21 +3,27 @@
 
 use super::shape_element::ShapeElement;
 use super::AnyShape;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
     /// Number of properties
-    #[serde(rename = "np")]
+    #[serde(rename = "np", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_properties: Option<f64>,
     /// Array of shapes
     #[serde(rename = "it")]
     pub shapes: Vec<AnyShape>,
     /// Index used in expressions
-    #[serde(rename = "cix")]
+    #[serde(
+        rename = "cix",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_index: Option<f64>,
 }
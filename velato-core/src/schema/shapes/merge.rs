@@ -9,6 +9,7 @@ use super::shape_element::ShapeElement;
 
 /// Boolean operator on shapes
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MergeShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
@@ -13,19 +13,19 @@ pub mod pucker_bloat;
 pub mod rectangle;
 pub mod repeater;
 pub mod repeater_transform;
+pub mod rounded_corners;
 pub mod shape;
 pub mod shape_element;
 pub mod stroke;
 pub mod stroke_dash;
 pub mod transform;
 pub mod trim;
+pub mod twist;
+pub mod zig_zag;
 // todo pub mod stroke_dash;
 // todo pub mod shape_list;
-// todo pub mod zig_zag;
 // todo pub mod no_style;
 pub mod base_stroke;
-// todo pub mod twist;
-// todo pub mod rounded_corners;
 pub mod gradient;
 pub mod gradient_fill;
 // todo pub mod modifier;
@@ -36,12 +36,16 @@ use self::gradient_stroke::GradientStrokeShape;
 use self::merge::MergeShape;
 use self::offset_path::OffsetPathShape;
 use self::path::PathShape;
+use self::polystar::PolyStarShape;
 use self::pucker_bloat::PuckerBloatShape;
 use self::rectangle::RectangleShape;
 use self::repeater::RepeaterShape;
+use self::rounded_corners::RoundedCornersShape;
 use self::stroke::StrokeShape;
 use self::transform::TransformShape;
 use self::trim::TrimShape;
+use self::twist::TwistShape;
+use self::zig_zag::ZigZagShape;
 use super::animated_properties::value::FloatValue;
 use ellipse::EllipseShape;
 use group::GroupShape;
@@ -50,6 +54,7 @@ use serde::{Deserialize, Serialize};
 /// Lottie considers everything related to vector data as a "shape". All shapes
 /// share the properties in `shapes::common::Properties`.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "ty")]
 #[allow(clippy::large_enum_variant)]
 pub enum AnyShape {
@@ -63,6 +68,9 @@ pub enum AnyShape {
     /// An ellipse, defined by its center point and width and height.
     #[serde(rename = "el")]
     Ellipse(EllipseShape),
+    /// A star or regular polygon.
+    #[serde(rename = "sr")]
+    PolyStar(PolyStarShape),
     #[serde(rename = "tr")]
     Transform(TransformShape),
     #[serde(rename = "st")]
@@ -85,14 +93,18 @@ pub enum AnyShape {
     GradientFill(GradientFillShape),
     #[serde(rename = "gs")]
     GradientStroke(GradientStrokeShape),
+    #[serde(rename = "zz")]
+    ZigZag(ZigZagShape),
+    #[serde(rename = "rd")]
+    RoundedCorners(RoundedCornersShape),
+    #[serde(rename = "tw")]
+    Twist(TwistShape),
     // TODO: model other shapes
-    // todo ZigZag(zig_zag),
     // todo no_style(no_style),
-    // todo Twist(twist),
-    // todo RoundedCorners(rounded_corners),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ShapeType {
     #[serde(rename = "rc")]
     Rectangle,
@@ -140,6 +152,7 @@ mod tests {
     use crate::schema::{
         animated_properties::{
             animated_property::{AnimatedProperty, AnimatedPropertyK},
+            color_value::AnyColorValue,
             multi_dimensional::MultiDimensional,
             position::{Position, PositionValueK},
         },
@@ -208,10 +221,12 @@ mod tests {
                     css_class: None,
                     xml_id: None,
                 },
+                direction: None,
                 position: Position {
                     property_index: None,
                     animated: Some(BoolInt::False),
                     expression: None,
+                    slot_id: None,
                     length: None,
                     value: PositionValueK::Static(vec![303.9044776119403, 324.9671641791045]),
                 },
@@ -246,4 +261,24 @@ mod tests {
     fn test_can_serialize() {
         serde_json::to_value(&*LAYER).unwrap();
     }
+
+    #[test]
+    fn test_deserialize_split_color() {
+        let json = json!(
+            {
+                "ty": "fl",
+                "c": {
+                    "s": true,
+                    "r": { "a": 0, "k": 1 },
+                    "g": { "a": 0, "k": 0.5 },
+                    "b": { "a": 0, "k": 0 }
+                }
+            }
+        );
+        let actual: AnyShape = serde_json::from_value(json).unwrap();
+        let AnyShape::Fill(fill) = actual else {
+            panic!("expected fill");
+        };
+        assert!(matches!(fill.color, AnyColorValue::SplitColor(_)));
+    }
 }
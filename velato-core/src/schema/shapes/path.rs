@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// Animatable Bezier curve
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PathShape {
     #[serde(flatten)]
     pub shape: Shape,
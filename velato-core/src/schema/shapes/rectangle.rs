@@ -4,15 +4,21 @@
 use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
 use crate::schema::animated_properties::position::Position;
 use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::constants::shape_direction::ShapeDirection;
 use serde::{Deserialize, Serialize};
 
 use super::shape_element::ShapeElement;
 
 /// A rectangle, defined by its center point and size.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RectangleShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
+    /// Direction the shape is drawn as, mostly relevant when using trim path
+    #[serde(rename = "d")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<ShapeDirection>,
     /// Center of the rectangle
     #[serde(rename = "p")]
     pub position: Position,
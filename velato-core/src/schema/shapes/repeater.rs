@@ -7,14 +7,15 @@ use crate::schema::animated_properties::value::FloatValue;
 use crate::schema::constants::composite::Composite;
 
 use super::repeater_transform::RepeaterTransformShape;
+use super::shape_element::ShapeElement;
 
 /// Duplicates previous shapes in a group
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RepeaterShape {
-    /// Shape Type
-    #[serde(rename = "ty")]
-    pub shape_type: String,
+    #[serde(flatten)]
+    pub shape_element: ShapeElement,
 
     /// Number of copies
     #[serde(rename = "c")]
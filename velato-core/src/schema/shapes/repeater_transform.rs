@@ -0,0 +1,43 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
+use crate::schema::animated_properties::position::Position;
+use crate::schema::shapes::FloatValue;
+use serde::{Deserialize, Serialize};
+
+/// Transform used by a repe…equent repeated object.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RepeaterTransformShape {
+    /// Position around which rotation and scale are applied
+    #[serde(rename = "a")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_point: Option<Position>,
+
+    /// Translation applied to each subsequent object
+    #[serde(rename = "p")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+
+    /// Rotation in degrees applied to each subsequent object
+    #[serde(rename = "r")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<FloatValue>,
+
+    /// Scale factor applied to each subsequent object, 100 for no scaling
+    #[serde(rename = "s")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<MultiDimensional>,
+
+    /// Transform used by a repeater, the transform is applied to each
+    /// subsequent repeated object.
+    #[serde(rename = "so")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_opacity: Option<FloatValue>,
+
+    // Opacity of the last repeated object
+    #[serde(rename = "eo")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_opacity: Option<FloatValue>,
+}
@@ -1,19 +1,18 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::base_stroke::BaseStroke;
-use super::gradient::Gradient;
 use super::shape_element::ShapeElement;
+use crate::schema::animated_properties::value::FloatValue;
 use serde::{Deserialize, Serialize};
 
+/// Rounds corners of other shapes
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
-pub struct GradientStrokeShape {
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RoundedCornersShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
 
-    #[serde(flatten)]
-    pub base_stroke: BaseStroke,
-
-    #[serde(flatten)]
-    pub gradient: Gradient,
+    /// Radius of the rounded corners
+    #[serde(rename = "r")]
+    pub radius: FloatValue,
 }
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// Drawable shape
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Shape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
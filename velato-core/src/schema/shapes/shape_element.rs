@@ -2,17 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::schema::constants::blend_mode::BlendMode;
+use crate::schema::helpers::permissive;
 use crate::schema::helpers::visual_object::VisualObject;
 use serde::{Deserialize, Serialize};
 
 /// Base class for all elements of `ShapeLayer` and `Group`
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShapeElement {
     #[serde(flatten)]
     pub visual_object: VisualObject,
 
     /// Whether the shape is hidden
-    #[serde(rename = "hd")]
+    #[serde(
+        rename = "hd",
+        deserialize_with = "permissive::option_boolean",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hidden: Option<bool>,
 
@@ -21,7 +27,7 @@ pub struct ShapeElement {
     pub blend_mode: Option<BlendMode>,
 
     /// Index used in expressions
-    #[serde(rename = "ix")]
+    #[serde(rename = "ix", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_index: Option<f64>,
 
@@ -37,7 +43,11 @@ pub struct ShapeElement {
 
     /// TODO: This is an unknown property, but it showed up sometimes in test
     /// files.
-    #[serde(rename = "ind")]
+    #[serde(
+        rename = "ind",
+        deserialize_with = "permissive::option_number",
+        default
+    )]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub index: Option<f64>,
 }
@@ -3,27 +3,29 @@
 
 use super::shape_element::ShapeElement;
 use super::stroke_dash::StrokeDash;
-use crate::schema::animated_properties::color_value::ColorValue;
+use crate::schema::animated_properties::color_value::AnyColorValue;
 use crate::schema::animated_properties::value::FloatValue;
-use crate::schema::constants::line_cap::LineCap;
-use crate::schema::constants::line_join::LineJoin;
+use crate::schema::constants::line_cap::AnyLineCap;
+use crate::schema::constants::line_join::AnyLineJoin;
+use crate::schema::helpers::permissive;
 use serde::{Deserialize, Serialize};
 
 /// Defines a stroke.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct StrokeShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
     /// Line Cap
     #[serde(rename = "lc")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub line_cap: Option<LineCap>,
+    pub line_cap: Option<AnyLineCap>,
     /// Line Join
     #[serde(rename = "lj")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub line_join: Option<LineJoin>,
+    pub line_join: Option<AnyLineJoin>,
     /// Miter Limit
-    #[serde(rename = "ml")]
+    #[serde(rename = "ml", deserialize_with = "permissive::option_number", default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub miter_limit: Option<f64>,
     /// Animatable alternative to `miter_limit`
@@ -42,5 +44,5 @@ pub struct StrokeShape {
     pub dash_array: Option<Vec<StrokeDash>>,
     /// Stroke color
     #[serde(rename = "c")]
-    pub stroke_color: ColorValue,
+    pub stroke_color: AnyColorValue,
 }
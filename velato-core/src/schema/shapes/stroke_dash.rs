@@ -8,16 +8,18 @@ use serde::{Deserialize, Serialize};
 
 /// An item used to described the dashe pattern in a stroked path
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct StrokeDash {
     #[serde(flatten)]
-    visual_object: VisualObject,
+    pub visual_object: VisualObject,
 
+    /// Type of the dash item
     #[serde(rename = "n")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    dash_type: Option<StrokeDashType>,
+    pub dash_type: Option<StrokeDashType>,
 
     /// Length of the dash
     #[serde(rename = "v")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    length: Option<FloatValue>,
+    pub length: Option<FloatValue>,
 }
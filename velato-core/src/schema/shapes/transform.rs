@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// A rectangle, defined by its center point and size.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TransformShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
@@ -9,6 +9,7 @@ use super::shape_element::ShapeElement;
 
 /// Trims shapes into a segment.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TrimShape {
     #[serde(flatten)]
     pub shape_element: ShapeElement,
@@ -0,0 +1,26 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::shape_element::ShapeElement;
+use crate::schema::animated_properties::multi_dimensional::MultiDimensional;
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+
+/// Rotates points of other shapes around a center by an amount proportional
+/// to their distance from it
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TwistShape {
+    #[serde(flatten)]
+    pub shape_element: ShapeElement,
+
+    /// Rotation in degrees at the farthest distance from the center
+    #[serde(rename = "a")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angle: Option<FloatValue>,
+
+    /// Offset of the center of rotation from the center of the shape
+    #[serde(rename = "c")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub center: Option<MultiDimensional>,
+}
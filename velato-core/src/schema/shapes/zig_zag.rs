@@ -0,0 +1,27 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::shape_element::ShapeElement;
+use crate::schema::animated_properties::value::FloatValue;
+use serde::{Deserialize, Serialize};
+
+/// Changes the edges of affected shapes into a series of peaks and valleys of
+/// uniform size
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ZigZagShape {
+    #[serde(flatten)]
+    pub shape_element: ShapeElement,
+
+    /// Number of ridges per segment
+    #[serde(rename = "r")]
+    pub frequency: FloatValue,
+
+    /// Distance between peaks and troughs
+    #[serde(rename = "s")]
+    pub amplitude: FloatValue,
+
+    /// Point type (1 = corner, 2 = smooth)
+    #[serde(rename = "pt")]
+    pub point_type: FloatValue,
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 ///
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ColorOverlayStyle {
     #[serde(flatten)]
     pub layer_style: LayerStyle,
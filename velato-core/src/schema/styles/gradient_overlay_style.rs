@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 ///
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GradientOverlayStyle {
     #[serde(flatten)]
     pub layer_style: LayerStyle,
@@ -0,0 +1,59 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::color_value::ColorValue;
+use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::helpers::visual_object::VisualObject;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Style type of an inner shadow, used to distinguish it from other styles.
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum InnerShadowStyleType {
+    InnerShadow = 2,
+}
+
+/// Inner shadow layer style
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct InnerShadowStyle {
+    #[serde(flatten)]
+    pub visual_object: VisualObject,
+    /// Style Type
+    #[serde(rename = "ty")]
+    pub style_type: InnerShadowStyleType,
+    /// Blend mode
+    #[serde(rename = "bm")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blend_mode: Option<FloatValue>,
+    /// Shadow color
+    #[serde(rename = "c")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorValue>,
+    /// Opacity, from 0 to 100
+    #[serde(rename = "o")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<FloatValue>,
+    /// Light angle in degrees. The shadow is cast in the opposite direction.
+    #[serde(rename = "a")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angle: Option<FloatValue>,
+    /// Blur size
+    #[serde(rename = "s")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<FloatValue>,
+    /// Distance of the shadow from the edges of the layer
+    #[serde(rename = "d")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<FloatValue>,
+    /// Choke spread
+    #[serde(rename = "ch")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choke_spread: Option<FloatValue>,
+    /// Noise
+    #[serde(rename = "no")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noise: Option<FloatValue>,
+}
@@ -8,6 +8,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// Style Type
 #[repr(u8)]
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum StyleType {
     Stroke = 0,
     DropShadow = 1,
@@ -22,6 +23,7 @@ pub enum StyleType {
 
 /// Style applied to a layer
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LayerStyle {
     #[serde(flatten)]
     pub visual_object: VisualObject,
@@ -0,0 +1,63 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+pub mod color_overlay_style;
+pub mod drop_shadow_style;
+pub mod gradient_overlay_style;
+pub mod inner_shadow_style;
+pub mod layer_style;
+pub mod outer_glow_style;
+pub mod satin_style;
+pub mod stroke_style;
+// todo pub mod bevel_embess_style;
+// todo pub mod inner_glow_style;
+
+use self::drop_shadow_style::DropShadowStyle;
+use self::inner_shadow_style::InnerShadowStyle;
+use self::layer_style::LayerStyle;
+use self::outer_glow_style::OuterGlowStyle;
+use self::stroke_style::StrokeStyle;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyLayerStyle {
+    /// Stroke
+    Stroke(StrokeStyle),
+    /// Drop shadow
+    DropShadow(DropShadowStyle),
+    /// Inner shadow
+    InnerShadow(InnerShadowStyle),
+    /// Outer glow
+    OuterGlow(OuterGlowStyle),
+    /// Any other style, which is not supported
+    Other(LayerStyle),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnyLayerStyle;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_drop_shadow() {
+        let styles: Vec<AnyLayerStyle> = serde_json::from_value(json!([
+            { "ty": 1, "nm": "Drop Shadow", "d": { "a": 0, "k": 10 } },
+            { "ty": 7, "nm": "Color Overlay" },
+            { "ty": 0, "nm": "Stroke", "s": { "a": 0, "k": 3 } },
+            { "ty": 2, "nm": "Inner Shadow" },
+            { "ty": 3, "nm": "Outer Glow" }
+        ]))
+        .unwrap();
+        assert!(matches!(
+            &styles[0],
+            AnyLayerStyle::DropShadow(style) if style.distance.is_some()
+        ));
+        assert!(matches!(&styles[1], AnyLayerStyle::Other(_)));
+        assert!(matches!(&styles[2], AnyLayerStyle::Stroke(style) if style.size.is_some()));
+        assert!(matches!(&styles[3], AnyLayerStyle::InnerShadow(_)));
+        assert!(matches!(&styles[4], AnyLayerStyle::OuterGlow(_)));
+    }
+}
@@ -1,41 +1,58 @@
 // Copyright 2024 the Velato Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::layer_style::LayerStyle;
 use crate::schema::animated_properties::color_value::ColorValue;
 use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::helpers::visual_object::VisualObject;
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-///
+/// Style type of an outer glow, used to distinguish it from other styles.
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum OuterGlowStyleType {
+    OuterGlow = 3,
+}
+
+/// Outer glow layer style
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OuterGlowStyle {
     #[serde(flatten)]
-    pub layer_style: LayerStyle,
-    ///
+    pub visual_object: VisualObject,
+    /// Style Type
+    #[serde(rename = "ty")]
+    pub style_type: OuterGlowStyleType,
+    /// Blend mode
     #[serde(rename = "bm")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blend_mode: Option<FloatValue>,
-    ///
+    /// Glow color
     #[serde(rename = "c")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<ColorValue>,
-    ///
+    /// Opacity, from 0 to 100
     #[serde(rename = "o")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub opacity: Option<FloatValue>,
-    ///
+    /// Blur size
+    #[serde(rename = "s")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<FloatValue>,
+    /// Range
     #[serde(rename = "r")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub range: Option<FloatValue>,
-    ///
+    /// Choke spread
     #[serde(rename = "ch")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub choke_spread: Option<FloatValue>,
-    ///
+    /// Noise
     #[serde(rename = "no")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub noise: Option<FloatValue>,
-    ///
+    /// Jitter
     #[serde(rename = "j")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jitter: Option<FloatValue>,
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 /// Style applied to a layer
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SatinStyle {
     #[serde(flatten)]
     pub layer_style: LayerStyle,
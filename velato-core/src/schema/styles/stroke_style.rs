@@ -0,0 +1,35 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::schema::animated_properties::color_value::ColorValue;
+use crate::schema::animated_properties::value::FloatValue;
+use crate::schema::helpers::visual_object::VisualObject;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Style type of a stroke, used to distinguish it from other styles.
+#[repr(u8)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum StrokeStyleType {
+    Stroke = 0,
+}
+
+/// Stroke layer style
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct StrokeStyle {
+    #[serde(flatten)]
+    pub visual_object: VisualObject,
+    /// Style Type
+    #[serde(rename = "ty")]
+    pub style_type: StrokeStyleType,
+    /// Width of the stroke outside the layer
+    #[serde(rename = "s")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<FloatValue>,
+    /// Stroke color
+    #[serde(rename = "c")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorValue>,
+}
@@ -0,0 +1,48 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Imports and renders Lottie files, as a stand-in for an application
+//! built with a combination of Velato's features.
+//!
+//! Usage:
+//! ```text
+//! xtask-probe ITERATIONS FILE...
+//! ```
+//! Each file is imported `ITERATIONS` times, and a line with the path, the
+//! fastest and the mean import time in nanoseconds is printed for it,
+//! separated by tabs. The first frame of each file is also rendered, so that
+//! the binary contains the renderer as well as the importer.
+
+use std::time::{Duration, Instant};
+use velato::vello::kurbo::Affine;
+use velato::{Composition, Renderer};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let iterations = args
+        .next()
+        .ok_or("usage: xtask-probe ITERATIONS FILE...")?
+        .parse::<u32>()?
+        .max(1);
+    let mut renderer = Renderer::new();
+    for path in args {
+        let contents = std::fs::read(&path)?;
+        let mut min = Duration::MAX;
+        let mut total = Duration::ZERO;
+        let mut composition = None;
+        for _ in 0..iterations {
+            let start = Instant::now();
+            composition = Some(Composition::from_slice(&contents)?);
+            let elapsed = start.elapsed();
+            min = min.min(elapsed);
+            total += elapsed;
+        }
+        if let Some(composition) = composition {
+            let frame = composition.frames.start;
+            std::hint::black_box(renderer.render(&composition, frame, Affine::IDENTITY, 1.0));
+        }
+        let mean = total / iterations;
+        println!("{path}\t{}\t{}", min.as_nanos(), mean.as_nanos());
+    }
+    Ok(())
+}
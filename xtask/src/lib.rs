@@ -0,0 +1,413 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Binary size and import time measurements for each combination of
+//! Velato's features, evaluation benchmarks, determinism checks and digests over
+//! catalogs of assets, and tables of evaluated keyframes.
+//!
+//! Each feature of `velato` should gate an independent subsystem, so that
+//! the size of every combination is meaningful. The measurements are exposed
+//! as plain data so other tools can query them.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use velato_core::{Digest, KeyframeTable, Painter};
+
+/// Optional features of the `velato` crate.
+pub const FEATURES: &[&str] = &["arbitrary", "expressions", "wgpu"];
+
+/// Size of the probe binary for one feature combination.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SizeReport {
+    /// Enabled features.
+    pub features: Vec<String>,
+    /// Size in bytes of the stripped release binary.
+    pub binary_bytes: u64,
+}
+
+/// Time taken to import a single asset with one feature combination.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Enabled features.
+    pub features: Vec<String>,
+    /// Path of the asset.
+    pub asset: PathBuf,
+    /// Size in bytes of the asset.
+    pub asset_bytes: u64,
+    /// Fastest import time over all iterations.
+    pub min: Duration,
+    /// Mean import time over all iterations.
+    pub mean: Duration,
+}
+
+/// Time taken to evaluate a frame of a synthetic asset with deeply nested
+/// shape groups.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShapesReport {
+    /// Number of nested groups.
+    pub depth: usize,
+    /// Fastest evaluation time over all iterations.
+    pub min: Duration,
+    /// Mean evaluation time over all iterations.
+    pub mean: Duration,
+}
+
+/// Quantum to which floating point values are rounded in frame digests,
+/// which is well below a pixel.
+pub const DIGEST_QUANTUM: f64 = 1.0 / 1024.0;
+
+/// Platform-stable digest of the evaluation of a single frame.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameDigest {
+    /// Path of the asset, relative to the asset directory.
+    pub asset: PathBuf,
+    /// Frame number.
+    pub frame: f64,
+    /// Digest of the evaluated frame.
+    pub digest: u64,
+}
+
+/// Combined results of a benchmark run.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Report {
+    /// Binary sizes for each feature combination.
+    pub sizes: Vec<SizeReport>,
+    /// Import times for each reference asset and feature combination.
+    pub imports: Vec<ImportReport>,
+}
+
+impl Report {
+    /// Returns the size report for the given set of features, if measured.
+    pub fn size_for(&self, features: &[&str]) -> Option<&SizeReport> {
+        self.sizes
+            .iter()
+            .find(|report| same_features(&report.features, features))
+    }
+
+    /// Returns the import reports for the given set of features.
+    pub fn imports_for<'a>(
+        &'a self,
+        features: &'a [&'a str],
+    ) -> impl Iterator<Item = &'a ImportReport> + 'a {
+        self.imports
+            .iter()
+            .filter(move |report| same_features(&report.features, features))
+    }
+}
+
+/// Returns true if the measured features are the given ones, in any order.
+fn same_features(measured: &[String], features: &[&str]) -> bool {
+    let mut features = features.to_vec();
+    features.sort_unstable();
+    let mut measured = measured.iter().map(String::as_str).collect::<Vec<_>>();
+    measured.sort_unstable();
+    measured == features
+}
+
+/// Returns every combination of the given features, starting with none.
+pub fn feature_combinations<'a>(features: &[&'a str]) -> Vec<Vec<&'a str>> {
+    (0..1_usize << features.len())
+        .map(|mask| {
+            features
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, feature)| *feature)
+                .collect()
+        })
+        .collect()
+}
+
+/// Builds the probe binary, a small application that imports and renders
+/// Lottie files, in release mode with the given features of `velato`, and
+/// returns its path.
+///
+/// Each combination uses its own target directory below `target_root` so
+/// that builds don't invalidate each other. Symbols are stripped so that the
+/// size of the binary is close to that of an application shipping it.
+pub fn build_probe(workspace: &Path, target_root: &Path, features: &[&str]) -> Result<PathBuf> {
+    let name = if features.is_empty() {
+        "no-features".to_string()
+    } else {
+        features.join("+")
+    };
+    let target_dir = target_root.join(name);
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .current_dir(workspace)
+        .args(["build", "--release", "-p", "xtask-probe"])
+        .args(["--config", "profile.release.strip=true"])
+        .arg("--no-default-features")
+        .arg("--features")
+        .arg(features.join(","))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .context("failed to run cargo")?;
+    if !status.success() {
+        bail!("build failed for features {features:?}");
+    }
+    let binary = target_dir
+        .join("release")
+        .join(format!("xtask-probe{}", std::env::consts::EXE_SUFFIX));
+    if !binary.exists() {
+        bail!("missing build output {}", binary.display());
+    }
+    Ok(binary)
+}
+
+/// Returns the size of the probe binary built with the given features by
+/// [`build_probe`].
+pub fn measure_size(binary: &Path, features: &[&str]) -> Result<SizeReport> {
+    let binary_bytes = std::fs::metadata(binary)
+        .with_context(|| format!("missing probe binary {}", binary.display()))?
+        .len();
+    Ok(SizeReport {
+        features: features.iter().map(|s| s.to_string()).collect(),
+        binary_bytes,
+    })
+}
+
+/// Returns the paths of the Lottie files in `assets`, sorted.
+fn lottie_files(assets: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = std::fs::read_dir(assets)
+        .with_context(|| format!("failed to read {}", assets.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Measures the time to import each Lottie file in `assets` with the probe
+/// binary built with the given features by [`build_probe`].
+pub fn measure_imports(
+    binary: &Path,
+    features: &[&str],
+    assets: &Path,
+    iterations: usize,
+) -> Result<Vec<ImportReport>> {
+    let paths = lottie_files(assets)?;
+    if paths.is_empty() {
+        return Ok(vec![]);
+    }
+    let output = Command::new(binary)
+        .arg(iterations.max(1).to_string())
+        .args(&paths)
+        .output()
+        .with_context(|| format!("failed to run {}", binary.display()))?;
+    if !output.status.success() {
+        bail!(
+            "probe failed for features {features:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut reports = vec![];
+    for (path, line) in paths.into_iter().zip(stdout.lines()) {
+        let nanos = |field: Option<&str>| -> Result<Duration> {
+            let field = field.with_context(|| format!("malformed probe output `{line}`"))?;
+            Ok(Duration::from_nanos(field.parse()?))
+        };
+        let mut fields = line.split('\t').skip(1);
+        let min = nanos(fields.next())?;
+        let mean = nanos(fields.next())?;
+        reports.push(ImportReport {
+            features: features.iter().map(|s| s.to_string()).collect(),
+            asset_bytes: std::fs::metadata(&path)?.len(),
+            asset: path,
+            min,
+            mean,
+        });
+    }
+    Ok(reports)
+}
+
+/// Returns a composition with a single layer of `depth` nested groups, each
+/// containing an animated rectangle and a fill besides the next group.
+///
+/// JSON parsers limit nesting, so the asset is built in memory rather than
+/// loaded from a file.
+pub fn nested_shapes(depth: usize) -> velato_core::Composition {
+    use velato_core::kurbo::{Affine, Point, Size};
+    use velato_core::model::{animated, fixed, Animated, Brush, Content, Draw, Geometry, Layer};
+    use velato_core::model::{GroupTransform, Shape, Time, Transform, Value};
+
+    let time = |frame| Time {
+        frame,
+        in_tangent: None,
+        out_tangent: None,
+        axis_in_tangents: [None; 2],
+        axis_out_tangents: [None; 2],
+        hold: false,
+    };
+    let mut shapes = vec![];
+    for i in 0..depth {
+        let offset = (i % 10) as f64;
+        let rect = Shape::Geometry(Geometry::Rect(animated::Rect {
+            is_ccw: false,
+            position: Value::Animated(Box::new(Animated {
+                times: vec![time(0.0), time(60.0)],
+                values: vec![Point::new(offset, 0.0), Point::new(0.0, offset)],
+                loops: Default::default(),
+            })),
+            size: Value::Fixed(Size::new(4.0, 4.0)),
+            corner_radius: Value::Fixed(0.0),
+        }));
+        let fill = Shape::Draw(Draw {
+            stroke: None,
+            brush: Brush::Fixed(fixed::Color::rgb8(0, 0, 255).into()),
+            opacity: Value::Fixed(100.0),
+        });
+        let group = GroupTransform {
+            transform: Transform::Fixed(Affine::translate((0.1, 0.1))),
+            opacity: Value::Fixed(100.0),
+        };
+        shapes = vec![
+            Shape::Group(vec![rect, fill], None),
+            Shape::Group(shapes, Some(group)),
+        ];
+    }
+    velato_core::Composition {
+        frames: 0.0..60.0,
+        frame_rate: 60.0,
+        width: 100,
+        height: 100,
+        layers: vec![Layer {
+            opacity: Value::Fixed(100.0),
+            frames: 0.0..60.0,
+            stretch: 1.0,
+            content: Content::Shape(shapes),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+/// Measures the time to evaluate every frame of [`nested_shapes`] with the
+/// given depth, without rendering.
+pub fn measure_shapes(depth: usize, iterations: usize) -> ShapesReport {
+    let iterations = iterations.max(1);
+    let composition = nested_shapes(depth);
+    let mut painter = Painter::<Digest>::new();
+    let mut min = Duration::MAX;
+    let mut total = Duration::ZERO;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        for frame in 0..60 {
+            std::hint::black_box(painter.evaluate_only(&composition, frame as f64));
+        }
+        let elapsed = start.elapsed();
+        min = min.min(elapsed);
+        total += elapsed;
+    }
+    ShapesReport {
+        depth,
+        min,
+        mean: total / iterations as u32,
+    }
+}
+
+/// Evaluates every frame of each Lottie file in `assets` twice, with
+/// separate painters, and fails if any checksums differ. Returns the
+/// number of frames checked.
+///
+/// This needs no GPU, and panics in evaluation propagate as failures.
+pub fn check_determinism(assets: &Path) -> Result<usize> {
+    let mut frames = 0;
+    for path in lottie_files(assets)? {
+        let contents = std::fs::read(&path)?;
+        let composition = velato_core::Composition::from_slice(&contents)
+            .with_context(|| format!("failed to import {}", path.display()))?;
+        let mut first = Painter::<Digest>::new();
+        let mut second = Painter::<Digest>::new();
+        let start = composition.frames.start.floor() as i64;
+        let end = composition.frames.end.ceil() as i64;
+        for frame in start..=end {
+            let frame = frame as f64;
+            let expected = first.evaluate_only(&composition, frame);
+            if second.evaluate_only(&composition, frame) != expected {
+                bail!("{} is not deterministic at frame {frame}", path.display());
+            }
+            frames += 1;
+        }
+    }
+    Ok(frames)
+}
+
+/// Evaluates every frame of each Lottie file in `assets` and returns their
+/// platform-stable digests, with values rounded to [`DIGEST_QUANTUM`].
+///
+/// Comparing the output on different targets catches evaluation that
+/// depends on the platform, such as differences in libm functions.
+pub fn digest_frames(assets: &Path) -> Result<Vec<FrameDigest>> {
+    let mut digests = vec![];
+    for path in lottie_files(assets)? {
+        let contents = std::fs::read(&path)?;
+        let composition = velato_core::Composition::from_slice(&contents)
+            .with_context(|| format!("failed to import {}", path.display()))?;
+        let asset = path.strip_prefix(assets).unwrap_or(&path).to_path_buf();
+        let mut painter = Painter::<Digest>::new();
+        let start = composition.frames.start.floor() as i64;
+        let end = composition.frames.end.ceil() as i64;
+        for frame in start..=end {
+            let frame = frame as f64;
+            digests.push(FrameDigest {
+                asset: asset.clone(),
+                frame,
+                digest: painter.evaluate_digest(&composition, frame, Some(DIGEST_QUANTUM)),
+            });
+        }
+    }
+    Ok(digests)
+}
+
+/// Imports the Lottie file at `path` and samples the animated properties at
+/// or below any of `paths`, or every animated property if `paths` is empty,
+/// at their keyframes and `steps` frames between them.
+pub fn keyframe_table(path: &Path, paths: Vec<String>, steps: usize) -> Result<KeyframeTable> {
+    let contents = std::fs::read(path)?;
+    let composition = velato_core::Composition::from_slice(&contents)
+        .with_context(|| format!("failed to import {}", path.display()))?;
+    let filter = if paths.is_empty() {
+        velato_core::PropertyFilter::All
+    } else {
+        velato_core::PropertyFilter::Paths(paths)
+    };
+    Ok(composition.keyframe_table(&filter, steps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{feature_combinations, measure_shapes, Report, SizeReport};
+
+    #[test]
+    fn test_feature_combinations() {
+        let combinations = feature_combinations(&["a", "b"]);
+        assert_eq!(
+            combinations,
+            vec![vec![], vec!["a"], vec!["b"], vec!["a", "b"]]
+        );
+    }
+
+    #[test]
+    fn test_size_for() {
+        let report = Report {
+            sizes: vec![SizeReport {
+                features: vec!["b".into(), "a".into()],
+                binary_bytes: 1,
+            }],
+            imports: vec![],
+        };
+        assert_eq!(report.size_for(&["a", "b"]).unwrap().binary_bytes, 1);
+        assert!(report.size_for(&["a"]).is_none());
+    }
+
+    #[test]
+    fn test_measure_shapes() {
+        assert_eq!(measure_shapes(500, 1).depth, 500);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 the Velato Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Development tasks.
+//!
+//! Usage:
+//! ```text
+//! cargo xtask bench-size [ASSET_DIR]
+//! cargo xtask check-determinism [ASSET_DIR]
+//! cargo xtask digest [ASSET_DIR]
+//! cargo xtask bench-shapes [DEPTH]
+//! cargo xtask keyframes <csv|json> FILE [PATH...]
+//! ```
+//! `bench-size` builds a small binary that imports and renders Lottie files
+//! for each feature combination, and prints a JSON report of its stripped
+//! size and of its import time for each Lottie file in `ASSET_DIR`.
+//! `check-determinism` evaluates every frame of each Lottie file in
+//! `ASSET_DIR` twice without rendering and fails if the results differ.
+//! `digest` prints a platform-stable digest of every frame of each Lottie
+//! file in `ASSET_DIR`, to compare the output of builds for different
+//! targets.
+//! `bench-shapes` prints a JSON report of the time to evaluate a synthetic
+//! asset with `DEPTH` nested shape groups, 1000 by default.
+//! `keyframes` prints the values of the animated properties of the Lottie
+//! file `FILE` at each keyframe and at frames between keyframes, as CSV or
+//! JSON, for comparing with the graph editor of After Effects. `PATH`
+//! selects properties by path, such as `layers/0/transform/position`.
+//! `ASSET_DIR` defaults to `examples/assets/google_fonts`.
+
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+use xtask::{
+    build_probe, check_determinism, digest_frames, feature_combinations, keyframe_table,
+    measure_imports, measure_shapes, measure_size, Report, FEATURES,
+};
+
+const IMPORT_ITERATIONS: usize = 10;
+const SHAPES_ITERATIONS: usize = 10;
+const SHAPES_DEPTH: usize = 1000;
+const KEYFRAME_STEPS: usize = 9;
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let workspace = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the workspace")
+        .to_path_buf();
+    let task = args.next();
+    let arg = args.next();
+    let assets = arg
+        .clone()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace.join("examples/assets/google_fonts"));
+    match task.as_deref() {
+        Some("bench-size") => {
+            let target_root = workspace.join("target/xtask");
+            let mut report = Report::default();
+            for features in feature_combinations(FEATURES) {
+                let binary = build_probe(&workspace, &target_root, &features)?;
+                report.sizes.push(measure_size(&binary, &features)?);
+                report.imports.extend(measure_imports(
+                    &binary,
+                    &features,
+                    &assets,
+                    IMPORT_ITERATIONS,
+                )?);
+            }
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            Ok(())
+        }
+        Some("check-determinism") => {
+            let frames = check_determinism(&assets)?;
+            println!("{frames} frames evaluated deterministically");
+            Ok(())
+        }
+        Some("digest") => {
+            for digest in digest_frames(&assets)? {
+                println!(
+                    "{} {} {:016x}",
+                    digest.asset.display(),
+                    digest.frame,
+                    digest.digest
+                );
+            }
+            Ok(())
+        }
+        Some("bench-shapes") => {
+            let depth = match arg {
+                Some(depth) => depth.parse()?,
+                None => SHAPES_DEPTH,
+            };
+            let report = measure_shapes(depth, SHAPES_ITERATIONS);
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            Ok(())
+        }
+        Some("keyframes") => {
+            let Some(path) = args.next() else {
+                bail!("usage: cargo xtask keyframes <csv|json> FILE [PATH...]");
+            };
+            let table = keyframe_table(path.as_ref(), args.collect(), KEYFRAME_STEPS)?;
+            match arg.as_deref() {
+                Some("csv") => print!("{}", table.to_csv()),
+                Some("json") => println!("{}", serde_json::to_string_pretty(&table.to_json())?),
+                _ => bail!("unknown format, expected `csv` or `json`"),
+            }
+            Ok(())
+        }
+        Some(task) => bail!("unknown task `{task}`"),
+        None => bail!(
+            "usage: cargo xtask <bench-size|check-determinism|digest> [ASSET_DIR] | bench-shapes [DEPTH] | keyframes <csv|json> FILE [PATH...]"
+        ),
+    }
+}